@@ -73,11 +73,20 @@ struct TerrainShadowSettings {
     interpolation_distance: f32;
 };
 // ----------------------------------------------------------------------------
+// render settings not expressible as shader defs
+struct TerrainRenderSettings {
+    highlighted_material: u32;
+    show_selection: u32;
+    selection_min: vec2<f32>;
+    selection_max: vec2<f32>;
+};
+// ----------------------------------------------------------------------------
 // view
 [[group(0), binding(0)]] var<uniform> view: View;
 [[group(0), binding(1)]] var<uniform> sunlight: DirectionalLight;
 [[group(0), binding(2)]] var<uniform> mapInfo: TerrainMapInfo;
 [[group(0), binding(3)]] var<uniform> shadows: TerrainShadowSettings;
+[[group(0), binding(4)]] var<uniform> renderSettings: TerrainRenderSettings;
 // ----------------------------------------------------------------------------
 [[group(1), binding(0)]] var<uniform> mesh: Mesh;
 // ----------------------------------------------------------------------------
@@ -391,7 +400,8 @@ fn fragment(in: FragmentInput) -> FragmentOutput {
     // Note about zero texture id: zero slot represents a terrain hole
     // by subtracting 1 the id overflows and the shader uses the *last* texture
     // in the texture array. this is a dedicated placeholder texture in the editor
-    // independent of the loaded materialset to visualize terrain holes
+    // independent of the loaded materialset. holes are visualized separately
+    // (see below).
     // it seems only background zero texture indicates a terrain hole (overlay
     // texture is ignored in the case). unclear what a non-zero bkgrnd and zero
     // overlay texture represent.
@@ -717,6 +727,17 @@ fn fragment(in: FragmentInput) -> FragmentOutput {
     # endif // FAST_TERRAIN_SHADOWS
     # endif // DISABLE_TERRAIN_SHADOWS
     // --------------------------------------------------------------------------------------------
+    // terrain holes: triangles completely covered by holes are cut out of the tile meshes. the
+    // remaining hole parts (e.g. at hole borders) are shown as magenta checker pattern to make
+    // them distinguishable from the black placeholder texture. hole slot overflowed (see above)
+    let holeCoverage = dot(fractionalWeights,
+        select(vec4<f32>(0.0), vec4<f32>(1.0), bkgrndTextureSlots == vec4<u32>(4294967295u)));
+
+    if (holeCoverage > 0.5) {
+        let checker = (u32(floor(clipmapPos.x * 2.0)) + u32(floor(clipmapPos.y * 2.0))) % 2u;
+        fragmentCol = select(vec4<f32>(0.15, 0.0, 0.15, 1.0), vec4<f32>(1.0, 0.0, 1.0, 1.0), checker == 1u);
+    }
+    // --------------------------------------------------------------------------------------------
     // debug visualization for texture control
     # ifdef SHOW_BLEND_VALUE
     fragmentCol = vec4<f32>(slopeThreshold, slopeThreshold, slopeThreshold, 1.0);
@@ -726,6 +747,19 @@ fn fragment(in: FragmentInput) -> FragmentOutput {
     let value = f32(bkgrndUvScaling.x) / 8.0;
     fragmentCol = vec4<f32>(value, value, value, 1.0);
     # endif
+
+    # ifdef SHOW_MATERIAL_COVERAGE
+    // overlay coverage of highlighted material in red, background in green
+    // (both: yellow) on top of darkened grayscale terrain
+    let highlighted = vec4<u32>(renderSettings.highlighted_material);
+    let overlayCoverage = dot(fractionalWeights,
+        select(vec4<f32>(0.0), vec4<f32>(1.0), overlayTextureSlots == highlighted));
+    let bkgrndCoverage = dot(fractionalWeights,
+        select(vec4<f32>(0.0), vec4<f32>(1.0), bkgrndTextureSlots == highlighted));
+
+    let luminance = 0.3 * dot(diffuse.rgb, vec3<f32>(0.299, 0.587, 0.114));
+    fragmentCol = vec4<f32>(max(vec3<f32>(luminance), vec3<f32>(overlayCoverage, bkgrndCoverage, 0.0)), 1.0);
+    # endif
     // --------------------------------------------------------------------------------------------
     // debug visualization for normals
     # ifdef SHOW_FRAGMENT_NORMAL
@@ -772,6 +806,24 @@ fn fragment(in: FragmentInput) -> FragmentOutput {
     fragmentCol = mix(fragmentCol, clipmapCol, f32(clipmap_level) / 6.0);
     # endif
     // --------------------------------------------------------------------------------------------
+    // outline of painting selection bounds (dashed, constant width in screen space)
+    let selectionPos = fragmentPos.xz;
+    let outlineWidth = 1.5 * max(fwidth(selectionPos.x), fwidth(selectionPos.y));
+
+    if (renderSettings.show_selection == 1u) {
+        let selectionMin = renderSettings.selection_min;
+        let selectionMax = renderSettings.selection_max;
+        let inside = all(selectionPos >= selectionMin) && all(selectionPos <= selectionMax);
+        let edgeDistance = min(
+            min(selectionPos.x - selectionMin.x, selectionMax.x - selectionPos.x),
+            min(selectionPos.y - selectionMin.y, selectionMax.y - selectionPos.y));
+
+        if (inside && edgeDistance < outlineWidth) {
+            let dash = i32(floor((selectionPos.x + selectionPos.y) / (8.0 * outlineWidth))) & 1;
+            fragmentCol = select(vec4<f32>(0.0, 0.0, 0.0, 1.0), vec4<f32>(1.0), dash == 1);
+        }
+    }
+    // --------------------------------------------------------------------------------------------
 
     return FragmentOutput(fragmentCol, in.world_position);
 }
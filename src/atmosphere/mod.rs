@@ -7,7 +7,7 @@
 //! A procedural sky plugin for bevy
 //!
 //! ## Example
-//! ```
+//! ```ignore
 //! use bevy::prelude::*;
 //! use bevy_atmosphere::*;
 //!
@@ -42,7 +42,7 @@ pub struct AtmospherePlugin {
     /// resource, a [`Handle`](bevy::asset::Handle) to the [`AtmosphereMat`](crate::AtmosphereMat)
     /// used and the [`Assets`](bevy::asset::Assets) that stores the [`AtmosphereMat`](crate::AtmosphereMat)
     /// ### Example
-    /// ```
+    /// ```ignore
     /// use bevy::prelude::*;
     /// use bevy_atmosphere::AtmosphereMat;
     /// use std::ops::Deref;
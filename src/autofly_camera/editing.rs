@@ -0,0 +1,346 @@
+// ----------------------------------------------------------------------------
+// interactive editing of camera paths with keypoint and control point gizmos
+// ----------------------------------------------------------------------------
+use std::cmp::Ordering;
+
+use bevy::{
+    prelude::*,
+    render::camera::{Camera3d, PerspectiveProjection},
+};
+
+use super::visualization::{PathGizmos, VisualizedPathInterpolation};
+use super::{normalized_times, CameraPath, CameraPathId, InterpolatedPath, PathKeypoint};
+// ----------------------------------------------------------------------------
+/// max screen distance (logical pixels) between pointer and picked gizmo
+const PICKING_DISTANCE: f32 = 12.0;
+const KEYPOINT_SIZE: f32 = 4.0;
+const CONTROL_POINT_SIZE: f32 = 3.0;
+const MIN_SHARE: f32 = 0.05;
+// ----------------------------------------------------------------------------
+#[derive(Default)]
+pub struct CameraPathEditor {
+    path: Option<CameraPathId>,
+    selected: Option<usize>,
+    pointer: EditPointer,
+    drag: Option<DraggedPoint>,
+    /// gizmos must be updated (e.g. selection changed)
+    outdated: bool,
+}
+// ----------------------------------------------------------------------------
+impl CameraPathEditor {
+    // ------------------------------------------------------------------------
+    pub fn start(&mut self, id: CameraPathId) {
+        if self.path != Some(id) {
+            self.path = Some(id);
+            self.selected = None;
+            self.drag = None;
+            self.outdated = true;
+        }
+    }
+    // ------------------------------------------------------------------------
+    pub fn stop(&mut self) {
+        self.path = None;
+        self.selected = None;
+        self.drag = None;
+        self.outdated = true;
+    }
+    // ------------------------------------------------------------------------
+    pub fn is_active(&self) -> bool {
+        self.path.is_some()
+    }
+    // ------------------------------------------------------------------------
+    pub fn edited(&self) -> Option<CameraPathId> {
+        self.path
+    }
+    // ------------------------------------------------------------------------
+    pub fn selected_keypoint(&self) -> Option<usize> {
+        self.selected
+    }
+    // ------------------------------------------------------------------------
+    pub fn select_keypoint(&mut self, keypoint: Option<usize>) {
+        if self.selected != keypoint {
+            self.selected = keypoint;
+            self.outdated = true;
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// pointer position in logical window coordinates. None if the pointer is
+    /// not available for editing (e.g. used by gui)
+    pub fn update_pointer(&mut self, pos: Option<Vec2>, pressed: bool) {
+        self.pointer = EditPointer {
+            active: pos.is_some(),
+            pos: pos.unwrap_or(self.pointer.pos),
+            just_pressed: pressed && !self.pointer.pressed,
+            pressed,
+        };
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl CameraPath {
+    // ------------------------------------------------------------------------
+    pub fn keypoint_shares(&self) -> &[f32] {
+        &self.shares
+    }
+    // ------------------------------------------------------------------------
+    pub fn set_keypoint_share(&mut self, keypoint: usize, share: f32) {
+        if let Some(s) = self.shares.get_mut(keypoint) {
+            *s = share.max(MIN_SHARE);
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// inserts a new keypoint on the path after the given keypoint (or extends
+    /// the path if it's the last one). the segment share is split between the
+    /// new segments.
+    pub fn insert_keypoint(&mut self, after: usize) {
+        let last = self.keypoints.len() - 1;
+        let prev = self.keypoints[after.min(last)];
+
+        let (key, control, share) = if after < last {
+            let next = self.keypoints[after + 1];
+            let times = normalized_times(&self.shares[1..]);
+            let t = 0.5 * (times[after] + times[after + 1]);
+
+            let path: &CameraPath = self;
+            let (pos, rot) = path.interpolation().sample(t);
+
+            let share = 0.5 * self.shares[after + 1];
+            self.shares[after + 1] = share;
+
+            let control = pos + (next.pos - prev.pos) / 6.0;
+            (PathKeypoint::from((pos, rot)), control, share)
+        } else {
+            let dir = prev.pos - self.keypoints[last - 1].pos;
+            let pos = prev.pos + 0.5 * dir;
+
+            let control = pos + dir / 6.0;
+            (PathKeypoint::new(pos, prev.rot), control, self.shares[last])
+        };
+
+        let control = PathKeypoint::new(control, key.rot);
+        self.keypoints.insert(after + 1, key);
+        self.control_points.insert(after + 1, control);
+        self.shares.insert(after + 1, share);
+    }
+    // ------------------------------------------------------------------------
+    /// removes keypoint if at least two keypoints remain. the share of the
+    /// removed keypoint is added to the following segment.
+    pub fn remove_keypoint(&mut self, keypoint: usize) -> bool {
+        if self.keypoints.len() <= 2 || keypoint >= self.keypoints.len() {
+            return false;
+        }
+        let share = self.shares.remove(keypoint);
+        if let Some(next) = self.shares.get_mut(keypoint) {
+            *next += share;
+        }
+        self.keypoints.remove(keypoint);
+        self.control_points.remove(keypoint);
+        true
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+// systems
+// ----------------------------------------------------------------------------
+pub(super) fn edit_camera_path(
+    windows: Res<Windows>,
+    images: Res<Assets<Image>>,
+    query: Query<(&Camera, &GlobalTransform, &PerspectiveProjection), With<Camera3d>>,
+    mut editor: ResMut<CameraPathEditor>,
+    mut paths: ResMut<Assets<CameraPath>>,
+) {
+    let id = match editor.path {
+        Some(id) => id,
+        None => return,
+    };
+    let (camera, cam_transform, projection) = match query.get_single() {
+        Ok(cam) => cam,
+        Err(_) => return,
+    };
+    let pointer = editor.pointer;
+
+    if !pointer.pressed {
+        editor.drag = None;
+    }
+    if !pointer.active {
+        return;
+    }
+
+    if pointer.just_pressed {
+        let path = match paths.get(id) {
+            Some(path) => path,
+            None => return,
+        };
+        let picked = editor
+            .pickable_points(path)
+            .filter_map(|point| {
+                camera
+                    .world_to_screen(&windows, &images, cam_transform, path.point(point))
+                    .map(|screen_pos| (point, screen_pos.distance(pointer.pos)))
+            })
+            .filter(|(_, distance)| *distance < PICKING_DISTANCE)
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+            .map(|(point, _)| point);
+
+        editor.select_keypoint(picked.map(|point| point.keypoint()));
+        editor.drag = picked.map(|point| DraggedPoint {
+            point,
+            last_pos: pointer.pos,
+        });
+    } else if let Some(drag) = editor.drag.as_mut() {
+        let delta = pointer.pos - drag.last_pos;
+        if delta != Vec2::ZERO {
+            if let Some(path) = paths.get_mut(id) {
+                // move point within plane parallel to the view plane so it
+                // follows the pointer
+                let depth = (path.point(drag.point) - cam_transform.translation)
+                    .dot(cam_transform.forward())
+                    .max(projection.near);
+                let window_height = windows.get_primary().map_or(1.0, |w| w.height());
+                let world_per_pixel = 2.0 * depth * (0.5 * projection.fov).tan() / window_height;
+
+                let offset = (cam_transform.right() * delta.x + cam_transform.up() * delta.y)
+                    * world_per_pixel;
+                path.move_point(drag.point, offset);
+            }
+            drag.last_pos = pointer.pos;
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+/// updates path visualization and gizmos on any change of the edited path
+pub(super) fn update_edited_path_visualization(
+    paths: Res<Assets<CameraPath>>,
+    mut asset_events: EventReader<AssetEvent<CameraPath>>,
+    mut editor: ResMut<CameraPathEditor>,
+    mut visualization: ResMut<VisualizedPathInterpolation>,
+) {
+    let edited = editor.path;
+    let modified = asset_events.iter().any(|e| match e {
+        AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+            Some(handle.id) == edited
+        }
+        AssetEvent::Removed { .. } => false,
+    });
+    // visualization may have been removed by other states (e.g. autofly)
+    let missing = edited.is_some() && !visualization.as_ref().is_active();
+
+    if !(modified || missing || editor.outdated) {
+        return;
+    }
+    editor.outdated = false;
+
+    match edited.and_then(|id| paths.get(id)) {
+        Some(path) => visualization.set_with_gizmos(path.interpolation(), editor.gizmos(path)),
+        None => {
+            if visualization.as_ref().is_active() {
+                visualization.remove();
+            }
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+pub(super) fn stop_editing(
+    mut editor: ResMut<CameraPathEditor>,
+    mut visualization: ResMut<VisualizedPathInterpolation>,
+) {
+    if editor.as_ref().is_active() {
+        editor.stop();
+        visualization.remove();
+    }
+}
+// ----------------------------------------------------------------------------
+// internal types
+// ----------------------------------------------------------------------------
+#[derive(Default, Clone, Copy)]
+struct EditPointer {
+    active: bool,
+    pos: Vec2,
+    pressed: bool,
+    just_pressed: bool,
+}
+// ----------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PathPoint {
+    Key(usize),
+    Control(usize),
+}
+// ----------------------------------------------------------------------------
+struct DraggedPoint {
+    point: PathPoint,
+    last_pos: Vec2,
+}
+// ----------------------------------------------------------------------------
+// helper
+// ----------------------------------------------------------------------------
+impl CameraPathEditor {
+    // ------------------------------------------------------------------------
+    /// all keypoints and the control point of the selected keypoint
+    fn pickable_points(&self, path: &CameraPath) -> impl Iterator<Item = PathPoint> {
+        (0..path.keypoints.len())
+            .map(PathPoint::Key)
+            .chain(self.selected.map(PathPoint::Control))
+    }
+    // ------------------------------------------------------------------------
+    fn gizmos(&self, path: &CameraPath) -> PathGizmos {
+        let mut gizmos = PathGizmos::default();
+
+        for (i, (key, control)) in path
+            .keypoints
+            .iter()
+            .zip(path.control_points.iter())
+            .enumerate()
+        {
+            if self.selected == Some(i) {
+                gizmos
+                    .markers
+                    .push((key.pos, 1.5 * KEYPOINT_SIZE, Color::ORANGE));
+                gizmos
+                    .markers
+                    .push((control.pos, CONTROL_POINT_SIZE, Color::CYAN));
+                gizmos.lines.push((key.pos, control.pos, Color::CYAN));
+                // incoming tangent is mirrored outgoing tangent
+                gizmos
+                    .lines
+                    .push((key.pos, 2.0 * key.pos - control.pos, Color::GRAY));
+            } else {
+                gizmos.markers.push((key.pos, KEYPOINT_SIZE, Color::WHITE));
+            }
+        }
+        gizmos
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl PathPoint {
+    // ------------------------------------------------------------------------
+    fn keypoint(&self) -> usize {
+        match self {
+            PathPoint::Key(i) | PathPoint::Control(i) => *i,
+        }
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl CameraPath {
+    // ------------------------------------------------------------------------
+    fn point(&self, point: PathPoint) -> Vec3 {
+        match point {
+            PathPoint::Key(i) => self.keypoints[i].pos,
+            PathPoint::Control(i) => self.control_points[i].pos,
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// moving a keypoint moves its control point, too
+    fn move_point(&mut self, point: PathPoint, offset: Vec3) {
+        match point {
+            PathPoint::Key(i) => {
+                self.keypoints[i].pos += offset;
+                self.control_points[i].pos += offset;
+            }
+            PathPoint::Control(i) => self.control_points[i].pos += offset,
+        }
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
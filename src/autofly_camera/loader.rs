@@ -0,0 +1,193 @@
+// ----------------------------------------------------------------------------
+// loader for camera path assets (*.campath files in assets/campaths)
+// ----------------------------------------------------------------------------
+//
+// camera paths are stored as RON with a duration in seconds and a list of
+// keypoints. every keypoint consists of the camera position and rotation at the
+// key and a control point that defines the direction of the spline:
+//
+//   (
+//       name: "Prolog flyby",
+//       duration: 30.0,
+//       keypoints: [
+//           (
+//               key: (pos: (255.9, 10.6, -76.1), pitch: -0.10, yaw: 1.10),
+//               control: (pos: (177.8, 17.3, -118.9), pitch: 0.05, yaw: 0.78),
+//               share: 1.0,
+//           ),
+//           ..
+//       ],
+//   )
+//
+// name is optional and defaults to the filename. share is the optional relative
+// duration of the segment leading to the keypoint (default 1.0). the share of
+// the first keypoint is used for the approach from the current camera position.
+//
+use std::path::Path;
+
+use bevy::asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset};
+use serde::{Deserialize, Serialize};
+
+use super::{CameraPath, PathKeypoint};
+// ----------------------------------------------------------------------------
+pub(super) const CAMPATH_ASSET_ROOT: &str = "assets";
+pub(super) const CAMPATH_FOLDER: &str = "campaths";
+pub(super) const CAMPATH_EXTENSION: &str = "campath";
+// ----------------------------------------------------------------------------
+#[derive(Default)]
+pub(super) struct CameraPathLoader;
+// ----------------------------------------------------------------------------
+#[derive(Serialize, Deserialize)]
+struct CameraPathFile {
+    #[serde(default)]
+    name: String,
+    duration: f32,
+    keypoints: Vec<KeypointDefinition>,
+}
+// ----------------------------------------------------------------------------
+#[derive(Serialize, Deserialize)]
+struct KeypointDefinition {
+    key: PointDefinition,
+    control: PointDefinition,
+    #[serde(default = "default_share")]
+    share: f32,
+}
+// ----------------------------------------------------------------------------
+#[derive(Serialize, Deserialize)]
+struct PointDefinition {
+    pos: (f32, f32, f32),
+    pitch: f32,
+    yaw: f32,
+}
+// ----------------------------------------------------------------------------
+impl AssetLoader for CameraPathLoader {
+    // ------------------------------------------------------------------------
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let path = load_context.path();
+            let campath = CameraPath::from_ron(bytes, path).map_err(anyhow::Error::msg)?;
+
+            let filepath = Path::new(CAMPATH_ASSET_ROOT).join(path);
+            let campath = campath.set_filepath(filepath.to_string_lossy().to_string());
+
+            load_context.set_default_asset(LoadedAsset::new(campath));
+            Ok(())
+        })
+    }
+    // ------------------------------------------------------------------------
+    fn extensions(&self) -> &[&str] {
+        &[CAMPATH_EXTENSION]
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl CameraPath {
+    // ------------------------------------------------------------------------
+    fn from_ron(bytes: &[u8], path: &Path) -> Result<Self, String> {
+        let file: CameraPathFile = ron::de::from_bytes(bytes)
+            .map_err(|e| format!("failed to parse camera path {}: {}", path.display(), e))?;
+
+        if file.keypoints.len() < 2 {
+            return Err(format!(
+                "camera path {}: requires at least 2 keypoints. found: {}",
+                path.display(),
+                file.keypoints.len()
+            ));
+        }
+        if file.duration <= 0.0 {
+            return Err(format!(
+                "camera path {}: duration must be greater than 0. found: {}",
+                path.display(),
+                file.duration
+            ));
+        }
+
+        if let Some(k) = file.keypoints.iter().position(|k| k.share <= 0.0) {
+            return Err(format!(
+                "camera path {}: share of keypoint {} must be greater than 0. found: {}",
+                path.display(),
+                k,
+                file.keypoints[k].share
+            ));
+        }
+
+        let name = if file.name.is_empty() {
+            path.file_stem()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default()
+        } else {
+            file.name
+        };
+
+        let keys = file
+            .keypoints
+            .iter()
+            .map(|k| (PathKeypoint::from(&k.key), PathKeypoint::from(&k.control)))
+            .collect::<Vec<_>>();
+        let shares = file.keypoints.iter().map(|k| k.share).collect();
+
+        Ok(CameraPath::from(keys)
+            .set_duration(file.duration)
+            .set_shares(shares)
+            .set_name(name))
+    }
+    // ------------------------------------------------------------------------
+    /// saves path into the file it was loaded from. the asset is reloaded
+    /// automatically by the asset server.
+    pub fn save(&self) -> Result<(), String> {
+        let filepath = self
+            .filepath
+            .as_ref()
+            .ok_or_else(|| format!("camera path {} was not loaded from file", self.name))?;
+
+        let file = CameraPathFile {
+            name: self.name.clone(),
+            duration: self.duration,
+            keypoints: self
+                .keypoints
+                .iter()
+                .zip(self.control_points.iter())
+                .zip(self.shares.iter())
+                .map(|((key, control), share)| KeypointDefinition {
+                    key: PointDefinition::from(key),
+                    control: PointDefinition::from(control),
+                    share: *share,
+                })
+                .collect(),
+        };
+
+        let content = ron::ser::to_string_pretty(&file, ron::ser::PrettyConfig::default())
+            .map_err(|e| format!("failed to serialize camera path {}: {}", self.name, e))?;
+
+        std::fs::write(filepath, content)
+            .map_err(|e| format!("failed to write camera path {}: {}", filepath, e))
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+fn default_share() -> f32 {
+    1.0
+}
+// ----------------------------------------------------------------------------
+// converter
+// ----------------------------------------------------------------------------
+impl<'a> From<&'a PointDefinition> for PathKeypoint {
+    fn from(p: &'a PointDefinition) -> Self {
+        PathKeypoint::new(p.pos, (p.pitch, p.yaw))
+    }
+}
+// ----------------------------------------------------------------------------
+impl<'a> From<&'a PathKeypoint> for PointDefinition {
+    fn from(k: &'a PathKeypoint) -> Self {
+        Self {
+            pos: (k.pos.x, k.pos.y, k.pos.z),
+            pitch: k.rot.pitch,
+            yaw: k.rot.yaw,
+        }
+    }
+}
+// ----------------------------------------------------------------------------
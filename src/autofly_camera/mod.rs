@@ -1,9 +1,11 @@
 // ----------------------------------------------------------------------------
 use bevy::{
+    asset::HandleId,
     core::Time,
     ecs::schedule::StateData,
     math::{Quat, Vec3},
     prelude::*,
+    reflect::TypeUuid,
     render::camera::Camera3d,
 };
 
@@ -12,32 +14,72 @@ use self::{
     visualization::VisualizedPathInterpolation,
 };
 // ----------------------------------------------------------------------------
+mod editing;
 mod interpolation;
+mod loader;
+mod recording;
+mod rendering;
 mod shapes;
 mod visualization;
 // ----------------------------------------------------------------------------
+pub use self::editing::CameraPathEditor;
+pub use self::recording::{CameraPathRecorder, RecordingMode};
+pub use self::rendering::FrameSequenceRenderer;
+// ----------------------------------------------------------------------------
 pub struct AutoFlyCameraPlugin;
 // ----------------------------------------------------------------------------
+pub type CameraPathId = HandleId;
+// ----------------------------------------------------------------------------
 pub struct CameraPathsCollection {
-    selected: usize,
-    paths: Vec<CameraPath>,
+    selected: Option<CameraPathId>,
+    /// handles of all camera path assets in the campaths folder (keeps them
+    /// loaded and hot reloadable)
+    paths: Vec<HandleUntyped>,
     default_path: CameraPath,
 }
 // ----------------------------------------------------------------------------
 impl AutoFlyCameraPlugin {
+    // ------------------------------------------------------------------------
+    pub fn startup() -> SystemSet {
+        SystemSet::new().with_system(load_camera_paths)
+    }
     // ------------------------------------------------------------------------
     pub fn setup_autofly_path<T: StateData>(state: T) -> SystemSet {
-        SystemSet::on_enter(state).with_system(setup_flypath)
+        SystemSet::on_enter(state)
+            .with_system(setup_flypath.label("setup_flypath"))
+            .with_system(rendering::start_rendering.after("setup_flypath"))
     }
     // ------------------------------------------------------------------------
     pub fn active_autofly_camera<T: StateData>(state: T) -> SystemSet {
         SystemSet::on_update(state)
             .with_system(autofly_cam)
             .with_system(autofly_hotkeys)
+            // camera must be positioned before clipmaps are updated
+            .with_system(rendering::render_frame_sequence.before("update_clipmaps"))
     }
     // ------------------------------------------------------------------------
     pub fn stop_auto_fly<T: StateData>(state: T) -> SystemSet {
-        SystemSet::on_exit(state).with_system(stop_autofly)
+        SystemSet::on_exit(state)
+            .with_system(stop_autofly)
+            .with_system(rendering::stop_rendering)
+    }
+    // ------------------------------------------------------------------------
+    pub fn record_camera_path<T: StateData>(state: T) -> SystemSet {
+        SystemSet::on_update(state).with_system(recording::record_camera_path)
+    }
+    // ------------------------------------------------------------------------
+    pub fn stop_recording<T: StateData>(state: T) -> SystemSet {
+        SystemSet::on_exit(state).with_system(recording::stop_recording)
+    }
+    // ------------------------------------------------------------------------
+    pub fn edit_camera_path<T: StateData>(state: T) -> SystemSet {
+        SystemSet::on_update(state)
+            .with_system(editing::edit_camera_path.label("campath_editing"))
+            .with_system(editing::update_edited_path_visualization.after("campath_editing"))
+    }
+    // ------------------------------------------------------------------------
+    pub fn stop_path_editing<T: StateData>(state: T) -> SystemSet {
+        SystemSet::on_exit(state).with_system(editing::stop_editing)
     }
     // ------------------------------------------------------------------------
 }
@@ -49,6 +91,11 @@ impl Plugin for AutoFlyCameraPlugin {
             .init_resource::<AutoFlyCameraPath>()
             .init_resource::<CameraPath>()
             .init_resource::<PathInterpolation>()
+            .init_resource::<CameraPathRecorder>()
+            .init_resource::<CameraPathEditor>()
+            .init_resource::<FrameSequenceRenderer>()
+            .add_asset::<CameraPath>()
+            .init_asset_loader::<loader::CameraPathLoader>()
             .add_plugin(visualization::CameraPathVisualizationPlugin);
     }
     // ------------------------------------------------------------------------
@@ -56,27 +103,67 @@ impl Plugin for AutoFlyCameraPlugin {
 // ----------------------------------------------------------------------------
 impl CameraPathsCollection {
     // ------------------------------------------------------------------------
-    pub fn select(&mut self, id: usize) {
-        self.selected = id;
+    pub fn select(&mut self, id: CameraPathId) {
+        self.selected = Some(id);
+    }
+    // ------------------------------------------------------------------------
+    pub fn selected(&self) -> Option<CameraPathId> {
+        self.selected
+    }
+    // ------------------------------------------------------------------------
+    /// all successfully loaded camera paths
+    pub fn loaded<'a>(
+        &'a self,
+        paths: &'a Assets<CameraPath>,
+    ) -> impl Iterator<Item = (CameraPathId, &'a CameraPath)> {
+        self.paths
+            .iter()
+            .filter_map(|handle| paths.get(handle.id).map(|path| (handle.id, path)))
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl CameraPath {
+    // ------------------------------------------------------------------------
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    // ------------------------------------------------------------------------
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+    // ------------------------------------------------------------------------
+    pub fn update_duration(&mut self, duration: f32) {
+        self.duration = duration.max(1.0);
+    }
+    // ------------------------------------------------------------------------
+    pub fn keypoint_count(&self) -> usize {
+        self.keypoints.len()
     }
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
 // systems
 // ----------------------------------------------------------------------------
+fn load_camera_paths(asset_server: Res<AssetServer>, mut campaths: ResMut<CameraPathsCollection>) {
+    // Note: changes are picked up automatically as asset server is already
+    // watching for changes (see visualization pipeline setup)
+    match asset_server.load_folder(loader::CAMPATH_FOLDER) {
+        Ok(handles) => campaths.paths = handles,
+        Err(e) => error!("failed to load camera paths: {:?}", e),
+    }
+}
+// ----------------------------------------------------------------------------
 fn setup_flypath(
     time: Res<Time>,
     campaths: Res<CameraPathsCollection>,
+    paths: Res<Assets<CameraPath>>,
     query: Query<&Transform, With<Camera3d>>,
     mut autofly: ResMut<AutoFlyCameraPath>,
 ) {
     let cam = query.get_single().expect("Camera3d missing");
 
-    autofly.set_path_from_current_cam(
-        &*time,
-        (cam.translation, cam.rotation),
-        campaths.get(campaths.selected()),
-    );
+    autofly.set_path_from_current_cam(&time, (cam.translation, cam.rotation), campaths.get(&paths));
 }
 // ----------------------------------------------------------------------------
 fn stop_autofly(
@@ -147,11 +234,18 @@ struct PathKeypoint {
     rot: CameraRotation,
 }
 // ----------------------------------------------------------------------------
-#[derive(Default, Clone)]
-struct CameraPath {
+#[derive(Default, Clone, TypeUuid)]
+#[uuid = "5c8a3b0e-2f7d-4e61-9d7b-0a6f1c2e8b43"]
+pub struct CameraPath {
+    name: String,
+    /// file the path was loaded from (relative to working directory)
+    filepath: Option<String>,
     duration: f32,
     keypoints: Vec<PathKeypoint>,
     control_points: Vec<PathKeypoint>,
+    /// relative duration of the segment leading to the keypoint. share of the
+    /// first keypoint is used for the approach from the current camera position
+    shares: Vec<f32>,
 }
 // ----------------------------------------------------------------------------
 trait InterpolatedPath {
@@ -162,13 +256,16 @@ trait InterpolatedPath {
 // ----------------------------------------------------------------------------
 impl CameraPathsCollection {
     // ------------------------------------------------------------------------
-    fn get(&self, id: usize) -> &CameraPath {
-        // TODO as hash/asset + handle
-        self.paths.get(id).unwrap_or(&self.default_path)
+    fn add(&mut self, handle: HandleUntyped) {
+        self.paths.push(handle);
     }
     // ------------------------------------------------------------------------
-    fn selected(&self) -> usize {
+    /// selected path or default path if selected path is not (or no longer)
+    /// loaded
+    fn get<'a>(&'a self, paths: &'a Assets<CameraPath>) -> &'a CameraPath {
         self.selected
+            .and_then(|id| paths.get(id))
+            .unwrap_or(&self.default_path)
     }
     // ------------------------------------------------------------------------
 }
@@ -180,32 +277,47 @@ impl CameraPath {
         self
     }
     // ------------------------------------------------------------------------
+    fn set_name(mut self, name: String) -> Self {
+        self.name = name;
+        self
+    }
+    // ------------------------------------------------------------------------
+    fn set_filepath(mut self, filepath: String) -> Self {
+        self.filepath = Some(filepath);
+        self
+    }
+    // ------------------------------------------------------------------------
+    fn set_shares(mut self, shares: Vec<f32>) -> Self {
+        self.shares = shares;
+        self
+    }
+    // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
-impl<'a> InterpolatedPath for &'a CameraPath {
+impl InterpolatedPath for &CameraPath {
     // ------------------------------------------------------------------------
     fn duration(&self) -> f32 {
         self.duration
     }
     // ------------------------------------------------------------------------
     fn interpolation(&self) -> PathInterpolation {
-        let k = self.keypoints.len();
+        let times = normalized_times(&self.shares[1..]);
 
         let keypoints = self
             .keypoints
             .iter()
             .zip(self.control_points.iter())
-            .enumerate()
-            .map(|(i, (key, control))| PathCurveEntry::new(i as f32 / (k - 1) as f32, key, control))
+            .zip(times)
+            .map(|((key, control), t)| PathCurveEntry::new(t, key, control))
             .collect::<Vec<_>>();
 
         PathInterpolation::from(keypoints)
     }
     // ------------------------------------------------------------------------
     fn interpolation_with_start(&self, start_pos: Vec3, start_rotation: Quat) -> PathInterpolation {
-        let k = self.keypoints.len() + 1;
+        let times = normalized_times(&self.shares);
 
-        let mut keypoints = Vec::with_capacity(k);
+        let mut keypoints = Vec::with_capacity(times.len());
 
         keypoints.push(PathCurveEntry::new(
             0.0,
@@ -213,17 +325,13 @@ impl<'a> InterpolatedPath for &'a CameraPath {
             &PathKeypoint::from((start_pos, start_rotation)),
         ));
 
-        for (i, (key, control)) in self
+        for ((key, control), t) in self
             .keypoints
             .iter()
             .zip(self.control_points.iter())
-            .enumerate()
+            .zip(times.into_iter().skip(1))
         {
-            keypoints.push(PathCurveEntry::new(
-                (i + 1) as f32 / (k - 1) as f32,
-                key,
-                control,
-            ));
+            keypoints.push(PathCurveEntry::new(t, key, control));
         }
 
         PathInterpolation::from(keypoints)
@@ -231,6 +339,20 @@ impl<'a> InterpolatedPath for &'a CameraPath {
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
+/// normalized times of keys separated by segments with the given relative
+/// durations (first key is at 0.0)
+fn normalized_times(segment_shares: &[f32]) -> Vec<f32> {
+    let total = segment_shares.iter().sum::<f32>();
+    let mut t = 0.0;
+
+    std::iter::once(0.0)
+        .chain(segment_shares.iter().map(|share| {
+            t += share;
+            t / total
+        }))
+        .collect()
+}
+// ----------------------------------------------------------------------------
 impl AutoFlyCameraPath {
     // ------------------------------------------------------------------------
     fn set_path_from_current_cam<P: InterpolatedPath>(
@@ -262,10 +384,14 @@ impl PathKeypoint {
 // ----------------------------------------------------------------------------
 impl From<Vec<(PathKeypoint, PathKeypoint)>> for CameraPath {
     // ------------------------------------------------------------------------
-    fn from(mut keys: Vec<(PathKeypoint, PathKeypoint)>) -> Self {
-        let (keypoints, control_points) = keys.drain(..).unzip();
+    fn from(keys: Vec<(PathKeypoint, PathKeypoint)>) -> Self {
+        let duration = keys.len() as f32;
+        let (keypoints, control_points): (Vec<_>, Vec<_>) = keys.into_iter().unzip();
         Self {
-            duration: keys.len() as f32,
+            name: String::default(),
+            filepath: None,
+            duration,
+            shares: vec![1.0; keypoints.len()],
             keypoints,
             control_points,
         }
@@ -288,55 +414,8 @@ impl From<(Vec3, Quat)> for PathKeypoint {
 impl Default for CameraPathsCollection {
     fn default() -> Self {
         Self {
-            selected: 0,
-            paths: vec![
-                CameraPath::from(
-                    vec![
-                    // Prolog flyby
-(PathKeypoint::new((255.91779, 10.622536, -76.13155), (-0.10171645, 1.1042833)), PathKeypoint::new((177.8572, 17.39462, -118.93), (0.058383606, 0.78918463))),
-(PathKeypoint::new((-63.736633, 26.932966, -389.8001), (-0.19028237, 1.2149942)), PathKeypoint::new((-209.25946, 33.00782, -544.8217), (-0.13918665, 2.17389))),
-(PathKeypoint::new((-442.76254, 29.65642, -490.2772), (-0.2090176, 3.107241)), PathKeypoint::new((-534.4407, 34.387096, -389.53348), (-0.24648722, 3.125976))),
-(PathKeypoint::new((-300.4297, 46.70152, 409.05606), (-0.15451533, -2.097826)), PathKeypoint::new((-138.41705, 44.351563, 623.7485), (-0.14599925, -1.0282187))),
-(PathKeypoint::new((63.607903, 30.400412, 349.84894), (-0.17665695, -0.27541322)), PathKeypoint::new((85.48069, 21.930632, 192.8483), (-0.16814062, -1.4063339))),
-(PathKeypoint::new((353.05606, 14.783169, 217.19731), (-0.09490356, -1.6992819)), PathKeypoint::new((465.42148, 26.03579, 285.06723), (-0.14088984, -1.0725076))),
-(PathKeypoint::new((504.9525, 18.048256, 235.3283), (-0.13486393, -0.5417764)), PathKeypoint::new((525.9277, 15.619314, 220.25232), (-0.27452505, -0.8296174))),
-                    ]
-                ).set_duration(30.0),
-                CameraPath::from(
-                    vec![
-                    // KM flyby
-(PathKeypoint::new((56.97353, 543.30927, 3241.5125), (-0.11470131, -0.12294897)), PathKeypoint::new((169.61308, 545.84686, 2882.5461), (-0.012974373, 0.123259805))),
-(PathKeypoint::new((220.15457, 276.44788, 1460.1501), (0.16689055, 0.7498375)), PathKeypoint::new((217.09502, 246.26442, 1053.0331), (0.26124647, 1.1950774))),
-(PathKeypoint::new((32.115387, 170.37816, 800.5958), (0.20522296, 0.6540091)), PathKeypoint::new((3.7818117, 155.6384, 695.66534), (0.12413589, 0.7350955))),
-(PathKeypoint::new((11.072268, 153.73221, 523.7364), (0.06663814, 1.0078404)), PathKeypoint::new((-23.39103, 158.82796, 459.71487), (-0.2916172, 0.56997275))),
-(PathKeypoint::new((-170.25793, 202.21704, 208.69266), (0.022409113, -0.16717865)), PathKeypoint::new((-325.658, 219.9963, 17.369003), (-0.0041285385, -0.8379863))),
-(PathKeypoint::new((-69.9166, 195.5186, -3.2402902), (-0.28129748, -0.52543527)), PathKeypoint::new((-18.306587, 187.87553, -90.28981), (-0.21348023, 0.4181199))),
-(PathKeypoint::new((48.562737, 64.96414, -467.42825), (0.5620042, -0.058079917)), PathKeypoint::new((84.31649, 67.725204, -795.8491), (0.6268747, 0.9842528))),
-(PathKeypoint::new((-155.89055, 222.1669, -1092.7212), (0.49271277, 2.1622238)), PathKeypoint::new((-509.76254, 225.56508, -1075.3801), (0.36444816, 2.2816348))),
-(PathKeypoint::new((-349.52988, 198.44754, -512.10626), (-0.66461676, -1.8829784)), PathKeypoint::new((-123.21274, 38.767982, -488.5217), (-0.66461676, -1.8829784))),
-(PathKeypoint::new((7.9420624, 49.093983, -351.64032), (0.42784193, 3.005515)), PathKeypoint::new((-23.827808, 98.07367, -241.1435), (0.42784193, 3.005515))),
-(PathKeypoint::new((-188.58658, 209.00455, -121.90019), (0.37034306, 1.9499166)), PathKeypoint::new((-300.6122, 223.99196, 55.972622), (0.37034306, 1.9499166))),
-(PathKeypoint::new((-1030.9341, 510.7023, 393.7667), (-0.20905852, -2.920887)), PathKeypoint::new((-1007.3239, 510.7023, 501.26663), (-0.23264776, -1.8638119))),
-(PathKeypoint::new((-479.86066, 510.7023, 1148.5018), (-0.14124133, -0.8539164)), PathKeypoint::new((-314.99176, 510.7023, 1169.6135), (-0.12649775, -0.82442635))),
-(PathKeypoint::new((-215.36928, 481.96423, 1021.52295), (0.032392498, -0.08232771)), PathKeypoint::new((-215.36928, 481.96423, 1021.52295), (0.032392498, -0.08232771))),
-                    ]
-                ).set_duration(90.0),
-                CameraPath::from(
-                    vec![
-                    // Bevy
-(PathKeypoint::new((-315.72623, 103.768196, 401.8794), (-0.024039412, 0.49915105)), PathKeypoint::new((-321.66617, 106.56419, 363.94183), (0.039720524, 0.69609165))),
-(PathKeypoint::new((-393.63055, 130.72829, 292.78183), (-0.7115325, 1.700571)), PathKeypoint::new((-450.47342, 148.64452, 274.5253), (-0.6968611, 2.8029675))),
-(PathKeypoint::new((-547.3651, 151.24557, 395.5353), (-1.0848844, 2.7887573)), PathKeypoint::new((-563.7202, 80.82793, 452.24472), (-1.1973894, -3.1088214))),
-(PathKeypoint::new((-463.08203, 63.530838, 721.3241), (-0.41081488, -1.7832925)), PathKeypoint::new((-306.60126, 52.75315, 832.41876), (-0.20119968, -0.96219665))),
-(PathKeypoint::new((392.0325, 74.21312, 810.4432), (-0.32456416, -0.65305936)), PathKeypoint::new((655.2356, 74.94869, 665.02594), (-0.41034648, 0.06713191))),
-(PathKeypoint::new((697.6477, 65.35509, 53.788902), (-0.05940168, 0.37698948)), PathKeypoint::new((552.3927, 106.16961, -317.3467), (-0.029147582, 0.71782935))),
-(PathKeypoint::new((336.09164, 123.110756, -467.18527), (-0.23881307, 2.3443465)), PathKeypoint::new((163.87727, 133.2367, -554.20874), (-0.34135228, 2.4096048))),
-(PathKeypoint::new((-103.40079, 213.61072, -338.593), (-0.273216, -3.3245919)), PathKeypoint::new((-104.44727, 327.18008, -103.88107), (-0.6285311, -3.9323177))),
-(PathKeypoint::new((-537.7476, 562.15344, 271.05557), (-1.0217335, -1.345653)), PathKeypoint::new((-400.95953, 793.764, 485.98248), (-0.84882593, -0.8386505))),
-(PathKeypoint::new((477.61966, 1592.9587, 746.37695), (-1.0656929, 0.5841813)), PathKeypoint::new((501.53583, 1563.6324, 725.32214), (-1.0656929, 0.5841813))),
-                    ]
-                ).set_duration(35.0)
-            ],
+            selected: None,
+            paths: Vec::new(),
             default_path:
                 CameraPath::from(
                     vec![
@@ -0,0 +1,259 @@
+// ----------------------------------------------------------------------------
+// recording of camera paths from free cam flights
+// ----------------------------------------------------------------------------
+use std::f32::consts::PI;
+use std::path::Path;
+
+use bevy::{core::Time, prelude::*, render::camera::Camera3d};
+
+use super::loader::{CAMPATH_ASSET_ROOT, CAMPATH_EXTENSION, CAMPATH_FOLDER};
+use super::visualization::VisualizedPathInterpolation;
+use super::{CameraPath, CameraPathsCollection, InterpolatedPath, PathKeypoint};
+// ----------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RecordingMode {
+    /// samples camera every n seconds
+    Interval(f32),
+    /// samples camera on keypress
+    Keypress,
+}
+// ----------------------------------------------------------------------------
+pub struct CameraPathRecorder {
+    mode: RecordingMode,
+    active: bool,
+    last_sample: f64,
+    samples: Vec<(f64, PathKeypoint)>,
+    /// last recorded path (for visualization)
+    recorded: Option<CameraPath>,
+}
+// ----------------------------------------------------------------------------
+impl CameraPathRecorder {
+    // ------------------------------------------------------------------------
+    pub fn mode(&self) -> RecordingMode {
+        self.mode
+    }
+    // ------------------------------------------------------------------------
+    pub fn set_mode(&mut self, mode: RecordingMode) {
+        self.mode = match mode {
+            RecordingMode::Interval(seconds) => RecordingMode::Interval(seconds.max(0.1)),
+            RecordingMode::Keypress => RecordingMode::Keypress,
+        };
+    }
+    // ------------------------------------------------------------------------
+    pub fn is_recording(&self) -> bool {
+        self.active
+    }
+    // ------------------------------------------------------------------------
+    pub fn sample_count(&self) -> usize {
+        self.samples.len()
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+// systems
+// ----------------------------------------------------------------------------
+#[allow(clippy::too_many_arguments)]
+pub(super) fn record_camera_path(
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    asset_server: Res<AssetServer>,
+    query: Query<&Transform, With<Camera3d>>,
+    mut recorder: ResMut<CameraPathRecorder>,
+    mut campaths: ResMut<CameraPathsCollection>,
+    mut paths: ResMut<Assets<CameraPath>>,
+    mut visualization: ResMut<VisualizedPathInterpolation>,
+) {
+    let cam = match query.get_single() {
+        Ok(cam) => PathKeypoint::from((cam.translation, cam.rotation)),
+        Err(_) => return,
+    };
+    let now = time.seconds_since_startup();
+
+    for key in keys.get_just_pressed() {
+        match key {
+            KeyCode::R if recorder.active => {
+                recorder.add_sample(now, cam);
+                finish_recording(&mut recorder, &asset_server, &mut campaths, &mut paths);
+                if let Some(path) = recorder.recorded.as_ref() {
+                    visualization.set(path.interpolation());
+                }
+            }
+            KeyCode::R => {
+                info!("camera path recording started ({:?})", recorder.mode);
+                recorder.active = true;
+                recorder.samples.clear();
+                recorder.add_sample(now, cam);
+            }
+            KeyCode::Space if recorder.active && recorder.mode == RecordingMode::Keypress => {
+                recorder.add_sample(now, cam);
+            }
+            KeyCode::V => {
+                if visualization.as_ref().is_active() {
+                    visualization.remove();
+                } else if let Some(path) = recorder.recorded.as_ref() {
+                    visualization.set(path.interpolation());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if let RecordingMode::Interval(interval) = recorder.mode {
+        if recorder.active && now - recorder.last_sample >= interval as f64 {
+            recorder.add_sample(now, cam);
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+/// finishes an active recording if free cam is left
+pub(super) fn stop_recording(
+    asset_server: Res<AssetServer>,
+    mut recorder: ResMut<CameraPathRecorder>,
+    mut campaths: ResMut<CameraPathsCollection>,
+    mut paths: ResMut<Assets<CameraPath>>,
+    mut visualization: ResMut<VisualizedPathInterpolation>,
+) {
+    if recorder.as_ref().active {
+        finish_recording(&mut recorder, &asset_server, &mut campaths, &mut paths);
+    }
+    if visualization.as_ref().is_active() {
+        visualization.remove();
+    }
+}
+// ----------------------------------------------------------------------------
+// helper
+// ----------------------------------------------------------------------------
+impl CameraPathRecorder {
+    // ------------------------------------------------------------------------
+    fn add_sample(&mut self, time: f64, key: PathKeypoint) {
+        // ignore repeated keypresses without any camera movement
+        let unchanged = self
+            .samples
+            .last()
+            .is_some_and(|(_, last)| last.pos.distance(key.pos) < 0.01 && last.rot == key.rot);
+        if !unchanged || self.mode != RecordingMode::Keypress {
+            self.samples.push((time, key));
+        }
+        self.last_sample = time;
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+fn finish_recording(
+    recorder: &mut CameraPathRecorder,
+    asset_server: &AssetServer,
+    campaths: &mut CameraPathsCollection,
+    paths: &mut Assets<CameraPath>,
+) {
+    recorder.active = false;
+
+    let samples = std::mem::take(&mut recorder.samples);
+    if samples.len() < 2 {
+        warn!("camera path recording stopped: at least 2 keypoints are required");
+        return;
+    }
+
+    let duration = (samples[samples.len() - 1].0 - samples[0].0) as f32;
+    // segment shares follow the recorded timing, approach to the first key
+    // gets an average segment share
+    let mut shares = samples
+        .windows(2)
+        .map(|w| ((w[1].0 - w[0].0) as f32).max(0.01))
+        .collect::<Vec<_>>();
+    shares.insert(0, shares.iter().sum::<f32>() / shares.len() as f32);
+
+    let keys = samples.into_iter().map(|(_, key)| key).collect::<Vec<_>>();
+
+    let (name, asset_path) = next_recording_name();
+    let filepath = Path::new(CAMPATH_ASSET_ROOT).join(&asset_path);
+
+    let path = CameraPath::from(fit_control_points(&keys))
+        .set_duration(duration.max(1.0))
+        .set_shares(shares)
+        .set_name(name)
+        .set_filepath(filepath.to_string_lossy().to_string());
+
+    info!(
+        "recorded camera path {} with {} keypoints ({:.1}s)",
+        path.name(),
+        path.keypoint_count(),
+        path.duration()
+    );
+
+    // saved paths are loaded by the asset server to get hot reloading for
+    // further changes
+    let handle = match path.save() {
+        Ok(_) => asset_server.load::<CameraPath, _>(asset_path.as_str()),
+        Err(e) => {
+            error!("{}", e);
+            paths.add(path.clone())
+        }
+    };
+    campaths.select(handle.id);
+    campaths.add(handle.clone_untyped());
+
+    recorder.recorded = Some(path);
+}
+// ----------------------------------------------------------------------------
+/// returns name and asset path of first unused recording filename
+fn next_recording_name() -> (String, String) {
+    (1..)
+        .map(|i| {
+            (
+                format!("recorded {:02}", i),
+                format!("{}/recorded_{:02}.{}", CAMPATH_FOLDER, i, CAMPATH_EXTENSION),
+            )
+        })
+        .find(|(_, asset_path)| !Path::new(CAMPATH_ASSET_ROOT).join(asset_path).exists())
+        .unwrap()
+}
+// ----------------------------------------------------------------------------
+/// control points for a smooth path through all keys (catmull-rom tangents
+/// converted to bezier control points, one sided tangents at both ends)
+fn fit_control_points(keys: &[PathKeypoint]) -> Vec<(PathKeypoint, PathKeypoint)> {
+    let last = keys.len() - 1;
+
+    keys.iter()
+        .enumerate()
+        .map(|(i, key)| {
+            let prev = &keys[i.saturating_sub(1)];
+            let next = &keys[(i + 1).min(last)];
+            let scale = if i == 0 || i == last {
+                1.0 / 3.0
+            } else {
+                1.0 / 6.0
+            };
+
+            let pos = key.pos + (next.pos - prev.pos) * scale;
+            let pitch =
+                (key.rot.pitch + (next.rot.pitch - prev.rot.pitch) * scale).clamp(-1.54, 1.54);
+            // yaw deltas must take the shorter direction
+            let yaw_delta = wrapped_angle(next.rot.yaw - key.rot.yaw)
+                + wrapped_angle(key.rot.yaw - prev.rot.yaw);
+            let yaw = wrapped_angle(key.rot.yaw + yaw_delta * scale);
+
+            (*key, PathKeypoint::new(pos, (pitch, yaw)))
+        })
+        .collect()
+}
+// ----------------------------------------------------------------------------
+/// maps angle into [-PI..PI]
+#[inline]
+fn wrapped_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(2.0 * PI) - PI
+}
+// ----------------------------------------------------------------------------
+// default impl
+// ----------------------------------------------------------------------------
+impl Default for CameraPathRecorder {
+    fn default() -> Self {
+        Self {
+            mode: RecordingMode::Interval(2.0),
+            active: false,
+            last_sample: 0.0,
+            samples: Vec::new(),
+            recorded: None,
+        }
+    }
+}
+// ----------------------------------------------------------------------------
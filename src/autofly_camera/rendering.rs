@@ -0,0 +1,352 @@
+// ----------------------------------------------------------------------------
+// offline rendering of camera paths into numbered png frame sequences
+// ----------------------------------------------------------------------------
+use std::path::{Path, PathBuf};
+
+use bevy::{prelude::*, render::camera::Camera3d};
+
+use crate::cmds::TrackedProgress;
+use crate::environment::DayNightCycle;
+use crate::terrain_render::{
+    CapturedFrame, CapturedFrameReceiver, FrameCaptureRequest, TerrainRenderSettings,
+    TerrainShadowsUpdateTracker,
+};
+use crate::terrain_tiles::TerrainStats;
+use crate::{EditorEvent, EditorState};
+
+use super::interpolation::PathInterpolation;
+use super::visualization::VisualizedPathInterpolation;
+use super::{AutoFlyCameraPath, CameraPath, CameraPathsCollection, InterpolatedPath};
+// ----------------------------------------------------------------------------
+const RENDERING_OUTPUT_FOLDER: &str = "renderings";
+/// consecutive frames without any pending terrain updates before a frame is
+/// captured
+const SETTLED_FRAMES: u32 = 3;
+/// max frames to wait for settled terrain updates before capturing anyway
+const MAX_SETTLE_WAIT: u32 = 600;
+// ----------------------------------------------------------------------------
+pub struct FrameSequenceRenderer {
+    fps: u32,
+    /// normalized time of day at start and end of sequence
+    time_of_day: Option<(f32, f32)>,
+    /// rendering was requested for the next autofly
+    requested: bool,
+    rendering: Option<FrameSequence>,
+}
+// ----------------------------------------------------------------------------
+impl FrameSequenceRenderer {
+    // ------------------------------------------------------------------------
+    pub fn fps(&self) -> u32 {
+        self.fps
+    }
+    // ------------------------------------------------------------------------
+    pub fn set_fps(&mut self, fps: u32) {
+        self.fps = fps.clamp(1, 120);
+    }
+    // ------------------------------------------------------------------------
+    /// normalized time of day at start and end of sequence. None keeps current
+    /// time of day for all frames.
+    pub fn time_of_day_animation(&self) -> Option<(f32, f32)> {
+        self.time_of_day
+    }
+    // ------------------------------------------------------------------------
+    pub fn set_time_of_day_animation(&mut self, time_of_day: Option<(f32, f32)>) {
+        self.time_of_day =
+            time_of_day.map(|(start, end)| (start.rem_euclid(1.0), end.rem_euclid(1.0)));
+    }
+    // ------------------------------------------------------------------------
+    /// renders selected camera path with next autofly
+    pub fn request_rendering(&mut self) {
+        self.requested = true;
+    }
+    // ------------------------------------------------------------------------
+    pub fn is_rendering(&self) -> bool {
+        self.rendering.is_some()
+    }
+    // ------------------------------------------------------------------------
+    /// (rendered frames, frame count) of active rendering
+    pub fn progress(&self) -> Option<(u32, u32)> {
+        self.rendering.as_ref().map(|r| (r.frame, r.frame_count))
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+// systems
+// ----------------------------------------------------------------------------
+#[allow(clippy::too_many_arguments)]
+pub(super) fn start_rendering(
+    campaths: Res<CameraPathsCollection>,
+    paths: Res<Assets<CameraPath>>,
+    mut renderer: ResMut<FrameSequenceRenderer>,
+    mut autofly: ResMut<AutoFlyCameraPath>,
+    mut daynight_cycle: ResMut<DayNightCycle>,
+    mut visualization: ResMut<VisualizedPathInterpolation>,
+    captured_frames: Res<CapturedFrameReceiver>,
+    mut app_state: ResMut<State<EditorState>>,
+    mut editor_events: EventWriter<EditorEvent>,
+) {
+    // Note: as_ref required to prevent setting "changed" flag
+    if !renderer.as_ref().requested {
+        return;
+    }
+    renderer.requested = false;
+
+    // discard leftovers of an aborted rendering
+    while captured_frames.try_recv().is_ok() {}
+
+    let path = campaths.get(&paths);
+    let output_dir = Path::new(RENDERING_OUTPUT_FOLDER).join(output_folder_name(path.name()));
+
+    if let Err(e) = std::fs::create_dir_all(&output_dir) {
+        error!(
+            "failed to create rendering output folder {}: {}",
+            output_dir.display(),
+            e
+        );
+        app_state.overwrite_pop().ok();
+        return;
+    }
+
+    // sequence starts at first keypoint (no approach from current camera)
+    // and includes the last keypoint
+    let frame_count = (path.duration() * renderer.fps as f32).ceil() as u32 + 1;
+
+    info!(
+        "rendering camera path {} into {} ({} frames @ {} fps)",
+        path.name(),
+        output_dir.display(),
+        frame_count,
+        renderer.fps
+    );
+
+    // camera and time of day are controlled by rendering only
+    autofly.active = false;
+    if visualization.as_ref().is_active() {
+        visualization.remove();
+    }
+
+    renderer.rendering = Some(FrameSequence {
+        path: path.interpolation(),
+        output_dir,
+        frame: 0,
+        frame_count,
+        phase: RenderPhase::Positioning,
+        restore_time_of_day: (
+            daynight_cycle.time_of_day().normalized(),
+            daynight_cycle.cycle_active(),
+        ),
+    });
+    daynight_cycle.activate_cycle(false);
+
+    editor_events.send(EditorEvent::ProgressTrackingStart(
+        "Rendering frame sequence".into(),
+        vec![TrackedProgress::RenderedFrameSequence(
+            0,
+            frame_count as usize,
+        )],
+    ));
+}
+// ----------------------------------------------------------------------------
+#[allow(clippy::too_many_arguments)]
+pub(super) fn render_frame_sequence(
+    terrain_stats: Res<TerrainStats>,
+    shadows_tracker: Res<TerrainShadowsUpdateTracker>,
+    render_settings: Res<TerrainRenderSettings>,
+    captured_frames: Res<CapturedFrameReceiver>,
+    mut renderer: ResMut<FrameSequenceRenderer>,
+    mut capture_request: ResMut<FrameCaptureRequest>,
+    mut daynight_cycle: ResMut<DayNightCycle>,
+    mut query: Query<&mut Transform, With<Camera3d>>,
+    mut app_state: ResMut<State<EditorState>>,
+    mut editor_events: EventWriter<EditorEvent>,
+) {
+    // Note: as_ref required to prevent setting "changed" flag
+    if !renderer.as_ref().is_rendering() {
+        return;
+    }
+    let time_of_day = renderer.time_of_day;
+    let sequence = renderer.rendering.as_mut().unwrap();
+
+    match sequence.phase {
+        RenderPhase::Positioning => {
+            let t = sequence.frame as f32 / (sequence.frame_count - 1).max(1) as f32;
+
+            if let Ok(mut cam) = query.get_single_mut() {
+                let (pos, rot) = sequence.path.sample(t);
+                cam.translation = pos;
+                cam.rotation = rot;
+            }
+            if let Some((start, end)) = time_of_day {
+                // animation may continue over midnight
+                let end = if end < start { end + 1.0 } else { end };
+                daynight_cycle.update_time_of_day(start + (end - start) * t);
+            }
+            sequence.phase = RenderPhase::Settling {
+                settled: 0,
+                waited: 0,
+            };
+        }
+        RenderPhase::Settling { settled, waited } => {
+            // clipmaps are updated synchronously, tile meshes and shadows may
+            // take some frames
+            let pending = terrain_stats.has_pending_updates()
+                || (!render_settings.disable_shadows && shadows_tracker.is_recompute_pending());
+
+            let settled = if pending { 0 } else { settled + 1 };
+
+            if settled >= SETTLED_FRAMES || waited >= MAX_SETTLE_WAIT {
+                if waited >= MAX_SETTLE_WAIT {
+                    warn!(
+                        "frame {}: terrain updates did not settle. capturing anyway.",
+                        sequence.frame
+                    );
+                }
+                capture_request.request(sequence.frame);
+                sequence.phase = RenderPhase::Capturing;
+            } else {
+                sequence.phase = RenderPhase::Settling {
+                    settled,
+                    waited: waited + 1,
+                };
+            }
+        }
+        RenderPhase::Capturing => {
+            let captured = std::iter::from_fn(|| captured_frames.try_recv().ok())
+                .find(|captured| captured.frame == sequence.frame);
+
+            let captured = match captured {
+                Some(captured) => captured,
+                None => return,
+            };
+
+            match sequence.save_frame(&captured) {
+                Ok(_) => {
+                    sequence.frame += 1;
+                    editor_events.send(EditorEvent::ProgressTrackingUpdate(
+                        TrackedProgress::RenderedFrameSequence(
+                            sequence.frame as usize,
+                            sequence.frame_count as usize,
+                        ),
+                    ));
+
+                    if sequence.frame < sequence.frame_count {
+                        sequence.phase = RenderPhase::Positioning;
+                        return;
+                    }
+                    info!(
+                        "rendered {} frames into {}",
+                        sequence.frame_count,
+                        sequence.output_dir.display()
+                    );
+                }
+                Err(e) => error!("{}", e),
+            }
+            // finished or failed
+            stop_sequence(&mut renderer, &mut daynight_cycle, &mut editor_events);
+            app_state.overwrite_pop().ok();
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+/// aborts an active rendering if autofly state is left
+pub(super) fn stop_rendering(
+    mut renderer: ResMut<FrameSequenceRenderer>,
+    mut daynight_cycle: ResMut<DayNightCycle>,
+    mut editor_events: EventWriter<EditorEvent>,
+) {
+    renderer.requested = false;
+    if let Some((frame, frame_count)) = renderer.progress() {
+        warn!(
+            "rendering of frame sequence aborted after {}/{} frames",
+            frame, frame_count
+        );
+        stop_sequence(&mut renderer, &mut daynight_cycle, &mut editor_events);
+    }
+}
+// ----------------------------------------------------------------------------
+// internal types
+// ----------------------------------------------------------------------------
+struct FrameSequence {
+    path: PathInterpolation,
+    output_dir: PathBuf,
+    frame: u32,
+    frame_count: u32,
+    phase: RenderPhase,
+    /// time of day and cycle state before rendering started
+    restore_time_of_day: (f32, bool),
+}
+// ----------------------------------------------------------------------------
+#[derive(Clone, Copy)]
+enum RenderPhase {
+    /// camera (and time of day) is moved to the next frame
+    Positioning,
+    /// waiting for terrain updates (tiles, shadows) to settle
+    Settling { settled: u32, waited: u32 },
+    /// waiting for captured frame
+    Capturing,
+}
+// ----------------------------------------------------------------------------
+// helper
+// ----------------------------------------------------------------------------
+impl FrameSequence {
+    // ------------------------------------------------------------------------
+    fn save_frame(&self, captured: &CapturedFrame) -> Result<(), String> {
+        let filepath = self
+            .output_dir
+            .join(format!("frame_{:05}.png", captured.frame));
+
+        image::save_buffer(
+            &filepath,
+            &captured.data,
+            captured.width,
+            captured.height,
+            image::ColorType::Rgba8,
+        )
+        .map_err(|e| format!("failed to save frame {}: {}", filepath.display(), e))
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+fn stop_sequence(
+    renderer: &mut FrameSequenceRenderer,
+    daynight_cycle: &mut DayNightCycle,
+    editor_events: &mut EventWriter<EditorEvent>,
+) {
+    if let Some(sequence) = renderer.rendering.take() {
+        let (time_of_day, cycle_active) = sequence.restore_time_of_day;
+        daynight_cycle.update_time_of_day(time_of_day);
+        daynight_cycle.activate_cycle(cycle_active);
+
+        if sequence.frame < sequence.frame_count {
+            editor_events.send(EditorEvent::ProgressTrackingCancel);
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+/// path name as folder name (only alphanumeric chars)
+fn output_folder_name(name: &str) -> String {
+    let name = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+
+    if name.is_empty() {
+        "campath".to_string()
+    } else {
+        name
+    }
+}
+// ----------------------------------------------------------------------------
+// default impl
+// ----------------------------------------------------------------------------
+impl Default for FrameSequenceRenderer {
+    fn default() -> Self {
+        Self {
+            fps: 30,
+            time_of_day: None,
+            requested: false,
+            rendering: None,
+        }
+    }
+}
+// ----------------------------------------------------------------------------
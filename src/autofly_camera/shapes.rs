@@ -59,3 +59,68 @@ impl Default for CameraVisualization {
     }
 }
 // ----------------------------------------------------------------------------
+/// wireframe unit cube centered at origin
+#[derive(Debug, Copy, Clone)]
+pub struct MarkerVisualization;
+// ----------------------------------------------------------------------------
+impl From<MarkerVisualization> for Mesh {
+    // ------------------------------------------------------------------------
+    #[rustfmt::skip]
+    fn from(_: MarkerVisualization) -> Self {
+        let vertex_count = 8;
+
+        let normals = vec![[0.0, 1.0, 0.0]; vertex_count];
+        let uvs = vec![[1.0, 0.0]; vertex_count];
+
+        let vertices = vec![
+            // bottom
+            [-0.5, -0.5, -0.5],
+            [ 0.5, -0.5, -0.5],
+            [ 0.5, -0.5,  0.5],
+            [-0.5, -0.5,  0.5],
+            // top
+            [-0.5,  0.5, -0.5],
+            [ 0.5,  0.5, -0.5],
+            [ 0.5,  0.5,  0.5],
+            [-0.5,  0.5,  0.5],
+        ];
+
+        let indices = Indices::U16(vec![
+            0, 1, 1, 2, 2, 3, 3, 0,
+            4, 5, 5, 6, 6, 7, 7, 4,
+            0, 4, 1, 5, 2, 6, 3, 7,
+        ]);
+
+        let mut mesh = Mesh::new(PrimitiveTopology::LineList);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertices);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        mesh.set_indices(Some(indices));
+        mesh
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+/// unit line segment from origin along z axis
+#[derive(Debug, Copy, Clone)]
+pub struct LineVisualization;
+// ----------------------------------------------------------------------------
+impl From<LineVisualization> for Mesh {
+    // ------------------------------------------------------------------------
+    fn from(_: LineVisualization) -> Self {
+        let vertex_count = 2;
+
+        let normals = vec![[0.0, 1.0, 0.0]; vertex_count];
+        let uvs = vec![[1.0, 0.0]; vertex_count];
+        let vertices = vec![[0.0, 0.0, 0.0], [0.0, 0.0, 1.0]];
+
+        let mut mesh = Mesh::new(PrimitiveTopology::LineList);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertices);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        mesh.set_indices(Some(Indices::U16(vec![0, 1])));
+        mesh
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
@@ -29,7 +29,19 @@ use super::PathInterpolation;
 pub struct CameraPathVisualizationPlugin;
 // ----------------------------------------------------------------------------
 #[derive(Default)]
-pub(super) struct VisualizedPathInterpolation(Option<PathInterpolation>);
+pub(super) struct VisualizedPathInterpolation {
+    path: Option<PathInterpolation>,
+    gizmos: Option<PathGizmos>,
+}
+// ----------------------------------------------------------------------------
+/// additional markers and lines (e.g. keypoints and control handles)
+#[derive(Default)]
+pub(super) struct PathGizmos {
+    /// position, size, color
+    pub markers: Vec<(Vec3, f32, Color)>,
+    /// start, end, color
+    pub lines: Vec<(Vec3, Vec3, Color)>,
+}
 // ----------------------------------------------------------------------------
 impl Plugin for CameraPathVisualizationPlugin {
     // ------------------------------------------------------------------------
@@ -52,19 +64,27 @@ impl Plugin for CameraPathVisualizationPlugin {
 impl VisualizedPathInterpolation {
     // ------------------------------------------------------------------------
     pub fn is_active(&self) -> bool {
-        self.0.is_some()
+        self.path.is_some()
     }
     // ------------------------------------------------------------------------
     pub fn set(&mut self, interpolation: PathInterpolation) {
-        self.0 = Some(interpolation)
+        self.path = Some(interpolation);
+        self.gizmos = None;
+    }
+    // ------------------------------------------------------------------------
+    pub fn set_with_gizmos(&mut self, interpolation: PathInterpolation, gizmos: PathGizmos) {
+        self.path = Some(interpolation);
+        self.gizmos = Some(gizmos);
     }
     // ------------------------------------------------------------------------
     pub fn remove(&mut self) {
-        self.0 = None;
+        self.path = None;
+        self.gizmos = None;
     }
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
+/// instance data for all visualization meshes (arrows, markers and lines)
 #[derive(Component, Deref)]
 struct PathVisualizationArrowData(Vec<InstanceData>);
 // ----------------------------------------------------------------------------
@@ -90,6 +110,16 @@ struct InstanceData {
 #[derive(Default)]
 struct PathVisualization {
     path: Option<Entity>,
+    gizmos: Vec<Entity>,
+    /// meshes are reused as gizmos are respawned on every change while editing
+    meshes: Option<VisualizationMeshes>,
+}
+// ----------------------------------------------------------------------------
+#[derive(Clone)]
+struct VisualizationMeshes {
+    arrow: Handle<Mesh>,
+    marker: Handle<Mesh>,
+    line: Handle<Mesh>,
 }
 // ----------------------------------------------------------------------------
 // systems
@@ -101,12 +131,23 @@ fn respawn_path(
     mut visualization: ResMut<PathVisualization>,
 ) {
     if path_interpolation.is_changed() {
-        if let Some(visu_entity) = visualization.path {
+        let visu_meshes = visualization
+            .meshes
+            .get_or_insert_with(|| VisualizationMeshes {
+                arrow: meshes.add(Mesh::from(shapes::CameraVisualization::new(10.0))),
+                marker: meshes.add(Mesh::from(shapes::MarkerVisualization)),
+                line: meshes.add(Mesh::from(shapes::LineVisualization)),
+            })
+            .clone();
+
+        if let Some(visu_entity) = visualization.path.take() {
+            commands.entity(visu_entity).despawn();
+        }
+        for visu_entity in visualization.gizmos.drain(..) {
             commands.entity(visu_entity).despawn();
-            visualization.path = None;
         }
 
-        if let Some(path_interpolation) = path_interpolation.0.as_ref() {
+        if let Some(path_interpolation) = path_interpolation.path.as_ref() {
             let arrows = 20 * path_interpolation.keypoint_count();
 
             let instance_data = (0..arrows)
@@ -117,31 +158,71 @@ fn respawn_path(
                 })
                 .collect();
 
-            let id = commands
-                .spawn()
-                .insert_bundle((
-                    meshes.add(Mesh::from(shapes::CameraVisualization::new(10.0))),
-                    Transform::from_xyz(0.0, 0.0, 0.0),
-                    GlobalTransform::default(),
-                    PathVisualizationArrowData(instance_data),
-                    Visibility::default(),
-                    ComputedVisibility::default(),
-                    // NOTE: Frustum culling is done based on the Aabb of the Mesh and the GlobalTransform.
-                    // As the cube is at the origin, if its Aabb moves outside the view frustum, all the
-                    // instanced cubes will be culled.
-                    // The InstanceMaterialData contains the 'GlobalTransform' information for this custom
-                    // instancing, and that is not taken into account with the built-in frustum culling.
-                    // We must disable the built-in frustum culling by adding the `NoFrustumCulling` marker
-                    // component to avoid incorrect culling.
-                    NoFrustumCulling,
-                ))
-                .id();
+            visualization.path = Some(spawn_instanced(
+                &mut commands,
+                visu_meshes.arrow,
+                instance_data,
+            ));
+        }
+
+        if let Some(gizmos) = path_interpolation.gizmos.as_ref() {
+            let markers = gizmos
+                .markers
+                .iter()
+                .map(|(pos, size, color)| {
+                    InstanceData::from((*pos, Quat::IDENTITY, Vec3::splat(*size), *color))
+                })
+                .collect::<Vec<_>>();
+
+            // line mesh is a unit segment along z which is scaled and rotated to
+            // span start and end
+            let lines = gizmos
+                .lines
+                .iter()
+                .filter(|(start, end, _)| start.distance(*end) > 0.001)
+                .map(|(start, end, color)| {
+                    let dir = *end - *start;
+                    let rot = Quat::from_rotation_arc(Vec3::Z, dir.normalize());
+                    InstanceData::from((*start, rot, Vec3::new(1.0, 1.0, dir.length()), *color))
+                })
+                .collect::<Vec<_>>();
 
-            visualization.path = Some(id);
+            // empty instance buffers are not allowed
+            visualization.gizmos = [(visu_meshes.marker, markers), (visu_meshes.line, lines)]
+                .into_iter()
+                .filter(|(_, instance_data)| !instance_data.is_empty())
+                .map(|(mesh, instance_data)| spawn_instanced(&mut commands, mesh, instance_data))
+                .collect();
         }
     }
 }
 // ----------------------------------------------------------------------------
+fn spawn_instanced(
+    commands: &mut Commands,
+    mesh: Handle<Mesh>,
+    instance_data: Vec<InstanceData>,
+) -> Entity {
+    commands
+        .spawn()
+        .insert_bundle((
+            mesh,
+            Transform::from_xyz(0.0, 0.0, 0.0),
+            GlobalTransform::default(),
+            PathVisualizationArrowData(instance_data),
+            Visibility::default(),
+            ComputedVisibility::default(),
+            // NOTE: Frustum culling is done based on the Aabb of the Mesh and the GlobalTransform.
+            // As the cube is at the origin, if its Aabb moves outside the view frustum, all the
+            // instanced cubes will be culled.
+            // The InstanceMaterialData contains the 'GlobalTransform' information for this custom
+            // instancing, and that is not taken into account with the built-in frustum culling.
+            // We must disable the built-in frustum culling by adding the `NoFrustumCulling` marker
+            // component to avoid incorrect culling.
+            NoFrustumCulling,
+        ))
+        .id()
+}
+// ----------------------------------------------------------------------------
 #[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn queue_path_visualization(
     transparent_3d_draw_functions: Res<DrawFunctions<Opaque3d>>,
@@ -354,3 +435,18 @@ impl From<(Vec3, Quat, Color)> for InstanceData {
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
+impl From<(Vec3, Quat, Vec3, Color)> for InstanceData {
+    // ------------------------------------------------------------------------
+    fn from((pos, rot, scale, color): (Vec3, Quat, Vec3, Color)) -> Self {
+        let mat = Mat4::from_scale_rotation_translation(scale, rot, pos);
+        InstanceData {
+            c1: mat.x_axis,
+            c2: mat.y_axis,
+            c3: mat.z_axis,
+            c4: mat.w_axis,
+            color: color.as_rgba_f32(),
+        }
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
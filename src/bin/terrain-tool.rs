@@ -0,0 +1,218 @@
+// ----------------------------------------------------------------------------
+#![forbid(unsafe_code)]
+
+// command line tool for validating and converting terrain data. does not
+// require a window or gpu.
+use std::path::Path;
+use std::process::exit;
+
+use terrain_editor::{MaterialSlot, TerrainConfig, TerrainDocument, TileExportSettings};
+// ----------------------------------------------------------------------------
+const USAGE: &str = "usage: terrain-tool <command> <project file> [args]
+
+commands:
+  validate <project>                     checks config, formats and value ranges of all maps
+  stats <project>                        prints material usage and height range
+  pack <project> <controlmap.png>        packs texturing maps into one 16bit control map png
+  unpack <project> <controlmap.png>      splits 16bit control map png into the texturing maps
+  resample <project> <size> <directory>  resamples all maps to map size and saves a copy of
+                                         project and maps into directory
+  export-tiles <project> <directory> [maps]
+                                         exports maps as per tile buffers of the game (tiles
+                                         per edge of the project). maps: comma separated list
+                                         of height, control, color (default: all)";
+// ----------------------------------------------------------------------------
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+    let result = match args.as_slice() {
+        ["validate", project] => validate(project),
+        ["stats", project] => stats(project),
+        ["pack", project, controlmap] => pack(project, controlmap),
+        ["unpack", project, controlmap] => unpack(project, controlmap),
+        ["resample", project, size, directory] => resample(project, size, directory),
+        ["export-tiles", project, directory] => export_tiles(project, directory, None),
+        ["export-tiles", project, directory, maps] => export_tiles(project, directory, Some(maps)),
+        _ => {
+            eprintln!("{}", USAGE);
+            exit(2);
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        exit(1);
+    }
+}
+// ----------------------------------------------------------------------------
+// commands
+// ----------------------------------------------------------------------------
+fn validate(project: &str) -> Result<(), String> {
+    // config problems are reported together with the problems of the maps
+    let config = TerrainConfig::load_unvalidated(project)?;
+
+    match TerrainDocument::validate(&config) {
+        Ok(_) => {
+            println!("{}: ok", project);
+            Ok(())
+        }
+        Err(errors) => {
+            for e in &errors {
+                println!("{}", e);
+            }
+            Err(format!("{}: found {} problem(s)", project, errors.len()))
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+fn stats(project: &str) -> Result<(), String> {
+    let doc = TerrainDocument::open(TerrainConfig::load(project)?)?;
+    let config = doc.config();
+
+    println!(
+        "terrain {}: map size {} ({} tiles), terrain size {} m",
+        config.name(),
+        config.map_size(),
+        config.tile_count(),
+        config.terrain_size()
+    );
+
+    let (min, max) = doc.height_range();
+    println!(
+        "height range: {:.2} .. {:.2} m (terrain height range: {:.2} .. {:.2} m)",
+        min,
+        max,
+        config.min_height(),
+        config.max_height()
+    );
+
+    let usage = doc.material_usage();
+    let datapoints = (config.map_size() * config.map_size()) as f32;
+    let percent = |count: usize| 100.0 * count as f32 / datapoints;
+
+    println!("material usage (overlay / background):");
+    for (i, (overlay, background)) in usage
+        .overlay
+        .iter()
+        .zip(usage.background.iter())
+        .enumerate()
+        .filter(|(_, (o, b))| **o > 0 || **b > 0)
+    {
+        let name = if i == 0 {
+            "holes".to_string()
+        } else {
+            format!("material {}", MaterialSlot::from(i as u8 - 1))
+        };
+        println!(
+            "  {:<12} {:>6.2}% / {:>6.2}%",
+            name,
+            percent(*overlay),
+            percent(*background)
+        );
+    }
+
+    let histogram = |counts: &[usize]| {
+        counts
+            .iter()
+            .map(|count| format!("{:.1}%", percent(*count)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    println!(
+        "blend threshold 0..7: {}",
+        histogram(&usage.blend_threshold)
+    );
+    println!("uv scaling 0..7:      {}", histogram(&usage.uv_scaling));
+    Ok(())
+}
+// ----------------------------------------------------------------------------
+fn pack(project: &str, controlmap: &str) -> Result<(), String> {
+    let doc = TerrainDocument::open(TerrainConfig::load(project)?)?;
+
+    doc.export_control_map(controlmap)?;
+    println!("saved {}", controlmap);
+    Ok(())
+}
+// ----------------------------------------------------------------------------
+fn unpack(project: &str, controlmap: &str) -> Result<(), String> {
+    let mut doc = TerrainDocument::open(TerrainConfig::load(project)?)?;
+
+    doc.import_control_map(controlmap)?;
+    doc.save()?;
+
+    let texturing = doc.config().texturemaps();
+    println!(
+        "saved {}, {}, {}",
+        texturing.background(),
+        texturing.overlay(),
+        texturing.blendcontrol()
+    );
+    Ok(())
+}
+// ----------------------------------------------------------------------------
+fn resample(project: &str, size: &str, directory: &str) -> Result<(), String> {
+    let map_size = size
+        .parse::<u32>()
+        .map_err(|e| format!("invalid map size {}: {}", size, e))?;
+
+    let mut doc = TerrainDocument::open(TerrainConfig::load(project)?)?;
+
+    std::fs::create_dir_all(directory)
+        .map_err(|e| format!("failed to create directory {}: {}", directory, e))?;
+
+    doc.resample(map_size)?;
+    doc.relocate_maps(directory);
+    doc.save()?;
+
+    let project_file = Path::new(project)
+        .file_name()
+        .map(|filename| Path::new(directory).join(filename))
+        .ok_or_else(|| format!("invalid project filepath {}", project))?;
+    let project_file = project_file.to_string_lossy();
+
+    doc.config().save(&project_file)?;
+    println!("saved {} with map size {}", project_file, map_size);
+    Ok(())
+}
+// ----------------------------------------------------------------------------
+fn export_tiles(project: &str, directory: &str, maps: Option<&str>) -> Result<(), String> {
+    let mut settings = TileExportSettings {
+        directory: directory.to_string(),
+        ..Default::default()
+    };
+    if let Some(maps) = maps {
+        settings.heightmap = false;
+        settings.controlmap = false;
+        settings.tintmap = false;
+        for map in maps.split(',').map(str::trim) {
+            match map {
+                "height" => settings.heightmap = true,
+                "control" => settings.controlmap = true,
+                "color" => settings.tintmap = true,
+                _ => {
+                    return Err(format!(
+                        "unknown map {} (expected height, control or color)",
+                        map
+                    ))
+                }
+            }
+        }
+    }
+
+    let doc = TerrainDocument::open(TerrainConfig::load(project)?)?;
+    settings.validate(doc.config())?;
+
+    doc.export_tiles(settings)?;
+
+    let tiles_per_edge = doc.config().tiles_per_edge();
+    println!(
+        "exported {}x{} tiles with resolution {} (+1 shared border) into {}",
+        tiles_per_edge,
+        tiles_per_edge,
+        doc.config().map_size() / tiles_per_edge as u32,
+        directory
+    );
+    Ok(())
+}
+// ----------------------------------------------------------------------------
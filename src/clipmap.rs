@@ -1,10 +1,14 @@
 // ----------------------------------------------------------------------------
-use bevy::{
-    prelude::*,
-    render::render_resource::{TextureFormat, TextureUsages},
-};
+use glam::UVec2;
 
-use crate::texturearray::{TextureArray, TextureArrayBuilder};
+#[cfg(feature = "editor")]
+use {
+    crate::texturearray::{TextureArray, TextureArrayBuilder},
+    bevy::{
+        prelude::*,
+        render::render_resource::{TextureFormat, TextureUsages},
+    },
+};
 // ----------------------------------------------------------------------------
 #[derive(Default, Clone, Debug)]
 pub struct Rectangle {
@@ -20,6 +24,7 @@ pub trait ClipmapData: Default {
     ///     1 for 16 bit datapoint if data type is u16
     fn datapoint_size(&self) -> u32;
     // ------------------------------------------------------------------------
+    #[cfg(feature = "editor")]
     fn texture_format(&self) -> TextureFormat;
     // ------------------------------------------------------------------------
     fn wrap_as_image(&self, size: u32, data: Vec<Self::DataType>) -> image::DynamicImage;
@@ -41,6 +46,7 @@ pub trait ClipmapData: Default {
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 pub struct Clipmap<const CLIPMAP_SIZE: u32, D: ClipmapData> {
     /// debug name
     label: String,
@@ -58,6 +64,7 @@ pub struct Clipmap<const CLIPMAP_SIZE: u32, D: ClipmapData> {
     cache: Vec<Vec<D::DataType>>,
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 impl<const CLIPMAP_SIZE: u32, D: ClipmapData> Clipmap<CLIPMAP_SIZE, D> {
     // ------------------------------------------------------------------------
     pub fn label(&self) -> &str {
@@ -85,7 +92,6 @@ impl<const CLIPMAP_SIZE: u32, D: ClipmapData> Clipmap<CLIPMAP_SIZE, D> {
             .update_slot(level, new_data);
     }
     // ------------------------------------------------------------------------
-    #[allow(dead_code)]
     pub fn fullres_data_slice(&self) -> &[D::DataType] {
         self.data.as_slice()
     }
@@ -99,9 +105,10 @@ impl<const CLIPMAP_SIZE: u32, D: ClipmapData> Clipmap<CLIPMAP_SIZE, D> {
         assert!(rectangle.size.y >= 1);
         assert!(rectangle.pos.x + rectangle.size.x <= self.data_size);
         assert!(rectangle.pos.y + rectangle.size.y <= self.data_size);
-        assert!(rectangle.size.x * rectangle.size.y == new_data.len() as u32);
 
         let datapoint_size = self.data.datapoint_size() as usize;
+        assert!(datapoint_size * (rectangle.size.x * rectangle.size.y) as usize == new_data.len());
+
         let target_dataline_size = datapoint_size * self.data_size as usize;
         let src_dataline_size = datapoint_size * rectangle.size.x as usize;
 
@@ -135,6 +142,7 @@ impl<const CLIPMAP_SIZE: u32, D: ClipmapData> Clipmap<CLIPMAP_SIZE, D> {
 // ----------------------------------------------------------------------------
 // Helper clipmap builder to hide cache generation and texture array init.
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 pub struct ClipmapBuilder<const CLIPMAP_SIZE: u32, D: ClipmapData> {
     clipmap: Clipmap<CLIPMAP_SIZE, D>,
     /// texture format of used data (required to setup texture array)
@@ -142,6 +150,7 @@ pub struct ClipmapBuilder<const CLIPMAP_SIZE: u32, D: ClipmapData> {
     enable_cache: bool,
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 impl<const CLIPMAP_SIZE: u32, D: ClipmapData> ClipmapBuilder<CLIPMAP_SIZE, D> {
     // ------------------------------------------------------------------------
     pub fn new(label: &str, clipmap_data: D, full_size: u32, layer_sizes: Vec<u32>) -> Self {
@@ -161,7 +170,7 @@ impl<const CLIPMAP_SIZE: u32, D: ClipmapData> ClipmapBuilder<CLIPMAP_SIZE, D> {
         assert!(layer_sizes[0] == full_size);
         let mut prev_size = full_size;
         for size in layer_sizes.iter().copied().skip(1) {
-            assert!(prev_size % size == 0,
+            assert!(prev_size.is_multiple_of(size),
                 "{label}: clipmap layer size {prev_size} must be divideable by next level size {size}");
             prev_size = size;
         }
@@ -225,6 +234,7 @@ impl<const CLIPMAP_SIZE: u32, D: ClipmapData> ClipmapBuilder<CLIPMAP_SIZE, D> {
 // ----------------------------------------------------------------------------
 // private impl
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 impl<const CLIPMAP_SIZE: u32, D: ClipmapData> Clipmap<CLIPMAP_SIZE, D> {
     // ------------------------------------------------------------------------
     fn extract(
@@ -263,7 +273,7 @@ impl<const CLIPMAP_SIZE: u32, D: ClipmapData> Clipmap<CLIPMAP_SIZE, D> {
         } else if let Some(downscaled) = self.cache.get(level - 1) {
             // use pregenerated downscaled layer to extract rectangle
             // adjust full res rectangle based on clipmap level
-            let level_size = self.layer_sizes[level as usize];
+            let level_size = self.layer_sizes[level];
             let scale = self.data_size / level_size;
 
             self.data.wrap_as_image(
@@ -321,6 +331,7 @@ impl<const CLIPMAP_SIZE: u32, D: ClipmapData> Clipmap<CLIPMAP_SIZE, D> {
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 impl<const CLIPMAP_SIZE: u32, D: ClipmapData> Default for Clipmap<CLIPMAP_SIZE, D> {
     fn default() -> Self {
         Self {
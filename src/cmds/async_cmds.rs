@@ -17,14 +17,17 @@ use crate::terrain_clipmap::{
 };
 use crate::texturearray::TextureArray;
 use crate::texturecontrol::TextureControl;
+use crate::tileexport::TileExportSettings;
 use crate::tintmap::TintMap;
+use crate::writer::WriterPlugin;
 use crate::{EditorEvent, TaskResult, TaskResultData};
 
 use super::{
-    AsyncTask, AsyncTaskFinishedEvent, AsyncTaskStartEvent, GenerateClipmap,
-    GenerateHeightmapNormals, GenerateTerrainMeshErrorMaps, GenerateTerrainMeshes,
-    GenerateTerrainTiles, LoadHeightmap, LoadTerrainMaterialSet, LoadTextureMap, LoadTintMap,
-    MergeTerrainMeshErrorMapSeams, TrackedProgress, WaitForTerrainLoaded,
+    AsyncTask, AsyncTaskFinishedEvent, AsyncTaskStartEvent, ExportHeightmap, ExportTiles,
+    GenerateAutoTexturing, GenerateClipmap, GenerateHeightmapNormals, GenerateTerrainMeshErrorMaps,
+    GenerateTerrainMeshes, GenerateTerrainTiles, LoadHeightmap, LoadTerrainMaterialSet,
+    LoadTextureMap, LoadTintMap, MergeTerrainMeshErrorMapSeams, RemapMaterials, SaveHeightmap,
+    SaveTextureMap, SaveTintMap, TrackedProgress, WaitForTerrainLoaded,
 };
 // ----------------------------------------------------------------------------
 pub struct AsyncCmdsPlugin;
@@ -41,6 +44,7 @@ pub struct AsyncCommandManager {
 impl Plugin for AsyncCmdsPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<AsyncCommandManager>()
+            .init_resource::<TileExportSettings>()
             .add_event::<AsyncTaskStartEvent>()
             .add_event::<AsyncTaskFinishedEvent>();
     }
@@ -114,8 +118,10 @@ pub(crate) fn start_async_operations(
     texture_clipmap: Res<TextureControlClipmap>,
     tint_clipmap: Res<TintClipmap>,
     heightmap_clipmap: Res<HeightmapClipmap>,
+    terrain_heightmap: Res<TerrainHeightMap>,
     thread_pool: Res<IoTaskPool>,
     terrain_config: Res<config::TerrainConfig>,
+    tile_export_settings: Res<TileExportSettings>,
 ) {
     for task in tasks_finished.iter().copied() {
         async_cmd_tracker.update(task);
@@ -145,6 +151,44 @@ pub(crate) fn start_async_operations(
                     commands.spawn().insert(task);
                 }
                 LoadTerrainMaterialSet => task_ready.send(LoadTerrainMaterialSet),
+                SaveTextureMap => {
+                    let task = thread_pool.spawn(WriterPlugin::save_texturemap(
+                        &terrain_config,
+                        texture_clipmap.fullres_data_slice().to_vec(),
+                    ));
+                    commands.spawn().insert(task);
+                }
+                SaveTintMap => {
+                    let task = thread_pool.spawn(WriterPlugin::save_tintmap(
+                        &terrain_config,
+                        tint_clipmap.fullres_data_slice().to_vec(),
+                    ));
+                    commands.spawn().insert(task);
+                }
+                SaveHeightmap => {
+                    let task = thread_pool.spawn(WriterPlugin::save_heightmap(
+                        &terrain_config,
+                        terrain_heightmap.clone(),
+                    ));
+                    commands.spawn().insert(task);
+                }
+                ExportHeightmap => {
+                    let task = thread_pool.spawn(WriterPlugin::export_heightmap_r16(
+                        &terrain_config,
+                        terrain_heightmap.clone(),
+                    ));
+                    commands.spawn().insert(task);
+                }
+                ExportTiles => {
+                    let task = thread_pool.spawn(WriterPlugin::export_tiles(
+                        &terrain_config,
+                        tile_export_settings.clone(),
+                        terrain_heightmap.clone(),
+                        texture_clipmap.fullres_data_slice().to_vec(),
+                        tint_clipmap.fullres_data_slice().to_vec(),
+                    ));
+                    commands.spawn().insert(task);
+                }
                 // -- these tasks are more involved and may be handled by specialized systems
                 GenerateClipmap => {
                     // dedicated clipmaps will update their texturearray but the clipmap
@@ -172,6 +216,8 @@ pub(crate) fn start_async_operations(
                 GenerateTerrainMeshErrorMaps => task_ready.send(GenerateTerrainMeshErrorMaps),
                 MergeTerrainMeshErrorMapSeams => task_ready.send(MergeTerrainMeshErrorMapSeams),
                 GenerateTerrainMeshes => task_ready.send(GenerateTerrainMeshes),
+                GenerateAutoTexturing => task_ready.send(GenerateAutoTexturing),
+                RemapMaterials => task_ready.send(RemapMaterials),
                 // -- these are just wrapper for sinks (join multiple events but do nothing)
                 WaitForTerrainLoaded => task_ready.send(WaitForTerrainLoaded),
             }
@@ -194,6 +240,7 @@ pub(crate) fn poll_async_task_state(
 
     clipmap_tracker: ResMut<ClipmapTracker>,
     terrain_config: Res<config::TerrainConfig>,
+    mut editor_events: EventWriter<EditorEvent>,
 ) {
     use futures_lite::future;
 
@@ -257,10 +304,32 @@ pub(crate) fn poll_async_task_state(
 
                         task_finished.send(AsyncTaskFinishedEvent::TintMapLoaded);
                     }
+                    TaskResultData::TextureMapSaved => {
+                        info!("saving texturing maps...finished");
+                        task_finished.send(AsyncTaskFinishedEvent::TextureMapSaved);
+                    }
+                    TaskResultData::TintMapSaved => {
+                        info!("saving tint map...finished");
+                        task_finished.send(AsyncTaskFinishedEvent::TintMapSaved);
+                    }
+                    TaskResultData::HeightmapSaved => {
+                        info!("saving heightmap...finished");
+                        task_finished.send(AsyncTaskFinishedEvent::HeightmapSaved);
+                    }
+                    TaskResultData::HeightmapExported => {
+                        info!("exporting heightmap...finished");
+                        task_finished.send(AsyncTaskFinishedEvent::HeightmapExported);
+                    }
+                    TaskResultData::TilesExported => {
+                        info!("exporting terrain tiles...finished");
+                        task_finished.send(AsyncTaskFinishedEvent::TilesExported);
+                    }
                 },
                 Err(e) => {
                     //TODO this involves canceling all futures and stoping other tasks
                     error!("{}", e);
+                    // the finished event will never be sent
+                    editor_events.send(EditorEvent::ProgressTrackingFailed(e));
                 }
             }
         }
@@ -293,9 +362,9 @@ impl AsyncTaskNode for LoadHeightmap {
     fn ready_event(&self) -> AsyncTaskFinishedEvent { AsyncTaskFinishedEvent::HeightmapLoaded }
     fn subsequent_tasks(&self) -> Vec<AsyncTask> {
         vec![
-            GenerateHeightmapNormals::default().into(),
-            GenerateTerrainTiles::default().into(),
-            GenerateTerrainMeshErrorMaps::default().into(),
+            GenerateHeightmapNormals.into(),
+            GenerateTerrainTiles.into(),
+            GenerateTerrainMeshErrorMaps.into(),
         ]
     }
 }
@@ -304,14 +373,14 @@ impl AsyncTaskNode for LoadHeightmap {
 impl AsyncTaskNode for LoadTextureMap {
     fn start_event(self) -> AsyncTaskStartEvent { AsyncTaskStartEvent::LoadTextureMap }
     fn ready_event(&self) -> AsyncTaskFinishedEvent { AsyncTaskFinishedEvent::TextureMapLoaded }
-    fn subsequent_tasks(&self) -> Vec<AsyncTask> { vec![GenerateClipmap::default().into()] }
+    fn subsequent_tasks(&self) -> Vec<AsyncTask> { vec![GenerateClipmap.into()] }
 }
 // ----------------------------------------------------------------------------
 #[rustfmt::skip]
 impl AsyncTaskNode for LoadTintMap {
     fn start_event(self) -> AsyncTaskStartEvent { AsyncTaskStartEvent::LoadTintMap }
     fn ready_event(&self) -> AsyncTaskFinishedEvent { AsyncTaskFinishedEvent::TintMapLoaded }
-    fn subsequent_tasks(&self) -> Vec<AsyncTask> { vec![GenerateClipmap::default().into()] }
+    fn subsequent_tasks(&self) -> Vec<AsyncTask> { vec![GenerateClipmap.into()] }
 }
 // ----------------------------------------------------------------------------
 #[rustfmt::skip]
@@ -330,7 +399,7 @@ impl AsyncTaskNode for GenerateHeightmapNormals {
     fn preconditions(&self) -> &[AsyncTaskFinishedEvent] { &[AsyncTaskFinishedEvent::HeightmapLoaded] }
     fn start_event(self) -> AsyncTaskStartEvent { AsyncTaskStartEvent::GenerateHeightmapNormals }
     fn ready_event(&self) -> AsyncTaskFinishedEvent { AsyncTaskFinishedEvent::HeightmapNormalsGenerated }
-    fn subsequent_tasks(&self) -> Vec<AsyncTask> { vec![ GenerateTerrainMeshErrorMaps::default().into() ] }
+    fn subsequent_tasks(&self) -> Vec<AsyncTask> { vec![ GenerateTerrainMeshErrorMaps.into() ] }
 }
 // ----------------------------------------------------------------------------
 #[rustfmt::skip]
@@ -349,7 +418,7 @@ impl AsyncTaskNode for GenerateTerrainMeshErrorMaps {
     ]}
     fn start_event(self) -> AsyncTaskStartEvent { AsyncTaskStartEvent::GenerateTerrainMeshErrorMaps }
     fn ready_event(&self) -> AsyncTaskFinishedEvent { AsyncTaskFinishedEvent::TerrainMeshErrorMapsGenerated }
-    fn subsequent_tasks(&self) -> Vec<AsyncTask> { vec![MergeTerrainMeshErrorMapSeams::default().into()] }
+    fn subsequent_tasks(&self) -> Vec<AsyncTask> { vec![MergeTerrainMeshErrorMapSeams.into()] }
 }
 // ----------------------------------------------------------------------------
 #[rustfmt::skip]
@@ -357,12 +426,16 @@ impl AsyncTaskNode for MergeTerrainMeshErrorMapSeams {
     fn preconditions(&self) -> &[AsyncTaskFinishedEvent] { &[AsyncTaskFinishedEvent::TerrainMeshErrorMapsGenerated] }
     fn start_event(self) -> AsyncTaskStartEvent { AsyncTaskStartEvent::MergeTerrainMeshErrorMapSeams }
     fn ready_event(&self) -> AsyncTaskFinishedEvent { AsyncTaskFinishedEvent::TerrainMeshErrorMapsSeamsMerged }
-    fn subsequent_tasks(&self) -> Vec<AsyncTask> { vec![GenerateTerrainMeshes::default().into()] }
+    fn subsequent_tasks(&self) -> Vec<AsyncTask> { vec![GenerateTerrainMeshes.into()] }
 }
 // ----------------------------------------------------------------------------
 #[rustfmt::skip]
 impl AsyncTaskNode for GenerateTerrainMeshes {
-    fn preconditions(&self) -> &[AsyncTaskFinishedEvent] { &[AsyncTaskFinishedEvent::TerrainMeshErrorMapsSeamsMerged] }
+    // holes of the texture control map are cut out of the meshes
+    fn preconditions(&self) -> &[AsyncTaskFinishedEvent] { &[
+        AsyncTaskFinishedEvent::TerrainMeshErrorMapsSeamsMerged,
+        AsyncTaskFinishedEvent::TextureMapLoaded,
+    ]}
     fn start_event(self) -> AsyncTaskStartEvent { AsyncTaskStartEvent::GenerateTerrainMeshes }
     fn ready_event(&self) -> AsyncTaskFinishedEvent { AsyncTaskFinishedEvent::TerrainMeshesGenerated }
 }
@@ -384,6 +457,48 @@ impl AsyncTaskNode for WaitForTerrainLoaded {
     fn ready_event(&self) -> AsyncTaskFinishedEvent { AsyncTaskFinishedEvent::TerrainLoaded }
 }
 // ----------------------------------------------------------------------------
+#[rustfmt::skip]
+impl AsyncTaskNode for SaveTextureMap {
+    fn start_event(self) -> AsyncTaskStartEvent { AsyncTaskStartEvent::SaveTextureMap }
+    fn ready_event(&self) -> AsyncTaskFinishedEvent { AsyncTaskFinishedEvent::TextureMapSaved }
+}
+// ----------------------------------------------------------------------------
+#[rustfmt::skip]
+impl AsyncTaskNode for SaveTintMap {
+    fn start_event(self) -> AsyncTaskStartEvent { AsyncTaskStartEvent::SaveTintMap }
+    fn ready_event(&self) -> AsyncTaskFinishedEvent { AsyncTaskFinishedEvent::TintMapSaved }
+}
+// ----------------------------------------------------------------------------
+#[rustfmt::skip]
+impl AsyncTaskNode for SaveHeightmap {
+    fn start_event(self) -> AsyncTaskStartEvent { AsyncTaskStartEvent::SaveHeightmap }
+    fn ready_event(&self) -> AsyncTaskFinishedEvent { AsyncTaskFinishedEvent::HeightmapSaved }
+}
+// ----------------------------------------------------------------------------
+#[rustfmt::skip]
+impl AsyncTaskNode for ExportHeightmap {
+    fn start_event(self) -> AsyncTaskStartEvent { AsyncTaskStartEvent::ExportHeightmap }
+    fn ready_event(&self) -> AsyncTaskFinishedEvent { AsyncTaskFinishedEvent::HeightmapExported }
+}
+// ----------------------------------------------------------------------------
+#[rustfmt::skip]
+impl AsyncTaskNode for ExportTiles {
+    fn start_event(self) -> AsyncTaskStartEvent { AsyncTaskStartEvent::ExportTiles }
+    fn ready_event(&self) -> AsyncTaskFinishedEvent { AsyncTaskFinishedEvent::TilesExported }
+}
+// ----------------------------------------------------------------------------
+#[rustfmt::skip]
+impl AsyncTaskNode for GenerateAutoTexturing {
+    fn start_event(self) -> AsyncTaskStartEvent { AsyncTaskStartEvent::GenerateAutoTexturing }
+    fn ready_event(&self) -> AsyncTaskFinishedEvent { AsyncTaskFinishedEvent::AutoTexturingGenerated }
+}
+// ----------------------------------------------------------------------------
+#[rustfmt::skip]
+impl AsyncTaskNode for RemapMaterials {
+    fn start_event(self) -> AsyncTaskStartEvent { AsyncTaskStartEvent::RemapMaterials }
+    fn ready_event(&self) -> AsyncTaskFinishedEvent { AsyncTaskFinishedEvent::MaterialsRemapped }
+}
+// ----------------------------------------------------------------------------
 // mapping to progress tracking
 // ----------------------------------------------------------------------------
 impl From<AsyncTaskStartEvent> for TrackedProgress {
@@ -402,6 +517,13 @@ impl From<AsyncTaskStartEvent> for TrackedProgress {
             AsyncTaskStartEvent::GenerateTerrainMeshes => GeneratedTerrainMeshes(0, 1),
             AsyncTaskStartEvent::LoadTerrainMaterialSet => LoadTerrainMaterialSet(0, 1),
             AsyncTaskStartEvent::WaitForTerrainLoaded => Ignored,
+            AsyncTaskStartEvent::SaveTextureMap => SaveTextureMap(false),
+            AsyncTaskStartEvent::SaveTintMap => SaveTintMap(false),
+            AsyncTaskStartEvent::SaveHeightmap => SaveHeightmap(false),
+            AsyncTaskStartEvent::ExportHeightmap => ExportHeightmap(false),
+            AsyncTaskStartEvent::ExportTiles => ExportTiles(false),
+            AsyncTaskStartEvent::GenerateAutoTexturing => GeneratedAutoTexturing(0, 1),
+            AsyncTaskStartEvent::RemapMaterials => RemappedMaterials(0, 1),
         }
     }
 }
@@ -426,6 +548,13 @@ impl From<AsyncTaskFinishedEvent> for TrackedProgress {
             AsyncTaskFinishedEvent::TerrainMeshesGenerated => GeneratedTerrainMeshes(1, 1),
             AsyncTaskFinishedEvent::TerrainMaterialSetLoaded => LoadTerrainMaterialSet(1, 1),
             AsyncTaskFinishedEvent::TerrainLoaded => Ignored,
+            AsyncTaskFinishedEvent::TextureMapSaved => SaveTextureMap(true),
+            AsyncTaskFinishedEvent::TintMapSaved => SaveTintMap(true),
+            AsyncTaskFinishedEvent::HeightmapSaved => SaveHeightmap(true),
+            AsyncTaskFinishedEvent::HeightmapExported => ExportHeightmap(true),
+            AsyncTaskFinishedEvent::TilesExported => ExportTiles(true),
+            AsyncTaskFinishedEvent::AutoTexturingGenerated => GeneratedAutoTexturing(1, 1),
+            AsyncTaskFinishedEvent::MaterialsRemapped => RemappedMaterials(1, 1),
         }
     }
 }
@@ -21,6 +21,13 @@ pub enum AsyncTask {
     GenerateTerrainMeshes,
     LoadTerrainMaterialSet,
     WaitForTerrainLoaded,
+    SaveTextureMap,
+    SaveTintMap,
+    SaveHeightmap,
+    ExportHeightmap,
+    ExportTiles,
+    GenerateAutoTexturing,
+    RemapMaterials,
 }
 // ----------------------------------------------------------------------------
 pub use self::progress::{TrackedProgress, TrackedTaskname};
@@ -38,6 +45,13 @@ pub enum AsyncTaskStartEvent {
     GenerateTerrainMeshes,
     LoadTerrainMaterialSet,
     WaitForTerrainLoaded,
+    SaveTextureMap,
+    SaveTintMap,
+    SaveHeightmap,
+    ExportHeightmap,
+    ExportTiles,
+    GenerateAutoTexturing,
+    RemapMaterials,
 }
 // ----------------------------------------------------------------------------
 #[derive(Hash, PartialEq, Eq, Clone, Copy, Debug)]
@@ -53,6 +67,13 @@ pub enum AsyncTaskFinishedEvent {
     TerrainMeshesGenerated,
     TerrainLoaded,
     TerrainMaterialSetLoaded,
+    TextureMapSaved,
+    TintMapSaved,
+    HeightmapSaved,
+    HeightmapExported,
+    TilesExported,
+    AutoTexturingGenerated,
+    MaterialsRemapped,
 }
 // ----------------------------------------------------------------------------
 #[derive(Debug, Default)]
@@ -88,6 +109,31 @@ pub struct LoadTerrainMaterialSet;
 #[derive(Debug, Default)]
 pub struct WaitForTerrainLoaded;
 // ----------------------------------------------------------------------------
+#[derive(Debug, Default)]
+pub struct SaveTextureMap;
+// ----------------------------------------------------------------------------
+#[derive(Debug, Default)]
+pub struct SaveTintMap;
+// ----------------------------------------------------------------------------
+#[derive(Debug, Default)]
+pub struct SaveHeightmap;
+// ----------------------------------------------------------------------------
+#[derive(Debug, Default)]
+/// exports heightmap as raw 16bit little endian (r16) file
+pub struct ExportHeightmap;
+// ----------------------------------------------------------------------------
+#[derive(Debug, Default)]
+/// exports all maps sliced into raw per tile buffers
+pub struct ExportTiles;
+// ----------------------------------------------------------------------------
+#[derive(Debug, Default)]
+/// generates auto texturing preview for requested rules and region
+pub struct GenerateAutoTexturing;
+// ----------------------------------------------------------------------------
+#[derive(Debug, Default)]
+/// counts or rewrites all datapoints of requested material remapping
+pub struct RemapMaterials;
+// ----------------------------------------------------------------------------
 // systems
 // ----------------------------------------------------------------------------
 pub(crate) use async_cmds::poll_async_task_state;
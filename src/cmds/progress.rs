@@ -16,6 +16,14 @@ pub enum TrackedProgress {
     GenerateTerrainTiles(bool),
     GeneratedTerrainMeshes(usize, usize),
     LoadTerrainMaterialSet(usize, usize),
+    SaveTextureMap(bool),
+    SaveTintMap(bool),
+    SaveHeightmap(bool),
+    ExportHeightmap(bool),
+    ExportTiles(bool),
+    GeneratedAutoTexturing(usize, usize),
+    RenderedFrameSequence(usize, usize),
+    RemappedMaterials(usize, usize),
     Ignored,
 }
 // ----------------------------------------------------------------------------
@@ -30,12 +38,20 @@ impl TrackedProgress {
             | Self::LoadTextureMap(b)
             | Self::LoadTintMap(b)
             | Self::GenerateClipmap(b)
-            | Self::GenerateTerrainTiles(b) => *b,
+            | Self::GenerateTerrainTiles(b)
+            | Self::SaveTextureMap(b)
+            | Self::SaveTintMap(b)
+            | Self::SaveHeightmap(b)
+            | Self::ExportHeightmap(b)
+            | Self::ExportTiles(b) => *b,
             Self::GeneratedHeightmapNormals(a, b)
             | Self::GeneratedTerrainErrorMaps(a, b)
             | Self::MergedTerrainErrorMapSeams(a, b)
             | Self::GeneratedTerrainMeshes(a, b)
-            | Self::LoadTerrainMaterialSet(a, b) => *a == *b,
+            | Self::LoadTerrainMaterialSet(a, b)
+            | Self::GeneratedAutoTexturing(a, b)
+            | Self::RenderedFrameSequence(a, b)
+            | Self::RemappedMaterials(a, b) => *a == *b,
             Self::Ignored => true,
         }
     }
@@ -46,7 +62,12 @@ impl TrackedProgress {
             | Self::LoadTextureMap(b)
             | Self::LoadTintMap(b)
             | Self::GenerateClipmap(b)
-            | Self::GenerateTerrainTiles(b) => {
+            | Self::GenerateTerrainTiles(b)
+            | Self::SaveTextureMap(b)
+            | Self::SaveTintMap(b)
+            | Self::SaveHeightmap(b)
+            | Self::ExportHeightmap(b)
+            | Self::ExportTiles(b) => {
                 if *b {
                     1.0
                 } else {
@@ -57,7 +78,10 @@ impl TrackedProgress {
             | Self::GeneratedTerrainErrorMaps(a, b)
             | Self::MergedTerrainErrorMapSeams(a, b)
             | Self::GeneratedTerrainMeshes(a, b)
-            | Self::LoadTerrainMaterialSet(a, b) => *a as f32 / *b as f32,
+            | Self::LoadTerrainMaterialSet(a, b)
+            | Self::GeneratedAutoTexturing(a, b)
+            | Self::RenderedFrameSequence(a, b)
+            | Self::RemappedMaterials(a, b) => *a as f32 / *b as f32,
             Self::Ignored => 1.0,
         }
     }
@@ -88,6 +112,18 @@ impl TrackedProgress {
             Self::LoadTerrainMaterialSet(a, b) => {
                 format!("loading materials...{}/{}", a, b)
             }
+            Self::SaveTextureMap(_) => "saving texturing maps...".to_string(),
+            Self::SaveTintMap(_) => "saving tint map...".to_string(),
+            Self::SaveHeightmap(_) => "saving heightmap...".to_string(),
+            Self::ExportHeightmap(_) => "exporting heightmap...".to_string(),
+            Self::ExportTiles(_) => "exporting terrain tiles...".to_string(),
+            Self::GeneratedAutoTexturing(_, _) => {
+                Self::format_progress("generating auto texturing", self.progress())
+            }
+            Self::RenderedFrameSequence(a, b) => format!("rendering frames...{}/{}", a, b),
+            Self::RemappedMaterials(_, _) => {
+                Self::format_progress("remapping materials", self.progress())
+            }
             Self::Ignored => String::default(),
         }
     }
@@ -104,6 +140,14 @@ impl TrackedProgress {
             Self::MergedTerrainErrorMapSeams(_, _) => "terrain error map seams merged.",
             Self::GeneratedTerrainMeshes(_, _) => "terrain mesh generation finished.",
             Self::LoadTerrainMaterialSet(_, _) => "materials loaded.",
+            Self::SaveTextureMap(_) => "texturing maps saved.",
+            Self::SaveTintMap(_) => "tint map saved.",
+            Self::SaveHeightmap(_) => "heightmap saved.",
+            Self::ExportHeightmap(_) => "heightmap exported.",
+            Self::ExportTiles(_) => "terrain tiles exported.",
+            Self::GeneratedAutoTexturing(_, _) => "auto texturing preview generated.",
+            Self::RenderedFrameSequence(_, _) => "frame sequence rendered.",
+            Self::RemappedMaterials(_, _) => "material remapping finished.",
             Self::Ignored => "",
         }
     }
@@ -128,6 +172,14 @@ impl hash::Hash for TrackedProgress {
             MergedTerrainErrorMapSeams(_, _) => state.write_u8(8),
             GeneratedTerrainMeshes(_, _) => state.write_u8(9),
             LoadTerrainMaterialSet(_, _) => state.write_u8(10),
+            SaveTextureMap(_) => state.write_u8(11),
+            SaveTintMap(_) => state.write_u8(12),
+            SaveHeightmap(_) => state.write_u8(13),
+            ExportHeightmap(_) => state.write_u8(14),
+            GeneratedAutoTexturing(_, _) => state.write_u8(15),
+            ExportTiles(_) => state.write_u8(16),
+            RenderedFrameSequence(_, _) => state.write_u8(17),
+            RemappedMaterials(_, _) => state.write_u8(18),
         }
     }
 }
@@ -147,6 +199,14 @@ impl cmp::PartialEq for TrackedProgress {
             MergedTerrainErrorMapSeams(_, _) => matches!(other, MergedTerrainErrorMapSeams(_, _)),
             GeneratedTerrainMeshes(_, _) => matches!(other, GeneratedTerrainMeshes(_, _)),
             LoadTerrainMaterialSet(_, _) => matches!(other, LoadTerrainMaterialSet(_, _)),
+            SaveTextureMap(_) => matches!(other, SaveTextureMap(_)),
+            SaveTintMap(_) => matches!(other, SaveTintMap(_)),
+            SaveHeightmap(_) => matches!(other, SaveHeightmap(_)),
+            ExportHeightmap(_) => matches!(other, ExportHeightmap(_)),
+            GeneratedAutoTexturing(_, _) => matches!(other, GeneratedAutoTexturing(_, _)),
+            ExportTiles(_) => matches!(other, ExportTiles(_)),
+            RenderedFrameSequence(_, _) => matches!(other, RenderedFrameSequence(_, _)),
+            RemappedMaterials(_, _) => matches!(other, RemappedMaterials(_, _)),
         }
     }
 }
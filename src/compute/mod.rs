@@ -92,7 +92,7 @@ impl GpuComputeTask {
     // ------------------------------------------------------------------------
     fn wait_for_result(
         &self,
-    ) -> impl Future<Output = Result<BufferSlice, BufferAsyncError>> + Send {
+    ) -> impl Future<Output = Result<BufferSlice<'_>, BufferAsyncError>> + Send {
         use futures_lite::FutureExt;
 
         match self {
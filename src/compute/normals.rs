@@ -324,7 +324,7 @@ impl GpuComputeNormals {
     // ------------------------------------------------------------------------
     pub fn wait_for_result(
         &self,
-    ) -> impl Future<Output = Result<BufferSlice, BufferAsyncError>> + Send {
+    ) -> impl Future<Output = Result<BufferSlice<'_>, BufferAsyncError>> + Send {
         let buf_slice = self.staging_buffer.slice(..);
         let buf_future = buf_slice.map_async(MapMode::Read);
         async move {
@@ -12,9 +12,14 @@ pub const CLIPMAP_SIZE: u32 = TILE_SIZE * 4;
 // clipmap data more often but tiles will only be updated if they are fully
 // covered).
 pub const CLIPMAP_GRANULARITY: u32 = TILE_SIZE;
+// Restriction for clipmap level parameter (e.g. if clipmap size is smallish
+// and map size is biggish).
+pub const MAX_SUPPORTED_CLIPMAP_LEVEL: u8 = 8;
+// max supported materials (texture + parameter sets) of a materialset
+const MAX_MATERIALS: usize = 31;
 // ----------------------------------------------------------------------------
 /// config for texturing maps
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct TextureMaps {
     // stackvalue := background texture id + overlay textureid + blendcontrol
     background: String,
@@ -23,7 +28,7 @@ pub struct TextureMaps {
 }
 // ----------------------------------------------------------------------------
 /// config for current world/terrain
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct TerrainConfig {
     name: String,
     /// terrain size in meters
@@ -31,6 +36,7 @@ pub struct TerrainConfig {
     /// pixel size of all maps
     map_size: u32,
     /// precalculated resolution of terrain (terrain_size / map_size)
+    #[serde(skip)]
     resolution: f32,
     /// lowest height of terrain in meters (absolute)
     min_height: f32,
@@ -47,10 +53,16 @@ pub struct TerrainConfig {
     /// currently assigned materialset info
     materialset: MaterialSetConfig,
     /// assigned environment definition
+    #[serde(default)]
     environment: Option<String>,
 }
 // ----------------------------------------------------------------------------
-use bevy::math::{uvec2, vec2, UVec2, Vec2};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use glam::{uvec2, vec2, UVec2, Vec2};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
 
 use crate::terrain_material::{MaterialSlot, TerrainMaterialParam};
 // ----------------------------------------------------------------------------
@@ -145,7 +157,161 @@ impl TerrainConfig {
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
-#[derive(Clone)]
+// project file
+// ----------------------------------------------------------------------------
+impl TerrainConfig {
+    // ------------------------------------------------------------------------
+    /// loads and validates terrain config from a (ron) project file. relative
+    /// paths in the project file are relative to the project file directory.
+    pub fn load(filepath: &str) -> Result<Self, String> {
+        let config = Self::load_unvalidated(filepath)?;
+
+        config.validate().map_err(|errors| {
+            format!("invalid project file {}:\n{}", filepath, errors.join("\n"))
+        })?;
+
+        Ok(config)
+    }
+    // ------------------------------------------------------------------------
+    /// loads terrain config from a (ron) project file without validating the
+    /// settings (e.g. to report config and map problems together).
+    pub fn load_unvalidated(filepath: &str) -> Result<Self, String> {
+        let content = fs::read_to_string(filepath)
+            .map_err(|e| format!("failed to read project file {}: {}", filepath, e))?;
+
+        let mut config: Self = ron::from_str(&content)
+            .map_err(|e| format!("failed to parse project file {}: {}", filepath, e))?;
+
+        let project_dir = project_directory(filepath);
+        config.map_paths(|path| resolve_path(&project_dir, path));
+        config.materialset.add_missing_parameters(filepath);
+
+        config.resolution = config.terrain_size / config.map_size as f32;
+        Ok(config)
+    }
+    // ------------------------------------------------------------------------
+    /// saves terrain config as (ron) project file. paths are written relative
+    /// to the project file directory (if possible).
+    pub fn save(&self, filepath: &str) -> Result<(), String> {
+        let project_dir = project_directory(filepath);
+        let mut config = self.clone();
+        config.map_paths(|path| relative_path(&project_dir, path));
+
+        let content = ron::ser::to_string_pretty(&config, ron::ser::PrettyConfig::default())
+            .map_err(|e| format!("failed to serialize project {}: {}", self.name, e))?;
+
+        fs::write(filepath, content)
+            .map_err(|e| format!("failed to write project file {}: {}", filepath, e))
+    }
+    // ------------------------------------------------------------------------
+    /// checks all settings and returns *all* found problems
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if !self.map_size.is_power_of_two() {
+            errors.push(format!(
+                "map size must be a power of two. found: {}",
+                self.map_size
+            ));
+        }
+        if self.map_size < TILE_SIZE || !self.map_size.is_multiple_of(TILE_SIZE) {
+            errors.push(format!(
+                "map size must be a multiple of tile size ({}). found: {}",
+                TILE_SIZE, self.map_size
+            ));
+        }
+        if self.map_size / TILE_SIZE > u8::MAX as u32 {
+            errors.push(format!(
+                "map size must not exceed {}. found: {}",
+                u8::MAX as u32 * TILE_SIZE,
+                self.map_size
+            ));
+        }
+        if !self.terrain_size.is_finite() || self.terrain_size <= 0.0 {
+            errors.push(format!(
+                "terrain size must be greater than zero. found: {}",
+                self.terrain_size
+            ));
+        }
+        if !self.min_height.is_finite() || !self.max_height.is_finite() {
+            errors.push(format!(
+                "min height ({}) and max height ({}) must be finite numbers",
+                self.min_height, self.max_height
+            ));
+        } else if self.min_height >= self.max_height {
+            errors.push(format!(
+                "min height ({}) must be lower than max height ({})",
+                self.min_height, self.max_height
+            ));
+        }
+        if self.clipmap_levels == 0 {
+            errors.push("clipmap levels must be at least 1.".to_string());
+        }
+        let max_levels = max_clipmap_levels(self.map_size);
+        if self.clipmap_levels > max_levels {
+            errors.push(format!(
+                "clipmap levels must not exceed {} for map size {}. found: {}",
+                max_levels, self.map_size, self.clipmap_levels
+            ));
+        }
+        errors.extend(self.materialset.validate());
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+    // ------------------------------------------------------------------------
+    pub fn set_material_parameters(&mut self, parameter: &[TerrainMaterialParam]) {
+        self.materialset.parameter = parameter.to_vec();
+    }
+    // ------------------------------------------------------------------------
+    /// changes map size of all maps (terrain size stays the same)
+    pub fn set_map_size(&mut self, map_size: u32) {
+        self.map_size = map_size;
+        self.resolution = self.terrain_size / self.map_size as f32;
+    }
+    // ------------------------------------------------------------------------
+    pub fn set_heightmap(&mut self, filepath: &str) {
+        self.heightmap = filepath.to_string();
+    }
+    // ------------------------------------------------------------------------
+    pub fn set_texturemaps(&mut self, background: &str, overlay: &str, blendcontrol: &str) {
+        self.texturemaps = TextureMaps {
+            background: background.to_string(),
+            overlay: overlay.to_string(),
+            blendcontrol: blendcontrol.to_string(),
+        };
+    }
+    // ------------------------------------------------------------------------
+    pub fn set_tintmap(&mut self, filepath: &str) {
+        self.tintmap = filepath.to_string();
+    }
+    // ------------------------------------------------------------------------
+    /// applies f to all (non empty) paths of maps, textures and environment
+    fn map_paths(&mut self, f: impl Fn(&str) -> String) {
+        let texturemaps = &mut self.texturemaps;
+        let materialset = &mut self.materialset;
+
+        [
+            &mut self.heightmap,
+            &mut texturemaps.background,
+            &mut texturemaps.overlay,
+            &mut texturemaps.blendcontrol,
+            &mut self.tintmap,
+        ]
+        .into_iter()
+        .chain(materialset.diffuse.iter_mut())
+        .chain(materialset.normal.iter_mut())
+        .chain(self.environment.iter_mut())
+        .filter(|path| !path.is_empty())
+        .for_each(|path| *path = f(path));
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+#[derive(Clone, Serialize, Deserialize)]
 pub struct MaterialSetConfig {
     /// (full) path to diffuse textures (size and type verified)
     diffuse: Vec<String>,
@@ -184,6 +350,50 @@ impl MaterialSetConfig {
             .map(|(i, p)| (MaterialSlot::from(i as u8), p))
     }
     // ------------------------------------------------------------------------
+    /// older project files may define less than the required material
+    /// parameter sets: missing sets are added with default values.
+    fn add_missing_parameters(&mut self, filepath: &str) {
+        let count = self.parameter.len();
+        if count < MAX_MATERIALS {
+            warn!(
+                "project file {}: found {} of {} material parameter sets. using defaults for the missing sets.",
+                filepath, count, MAX_MATERIALS
+            );
+            self.parameter
+                .resize(MAX_MATERIALS, TerrainMaterialParam::default());
+        }
+    }
+    // ------------------------------------------------------------------------
+    fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        if self.diffuse.len() != self.normal.len() {
+            errors.push(format!(
+                "materialset: count of diffuse ({}) and normal ({}) textures must be equal",
+                self.diffuse.len(),
+                self.normal.len()
+            ));
+        }
+        for (textures, name) in [(&self.diffuse, "diffuse"), (&self.normal, "normal")] {
+            if textures.len() > MAX_MATERIALS {
+                errors.push(format!(
+                    "materialset: max {} {} textures supported. found: {}",
+                    MAX_MATERIALS,
+                    name,
+                    textures.len()
+                ));
+            }
+        }
+        if self.parameter.len() != MAX_MATERIALS {
+            errors.push(format!(
+                "materialset: exactly {} material parameter sets required. found: {}",
+                MAX_MATERIALS,
+                self.parameter.len()
+            ));
+        }
+        errors
+    }
+    // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
 #[allow(dead_code)]
@@ -208,25 +418,25 @@ impl TextureMaps {
 impl Default for TerrainConfig {
     // ------------------------------------------------------------------------
     fn default() -> Self {
-        // let map_size = 512;
-        // Self {
-        //     name: "Empty Terrain".into(),
-        //     terrain_size: map_size as f32 / 2.0,
-        //     map_size,
-        //     resolution: 0.5,
-        //     min_height: 0.0,
-        //     max_height: 100.0,
-        //     heightmap: String::default(),
-        //     texturemaps: TextureMaps {
-        //         background: String::default(),
-        //         overlay: String::default(),
-        //         blendcontrol: String::default(),
-        //     },
-        //     tintmap: String::default(),
-        //     clipmap_levels: 2,
-        //     materialset: MaterialSetConfig::default(),
-        // }
-        Self::prolog_village(1024)
+        let map_size = 512;
+        Self {
+            name: "Empty Terrain".into(),
+            terrain_size: map_size as f32 / 2.0,
+            map_size,
+            resolution: 0.5,
+            min_height: 0.0,
+            max_height: 100.0,
+            heightmap: String::default(),
+            texturemaps: TextureMaps {
+                background: String::default(),
+                overlay: String::default(),
+                blendcontrol: String::default(),
+            },
+            tintmap: String::default(),
+            clipmap_levels: 2,
+            materialset: MaterialSetConfig::default(),
+            environment: None,
+        }
     }
     // ------------------------------------------------------------------------
 }
@@ -244,783 +454,199 @@ impl Default for MaterialSetConfig {
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
-// fmt
+// helper
 // ----------------------------------------------------------------------------
-use std::fmt;
-
-impl fmt::Debug for TerrainConfig {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "TerrainConfig: {}", self.name())
+/// every clipmap level covers twice the size of the previous level starting
+/// with CLIPMAP_SIZE up to the full map size. at least two levels (full res
+/// and full map) are always used.
+fn max_clipmap_levels(map_size: u32) -> u8 {
+    let levels_by_size = 1 + (map_size / CLIPMAP_SIZE).max(1).ilog2() as u8;
+    levels_by_size.clamp(2, MAX_SUPPORTED_CLIPMAP_LEVEL)
+}
+// ----------------------------------------------------------------------------
+// path helper
+// ----------------------------------------------------------------------------
+fn project_directory(filepath: &str) -> PathBuf {
+    Path::new(filepath)
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default()
+}
+// ----------------------------------------------------------------------------
+/// relative paths are interpreted relative to base directory
+fn resolve_path(base: &Path, path: &str) -> String {
+    if Path::new(path).is_absolute() {
+        path.to_string()
+    } else {
+        normalize(&base.join(path)).to_string_lossy().to_string()
     }
 }
 // ----------------------------------------------------------------------------
-impl TerrainConfig {
-    // ------------------------------------------------------------------------
-    #[allow(dead_code)]
-    pub fn prolog_village(size: u32) -> Self {
-        let basepath = "_test-data_/terrain/";
-        let _hubid = "prolog_village";
+/// path relative to base directory. paths which cannot be expressed relative
+/// to base (e.g. different drive) are returned unchanged.
+fn relative_path(base: &Path, path: &str) -> String {
+    let (base, target) = (normalize(base), normalize(Path::new(path)));
 
-        Self {
-            name: format!("Prologue ({} x {})", size, size),
-            terrain_size: size as f32 / 2.0,
-            map_size: size,
-            resolution: 0.5,
-            min_height: -37.0,
-            max_height: 45.0,
-            heightmap: format!("{}/test.heightmap.{}x{}.png", basepath, size, size),
-            texturemaps: TextureMaps {
-                background: format!("{}/test.bkgrnd.{}x{}.png", basepath, size, size),
-                overlay: format!("{}/test.overlay.{}x{}.png", basepath, size, size),
-                blendcontrol: format!("{}/test.blendcontrol.{}x{}.png", basepath, size, size),
-            },
-            tintmap: format!("{}/test.tint.{}x{}.png", basepath, size, size),
-            clipmap_levels: 3,
-            materialset: MaterialSetConfig::prolog_village(),
-            environment: Some("environment/definitions/env_prologue/env_prolog_colors_v1_b_sunset.env".to_string()),
+    if base.is_absolute() != target.is_absolute() {
+        return path.to_string();
+    }
+    let mut base_components = base.components().peekable();
+    let mut target_components = target.components().peekable();
+
+    let mut common = 0;
+    while let (Some(a), Some(b)) = (base_components.peek(), target_components.peek()) {
+        if a != b {
+            break;
         }
+        base_components.next();
+        target_components.next();
+        common += 1;
     }
-    // ------------------------------------------------------------------------
-    #[allow(dead_code)]
-    pub fn kaer_morhen() -> Self {
-        let basepath = "_test-data_/terrain/";
-        let _hubid = "kaer_morhen";
-        let size = 16384;
-        Self {
-            name: "Kaer Morhen (16384 x 16384)".into(),
-            terrain_size: size as f32 / 2.0,
-            map_size: size,
-            resolution: 0.5,
-            min_height: -118.0,
-            max_height: 1682.0,
-            heightmap: format!("{}/test.heightmap.{}x{}.png", basepath, size, size),
-            texturemaps: TextureMaps {
-                background: format!("{}/test.bkgrnd.{}x{}.png", basepath, size, size),
-                overlay: format!("{}/test.overlay.{}x{}.png", basepath, size, size),
-                blendcontrol: format!("{}/test.blendcontrol.{}x{}.png", basepath, size, size),
-            },
-            tintmap: format!("{}/test.tint.{}x{}.png", basepath, size, size),
-            clipmap_levels: 5,
-            materialset: MaterialSetConfig::kaer_morhen(),
-            environment: Some("environment/definitions/kaer_morhen/kaer_morhen_global/env_kaer_morhen_v09_tm.env".to_string()),
+    // absolute paths without common root (e.g. different drive)
+    if target.is_absolute() && common == 0 {
+        return path.to_string();
+    }
+
+    let mut result = PathBuf::new();
+    for component in base_components {
+        match component {
+            // base is above the common part and cannot be traversed back
+            Component::ParentDir => return path.to_string(),
+            _ => result.push(Component::ParentDir),
         }
     }
-    // ------------------------------------------------------------------------
-    #[allow(dead_code)]
-    pub fn bevy_example() -> Self {
-        let basepath = "_test-data_/terrain/";
-        let _hubid = "bevy";
-        let size = 4096;
-        Self {
-            name: format!("Bevy ({} x {})", size, size),
-            terrain_size: size as f32 / 2.0,
-            map_size: size,
-            resolution: 0.5,
-            min_height: -37.0,
-            max_height: 245.0,
-            heightmap: format!("{}/bevy.heightmap.{}x{}.png", basepath, size, size),
-            texturemaps: TextureMaps {
-                background: format!("{}/bevy.bkgrnd.{}x{}.png", basepath, size, size),
-                overlay: format!("{}/bevy.overlay.{}x{}.png", basepath, size, size),
-                blendcontrol: format!("{}/bevy.blendcontrol.{}x{}.png", basepath, size, size),
+    result.extend(target_components);
+    result.to_string_lossy().to_string()
+}
+// ----------------------------------------------------------------------------
+/// removes "." and resolves ".." (without accessing the filesystem)
+fn normalize(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match result.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    result.pop();
+                }
+                Some(Component::RootDir) | Some(Component::Prefix(_)) => {}
+                _ => result.push(component),
             },
-            tintmap: format!("{}/bevy.tint.{}x{}.png", basepath, size, size),
-            clipmap_levels: 3,
-            materialset: MaterialSetConfig::bevy_example(),
-            environment: Some("environment/definitions/env_prologue/env_prolog_colors_v1_b_sunset.env".to_string()),
+            _ => result.push(component),
         }
     }
-    // ------------------------------------------------------------------------
+    result
 }
 // ----------------------------------------------------------------------------
-impl MaterialSetConfig {
-    // ------------------------------------------------------------------------
-    fn prolog_village() -> Self {
-        let max_textures = 30;
-        let base = "_test-data_/w3.textures/levels/prolog_village/prolog_village";
-        let diffuse = (0..=max_textures.min(30))
-            .map(|i| format!("{}.texarray.texture_{}.png", base, i))
-            .collect::<Vec<_>>();
+// fmt
+// ----------------------------------------------------------------------------
+use std::fmt;
 
-        let normal = (0..=max_textures.min(30))
-            .map(|i| format!("{}_normals.texarray.texture_{}.png", base, i))
-            .collect::<Vec<_>>();
+impl fmt::Debug for TerrainConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TerrainConfig: {}", self.name())
+    }
+}
+// ----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        Self {
-            diffuse,
-            normal,
-            parameter: prolog_material_params().to_vec(),
-        }
+    #[test]
+    fn relative_paths_are_resolved_against_project_directory() {
+        let base = Path::new("projects");
+        assert_eq!(
+            resolve_path(base, "../data/heightmap.png"),
+            Path::new("data/heightmap.png").to_string_lossy()
+        );
+        assert_eq!(
+            resolve_path(base, "maps/./tint.png"),
+            Path::new("projects/maps/tint.png").to_string_lossy()
+        );
+        assert_eq!(
+            resolve_path(Path::new(""), "heightmap.png"),
+            "heightmap.png"
+        );
     }
-    // ------------------------------------------------------------------------
-    fn kaer_morhen() -> Self {
-        let max_textures = 19;
-        let basepath = "_test-data_/w3.textures/levels/kaer_morhen/kaer_morhen_valley";
-        let diffuse = (0..=max_textures.min(19))
-            .map(|i| format!("{}.texarray.texture_{}.png", basepath, i))
-            .collect::<Vec<_>>();
 
-        let normal = (0..=max_textures.min(19))
-            .map(|i| format!("{}_normals.texarray.texture_{}.png", basepath, i))
-            .collect::<Vec<_>>();
+    #[test]
+    fn paths_are_written_relative_to_project_directory() {
+        let base = Path::new("projects/kaer_morhen");
+        assert_eq!(
+            relative_path(base, "projects/kaer_morhen/maps/heightmap.png"),
+            Path::new("maps/heightmap.png").to_string_lossy()
+        );
+        assert_eq!(
+            relative_path(base, "data/heightmap.png"),
+            Path::new("../../data/heightmap.png").to_string_lossy()
+        );
+        // cannot be expressed relative to base
+        assert_eq!(
+            relative_path(Path::new("../projects"), "data/heightmap.png"),
+            "data/heightmap.png"
+        );
+    }
 
-        Self {
-            diffuse,
-            normal,
-            parameter: kaer_morhen_material_params()
-                .iter()
-                .take(20)
-                .cloned()
-                .collect::<Vec<_>>(),
+    #[test]
+    fn resolved_paths_round_trip() {
+        let base = Path::new("projects");
+        for path in [
+            "heightmap.png",
+            "../data/tint.png",
+            "../../textures/diffuse_0.png",
+        ] {
+            assert_eq!(
+                relative_path(base, &resolve_path(base, path)),
+                Path::new(path).to_string_lossy()
+            );
         }
     }
-    // ------------------------------------------------------------------------
-    fn bevy_example() -> Self {
-        let max_textures = 16;
-        let basepath = "_test-data_/textures/";
-        let diffuse = (0..=max_textures.min(16))
-            .map(|i| format!("{}texture.diffuse_{}.png", basepath, i))
-            .collect::<Vec<_>>();
-
-        let normal = (0..=max_textures.min(16))
-            .map(|i| format!("{}texture.normal_{}.png", basepath, i))
-            .collect::<Vec<_>>();
 
-        Self {
-            diffuse,
-            normal,
-            parameter: bevy_example_material_params()
-                .iter()
-                .take(20)
-                .cloned()
-                .collect::<Vec<_>>(),
+    #[test]
+    fn non_finite_sizes_and_heights_are_invalid() {
+        for (terrain_size, min_height, max_height) in [
+            (f32::NAN, 0.0, 100.0),
+            (f32::INFINITY, 0.0, 100.0),
+            (256.0, f32::NAN, 100.0),
+            (256.0, 0.0, f32::INFINITY),
+            (256.0, f32::NEG_INFINITY, 100.0),
+        ] {
+            let config = TerrainConfig {
+                terrain_size,
+                min_height,
+                max_height,
+                ..Default::default()
+            };
+            assert!(config.validate().is_err());
         }
+        assert!(TerrainConfig::default().validate().is_ok());
     }
-    // ------------------------------------------------------------------------
-}
-// ----------------------------------------------------------------------------
-#[allow(clippy::excessive_precision)]
-fn prolog_material_params() -> [TerrainMaterialParam; 31] {
-    [
-        // 1
-        TerrainMaterialParam {
-            blend_sharpness: 0.3650000095,
-            specularity_scale: 0.3225809932,
-            _specularity_scale_copy: 0.3225809932,
-            specularity: 0.224999994,
-            specularity_base: 0.5161290169,
-            ..Default::default()
-        },
-        // 2
-        TerrainMaterialParam {
-            blend_sharpness: 0.163635999,
-            specularity: 0.7170000076,
-            specularity_base: 0.5279999971,
-            ..Default::default()
-        },
-        // 3
-        TerrainMaterialParam {
-            blend_sharpness: 0.2060610056,
-            slope_normal_dampening: 0.0121210003,
-            specularity: 0.2460000068,
-            specularity_base: 0.5360000134,
-            ..Default::default()
-        },
-        // 4
-        TerrainMaterialParam {
-            blend_sharpness: 0.3220340014,
-            slope_base_dampening: 0.2711859941,
-            slope_normal_dampening: 0.4848479927,
-            specularity: 0.2630000114,
-            specularity_base: 0.5429999828,
-            ..Default::default()
-        },
-        // 5
-        TerrainMaterialParam {
-            blend_sharpness: 0.1557790041,
-            specularity: 0.0542169996,
-            specularity_base: 0.566264987,
-            ..Default::default()
-        },
-        // --- 6
-        TerrainMaterialParam {
-            blend_sharpness: 0.1700000018,
-            specularity_scale: 0.0160000008,
-            specularity: 0.0903609991,
-            specularity_base: 0.566264987,
-            _specularity_scale_copy: 0.0160000008,
-            ..Default::default()
-        },
-        // 7
-        TerrainMaterialParam {
-            specularity: 0.4169999957,
-            specularity_base: 0.5099999905,
-            _specularity_scale_copy: 0.3225809932,
-            ..Default::default()
-        },
-        // 8
-        TerrainMaterialParam {
-            blend_sharpness: 0.5921049714,
-            slope_base_dampening: 0.5789470077,
-            specularity_scale: 0.0160000008,
-            specularity: 0.52700001,
-            specularity_base: 0.5120000243,
-            _specularity_scale_copy: 0.0160000008,
-            ..Default::default()
-        },
-        // 9
-        TerrainMaterialParam {
-            specularity: 0.3870970011,
-            specularity_base: 0.5322579741,
-            ..Default::default()
-        },
-        // 10
-        TerrainMaterialParam {
-            blend_sharpness: 0.1368419975,
-            specularity_scale: 0.1700000018,
-            specularity: 0.224999994,
-            specularity_base: 0.4779999852,
-            _specularity_scale_copy: 0.1700000018,
-            ..Default::default()
-        },
-        // --- 11
-        TerrainMaterialParam {
-            blend_sharpness: 0.1684210002,
-            slope_base_dampening: 0.1894740015,
-            specularity_scale: 0.903226018,
-            specularity: 0.3870970011,
-            specularity_base: 0.5645160079,
-            _specularity_scale_copy: 0.903226018,
-            ..Default::default()
-        },
-        // 12
-        TerrainMaterialParam {
-            blend_sharpness: 0.5368419886,
-            slope_base_dampening: 0.400000006,
-            specularity_scale: 0.8548390269,
-            specularity: 0.370968014,
-            specularity_base: 0.596773982,
-            _specularity_scale_copy: 0.8548390269,
-            ..Default::default()
-        },
-        // 13
-        TerrainMaterialParam {
-            blend_sharpness: 0.1789470017,
-            specularity: 0.4609999955,
-            specularity_base: 0.5210000277,
-            ..Default::default()
-        },
-        // 14
-        TerrainMaterialParam {
-            blend_sharpness: 0.3644070029,
-            slope_base_dampening: 0.3644070029,
-            specularity: 0.4838710129,
-            specularity_base: 0.548386991,
-            ..Default::default()
-        },
-        // 15
-        TerrainMaterialParam {
-            blend_sharpness: 0.2150000036,
-            specularity_scale: 0.0869999975,
-            specularity: 0.351000011,
-            specularity_base: 0.4889999926,
-            _specularity_scale_copy: 0.0869999975,
-            ..Default::default()
-        },
-        // --- 16
-        TerrainMaterialParam {
-            slope_normal_dampening: 0.2181819975,
-            specularity_scale: 0.1640000045,
-            specularity: 0.4230000079,
-            specularity_base: 0.3619999886,
-            _specularity_scale_copy: 0.1640000045,
-            ..Default::default()
-        },
-        // 17
-        TerrainMaterialParam {
-            blend_sharpness: 0.1199999973,
-            specularity: 0.3790000081,
-            specularity_base: 0.5490000248,
-            ..Default::default()
-        },
-        // 18
-        TerrainMaterialParam {
-            blend_sharpness: 0.1757580042,
-            slope_base_dampening: 0.9878789783,
-            specularity: 0.5161290169,
-            specularity_base: 0.5645160079,
-            ..Default::default()
-        },
-        // 19
-        TerrainMaterialParam {
-            blend_sharpness: 0.1299999952,
-            specularity_scale: 0.0049999999,
-            specularity: 0.4720000029,
-            specularity_base: 0.5870000124,
-            _specularity_scale_copy: 0.0049999999,
-            ..Default::default()
-        },
-        // 20
-        TerrainMaterialParam {
-            blend_sharpness: 0.1052630022,
-            slope_base_dampening: 0.1016950011,
-            specularity: 0.1199999973,
-            specularity_base: 0.5870000124,
-            ..Default::default()
-        },
-        // --- 21
-        TerrainMaterialParam {
-            specularity_scale: 0.7741940022,
-            specularity: 0.1612900048,
-            specularity_base: 0.419355005,
-            _specularity_scale_copy: 0.7741940022,
-            ..Default::default()
-        },
-        // 22
-        TerrainMaterialParam {
-            specularity: 0.3449999988,
-            specularity_base: 0.5640000105,
-            ..Default::default()
-        },
-        // 23
-        TerrainMaterialParam {
-            specularity: 0.4169999957,
-            specularity_base: 0.5490000248,
-            ..Default::default()
-        },
-        // 24
-        TerrainMaterialParam {
-            ..Default::default()
-        },
-        // 25
-        TerrainMaterialParam {
-            blend_sharpness: 0.4322029948,
-            slope_base_dampening: 0.4067800045,
-            specularity: 0.3619999886,
-            specularity_base: 0.5149999857,
-            ..Default::default()
-        },
-        // --- 26
-        TerrainMaterialParam {
-            blend_sharpness: 0.174999997,
-            specularity: 0.370968014,
-            specularity_base: 0.5645160079,
-            falloff: 0.0549999997,
-            ..Default::default()
-        },
-        // 27
-        TerrainMaterialParam {
-            blend_sharpness: 0.3449999988,
-            specularity_scale: 0.2586210072,
-            specularity: 0.419355005,
-            specularity_base: 0.4838710129,
-            falloff: 0.3620690107,
-            _specularity_scale_copy: 0.2586210072,
-            ..Default::default()
-        },
-        // 28
-        TerrainMaterialParam {
-            blend_sharpness: 0.1319440007,
-            specularity: 0.3680000007,
-            specularity_base: 0.5049999952,
-            ..Default::default()
-        },
-        // 29
-        TerrainMaterialParam {
-            specularity_base: 0.5049999952,
-            ..Default::default()
-        },
-        // 30
-        TerrainMaterialParam {
-            specularity_scale: 0.4720000029,
-            specularity: 0.4449999928,
-            specularity_base: 0.4779999852,
-            _specularity_scale_copy: 0.4720000029,
-            ..Default::default()
-        },
-        // --- 31
-        TerrainMaterialParam {
-            specularity: 0.4889999926,
-            specularity_base: 0.5490000248,
-            ..Default::default()
-        },
-    ]
-}
-// ----------------------------------------------------------------------------
-#[allow(clippy::excessive_precision)]
-fn kaer_morhen_material_params() -> [TerrainMaterialParam; 30] {
-    [
-        // 01:
-        TerrainMaterialParam {
-            blend_sharpness: 0.3797470033,
-            slope_base_dampening: 0.6204379797,
-            specularity_scale: 0.419355005,
-            _specularity_scale_copy: 0.419355005,
-            specularity: 0.4032259881,
-            specularity_base: 0.451613009,
-            ..Default::default()
-        },
-        // 02:
-        TerrainMaterialParam {
-            blend_sharpness: 0.075000003,
-            slope_base_dampening: 1.0,
-            specularity: 0.6774190068,
-            specularity_base: 0.548386991,
-            ..Default::default()
-        },
-        // 03:
-        TerrainMaterialParam {
-            blend_sharpness: 0.1389999986,
-            slope_base_dampening: 0.2599999905,
-            specularity: 0.577113986,
-            specularity_base: 0.5174130201,
-            ..Default::default()
-        },
-        // 04:
-        TerrainMaterialParam {
-            specularity: 0.4776119888,
-            specularity_base: 0.5519999862,
-            ..Default::default()
-        },
-        // 05:
-        TerrainMaterialParam {
-            blend_sharpness: 0.139240995,
-            slope_base_dampening: 1.0,
-            specularity: 0.6290320158,
-            specularity_base: 0.5479999781,
-            ..Default::default()
-        },
-        // 06:
-        TerrainMaterialParam {
-            slope_base_dampening: 1.0,
-            specularity: 0.5671640038,
-            specularity_base: 0.562188983,
-            ..Default::default()
-        },
-        // 07:
-        TerrainMaterialParam {
-            blend_sharpness: 0.2300000042,
-            slope_base_dampening: 1.0,
-            specularity: 0.6069650054,
-            specularity_base: 0.5619999766,
-            ..Default::default()
-        },
-        // 08:
-        TerrainMaterialParam {
-            blend_sharpness: 0.4487800002,
-            slope_base_dampening: 0.2150000036,
-            specularity: 0.75,
-            specularity_base: 0.5174130201,
-            ..Default::default()
-        },
-        // 09:
-        TerrainMaterialParam {
-            blend_sharpness: 0.2303919941,
-            slope_base_dampening: 0.112999998,
-            specularity: 0.4925369918,
-            specularity_base: 0.52700001,
-            ..Default::default()
-        },
-        // 10:
-        TerrainMaterialParam {
-            blend_sharpness: 0.2025319934,
-            slope_base_dampening: 0.025316,
-            slope_normal_dampening: 0.3759999871,
-            specularity_scale: 0.3870970011,
-            _specularity_scale_copy: 0.3870970011,
-            specularity: 0.1935479939,
-            specularity_base: 0.467741996,
-            ..Default::default()
-        },
-        // 11:
-        TerrainMaterialParam {
-            blend_sharpness: 0.177214995,
-            slope_base_dampening: 0.4550000131,
-            specularity_scale: 0.5024880171,
-            _specularity_scale_copy: 0.5024880171,
-            specularity: 0.3980099857,
-            specularity_base: 0.4420000017,
-            ..Default::default()
-        },
-        // 12:
-        TerrainMaterialParam {
-            blend_sharpness: 0.1150000021,
-            slope_base_dampening: 0.5690000057,
-            specularity: 0.4726369977,
-            specularity_base: 0.5469999909,
-            ..Default::default()
-        },
-        // 13:
-        TerrainMaterialParam {
-            blend_sharpness: 0.150820002,
-            slope_base_dampening: 0.1770000011,
-            slope_normal_dampening: 0.376812011,
-            specularity: 0.4676620066,
-            specularity_base: 0.5009999871,
-            ..Default::default()
-        },
-        // 14:
-        TerrainMaterialParam {
-            blend_sharpness: 0.0759489983,
-            slope_base_dampening: 0.1449999958,
-            specularity: 0.5370000005,
-            specularity_base: 0.5,
-            ..Default::default()
-        },
-        // 15:
-        TerrainMaterialParam {
-            blend_sharpness: 0.3670000136,
-            slope_base_dampening: 0.3409999907,
-            specularity_scale: 0.1442790031,
-            _specularity_scale_copy: 0.1442790031,
-            specularity: 0.5273630023,
-            specularity_base: 0.5070000291,
-            ..Default::default()
-        },
-        // 16:
-        TerrainMaterialParam {
-            blend_sharpness: 0.0759489983,
-            slope_base_dampening: 0.1889999956,
-            specularity: 0.4079599977,
-            specularity_base: 0.5170000196,
-            ..Default::default()
-        },
-        // 17:
-        TerrainMaterialParam {
-            blend_sharpness: 0.151898995,
-            slope_base_dampening: 0.2399999946,
-            specularity_scale: 0.1612900048,
-            _specularity_scale_copy: 0.1612900048,
-            specularity: 0.4726369977,
-            specularity_base: 0.5161290169,
-            ..Default::default()
-        },
-        // 18:
-        TerrainMaterialParam {
-            blend_sharpness: 0.0886079967,
-            slope_base_dampening: 0.3030000031,
-            specularity: 0.548386991,
-            specularity_base: 0.5161290169,
-            ..Default::default()
-        },
-        // 19:
-        TerrainMaterialParam {
-            blend_sharpness: 0.0632909983,
-            slope_base_dampening: 1.0,
-            specularity: 0.4354839921,
-            specularity_base: 0.5070000291,
-            ..Default::default()
-        },
-        // 20:
-        TerrainMaterialParam {
-            blend_sharpness: 0.3670890033,
-            slope_base_dampening: 1.0,
-            specularity_scale: 0.7580649853,
-            _specularity_scale_copy: 0.7580649853,
-            specularity: 0.2096769959,
-            specularity_base: 0.419355005,
-            ..Default::default()
-        },
-        // 21:
-        TerrainMaterialParam {
-            blend_sharpness: 0.0886079967,
-            slope_base_dampening: 0.189872995,
-            specularity_scale: 0.3283579946,
-            _specularity_scale_copy: 0.3283579946,
-            specularity: 0.5323380232,
-            specularity_base: 0.4970000088,
-            ..Default::default()
-        },
-        // 22:
-        TerrainMaterialParam {
-            blend_sharpness: 0.1139239967,
-            slope_base_dampening: 0.2405059934,
-            specularity: 0.288556993,
-            specularity_base: 0.5960000157,
-            ..Default::default()
-        },
-        // 23:
-        TerrainMaterialParam {
-            blend_sharpness: 0.0506329983,
-            slope_base_dampening: 0.3037970066,
-            specularity: 0.3930349946,
-            specularity_base: 0.4925369918,
-            ..Default::default()
-        },
-        // 24:
-        TerrainMaterialParam {
-            blend_sharpness: 0.1012659967,
-            slope_base_dampening: 0.9240509868,
-            specularity: 0.6119400263,
-            specularity_base: 0.5170000196,
-            ..Default::default()
-        },
-        // 25:
-        TerrainMaterialParam {
-            blend_sharpness: 0.1012659967,
-            slope_base_dampening: 0.177214995,
-            specularity: 0.5671640038,
-            specularity_base: 1.0,
-            ..Default::default()
-        },
-        // 26:
-        TerrainMaterialParam {
-            blend_sharpness: 0.0886079967,
-            slope_base_dampening: 1.0,
-            specularity: 0.3980099857,
-            specularity_base: 0.4925369918,
-            ..Default::default()
-        },
-        // 27:
-        TerrainMaterialParam {
-            blend_sharpness: 0.0886079967,
-            slope_base_dampening: 0.4854010046,
-            specularity: 0.8358209729,
-            specularity_base: 0.5024880171,
-            ..Default::default()
-        },
-        // 28:
-        TerrainMaterialParam {
-            blend_sharpness: 0.1265819967,
-            slope_base_dampening: 1.0,
-            specularity: 0.4776119888,
-            specularity_base: 0.5124379992,
-            ..Default::default()
-        },
-        // 29:
-        TerrainMaterialParam {
-            blend_sharpness: 0.189872995,
-            slope_base_dampening: 0.987342,
-            specularity: 0.5373129845,
-            specularity_base: 0.5223879814,
-            ..Default::default()
-        },
-        // 30:
-        TerrainMaterialParam {
-            specularity_scale: 0.5970150232,
-            _specularity_scale_copy: 0.5970150232,
-            specularity: 0.3134329915,
-            specularity_base: 0.4726369977,
-            ..Default::default()
-        },
-    ]
-}
-// ----------------------------------------------------------------------------
-#[allow(clippy::excessive_precision)]
-fn bevy_example_material_params() -> [TerrainMaterialParam; 16] {
-    [
-        // 1 grass ~ mossy
-        TerrainMaterialParam {
-            blend_sharpness: 0.45,
-            slope_base_dampening: 0.22,
-            slope_normal_dampening: 0.5,
-            ..Default::default()
-        },
-        // 2 rocks
-        TerrainMaterialParam {
-            blend_sharpness: 0.35,
-            slope_base_dampening: 0.35,
-            slope_normal_dampening: 0.5,
-            ..Default::default()
-        },
-        // 3 small rocks on rocks
-        TerrainMaterialParam {
-            blend_sharpness: 0.08,
-            slope_base_dampening: 0.3,
-            slope_normal_dampening: 0.5,
-            ..Default::default()
-        },
-        // 4 sand
-        TerrainMaterialParam {
-            blend_sharpness: 0.12,
-            slope_base_dampening: 0.5,
-            slope_normal_dampening: 0.5,
-            ..Default::default()
-        },
-        // 5 sand curly
-        TerrainMaterialParam {
-            blend_sharpness: 0.5,
-            slope_base_dampening: 0.5,
-            slope_normal_dampening: 0.5,
-            ..Default::default()
-        },
-        // 6 stones bkgrnd
-        TerrainMaterialParam {
-            blend_sharpness: 0.2,
-            slope_base_dampening: 0.4,
-            slope_normal_dampening: 0.5,
-            ..Default::default()
-        },
-        // 7 grass ~ hard cover rocks, as bkgrnd will be hidden on horizontal terrain
-        TerrainMaterialParam {
-            blend_sharpness: 0.3,
-            slope_base_dampening: 1.0,
-            slope_normal_dampening: 0.5,
-            ..Default::default()
-        },
-        // 8 rock dark
-        TerrainMaterialParam {
-            blend_sharpness: 0.5,
-            slope_base_dampening: 1.0,
-            slope_normal_dampening: 0.5,
-            ..Default::default()
-        },
-        // 9 rock grey
-        TerrainMaterialParam {
-            blend_sharpness: 0.5,
-            slope_base_dampening: 1.0,
-            slope_normal_dampening: 0.5,
-            ..Default::default()
-        },
-        // 10 rock light grey
-        TerrainMaterialParam {
-            blend_sharpness: 0.5,
-            slope_base_dampening: 1.0,
-            slope_normal_dampening: 0.5,
-            ..Default::default()
-        },
-        // 11 smaller rocks dark
-        TerrainMaterialParam {
-            blend_sharpness: 0.7,
-            slope_base_dampening: 0.7,
-            slope_normal_dampening: 1.0,
-            ..Default::default()
-        },
-        // 12 smaller rocks grey
-        TerrainMaterialParam {
-            blend_sharpness: 0.7,
-            slope_base_dampening: 0.7,
-            slope_normal_dampening: 1.0,
-            ..Default::default()
-        },
-        // 13 smaller rocks light greay
-        TerrainMaterialParam {
-            blend_sharpness: 0.7,
-            slope_base_dampening: 0.7,
-            slope_normal_dampening: 1.0,
-            ..Default::default()
-        },
-        // 14 pebbles dark
-        TerrainMaterialParam {
-            blend_sharpness: 0.3,
-            slope_base_dampening: 0.2,
-            slope_normal_dampening: 0.5,
-            ..Default::default()
-        },
-        // 15 pebbles grey
-        TerrainMaterialParam {
-            blend_sharpness: 0.3,
-            slope_base_dampening: 0.2,
-            slope_normal_dampening: 0.5,
-            ..Default::default()
-        },
-        // 16 pebbles light grey
-        TerrainMaterialParam {
-            blend_sharpness: 0.3,
-            slope_base_dampening: 0.2,
-            slope_normal_dampening: 0.5,
+
+    #[test]
+    fn clipmap_levels_are_limited_by_map_size() {
+        assert_eq!(max_clipmap_levels(512), 2);
+        assert_eq!(max_clipmap_levels(2048), 2);
+        assert_eq!(max_clipmap_levels(4096), 3);
+        assert_eq!(max_clipmap_levels(16384), 5);
+
+        let mut config = TerrainConfig {
+            clipmap_levels: 3,
             ..Default::default()
-        }
-    ]
+        };
+        config.set_map_size(2048);
+        assert!(config.validate().is_err());
+        config.set_map_size(4096);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn missing_material_parameters_are_added() {
+        let mut materialset = MaterialSetConfig::default();
+        materialset.parameter.truncate(2);
+        assert_eq!(materialset.validate().len(), 1);
+
+        materialset.add_missing_parameters("project.ron");
+        assert_eq!(materialset.parameter.len(), MAX_MATERIALS);
+        assert!(materialset.validate().is_empty());
+    }
 }
-// ----------------------------------------------------------------------------
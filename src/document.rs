@@ -0,0 +1,567 @@
+// ----------------------------------------------------------------------------
+// headless terrain data processing (no bevy app, window or gpu required)
+// ----------------------------------------------------------------------------
+use std::path::Path;
+
+use glam::UVec2;
+
+use futures_lite::future;
+
+use crate::clipmap::{ClipmapData, Rectangle};
+use crate::config::{TerrainConfig, TILE_SIZE};
+use crate::heightmap::{TerrainHeightMap, TerrainNormals, TerrainTileId};
+use crate::loader::LoaderPlugin;
+use crate::terrain_painting::{
+    self, BrushPlacement, ControlMapEntry, MaterialRemapStats, MaterialRemapTable, MaterialUsage,
+    PaintCommand, PaintingRng, RngStream, StrokeId,
+};
+use crate::terrain_tiles::{TerrainMesh, TileErrorMaps};
+use crate::texturecontrol::TextureControl;
+use crate::tileexport::TileExportSettings;
+use crate::tintmap::TintMap;
+use crate::writer::WriterPlugin;
+use crate::TaskResultData;
+// ----------------------------------------------------------------------------
+/// Terrain data (heightmap, texture control map and tint map) of a project
+/// with the same painting, saving and mesh generation operations as the
+/// editor. All operations are blocking.
+pub struct TerrainDocument {
+    config: TerrainConfig,
+    heightmap: TerrainHeightMap,
+    texture_control: TextureControl,
+    tintmap: TintMap,
+    modified: ModifiedData,
+    /// lazily (re)generated after heightmap changes
+    normals: Option<TerrainNormals>,
+    errormaps: Option<TileErrorMaps>,
+    painting_rng: PaintingRng,
+    stroke: StrokeId,
+}
+// ----------------------------------------------------------------------------
+#[derive(Default)]
+struct ModifiedData {
+    heightmap: bool,
+    texture_control: bool,
+    tintmap: bool,
+}
+// ----------------------------------------------------------------------------
+impl TerrainDocument {
+    // ------------------------------------------------------------------------
+    /// loads all terrain data defined in the config. missing heightmap,
+    /// texturing or tint map files are replaced by the same defaults as in
+    /// the editor. texturing maps must be defined either all or none as the
+    /// texture control map can only be saved into all three maps.
+    pub fn open(config: TerrainConfig) -> Result<Self, String> {
+        config
+            .validate()
+            .map_err(|errors| format!("invalid terrain config:\n{}", errors.join("\n")))?;
+
+        let texturing = config.texturemaps();
+        let missing = [
+            (texturing.background(), "background"),
+            (texturing.overlay(), "overlay"),
+            (texturing.blendcontrol(), "blendcontrol"),
+        ]
+        .iter()
+        .filter(|(filepath, _)| filepath.is_empty())
+        .map(|(_, name)| *name)
+        .collect::<Vec<_>>();
+
+        if !missing.is_empty() && missing.len() < 3 {
+            return Err(format!(
+                "invalid terrain config: missing filepath for texturing map(s): {}",
+                missing.join(", ")
+            ));
+        }
+
+        let heightmap = match future::block_on(LoaderPlugin::load_heightmap(&config))? {
+            TaskResultData::HeightmapData(heightmap) => heightmap,
+            _ => unreachable!("heightmap loader returned unexpected data"),
+        };
+        let texture_control = match future::block_on(LoaderPlugin::load_texturemap(&config))? {
+            TaskResultData::TextureControl(texture_control) => texture_control,
+            _ => unreachable!("texturemap loader returned unexpected data"),
+        };
+        let tintmap = match future::block_on(LoaderPlugin::load_tintmap(&config))? {
+            TaskResultData::TintMap(tintmap) => tintmap,
+            _ => unreachable!("tintmap loader returned unexpected data"),
+        };
+
+        Ok(Self {
+            config,
+            heightmap,
+            texture_control,
+            tintmap,
+            modified: ModifiedData::default(),
+            normals: None,
+            errormaps: None,
+            painting_rng: PaintingRng::default(),
+            stroke: StrokeId::default(),
+        })
+    }
+    // ------------------------------------------------------------------------
+    /// checks config, formats and value ranges of all maps without loading
+    /// them. reports all found problems.
+    pub fn validate(config: &TerrainConfig) -> Result<(), Vec<String>> {
+        LoaderPlugin::validate_terrain_data(config)
+    }
+    // ------------------------------------------------------------------------
+    pub fn config(&self) -> &TerrainConfig {
+        &self.config
+    }
+    // ------------------------------------------------------------------------
+    /// set a fixed session seed to make randomized commands reproducible
+    pub fn painting_rng_mut(&mut self) -> &mut PaintingRng {
+        &mut self.painting_rng
+    }
+    // ------------------------------------------------------------------------
+    pub fn is_modified(&self) -> bool {
+        self.modified.heightmap || self.modified.texture_control || self.modified.tintmap
+    }
+    // ------------------------------------------------------------------------
+    /// applies the paint command at brush placement (world coordinates). every
+    /// call is handled as a separate stroke.
+    pub fn apply(&mut self, cmd: PaintCommand, placement: &BrushPlacement) {
+        let cmds = std::slice::from_ref(&cmd);
+
+        let (rectangle, mask) =
+            terrain_painting::calculate_region_of_interest(&self.config, placement);
+
+        self.stroke = self.stroke.next();
+
+        // same rng streams as the editor systems
+        if cmd.is_texture_control_cmd() {
+            let rng = self
+                .painting_rng
+                .stroke_rng(self.stroke, RngStream::TextureControl);
+            let mut data = extract(&self.texture_control, &rectangle);
+            terrain_painting::apply_texture_control_cmds(rng, &mask, &mut data, cmds);
+            update(&mut self.texture_control, &rectangle, &data);
+            self.modified.texture_control = true;
+        } else if cmd.is_tint_cmd() {
+            let rng = self.painting_rng.stroke_rng(self.stroke, RngStream::Tint);
+            let mut data = extract(&self.tintmap, &rectangle);
+            terrain_painting::apply_tint_cmds(rng, &mask, &mut data, cmds);
+            update(&mut self.tintmap, &rectangle, &data);
+            self.modified.tintmap = true;
+        } else {
+            let rng = self.painting_rng.stroke_rng(self.stroke, RngStream::Sculpt);
+            let mut data = self.heightmap.extract(&rectangle);
+            let width = rectangle.size.x as usize;
+            terrain_painting::apply_sculpt_cmds(rng, &mask, &mut data, width, cmds);
+            self.heightmap.update_rectangle(&rectangle, &data);
+            self.modified.heightmap = true;
+
+            // normals and errormaps depend on heightmap
+            self.normals = None;
+            self.errormaps = None;
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// decoded texture control value at map position
+    pub fn control_map_entry(&self, map_pos: UVec2) -> Option<ControlMapEntry> {
+        let size = self.texture_control.size();
+        (map_pos.x < size && map_pos.y < size).then(|| {
+            let data = self.texture_control.as_slice();
+            ControlMapEntry::decode(data[(map_pos.y * size + map_pos.x) as usize])
+        })
+    }
+    // ------------------------------------------------------------------------
+    pub fn material_usage(&self) -> MaterialUsage {
+        MaterialUsage::from_texture_control(self.texture_control.as_slice())
+    }
+    // ------------------------------------------------------------------------
+    /// rewrites material slots of the complete texture control map (see
+    /// MaterialRemapTable) and returns the changed datapoints per table entry
+    pub fn remap_materials(&mut self, table: &MaterialRemapTable) -> MaterialRemapStats {
+        let stats =
+            terrain_painting::remap_texture_control(table, self.texture_control.as_slice_mut());
+
+        if stats.datapoints > 0 {
+            self.modified.texture_control = true;
+        }
+        stats
+    }
+    // ------------------------------------------------------------------------
+    /// lowest and highest terrain height in meters
+    pub fn height_range(&self) -> (f32, f32) {
+        let (min, max) = self
+            .heightmap
+            .as_slice()
+            .iter()
+            .fold((u16::MAX, u16::MIN), |(min, max), h| {
+                (min.min(*h), max.max(*h))
+            });
+
+        let scaling = self.config.height_scaling();
+        let offset = self.config.min_height();
+        (offset + min as f32 * scaling, offset + max as f32 * scaling)
+    }
+    // ------------------------------------------------------------------------
+    /// saves the packed texture control map (background, overlay and blend
+    /// control) as one 16bit grayscale png
+    pub fn export_control_map(&self, filepath: &str) -> Result<(), String> {
+        use png::{BitDepth::Sixteen, ColorType::Grayscale};
+
+        let data = self
+            .texture_control
+            .as_slice()
+            .iter()
+            .flat_map(|v| v.to_be_bytes())
+            .collect::<Vec<_>>();
+
+        let tmpfile = WriterPlugin::save_png_data_tmp(
+            Grayscale,
+            Sixteen,
+            self.texture_control.size(),
+            None,
+            &data,
+            filepath,
+        )?;
+        WriterPlugin::replace_files(&[(tmpfile, filepath)])
+    }
+    // ------------------------------------------------------------------------
+    /// replaces the texture control map with a packed 16bit grayscale png (see
+    /// export_control_map). saving splits it into the texturing maps defined in
+    /// the config.
+    pub fn import_control_map(&mut self, filepath: &str) -> Result<(), String> {
+        use png::{BitDepth::Sixteen, ColorType::Grayscale};
+
+        let size = self.config.map_size();
+        let data = LoaderPlugin::load_png_data(Grayscale, Sixteen, size, filepath)?
+            .chunks_exact(2)
+            .map(|v| u16::from_be_bytes([v[0], v[1]]))
+            .collect::<Vec<_>>();
+
+        self.texture_control = TextureControl::new(size, data);
+        self.modified.texture_control = true;
+        Ok(())
+    }
+    // ------------------------------------------------------------------------
+    /// changes map size of all maps: heightmap and tint map are interpolated
+    /// bilinear, texture control values are discrete and use nearest sampling.
+    /// all maps with a filepath are marked as modified.
+    pub fn resample(&mut self, map_size: u32) -> Result<(), String> {
+        let mut config = self.config.clone();
+        config.set_map_size(map_size);
+        config
+            .validate()
+            .map_err(|errors| format!("invalid map size:\n{}", errors.join("\n")))?;
+
+        let size = self.config.map_size();
+        let heightmap = resample_bilinear(self.heightmap.as_slice(), size, 1, map_size, |v| {
+            v.round().clamp(0.0, u16::MAX as f32) as u16
+        });
+        let tintmap = resample_bilinear(self.tintmap.as_slice(), size, 4, map_size, |v| {
+            v.round().clamp(0.0, u8::MAX as f32) as u8
+        });
+        let texture_control = resample_nearest(self.texture_control.as_slice(), size, map_size);
+
+        self.heightmap = TerrainHeightMap::new(map_size, config.height_scaling(), heightmap);
+        self.tintmap = TintMap::new(map_size, tintmap);
+        self.texture_control = TextureControl::new(map_size, texture_control);
+        self.modified = ModifiedData::with_filepaths(&config);
+        self.config = config;
+
+        self.normals = None;
+        self.errormaps = None;
+        Ok(())
+    }
+    // ------------------------------------------------------------------------
+    /// moves all maps into directory (keeping the filenames), e.g. to save a
+    /// converted copy. all maps with a filepath are marked as modified.
+    pub fn relocate_maps(&mut self, directory: &str) {
+        let relocate = |filepath: &str| match Path::new(filepath).file_name() {
+            Some(filename) if !filepath.is_empty() => Path::new(directory)
+                .join(filename)
+                .to_string_lossy()
+                .to_string(),
+            _ => String::new(),
+        };
+
+        let texturing = self.config.texturemaps().clone();
+        let heightmap = relocate(self.config.heightmap());
+        let tintmap = relocate(self.config.tintmap());
+
+        self.config.set_heightmap(&heightmap);
+        self.config.set_texturemaps(
+            &relocate(texturing.background()),
+            &relocate(texturing.overlay()),
+            &relocate(texturing.blendcontrol()),
+        );
+        self.config.set_tintmap(&tintmap);
+        self.modified = ModifiedData::with_filepaths(&self.config);
+    }
+    // ------------------------------------------------------------------------
+    /// saves all modified data to the files defined in the config. maps
+    /// without filepath in the config are skipped.
+    pub fn save(&mut self) -> Result<(), String> {
+        let saveable = ModifiedData::with_filepaths(&self.config);
+
+        if self.modified.heightmap && saveable.heightmap {
+            future::block_on(WriterPlugin::save_heightmap(
+                &self.config,
+                self.heightmap.clone(),
+            ))?;
+            self.modified.heightmap = false;
+        }
+        if self.modified.texture_control && saveable.texture_control {
+            future::block_on(WriterPlugin::save_texturemap(
+                &self.config,
+                self.texture_control.as_slice().to_vec(),
+            ))?;
+            self.modified.texture_control = false;
+        }
+        if self.modified.tintmap && saveable.tintmap {
+            future::block_on(WriterPlugin::save_tintmap(
+                &self.config,
+                self.tintmap.as_slice().to_vec(),
+            ))?;
+            self.modified.tintmap = false;
+        }
+        Ok(())
+    }
+    // ------------------------------------------------------------------------
+    /// exports heightmap as headerless 16bit little endian raw (.r16) file
+    /// next to the heightmap png
+    pub fn export_heightmap_r16(&self) -> Result<(), String> {
+        future::block_on(WriterPlugin::export_heightmap_r16(
+            &self.config,
+            self.heightmap.clone(),
+        ))
+        .map(|_| ())
+    }
+    // ------------------------------------------------------------------------
+    /// exports the selected maps as per tile buffers of the game and a manifest
+    /// into the directory of the settings
+    pub fn export_tiles(&self, settings: TileExportSettings) -> Result<(), String> {
+        future::block_on(WriterPlugin::export_tiles(
+            &self.config,
+            settings,
+            self.heightmap.clone(),
+            self.texture_control.as_slice().to_vec(),
+            self.tintmap.as_slice().to_vec(),
+        ))
+        .map(|_| ())
+    }
+    // ------------------------------------------------------------------------
+    /// generates the mesh of the tile with the same error threshold for the
+    /// complete tile. terrain holes are cut out. errormaps are (re)generated
+    /// for all tiles on first call and after sculpting.
+    pub fn generate_tile_mesh(
+        &mut self,
+        tile_id: TerrainTileId<TILE_SIZE>,
+        error_threshold: f32,
+    ) -> Result<TerrainMesh, String> {
+        let normals = &*self
+            .normals
+            .get_or_insert_with(|| TerrainNormals::generate(&self.heightmap, &self.config));
+
+        let errormaps = self
+            .errormaps
+            .get_or_insert_with(|| TileErrorMaps::generate(&self.config, &self.heightmap, normals));
+
+        errormaps.generate_mesh(
+            &self.config,
+            &self.heightmap,
+            normals,
+            self.texture_control.as_slice(),
+            tile_id,
+            error_threshold,
+        )
+    }
+    // ------------------------------------------------------------------------
+    /// downscaled (point sampled) texture control map, e.g. for previews. same
+    /// sampling as the clipmap levels.
+    pub fn downscaled_texture_control(&self, target_size: u32) -> Vec<u16> {
+        downscale(&self.texture_control, target_size)
+    }
+    // ------------------------------------------------------------------------
+    /// downscaled (point sampled) RGBA tint map. same sampling as the clipmap
+    /// levels.
+    pub fn downscaled_tintmap(&self, target_size: u32) -> Vec<u8> {
+        downscale(&self.tintmap, target_size)
+    }
+    // ------------------------------------------------------------------------
+    /// downscaled heightmap. same sampling as the clipmap levels.
+    pub fn downscaled_heightmap(&self, target_size: u32) -> Vec<u16> {
+        downscale(&self.heightmap, target_size)
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl ModifiedData {
+    // ------------------------------------------------------------------------
+    fn with_filepaths(config: &TerrainConfig) -> Self {
+        Self {
+            heightmap: !config.heightmap().is_empty(),
+            texture_control: has_texturemap_filepaths(config),
+            tintmap: !config.tintmap().is_empty(),
+        }
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+// helper
+// ----------------------------------------------------------------------------
+/// texture control map is saved only if all three texturing maps have a path
+fn has_texturemap_filepaths(config: &TerrainConfig) -> bool {
+    let texturing = config.texturemaps();
+    !texturing.background().is_empty()
+        && !texturing.overlay().is_empty()
+        && !texturing.blendcontrol().is_empty()
+}
+// ----------------------------------------------------------------------------
+fn extract<D: ClipmapData>(data: &D, rectangle: &Rectangle) -> Vec<D::DataType> {
+    let datapoint_size = data.datapoint_size() as usize;
+    let line_size = datapoint_size * data.size() as usize;
+    let (x, width) = (
+        datapoint_size * rectangle.pos.x as usize,
+        datapoint_size * rectangle.size.x as usize,
+    );
+
+    data.as_slice()
+        .chunks_exact(line_size)
+        .skip(rectangle.pos.y as usize)
+        .take(rectangle.size.y as usize)
+        .flat_map(|row| &row[x..x + width])
+        .copied()
+        .collect()
+}
+// ----------------------------------------------------------------------------
+fn update<D: ClipmapData>(data: &mut D, rectangle: &Rectangle, new_data: &[D::DataType]) {
+    let datapoint_size = data.datapoint_size() as usize;
+    let line_size = datapoint_size * data.size() as usize;
+    let (x, width) = (
+        datapoint_size * rectangle.pos.x as usize,
+        datapoint_size * rectangle.size.x as usize,
+    );
+
+    for (row, src) in data
+        .as_slice_mut()
+        .chunks_exact_mut(line_size)
+        .skip(rectangle.pos.y as usize)
+        .zip(new_data.chunks_exact(width))
+    {
+        row[x..x + width].copy_from_slice(src);
+    }
+}
+// ----------------------------------------------------------------------------
+fn resample_nearest<T: Copy>(data: &[T], size: u32, target_size: u32) -> Vec<T> {
+    let scale = size as f32 / target_size as f32;
+    let src = |v: u32| (((v as f32 + 0.5) * scale) as usize).min(size as usize - 1);
+
+    (0..target_size)
+        .flat_map(|y| (0..target_size).map(move |x| data[src(y) * size as usize + src(x)]))
+        .collect()
+}
+// ----------------------------------------------------------------------------
+fn resample_bilinear<T: Copy + Into<f32>>(
+    data: &[T],
+    size: u32,
+    channels: usize,
+    target_size: u32,
+    convert: impl Fn(f32) -> T,
+) -> Vec<T> {
+    let scale = size as f32 / target_size as f32;
+    let max = (size - 1) as f32;
+    let size = size as usize;
+    let sample =
+        |x: usize, y: usize, c: usize| -> f32 { data[(y * size + x) * channels + c].into() };
+
+    let mut result = Vec::with_capacity(target_size as usize * target_size as usize * channels);
+    for y in 0..target_size {
+        // texel centers
+        let sy = ((y as f32 + 0.5) * scale - 0.5).clamp(0.0, max);
+        let (y0, fy) = (sy.floor() as usize, sy.fract());
+        let y1 = (y0 + 1).min(size - 1);
+
+        for x in 0..target_size {
+            let sx = ((x as f32 + 0.5) * scale - 0.5).clamp(0.0, max);
+            let (x0, fx) = (sx.floor() as usize, sx.fract());
+            let x1 = (x0 + 1).min(size - 1);
+
+            for c in 0..channels {
+                let top = sample(x0, y0, c) * (1.0 - fx) + sample(x1, y0, c) * fx;
+                let bottom = sample(x0, y1, c) * (1.0 - fx) + sample(x1, y1, c) * fx;
+                result.push(convert(top * (1.0 - fy) + bottom * fy));
+            }
+        }
+    }
+    result
+}
+// ----------------------------------------------------------------------------
+fn downscale<D: ClipmapData>(data: &D, target_size: u32) -> Vec<D::DataType> {
+    let size = data.size() as usize;
+    data.downscale(
+        data.as_slice(),
+        size,
+        0,
+        0,
+        size,
+        (target_size as usize).clamp(1, size),
+    )
+}
+// ----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn control_map_pack_and_split_round_trip() {
+        let dir = std::env::temp_dir().join(format!("terrain-doc-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = |filename: &str| dir.join(filename).to_string_lossy().to_string();
+
+        let mut doc = TerrainDocument::open(TerrainConfig::default()).unwrap();
+        let size = doc.config().map_size();
+
+        // every bit of overlay (0..4), background (5..9) and blendcontrol (10..15) used
+        let controlmap = (0..size * size)
+            .map(|i| (i.wrapping_mul(7919) ^ (i >> 3)) as u16)
+            .collect::<Vec<_>>();
+        doc.texture_control = TextureControl::new(size, controlmap.clone());
+
+        let packed = path("controlmap.png");
+        doc.export_control_map(&packed).unwrap();
+
+        doc.config.set_texturemaps(
+            &path("background.png"),
+            &path("overlay.png"),
+            &path("blendcontrol.png"),
+        );
+        doc.import_control_map(&packed).unwrap();
+        doc.save().unwrap();
+
+        let reopened = TerrainDocument::open(doc.config().clone());
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(
+            reopened.unwrap().texture_control.as_slice(),
+            &controlmap[..]
+        );
+    }
+
+    #[test]
+    fn validation_reports_config_and_map_problems_together() {
+        let dir =
+            std::env::temp_dir().join(format!("terrain-validate-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let project = dir.join("project.ron").to_string_lossy().to_string();
+
+        let mut config = TerrainConfig::default();
+        config.set_map_size(1000);
+        config.set_heightmap("missing_heightmap.png");
+        config.save(&project).unwrap();
+
+        let loaded = TerrainConfig::load(&project);
+        let errors = TerrainConfig::load_unvalidated(&project)
+            .map(|config| TerrainDocument::validate(&config));
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert!(loaded.is_err());
+        let errors = errors.unwrap().unwrap_err();
+        assert!(errors.iter().any(|e| e.contains("power of two")));
+        assert!(errors.iter().any(|e| e.contains("missing_heightmap.png")));
+    }
+}
@@ -5,17 +5,19 @@ use bevy::prelude::*;
 use crate::config::TerrainConfig;
 use crate::terrain_render::EnvironmentData;
 
-use self::definition::EnvironmentConfig;
-use self::settings::EnvironmentSettings;
-use self::utils::{Angle, ColorCurveEntry, ScalarCurveEntry, TimeOfDay};
+use self::utils::Angle;
 // ----------------------------------------------------------------------------
 mod definition;
 mod interpolation;
+mod parser;
 mod settings;
 mod sun;
 mod utils;
 // ----------------------------------------------------------------------------
-pub use self::sun::{Sun, SunPositionSettings};
+pub use self::definition::{CurveId, CurveKeys, CurveValue, EnvironmentConfig, CURVES};
+pub use self::settings::EnvironmentSettings;
+pub use self::sun::SunPositionSettings;
+pub use self::utils::{ColorCurveEntry, ScalarCurveEntry, TimeOfDay};
 // ----------------------------------------------------------------------------
 pub struct EnvironmentPlugin;
 // ----------------------------------------------------------------------------
@@ -57,7 +59,18 @@ impl EnvironmentPlugin {
             // In a dedicated this will be always updated automatically but will
             // have one frame lag (which is ok).
             .with_system(sun::update_skybox)
-            .with_system(update_environment_data.after("daynight_cycle"))
+            .with_system(update_environment_settings.label("environment_settings_update"))
+            .with_system(
+                animate_environment_blend
+                    .label("environment_blend")
+                    .after("environment_settings_update"),
+            )
+            .with_system(
+                update_environment_data
+                    .after("daynight_cycle")
+                    .after("environment_settings_update")
+                    .after("environment_blend"),
+            )
     }
     // ------------------------------------------------------------------------
 }
@@ -66,6 +79,7 @@ impl Plugin for EnvironmentPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<DayNightCycle>()
             .init_resource::<SunPositionSettings>()
+            .init_resource::<EnvironmentConfig>()
             .insert_resource(EnvironmentSettings::from(EnvironmentConfig::default()));
     }
 }
@@ -75,15 +89,22 @@ impl Plugin for EnvironmentPlugin {
 fn setup_environment_settings(
     day_night_cycle: Res<DayNightCycle>,
     terrain_config: Res<TerrainConfig>,
+    mut env_config: ResMut<EnvironmentConfig>,
     mut env_settings: ResMut<EnvironmentSettings>,
     mut env_data: ResMut<EnvironmentData>,
 ) {
     let definition = terrain_config
         .environment_definition()
-        .map(|path| definition::EnvironmentConfig::load(path).unwrap_or_default())
+        .map(|path| {
+            definition::EnvironmentConfig::load(path).unwrap_or_else(|e| {
+                error!("{}", e);
+                EnvironmentConfig::default()
+            })
+        })
         .unwrap_or_default();
 
-    *env_settings = EnvironmentSettings::from(definition);
+    *env_settings = EnvironmentSettings::from(definition.clone());
+    *env_config = definition;
     set_sampled_environment_values(
         day_night_cycle.time_of_day(),
         env_settings.as_ref(),
@@ -91,16 +112,38 @@ fn setup_environment_settings(
     );
 }
 // ----------------------------------------------------------------------------
-fn reset_environment_settings(mut env_settings: ResMut<EnvironmentSettings>) {
+fn reset_environment_settings(
+    mut env_config: ResMut<EnvironmentConfig>,
+    mut env_settings: ResMut<EnvironmentSettings>,
+) {
+    *env_config = EnvironmentConfig::default();
     *env_settings = EnvironmentSettings::from(EnvironmentConfig::default());
 }
 // ----------------------------------------------------------------------------
+/// rebuilds interpolated settings after changes of the definition (e.g. by
+/// the curve editor)
+fn update_environment_settings(
+    env_config: Res<EnvironmentConfig>,
+    mut env_settings: ResMut<EnvironmentSettings>,
+) {
+    if env_config.is_changed() {
+        env_settings.set_primary(env_config.clone());
+    }
+}
+// ----------------------------------------------------------------------------
+fn animate_environment_blend(time: Res<Time>, mut env_settings: ResMut<EnvironmentSettings>) {
+    // Note: as_ref required to prevent setting "changed" flag
+    if env_settings.as_ref().is_blend_animated() {
+        env_settings.animate_blend(time.delta_seconds());
+    }
+}
+// ----------------------------------------------------------------------------
 fn update_environment_data(
     day_night_cycle: Res<DayNightCycle>,
     env_settings: Res<EnvironmentSettings>,
     mut env_data: ResMut<EnvironmentData>,
 ) {
-    if day_night_cycle.is_changed() {
+    if day_night_cycle.is_changed() || env_settings.is_changed() {
         set_sampled_environment_values(
             day_night_cycle.time_of_day(),
             env_settings.as_ref(),
@@ -129,9 +172,9 @@ fn set_sampled_environment_values(
     env_data: &mut EnvironmentData,
 ) {
     // sample new interpolated values and update current environment data
-    env_data.sun.color = env_settings.sun.color.sample(time_of_day);
+    env_data.sun.color = env_settings.sample_sun_color(time_of_day);
     // fog
-    env_data.fog = env_settings.fog.sample(time_of_day);
+    env_data.fog = env_settings.sample_fog(time_of_day);
 }
 // ----------------------------------------------------------------------------
 // day night cycle
@@ -162,7 +205,7 @@ impl DayNightCycle {
     }
     // ------------------------------------------------------------------------
     pub fn set_cycle_speed(&mut self, speed: u16) {
-        self.cycle_speed = speed.max(0).min(100);
+        self.cycle_speed = speed.min(100);
     }
     // ------------------------------------------------------------------------
 }
@@ -0,0 +1,549 @@
+// ----------------------------------------------------------------------------
+// parser for exported environment definitions (json or xml dumps)
+// ----------------------------------------------------------------------------
+//
+// both formats describe groups of named curves (names as used by the game,
+// e.g. globalFog.fogColorFront) with keypoints consisting of a time and a
+// value. time is either "HH:mm" or normalized day time [0..1.0]. scalar values
+// are a single number, color values are r, g, b (0..255) and intensity.
+//
+// json:
+//   {
+//     "globalLight": {
+//       "sunColor": [ { "time": "03:38", "value": [252.0, 196.0, 123.0, 85.08] }, .. ]
+//     },
+//     "globalFog": {
+//       "fogAppearDistance": [ { "time": 0.47847, "value": 4.0 }, .. ],
+//       ..
+//     }
+//   }
+//
+// xml:
+//   <environment>
+//     <globalLight>
+//       <sunColor>
+//         <entry time="03:38" value="252.0 196.0 123.0 85.08"/>
+//       </sunColor>
+//     </globalLight>
+//     ..
+//   </environment>
+//
+// additionally the property layout of the game's CEnvironmentDefinition
+// (cr2w) is accepted if the dump contains SSimpleCurve properties
+// (dataCurveValues). this layout was written from the rtti property names of
+// the game and was not verified against an actual dump of the radish tools:
+//
+//   {
+//     "envParams": {
+//       "m_globalLight": {
+//         "sunColor": {
+//           "CurveType": "SCT_ColorScaled",
+//           "dataCurveValues": [
+//             { "me": 0.15139, "ntrolPoint": { "X": 252.0, "Y": 196.0, "Z": 123.0, "W": 85.08 } },
+//             ..
+//           ]
+//         }
+//       },
+//       "m_globalFog": {
+//         "fogAppearDistance": {
+//           "CurveType": "SCT_Float",
+//           "dataCurveValues": [ { "me": 0.47847, "lue": 4.0 }, .. ]
+//         },
+//         ..
+//       }
+//     }
+//   }
+//
+// curves are searched recursively: the property name is the curve name and
+// the name of the parent property (without "m_" prefix) is the group. wrapped
+// values ({ "type": .., "value": .. } or { "name": .., "value": .. }) are
+// unwrapped. the xml variant uses the property names as element names (or a
+// name attribute) with the values as text content:
+//
+//   <envParams>
+//     <m_globalFog>
+//       <fogAppearDistance>
+//         <CurveType>SCT_Float</CurveType>
+//         <dataCurveValues>
+//           <SCurveDataEntry><me>0.47847</me><lue>4.0</lue></SCurveDataEntry>
+//         </dataCurveValues>
+//       </fogAppearDistance>
+//       ..
+//
+use super::TimeOfDay;
+// ----------------------------------------------------------------------------
+pub(super) struct CurveData {
+    pub group: String,
+    pub name: String,
+    pub keypoints: Vec<(TimeOfDay, Vec<f32>)>,
+}
+// ----------------------------------------------------------------------------
+pub(super) fn parse_json(content: &str) -> Result<Vec<CurveData>, String> {
+    use serde_json::Value;
+
+    let root: Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    if is_cr2w_layout(content) {
+        return parse_cr2w(&root);
+    }
+    let groups = root
+        .as_object()
+        .ok_or_else(|| "expected object with curve groups as root".to_string())?;
+
+    let mut result = Vec::new();
+    for (group, curves) in groups {
+        let curves = curves
+            .as_object()
+            .ok_or_else(|| format!("{}: expected object with curves", group))?;
+
+        for (name, keypoints) in curves {
+            let keypoints = keypoints
+                .as_array()
+                .ok_or_else(|| format!("{}.{}: expected array of keypoints", group, name))?
+                .iter()
+                .map(|keypoint| {
+                    let time = match keypoint.get("time") {
+                        Some(Value::String(time)) => TimeOfDay::try_from(time.as_str()),
+                        Some(Value::Number(time)) => Ok(TimeOfDay::from_normalized(
+                            time.as_f64().unwrap_or_default() as f32,
+                        )),
+                        _ => Err("missing time".to_string()),
+                    }?;
+                    let value = match keypoint.get("value") {
+                        Some(Value::Number(v)) => vec![v.as_f64().unwrap_or_default() as f32],
+                        Some(Value::Array(values)) => values
+                            .iter()
+                            .map(|v| v.as_f64().map(|v| v as f32))
+                            .collect::<Option<Vec<_>>>()
+                            .ok_or_else(|| "expected numbers as value".to_string())?,
+                        _ => return Err("missing value".to_string()),
+                    };
+                    Ok((time, value))
+                })
+                .collect::<Result<Vec<_>, String>>()
+                .map_err(|e| format!("{}.{}: {}", group, name, e))?;
+
+            result.push(CurveData {
+                group: group.clone(),
+                name: name.clone(),
+                keypoints,
+            });
+        }
+    }
+    Ok(result)
+}
+// ----------------------------------------------------------------------------
+pub(super) fn parse_xml(content: &str) -> Result<Vec<CurveData>, String> {
+    use quick_xml::events::{BytesStart, Event};
+    use quick_xml::Reader;
+
+    if is_cr2w_layout(content) {
+        return parse_cr2w(&xml_tree(content)?);
+    }
+
+    let mut reader = Reader::from_str(content);
+    reader.trim_text(true);
+
+    let name_of = |e: &BytesStart| String::from_utf8_lossy(e.name()).to_string();
+
+    let mut result = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut buf = Vec::new();
+
+    loop {
+        let event = reader
+            .read_event(&mut buf)
+            .map_err(|e| format!("position {}: {}", reader.buffer_position(), e))?;
+
+        match event {
+            Event::Start(ref e) => {
+                path.push(name_of(e));
+                // root / group / curve
+                if path.len() == 3 {
+                    result.push(CurveData {
+                        group: path[1].clone(),
+                        name: path[2].clone(),
+                        keypoints: Vec::new(),
+                    });
+                }
+            }
+            Event::Empty(ref e) if path.len() == 3 && e.name() == b"entry" => {
+                let (mut time, mut value) = (None, None);
+                for attribute in e.attributes() {
+                    let attribute = attribute.map_err(|e| e.to_string())?;
+                    let attr_value = attribute
+                        .unescape_and_decode_value(&reader)
+                        .map_err(|e| e.to_string())?;
+                    match attribute.key {
+                        b"time" => time = Some(attr_value),
+                        b"value" => value = Some(attr_value),
+                        _ => {}
+                    }
+                }
+                let curve = format!("{}.{}", path[1], path[2]);
+                let time = time
+                    .ok_or_else(|| format!("{}: missing time", curve))
+                    .and_then(|time| {
+                        TimeOfDay::try_from(time.as_str())
+                            .or_else(|_| {
+                                time.parse::<f32>()
+                                    .map(TimeOfDay::from_normalized)
+                                    .map_err(|_| format!("invalid time: {}", time))
+                            })
+                            .map_err(|e| format!("{}: {}", curve, e))
+                    })?;
+                let value = value
+                    .ok_or_else(|| format!("{}: missing value", curve))?
+                    .split_whitespace()
+                    .map(|v| v.parse::<f32>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| format!("{}: invalid value: {}", curve, e))?;
+
+                // unwrap is safe: curve was pushed on start of curve element
+                result.last_mut().unwrap().keypoints.push((time, value));
+            }
+            Event::End(_) => {
+                path.pop();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(result)
+}
+// ----------------------------------------------------------------------------
+// cr2w property layout
+// ----------------------------------------------------------------------------
+fn is_cr2w_layout(content: &str) -> bool {
+    content.contains("dataCurveValues")
+}
+// ----------------------------------------------------------------------------
+fn parse_cr2w(root: &serde_json::Value) -> Result<Vec<CurveData>, String> {
+    let mut result = Vec::new();
+    collect_cr2w_curves(root, None, None, &mut result)?;
+    Ok(result)
+}
+// ----------------------------------------------------------------------------
+/// property name (if the value is a named property) and unwrapped value
+fn unwrap_property(value: &serde_json::Value) -> (Option<&str>, &serde_json::Value) {
+    const META: [&str; 5] = ["name", "type", "Name", "Type", "_type"];
+
+    let mut name = None;
+    let mut value = value;
+    while let Some(map) = value.as_object() {
+        match map.get("value").or_else(|| map.get("Value")) {
+            Some(inner)
+                if map
+                    .keys()
+                    .all(|k| k == "value" || k == "Value" || META.contains(&k.as_str())) =>
+            {
+                name = name.or_else(|| map.get("name").or_else(|| map.get("Name"))?.as_str());
+                value = inner;
+            }
+            _ => break,
+        }
+    }
+    (name, value)
+}
+// ----------------------------------------------------------------------------
+fn collect_cr2w_curves(
+    value: &serde_json::Value,
+    key: Option<&str>,
+    parent: Option<&str>,
+    result: &mut Vec<CurveData>,
+) -> Result<(), String> {
+    use serde_json::Value;
+
+    let (name, value) = unwrap_property(value);
+    let key = name.or(key);
+
+    match value {
+        Value::Object(map) if map.contains_key("dataCurveValues") => {
+            let (group, name) = match (parent, key) {
+                (Some(group), Some(name)) => (group.trim_start_matches("m_"), name),
+                _ => return Err("found curve without property name".to_string()),
+            };
+            let keypoints =
+                parse_cr2w_curve(map).map_err(|e| format!("{}.{}: {}", group, name, e))?;
+
+            result.push(CurveData {
+                group: group.to_string(),
+                name: name.to_string(),
+                keypoints,
+            });
+        }
+        Value::Object(map) => {
+            for (child_key, child) in map {
+                collect_cr2w_curves(child, Some(child_key), key.or(parent), result)?;
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_cr2w_curves(item, None, key.or(parent), result)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+// ----------------------------------------------------------------------------
+/// SSimpleCurve: entries with normalized time (me) and either a scalar (lue)
+/// or a vector (ntrolPoint) value depending on the curve type
+fn parse_cr2w_curve(
+    curve: &serde_json::Map<String, serde_json::Value>,
+) -> Result<Vec<(TimeOfDay, Vec<f32>)>, String> {
+    use serde_json::Value;
+
+    let number = |value: &Value| match unwrap_property(value).1 {
+        Value::Number(v) => v.as_f64().map(|v| v as f32),
+        Value::String(v) => v.trim().parse::<f32>().ok(),
+        _ => None,
+    };
+
+    // scalar if not specified and no vector values are found
+    let curve_type = curve
+        .get("CurveType")
+        .and_then(|t| unwrap_property(t).1.as_str());
+
+    let entries = match unwrap_property(&curve["dataCurveValues"]).1 {
+        Value::Array(entries) => entries.iter().collect::<Vec<_>>(),
+        Value::Object(entry) if entry.is_empty() => Vec::new(),
+        entry @ Value::Object(_) => vec![entry],
+        Value::Null | Value::String(_) => Vec::new(),
+        _ => return Err("expected array of curve entries".to_string()),
+    };
+
+    entries
+        .into_iter()
+        .map(|entry| {
+            let entry = unwrap_property(entry)
+                .1
+                .as_object()
+                .ok_or_else(|| "expected curve entry object".to_string())?;
+
+            // default values are not stored in cr2w files
+            let time = entry.get("me").map(number).unwrap_or(Some(0.0));
+            let time = time
+                .map(TimeOfDay::from_normalized)
+                .ok_or_else(|| "invalid time (me)".to_string())?;
+
+            let vector = match curve_type {
+                Some(curve_type) => !curve_type.contains("Float"),
+                None => entry.contains_key("ntrolPoint"),
+            };
+            let value = if vector {
+                let point = entry
+                    .get("ntrolPoint")
+                    .map(|p| unwrap_property(p).1)
+                    .and_then(Value::as_object)
+                    .ok_or_else(|| format!("missing control point at {}", time.as_str()))?;
+
+                // missing components are defaults (0.0)
+                ["X", "Y", "Z", "W"]
+                    .iter()
+                    .map(|c| {
+                        point
+                            .get(*c)
+                            .or_else(|| point.get(&c.to_lowercase()))
+                            .map(number)
+                            .unwrap_or(Some(0.0))
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| format!("invalid control point at {}", time.as_str()))?
+            } else {
+                let value = entry.get("lue").map(number).unwrap_or(Some(0.0));
+                vec![value.ok_or_else(|| format!("invalid value (lue) at {}", time.as_str()))?]
+            };
+            Ok((time, value))
+        })
+        .collect()
+}
+// ----------------------------------------------------------------------------
+/// converts xml elements into a json value tree: element name (or name
+/// attribute) as key, attributes and child elements as properties, text
+/// content as value. entries of dataCurveValues are always an array.
+fn xml_tree(content: &str) -> Result<serde_json::Value, String> {
+    use quick_xml::events::{BytesStart, Event};
+    use quick_xml::Reader;
+    use serde_json::{Map, Value};
+
+    struct Element {
+        key: String,
+        properties: Map<String, Value>,
+        items: Vec<Value>,
+        text: String,
+    }
+
+    let mut reader = Reader::from_str(content);
+    reader.trim_text(true);
+
+    let start = |e: &BytesStart, reader: &Reader<&[u8]>| -> Result<Element, String> {
+        let mut element = Element {
+            key: String::from_utf8_lossy(e.name()).to_string(),
+            properties: Map::new(),
+            items: Vec::new(),
+            text: String::new(),
+        };
+        for attribute in e.attributes() {
+            let attribute = attribute.map_err(|e| e.to_string())?;
+            let value = attribute
+                .unescape_and_decode_value(reader)
+                .map_err(|e| e.to_string())?;
+            match attribute.key {
+                b"name" => element.key = value,
+                key => {
+                    element.properties.insert(
+                        String::from_utf8_lossy(key).to_string(),
+                        Value::String(value),
+                    );
+                }
+            }
+        }
+        Ok(element)
+    };
+
+    let end = |stack: &mut Vec<Element>, element: Element| {
+        let value = if element.key == "dataCurveValues" {
+            Value::Array(element.items)
+        } else if element.properties.is_empty() {
+            Value::String(element.text)
+        } else {
+            let mut properties = element.properties;
+            if !element.text.is_empty() {
+                properties.insert("value".to_string(), Value::String(element.text));
+            }
+            Value::Object(properties)
+        };
+        match stack.last_mut() {
+            Some(parent) if parent.key == "dataCurveValues" => parent.items.push(value),
+            Some(parent) => match parent.properties.get_mut(&element.key) {
+                Some(Value::Array(values)) => values.push(value),
+                Some(existing) => {
+                    let first = existing.take();
+                    *existing = Value::Array(vec![first, value]);
+                }
+                None => {
+                    parent.properties.insert(element.key, value);
+                }
+            },
+            None => {
+                let mut root = Map::new();
+                root.insert(element.key, value);
+                return Some(Value::Object(root));
+            }
+        }
+        None
+    };
+
+    let mut stack: Vec<Element> = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        let event = reader
+            .read_event(&mut buf)
+            .map_err(|e| format!("position {}: {}", reader.buffer_position(), e))?;
+
+        match event {
+            Event::Start(ref e) => stack.push(start(e, &reader)?),
+            Event::Empty(ref e) => {
+                let element = start(e, &reader)?;
+                if let Some(root) = end(&mut stack, element) {
+                    return Ok(root);
+                }
+            }
+            Event::Text(ref e) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text = e.unescape_and_decode(&reader).map_err(|e| e.to_string())?;
+                }
+            }
+            Event::End(_) => {
+                let element = stack.pop().ok_or_else(|| "unbalanced xml".to_string())?;
+                if let Some(root) = end(&mut stack, element) {
+                    return Ok(root);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Err("missing root element".to_string())
+}
+// ----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFINITION: &str = include_str!(
+        "../../assets/environment/definitions/env_prologue/env_prolog_colors_v1_b_sunset.env.json"
+    );
+    const DEFINITION_CR2W: &str = include_str!(
+        "../../assets/environment/definitions/env_prologue/env_prolog_colors_v1_b_sunset.cr2w.json"
+    );
+
+    fn find<'a>(curves: &'a [CurveData], group: &str, name: &str) -> &'a CurveData {
+        curves
+            .iter()
+            .find(|c| c.group == group && c.name == name)
+            .unwrap_or_else(|| panic!("missing curve {}.{}", group, name))
+    }
+
+    #[test]
+    fn cr2w_json_layout_matches_simple_layout() {
+        let expected = parse_json(DEFINITION).unwrap();
+        let curves = parse_json(DEFINITION_CR2W).unwrap();
+
+        assert_eq!(curves.len(), expected.len());
+        for expected in &expected {
+            let curve = find(&curves, &expected.group, &expected.name);
+            assert_eq!(curve.keypoints.len(), expected.keypoints.len());
+
+            for ((time, value), (expected_time, expected_value)) in
+                curve.keypoints.iter().zip(expected.keypoints.iter())
+            {
+                assert!((time.normalized() - expected_time.normalized()).abs() < 1.0 / 1440.0);
+                assert_eq!(value, expected_value);
+            }
+        }
+    }
+
+    #[test]
+    fn cr2w_xml_layout_with_wrapped_and_default_values() {
+        let content = r#"
+            <CEnvironmentDefinition>
+              <property name="envParams" type="CAreaEnvironmentParams">
+                <m_globalFog>
+                  <fogDensity>
+                    <CurveType>SCT_Float</CurveType>
+                    <dataCurveValues>
+                      <SCurveDataEntry><lue>0.5</lue></SCurveDataEntry>
+                      <SCurveDataEntry><me>0.5</me><lue>2.0</lue></SCurveDataEntry>
+                    </dataCurveValues>
+                  </fogDensity>
+                  <fogColorFront>
+                    <dataCurveValues>
+                      <SCurveDataEntry>
+                        <me type="Float" value="0.25"/>
+                        <ntrolPoint><X>10</X><Y>20</Y><Z>30</Z></ntrolPoint>
+                      </SCurveDataEntry>
+                    </dataCurveValues>
+                  </fogColorFront>
+                </m_globalFog>
+              </property>
+            </CEnvironmentDefinition>"#;
+
+        let curves = parse_xml(content).unwrap();
+        assert_eq!(curves.len(), 2);
+
+        let density = find(&curves, "globalFog", "fogDensity");
+        assert_eq!(density.keypoints.len(), 2);
+        assert_eq!(density.keypoints[0].0.as_str(), "00:00");
+        assert_eq!(density.keypoints[0].1, vec![0.5]);
+        assert_eq!(density.keypoints[1].0.as_str(), "12:00");
+        assert_eq!(density.keypoints[1].1, vec![2.0]);
+
+        let color = find(&curves, "globalFog", "fogColorFront");
+        assert_eq!(color.keypoints.len(), 1);
+        assert_eq!(color.keypoints[0].0.as_str(), "06:00");
+        assert_eq!(color.keypoints[0].1, vec![10.0, 20.0, 30.0, 0.0]);
+    }
+}
@@ -1,14 +1,30 @@
 // ----------------------------------------------------------------------------
+use bevy::prelude::{Color, Vec4};
+
 use crate::terrain_render::FogState;
 
 use super::interpolation::{ColorInterpolation, ScalarInterpolation};
 
 use super::{EnvironmentConfig, TimeOfDay};
 // ----------------------------------------------------------------------------
-/// environment settings prepared for interpolated sampling
+/// environment settings prepared for interpolated sampling. sampled values are
+/// blended between the primary and an optional secondary definition (e.g. to
+/// preview transitions between two environments).
 pub struct EnvironmentSettings {
-    pub sun: SunSettings,
-    pub fog: FogSettings,
+    primary: DefinitionSettings,
+    secondary: Option<DefinitionSettings>,
+    /// weight of secondary definition [0..1]
+    blend_weight: f32,
+    /// duration of one transition in seconds if blending is animated
+    blend_animation: Option<f32>,
+    blend_reverse: bool,
+}
+// ----------------------------------------------------------------------------
+/// interpolated curves of one environment definition
+pub struct DefinitionSettings {
+    name: Option<String>,
+    sun: SunSettings,
+    fog: FogSettings,
 }
 // ----------------------------------------------------------------------------
 pub struct SunSettings {
@@ -41,6 +57,92 @@ pub struct FogSettings {
     aerial_final_exp: ScalarInterpolation,
 }
 // ----------------------------------------------------------------------------
+impl EnvironmentSettings {
+    // ------------------------------------------------------------------------
+    /// replaces primary definition and keeps blending settings
+    pub fn set_primary(&mut self, conf: EnvironmentConfig) {
+        self.primary = DefinitionSettings::from(conf);
+    }
+    // ------------------------------------------------------------------------
+    pub fn set_secondary(&mut self, conf: Option<EnvironmentConfig>) {
+        self.secondary = conf.map(DefinitionSettings::from);
+    }
+    // ------------------------------------------------------------------------
+    /// name (filepath) of secondary definition. None if no secondary
+    /// definition is loaded.
+    pub fn secondary(&self) -> Option<&str> {
+        self.secondary
+            .as_ref()
+            .map(|s| s.name.as_deref().unwrap_or("unnamed"))
+    }
+    // ------------------------------------------------------------------------
+    pub fn blend_weight(&self) -> f32 {
+        self.blend_weight
+    }
+    // ------------------------------------------------------------------------
+    pub fn set_blend_weight(&mut self, weight: f32) {
+        self.blend_weight = weight.clamp(0.0, 1.0);
+    }
+    // ------------------------------------------------------------------------
+    pub fn blend_animation(&self) -> Option<f32> {
+        self.blend_animation
+    }
+    // ------------------------------------------------------------------------
+    /// duration of one transition in seconds. None stops animation.
+    pub fn set_blend_animation(&mut self, duration: Option<f32>) {
+        self.blend_animation = duration.map(|d| d.max(0.1));
+    }
+    // ------------------------------------------------------------------------
+    pub fn is_blend_animated(&self) -> bool {
+        self.secondary.is_some() && self.blend_animation.is_some()
+    }
+    // ------------------------------------------------------------------------
+    /// moves blend weight back and forth between both definitions
+    pub fn animate_blend(&mut self, delta_seconds: f32) {
+        if let Some(duration) = self.blend_animation {
+            let step = delta_seconds / duration;
+            let weight = if self.blend_reverse {
+                self.blend_weight - step
+            } else {
+                self.blend_weight + step
+            };
+            if weight >= 1.0 {
+                self.blend_reverse = true;
+            } else if weight <= 0.0 {
+                self.blend_reverse = false;
+            }
+            self.blend_weight = weight.clamp(0.0, 1.0);
+        }
+    }
+    // ------------------------------------------------------------------------
+    pub fn sample_sun_color(&self, time: &TimeOfDay) -> Color {
+        let color = self.primary.sun.color.sample(time);
+        match self.blended_secondary() {
+            Some((secondary, weight)) => {
+                lerp_color(color, secondary.sun.color.sample(time), weight)
+            }
+            None => color,
+        }
+    }
+    // ------------------------------------------------------------------------
+    pub fn sample_fog(&self, time: &TimeOfDay) -> FogState {
+        let fog = self.primary.fog.sample(time);
+        match self.blended_secondary() {
+            Some((secondary, weight)) => blend_fog(&fog, &secondary.fog.sample(time), weight),
+            None => fog,
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// secondary definition and weight if it contributes to sampled values
+    fn blended_secondary(&self) -> Option<(&DefinitionSettings, f32)> {
+        self.secondary
+            .as_ref()
+            .filter(|_| self.blend_weight > 0.0)
+            .map(|secondary| (secondary, self.blend_weight))
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
 impl FogSettings {
     // ------------------------------------------------------------------------
     pub fn sample(&self, time: &TimeOfDay) -> FogState {
@@ -79,6 +181,21 @@ impl From<EnvironmentConfig> for EnvironmentSettings {
     // ------------------------------------------------------------------------
     fn from(conf: EnvironmentConfig) -> Self {
         Self {
+            primary: DefinitionSettings::from(conf),
+            secondary: None,
+            blend_weight: 0.0,
+            blend_animation: None,
+            blend_reverse: false,
+        }
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl From<EnvironmentConfig> for DefinitionSettings {
+    // ------------------------------------------------------------------------
+    fn from(conf: EnvironmentConfig) -> Self {
+        Self {
+            name: conf.filepath().map(str::to_string),
             sun: SunSettings {
                 color: ColorInterpolation::from(conf.sun.color),
             },
@@ -122,3 +239,48 @@ impl From<EnvironmentConfig> for EnvironmentSettings {
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
+// blending
+// ----------------------------------------------------------------------------
+#[inline]
+fn lerp(a: f32, b: f32, weight: f32) -> f32 {
+    a + (b - a) * weight
+}
+// ----------------------------------------------------------------------------
+#[inline]
+fn lerp_color(a: Color, b: Color, weight: f32) -> Color {
+    // sampled colors are linear (and scaled by intensity)
+    let a = Vec4::from(a.as_linear_rgba_f32());
+    let b = Vec4::from(b.as_linear_rgba_f32());
+    let c = a.lerp(b, weight);
+    Color::rgba_linear(c.x, c.y, c.z, c.w)
+}
+// ----------------------------------------------------------------------------
+#[rustfmt::skip]
+fn blend_fog(a: &FogState, b: &FogState, w: f32) -> FogState {
+    FogState {
+        appear_distance: lerp(a.appear_distance, b.appear_distance, w),
+        appear_range: lerp(a.appear_range, b.appear_range, w),
+        color_front: lerp_color(a.color_front, b.color_front, w),
+        color_middle: lerp_color(a.color_middle, b.color_middle, w),
+        color_back: lerp_color(a.color_back, b.color_back, w),
+        density: lerp(a.density, b.density, w),
+        final_exp: lerp(a.final_exp, b.final_exp, w),
+        distance_clamp: lerp(a.distance_clamp, b.distance_clamp, w),
+        vertical_offset: lerp(a.vertical_offset, b.vertical_offset, w),
+        vertical_density: lerp(a.vertical_density, b.vertical_density, w),
+        vertical_density_light_front: lerp(a.vertical_density_light_front, b.vertical_density_light_front, w),
+        vertical_density_light_back: lerp(a.vertical_density_light_back, b.vertical_density_light_back, w),
+        vertical_density_rim_range: lerp(a.vertical_density_rim_range, b.vertical_density_rim_range, w),
+        custom_color: lerp_color(a.custom_color, b.custom_color, w),
+        custom_color_start: lerp(a.custom_color_start, b.custom_color_start, w),
+        custom_color_range: lerp(a.custom_color_range, b.custom_color_range, w),
+        custom_amount_scale: lerp(a.custom_amount_scale, b.custom_amount_scale, w),
+        custom_amount_scale_start: lerp(a.custom_amount_scale_start, b.custom_amount_scale_start, w),
+        custom_amount_scale_range: lerp(a.custom_amount_scale_range, b.custom_amount_scale_range, w),
+        aerial_color_front: lerp_color(a.aerial_color_front, b.aerial_color_front, w),
+        aerial_color_middle: lerp_color(a.aerial_color_middle, b.aerial_color_middle, w),
+        aerial_color_back: lerp_color(a.aerial_color_back, b.aerial_color_back, w),
+        aerial_final_exp: lerp(a.aerial_final_exp, b.aerial_final_exp, w),
+    }
+}
+// ----------------------------------------------------------------------------
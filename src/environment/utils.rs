@@ -32,6 +32,13 @@ impl TimeOfDay {
         }
     }
     // ------------------------------------------------------------------------
+    /// time from normalized [0..1.0] day time (representation used by the game)
+    pub fn from_normalized(linear: f32) -> Self {
+        let mut time = Self::new(0, 0, 0);
+        time.update(linear);
+        time
+    }
+    // ------------------------------------------------------------------------
     pub fn update(&mut self, linear: f32) {
         let t = linear.max(0.0) % 1.0 * (24 * 3600) as f32;
         let hour = (t / 3600.0).floor().min(23.0) as u8;
@@ -72,6 +79,10 @@ impl TimeOfDay {
 }
 // ----------------------------------------------------------------------------
 impl ScalarCurveEntry {
+    // ------------------------------------------------------------------------
+    pub fn new(time: TimeOfDay, value: f32) -> Self {
+        Self(time, value)
+    }
     // ------------------------------------------------------------------------
     pub fn time(&self) -> &TimeOfDay {
         &self.0
@@ -84,6 +95,15 @@ impl ScalarCurveEntry {
 }
 // ----------------------------------------------------------------------------
 impl ColorCurveEntry {
+    // ------------------------------------------------------------------------
+    /// rgb in 0..255 range
+    pub fn new(time: TimeOfDay, rgb: [f32; 3], intensity: f32) -> Self {
+        Self(
+            time,
+            Color::rgb(rgb[0] / 255.0, rgb[1] / 255.0, rgb[2] / 255.0),
+            intensity,
+        )
+    }
     // ------------------------------------------------------------------------
     pub fn time(&self) -> &TimeOfDay {
         &self.0
@@ -119,7 +139,7 @@ impl Angle {
 // ----------------------------------------------------------------------------
 // converter
 // ----------------------------------------------------------------------------
-impl<'a> TryFrom<&'a str> for TimeOfDay {
+impl TryFrom<&str> for TimeOfDay {
     type Error = String;
     // ------------------------------------------------------------------------
     fn try_from(src: &str) -> Result<Self, String> {
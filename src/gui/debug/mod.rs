@@ -51,7 +51,7 @@ fn handle_editor_events(
                     if let Some(array) = texture_arrays.get(handle) {
                         let (_, _, img_data) = array.imagedata(*slot, config::CLIPMAP_SIZE);
                         egui_image_registry.update_image(
-                            &mut *images,
+                            &mut images,
                             &format!("clipmap.{}.{}", clipmap_label, slot),
                             img_data,
                         );
@@ -67,7 +67,6 @@ fn handle_ui_debug_actions(
     mut ui_state: ResMut<UiState>,
     mut ui_action: EventReader<GuiAction>,
     mut app_state: ResMut<State<EditorState>>,
-    mut worldconf: ResMut<config::TerrainConfig>,
 
     mut egui_ctx: ResMut<EguiContext>,
     mut egui_image_registry: ResMut<UiImages>,
@@ -79,10 +78,6 @@ fn handle_ui_debug_actions(
 ) {
     for action in ui_action.iter() {
         match action {
-            GuiAction::DebugLoadTerrain(new_config) => {
-                *worldconf = (**new_config).clone();
-                app_state.overwrite_set(EditorState::TerrainLoading).ok();
-            }
             GuiAction::DebugShowClipmap(show) if *show => {
                 ui_state.debug.show_clipmaps = true;
                 for (label, handle) in [
@@ -97,7 +92,7 @@ fn handle_ui_debug_actions(
 
                             egui_image_registry.add_image(
                                 &mut egui_ctx,
-                                &mut *textures,
+                                &mut textures,
                                 format!("clipmap.{}.{}", label, i),
                                 format,
                                 (size, size),
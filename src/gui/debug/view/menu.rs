@@ -5,8 +5,7 @@ pub fn show_menu(
     ui_state: &UiState,
     gui_event: &mut EventWriter<GuiAction>,
 ) {
-    use crate::config::TerrainConfig;
-    use GuiAction::{DebugCloseProject, DebugLoadTerrain};
+    use GuiAction::{DebugCloseProject, OpenProject};
 
     ui.set_enabled(!ui_state.project_is_loading);
 
@@ -21,15 +20,15 @@ pub fn show_menu(
 
         ui.set_enabled(!ui_state.project_open);
         ui.label("Prolog");
-        if ui.button("Load Prolog (1024)").clicked() { result = Some(DebugLoadTerrain(Box::new(TerrainConfig::prolog_village(1024)))); }
-        if ui.button("Load Prolog (2048)").clicked() { result = Some(DebugLoadTerrain(Box::new(TerrainConfig::prolog_village(2048)))); }
-        if ui.button("Load Prolog (4096)").clicked() { result = Some(DebugLoadTerrain(Box::new(TerrainConfig::prolog_village(4096)))); }
+        if ui.button("Load Prolog (1024)").clicked() { result = Some(OpenProject("projects/prolog_village.1024.ron".into())); }
+        if ui.button("Load Prolog (2048)").clicked() { result = Some(OpenProject("projects/prolog_village.2048.ron".into())); }
+        if ui.button("Load Prolog (4096)").clicked() { result = Some(OpenProject("projects/prolog_village.4096.ron".into())); }
         ui.separator();
         ui.label("Bevy");
-        if ui.button("Bevy Terrain (4096)").clicked() { result = Some(DebugLoadTerrain(Box::new(TerrainConfig::bevy_example()))); }
+        if ui.button("Bevy Terrain (4096)").clicked() { result = Some(OpenProject("projects/bevy_example.ron".into())); }
         ui.separator();
         ui.label("Kaer Morhen");
-        if ui.button("Kaer Morhen (16384)").clicked() { result = Some(DebugLoadTerrain(Box::new(TerrainConfig::kaer_morhen()))); }
+        if ui.button("Kaer Morhen (16384)").clicked() { result = Some(OpenProject("projects/kaer_morhen.ron".into())); }
 
         if let Some(event) = result {
             ui.close_menu();
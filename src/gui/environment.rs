@@ -0,0 +1,28 @@
+// ----------------------------------------------------------------------------
+#[derive(Default)]
+pub(super) struct EnvironmentCurveEditor {
+    /// index of edited curve in environment::CURVES
+    pub curve: usize,
+    pub selected_key: Option<usize>,
+    pub dragged_key: Option<usize>,
+    /// displayed value range is frozen while a key is dragged
+    pub drag_range: Option<(f32, f32)>,
+    pub filepath: String,
+}
+// ----------------------------------------------------------------------------
+pub(super) struct EnvironmentBlending {
+    /// filepath of secondary definition
+    pub filepath: String,
+    /// transition duration in seconds for animated blending
+    pub duration: f32,
+}
+// ----------------------------------------------------------------------------
+impl Default for EnvironmentBlending {
+    fn default() -> Self {
+        Self {
+            filepath: String::default(),
+            duration: 10.0,
+        }
+    }
+}
+// ----------------------------------------------------------------------------
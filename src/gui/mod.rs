@@ -3,13 +3,18 @@ use bevy::{math::Vec3, prelude::*};
 use bevy_egui::EguiContext;
 
 use crate::atmosphere::AtmosphereMat;
+use crate::autofly_camera::{
+    CameraPath, CameraPathEditor, CameraPathId, CameraPathRecorder, CameraPathsCollection,
+    FrameSequenceRenderer, RecordingMode,
+};
 use crate::cmds;
-use crate::config;
 use crate::environment::DayNightCycle;
 use crate::environment::SunPositionSettings;
+use crate::environment::{CurveId, CurveValue, EnvironmentConfig, EnvironmentSettings};
 use crate::terrain_material::{TerrainMaterialSet, TextureType, TextureUpdatedEvent};
 use crate::terrain_tiles::{LodSlot, TerrainMeshSettings};
 use crate::texturearray::TextureArray;
+use crate::tileexport::TileExportSettings;
 use crate::{EditorEvent, EditorState};
 // ----------------------------------------------------------------------------
 pub struct EditorUiPlugin;
@@ -25,6 +30,12 @@ pub struct UiState {
 
     progress: ProgressTracking,
 
+    project: ProjectDialog,
+    tile_export: TileExportDialog,
+
+    environment: EnvironmentCurveEditor,
+    environment_blending: EnvironmentBlending,
+
     toolbox: toolbox::ToolboxState,
 
     is_using_pointer: bool,
@@ -42,11 +53,32 @@ pub enum GuiAction {
     UpdateDayNightCycleSetting(DayNightCycleSetting),
     UpdateSunSetting(SunSetting),
     UpdateAtmosphereSetting(AtmosphereSetting),
+    UpdateEnvironmentCurve(EnvironmentCurveSetting),
+    SaveEnvironmentDefinition(String),
+    UpdateEnvironmentBlending(EnvironmentBlendSetting),
+    StartAutoFly(CameraPathId),
+    UpdateCameraPath(CameraPathSetting),
+    SaveCameraPath(CameraPathId),
+    EditCameraPath(Option<CameraPathId>),
+    SelectCameraPathKeypoint(Option<usize>),
+    SetCameraPathRecordingMode(RecordingMode),
+    RenderCameraPath(CameraPathId),
+    UpdateFrameSequenceRendering(FrameSequenceSetting),
     UpdateMeshSetting(MeshSetting),
     UpdateRenderSettings(RenderSetting),
+    ShowProjectDialog(ProjectFileAction),
+    CloseProjectDialog,
+    OpenProject(String),
+    SaveProject(String),
+    SaveTextureMaps,
+    SaveTintMap,
+    SaveHeightmap,
+    ExportHeightmapR16,
+    ShowTileExportDialog,
+    CloseTileExportDialog,
+    ExportTiles(TileExportSettings),
     QuitRequest,
     DebugCloseProject,
-    DebugLoadTerrain(Box<config::TerrainConfig>),
     DebugShowClipmap(bool),
 }
 // ----------------------------------------------------------------------------
@@ -65,6 +97,40 @@ pub enum SunSetting {
 }
 // ----------------------------------------------------------------------------
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
+/// curve keys are addressed by index, times are normalized [0..1]
+pub enum EnvironmentCurveSetting {
+    AddKey(CurveId, f32, CurveValue),
+    UpdateKey(CurveId, usize, f32, CurveValue),
+    RemoveKey(CurveId, usize),
+}
+// ----------------------------------------------------------------------------
+#[derive(Debug)]
+pub enum EnvironmentBlendSetting {
+    LoadSecondary(String),
+    ClearSecondary,
+    SetWeight(f32),
+    /// transition duration in seconds, None stops animation
+    SetAnimation(Option<f32>),
+}
+// ----------------------------------------------------------------------------
+#[derive(Debug)]
+/// keypoints are addressed by index
+pub enum CameraPathSetting {
+    SetDuration(CameraPathId, f32),
+    SetKeypointShare(CameraPathId, usize, f32),
+    InsertKeypoint(CameraPathId, usize),
+    RemoveKeypoint(CameraPathId, usize),
+}
+// ----------------------------------------------------------------------------
+#[derive(Debug)]
+/// time of day is normalized [0..1]
+pub enum FrameSequenceSetting {
+    SetFps(u32),
+    SetTimeOfDayAnimation(Option<(f32, f32)>),
+}
+// ----------------------------------------------------------------------------
+#[derive(Debug)]
 pub enum AtmosphereSetting {
     SetRayOrigin(Vec3),
     SetSunIntensity(f32),
@@ -93,19 +159,25 @@ pub enum MeshSetting {
 #[derive(Debug)]
 // Note: as of now other settings are directly flipped
 pub enum RenderSetting {
-    OverlayWireframe(bool),
+    OverlayWireframe,
 }
 // ----------------------------------------------------------------------------
 use self::egui_extensions::UiExtension;
 
+use self::environment::{EnvironmentBlending, EnvironmentCurveEditor};
 use self::progresstracking::ProgressTracking;
+use self::project::{ProjectDialog, ProjectFileAction};
+use self::tileexport::TileExportDialog;
 // ----------------------------------------------------------------------------
 mod debug;
 
 mod toolbox;
 
+mod environment;
 mod images;
 mod progresstracking;
+mod project;
+mod tileexport;
 mod update;
 mod view;
 
@@ -122,7 +194,10 @@ impl Plugin for EditorUiPlugin {
             .add_event::<GuiAction>()
             .add_system(view::show_ui.label("gui_actions"))
             .add_system(update_input_processing_request.after("gui_actions"))
+            .add_system(update_campath_editor_pointer.after("gui_actions"))
             .add_system(handle_editor_events)
+            .add_system(project::handle_project_actions.after("gui_actions"))
+            .add_system(tileexport::handle_tileexport_actions.after("gui_actions"))
             .add_system(log_ui_actions.after("gui_actions"))
             .add_system(
                 handle_ui_actions
@@ -155,7 +230,7 @@ pub(super) fn initialize_ui(
 
                 egui_image_registry.add_image(
                     &mut egui_ctx,
-                    &mut *images,
+                    &mut images,
                     format!("terraintexture.{}.{}", texture_type, i),
                     format,
                     (size, size),
@@ -203,7 +278,7 @@ fn handle_editor_events(
                 if let Some(array) = texture_arrays.get(handle) {
                     let (_, _, img_data) = array.imagedata(**slot, TEXTURE_PREVIEW_SIZE_SMALL);
                     egui_image_registry.update_image(
-                        &mut *images,
+                        &mut images,
                         &format!("terraintexture.{}.{}", texture_ty, slot),
                         img_data,
                     );
@@ -215,6 +290,8 @@ fn handle_editor_events(
             ProgressTrackingUpdate(update) => {
                 ui_state.progress.update(update);
             }
+            ProgressTrackingCancel => ui_state.progress.cancel_tracking(),
+            ProgressTrackingFailed(error) => ui_state.progress.fail_tracking(error),
             StateChange(new_state) => ui_state.update(*new_state),
             ToggleGuiVisibility => {
                 ui_state.fullscreen = !ui_state.fullscreen;
@@ -231,8 +308,17 @@ fn handle_ui_actions(
     mut daylight_cycle: ResMut<DayNightCycle>,
     mut sun_settings: Option<ResMut<SunPositionSettings>>,
     mut atmosphere_settings: Option<ResMut<AtmosphereMat>>,
+    mut env_config: ResMut<EnvironmentConfig>,
+    mut env_settings: ResMut<EnvironmentSettings>,
     mut mesh_settings: Option<ResMut<TerrainMeshSettings>>,
+    mut campaths: ResMut<CameraPathsCollection>,
+    mut campath_assets: ResMut<Assets<CameraPath>>,
+    mut campath_recorder: ResMut<CameraPathRecorder>,
+    mut campath_editor: ResMut<CameraPathEditor>,
+    mut sequence_renderer: ResMut<FrameSequenceRenderer>,
     mut task_manager: ResMut<cmds::AsyncCommandManager>,
+    mut app_state: ResMut<State<EditorState>>,
+    mut editor_events: EventWriter<EditorEvent>,
 ) {
     for action in ui_action.iter() {
         match action {
@@ -240,6 +326,34 @@ fn handle_ui_actions(
                 // handled by toolbox module explicitely
                 // this would be the place for a reactive one shot system
             }
+            GuiAction::SaveTextureMaps => {
+                task_manager.add_new(cmds::SaveTextureMap.into());
+                editor_events.send(EditorEvent::ProgressTrackingStart(
+                    "Saving Texturing".into(),
+                    vec![cmds::TrackedProgress::SaveTextureMap(false)],
+                ));
+            }
+            GuiAction::SaveTintMap => {
+                task_manager.add_new(cmds::SaveTintMap.into());
+                editor_events.send(EditorEvent::ProgressTrackingStart(
+                    "Saving Tint Map".into(),
+                    vec![cmds::TrackedProgress::SaveTintMap(false)],
+                ));
+            }
+            GuiAction::SaveHeightmap => {
+                task_manager.add_new(cmds::SaveHeightmap.into());
+                editor_events.send(EditorEvent::ProgressTrackingStart(
+                    "Saving Heightmap".into(),
+                    vec![cmds::TrackedProgress::SaveHeightmap(false)],
+                ));
+            }
+            GuiAction::ExportHeightmapR16 => {
+                task_manager.add_new(cmds::ExportHeightmap.into());
+                editor_events.send(EditorEvent::ProgressTrackingStart(
+                    "Exporting Heightmap".into(),
+                    vec![cmds::TrackedProgress::ExportHeightmap(false)],
+                ));
+            }
             GuiAction::QuitRequest => {
                 warn!("TODO quit request");
             }
@@ -252,17 +366,74 @@ fn handle_ui_actions(
             GuiAction::UpdateAtmosphereSetting(setting) => {
                 update::update_atmosphere_settings(setting, &mut atmosphere_settings)
             }
+            GuiAction::UpdateEnvironmentCurve(setting) => {
+                update::update_environment_curve(setting, &mut env_config)
+            }
+            GuiAction::SaveEnvironmentDefinition(filepath) => {
+                if let Err(e) = env_config.save(filepath) {
+                    error!("{}", e);
+                }
+            }
+            GuiAction::UpdateEnvironmentBlending(setting) => {
+                update::update_environment_blending(setting, &mut env_settings)
+            }
+            GuiAction::StartAutoFly(id) => {
+                // autofly is a stacked state on top of editing
+                if *app_state.current() == EditorState::Editing {
+                    campaths.select(*id);
+                    app_state.overwrite_push(EditorState::AutoFlyCamera).ok();
+                }
+            }
+            GuiAction::UpdateCameraPath(setting) => {
+                update::update_camera_path(setting, &mut campath_assets, &mut campath_editor)
+            }
+            GuiAction::SaveCameraPath(id) => {
+                if let Some(path) = campath_assets.get(*id) {
+                    match path.save() {
+                        Ok(_) => info!("camera path {} saved", path.name()),
+                        Err(e) => error!("{}", e),
+                    }
+                }
+            }
+            GuiAction::EditCameraPath(Some(id)) => {
+                campaths.select(*id);
+                campath_editor.start(*id);
+            }
+            GuiAction::EditCameraPath(None) => campath_editor.stop(),
+            GuiAction::SelectCameraPathKeypoint(keypoint) => {
+                campath_editor.select_keypoint(*keypoint)
+            }
+            GuiAction::SetCameraPathRecordingMode(mode) => campath_recorder.set_mode(*mode),
+            GuiAction::RenderCameraPath(id) => {
+                // rendering is done by autofly state
+                if *app_state.current() == EditorState::Editing {
+                    campaths.select(*id);
+                    sequence_renderer.request_rendering();
+                    app_state.overwrite_push(EditorState::AutoFlyCamera).ok();
+                }
+            }
+            GuiAction::UpdateFrameSequenceRendering(setting) => {
+                update::update_frame_sequence_settings(setting, &mut sequence_renderer)
+            }
             GuiAction::UpdateMeshSetting(setting) => {
                 update::update_mesh_settings(setting, &mut mesh_settings)
             }
             GuiAction::UpdateRenderSettings(setting) => {
-                update::update_render_settings(setting, &mut *task_manager)
+                update::update_render_settings(setting, &mut task_manager)
             }
 
+            // handled by project module explicitely
+            GuiAction::ShowProjectDialog(_)
+            | GuiAction::CloseProjectDialog
+            | GuiAction::OpenProject(_)
+            | GuiAction::SaveProject(_) => {}
+
+            GuiAction::ShowTileExportDialog
+            | GuiAction::CloseTileExportDialog
+            | GuiAction::ExportTiles(_) => {}
+
             // TODO should be removed later
-            GuiAction::DebugLoadTerrain(_)
-            | GuiAction::DebugCloseProject
-            | GuiAction::DebugShowClipmap(_) => {}
+            GuiAction::DebugCloseProject | GuiAction::DebugShowClipmap(_) => {}
         }
     }
 }
@@ -277,6 +448,8 @@ impl UiState {
                 self.project_is_loading = false;
                 self.project_open = false;
                 self.toolbox.enabled = false;
+                self.environment = EnvironmentCurveEditor::default();
+                self.environment_blending = EnvironmentBlending::default();
             }
             EditorState::TerrainLoading => {
                 self.enabled = true;
@@ -326,6 +499,21 @@ fn update_input_processing_request(
     }
 }
 // ----------------------------------------------------------------------------
+fn update_campath_editor_pointer(
+    ui_state: Res<UiState>,
+    mouse_input: Res<Input<MouseButton>>,
+    windows: Res<Windows>,
+    mut campath_editor: ResMut<CameraPathEditor>,
+) {
+    if campath_editor.as_ref().is_active() {
+        let win = windows.get_primary().expect("no primary window");
+        // check if cursor is not over gui or used by gui
+        let pos = win.cursor_position().filter(|_| !ui_state.wants_input());
+
+        campath_editor.update_pointer(pos, mouse_input.pressed(MouseButton::Left));
+    }
+}
+// ----------------------------------------------------------------------------
 // debug
 // ----------------------------------------------------------------------------
 #[allow(dead_code)]
@@ -6,6 +6,8 @@ use crate::cmds::{TrackedProgress, TrackedTaskname};
 #[derive(Default)]
 pub struct ProgressTracking {
     task: Option<MultiTaskProgress>,
+    /// error of last failed task (cleared on start of next task)
+    error: Option<String>,
 }
 // ----------------------------------------------------------------------------
 #[derive(Default)]
@@ -55,6 +57,7 @@ impl ProgressTracking {
     }
     // ------------------------------------------------------------------------
     pub fn start_task_tracking(&mut self, name: &TrackedTaskname, sub_tasks: &[TrackedProgress]) {
+        self.error = None;
         let mut tasks = MultiTaskProgress {
             last_msg: name.as_str().map(|s| s.to_string()).unwrap_or_default(),
             progress: 0.0,
@@ -74,10 +77,18 @@ impl ProgressTracking {
         }
     }
     // ------------------------------------------------------------------------
-    #[allow(dead_code)]
     pub fn cancel_tracking(&mut self) {
         self.task = None;
     }
     // ------------------------------------------------------------------------
+    pub fn fail_tracking(&mut self, error: &str) {
+        self.task = None;
+        self.error = Some(error.to_string());
+    }
+    // ------------------------------------------------------------------------
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+    // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
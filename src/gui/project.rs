@@ -0,0 +1,81 @@
+// ----------------------------------------------------------------------------
+use bevy::prelude::*;
+
+use crate::config::TerrainConfig;
+use crate::terrain_material::TerrainMaterialSet;
+use crate::EditorState;
+
+use super::{GuiAction, UiState};
+// ----------------------------------------------------------------------------
+#[derive(Default)]
+pub(super) struct ProjectDialog {
+    pub show: Option<ProjectFileAction>,
+    pub filepath: String,
+    pub error: Option<String>,
+}
+// ----------------------------------------------------------------------------
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ProjectFileAction {
+    Open,
+    Save,
+}
+// ----------------------------------------------------------------------------
+// systems
+// ----------------------------------------------------------------------------
+pub(super) fn handle_project_actions(
+    mut ui_state: ResMut<UiState>,
+    mut ui_action: EventReader<GuiAction>,
+    mut app_state: ResMut<State<EditorState>>,
+    mut terrain_config: ResMut<TerrainConfig>,
+    materialset: Res<TerrainMaterialSet>,
+) {
+    for action in ui_action.iter() {
+        match action {
+            GuiAction::ShowProjectDialog(action) => {
+                ui_state.project.show = Some(*action);
+                ui_state.project.error = None;
+            }
+            GuiAction::CloseProjectDialog => {
+                ui_state.project.show = None;
+                ui_state.project.error = None;
+            }
+            GuiAction::OpenProject(filepath) => match TerrainConfig::load(filepath) {
+                Ok(new_config) => {
+                    info!("loading project {}...", filepath);
+                    ui_state.project.show = None;
+                    ui_state.project.error = None;
+                    ui_state.project.filepath = filepath.clone();
+
+                    *terrain_config = new_config;
+                    app_state.overwrite_set(EditorState::TerrainLoading).ok();
+                }
+                Err(e) => {
+                    error!("{}", e);
+                    // show error in dialog (even if triggered from somewhere else)
+                    ui_state.project.show = Some(ProjectFileAction::Open);
+                    ui_state.project.filepath = filepath.clone();
+                    ui_state.project.error = Some(e);
+                }
+            },
+            GuiAction::SaveProject(filepath) => {
+                // material parameters may have been changed in the editor
+                terrain_config.set_material_parameters(&materialset.parameter);
+
+                match terrain_config.save(filepath) {
+                    Ok(_) => {
+                        info!("project saved to {}", filepath);
+                        ui_state.project.show = None;
+                        ui_state.project.error = None;
+                        ui_state.project.filepath = filepath.clone();
+                    }
+                    Err(e) => {
+                        error!("{}", e);
+                        ui_state.project.error = Some(e);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+// ----------------------------------------------------------------------------
@@ -0,0 +1,69 @@
+// ----------------------------------------------------------------------------
+use bevy::prelude::*;
+
+use crate::cmds;
+use crate::config::TerrainConfig;
+use crate::tileexport::{self, TileExportSettings};
+use crate::EditorEvent;
+
+use super::{GuiAction, UiState};
+// ----------------------------------------------------------------------------
+#[derive(Default)]
+pub(super) struct TileExportDialog {
+    pub show: bool,
+    pub settings: TileExportSettings,
+    /// (tiles per edge, tile resolution) of the current terrain
+    pub layout: (u32, u32),
+    pub error: Option<String>,
+}
+// ----------------------------------------------------------------------------
+// systems
+// ----------------------------------------------------------------------------
+pub(super) fn handle_tileexport_actions(
+    mut ui_state: ResMut<UiState>,
+    mut ui_action: EventReader<GuiAction>,
+    mut export_settings: ResMut<TileExportSettings>,
+    mut task_manager: ResMut<cmds::AsyncCommandManager>,
+    mut editor_events: EventWriter<EditorEvent>,
+    terrain_config: Res<TerrainConfig>,
+) {
+    for action in ui_action.iter() {
+        match action {
+            GuiAction::ShowTileExportDialog => {
+                let dialog = &mut ui_state.tile_export;
+                if dialog.settings.directory.is_empty() {
+                    dialog.settings = TileExportSettings::new(&terrain_config);
+                }
+                dialog.layout = (
+                    tileexport::tiles_per_edge(&terrain_config),
+                    tileexport::tile_resolution(&terrain_config),
+                );
+                dialog.show = true;
+                dialog.error = None;
+            }
+            GuiAction::CloseTileExportDialog => {
+                ui_state.tile_export.show = false;
+                ui_state.tile_export.error = None;
+            }
+            GuiAction::ExportTiles(settings) => match settings.validate(&terrain_config) {
+                Ok(_) => {
+                    ui_state.tile_export.show = false;
+                    ui_state.tile_export.error = None;
+                    *export_settings = settings.clone();
+
+                    task_manager.add_new(cmds::ExportTiles.into());
+                    editor_events.send(EditorEvent::ProgressTrackingStart(
+                        "Exporting Terrain Tiles".into(),
+                        vec![cmds::TrackedProgress::ExportTiles(false)],
+                    ));
+                }
+                Err(e) => {
+                    error!("{}", e);
+                    ui_state.tile_export.error = Some(e);
+                }
+            },
+            _ => {}
+        }
+    }
+}
+// ----------------------------------------------------------------------------
@@ -0,0 +1,55 @@
+// ----------------------------------------------------------------------------
+// rule based auto texturing settings
+// ----------------------------------------------------------------------------
+use bevy::math::{uvec2, UVec2};
+
+use crate::clipmap::Rectangle;
+
+use super::AutoTexturingRule;
+// ----------------------------------------------------------------------------
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub(super) enum AutoTexturingRegion {
+    CompleteMap,
+    Rectangle,
+}
+// ----------------------------------------------------------------------------
+pub(super) struct AutoTexturingSettings {
+    pub rules: Vec<AutoTexturingRule>,
+
+    pub region: AutoTexturingRegion,
+    /// rectangle region in map coordinates
+    pub region_pos: UVec2,
+    pub region_size: UVec2,
+
+    /// a preview was requested and is neither applied nor discarded
+    pub preview_active: bool,
+}
+// ----------------------------------------------------------------------------
+impl AutoTexturingSettings {
+    // ------------------------------------------------------------------------
+    pub fn region(&self) -> Option<Rectangle> {
+        match self.region {
+            AutoTexturingRegion::CompleteMap => None,
+            AutoTexturingRegion::Rectangle => Some(Rectangle {
+                pos: self.region_pos,
+                size: self.region_size.max(UVec2::ONE),
+            }),
+        }
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+// Default
+// ----------------------------------------------------------------------------
+impl Default for AutoTexturingSettings {
+    fn default() -> Self {
+        Self {
+            rules: vec![AutoTexturingRule::default()],
+            region: AutoTexturingRegion::CompleteMap,
+            region_pos: UVec2::ZERO,
+            region_size: uvec2(1024, 1024),
+            preview_active: false,
+        }
+    }
+}
+// ----------------------------------------------------------------------------
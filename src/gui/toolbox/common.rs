@@ -1,14 +1,51 @@
 // ----------------------------------------------------------------------------
 pub(super) const BRUSH_SIZE_MIN: u8 = 1;
 pub(super) const BRUSH_SIZE_MAX: u8 = 250;
+/// distance between brush placements along the stroke (relative to radius)
+pub(super) const BRUSH_SPACING_MIN: f32 = 0.05;
+pub(super) const BRUSH_SPACING_MAX: f32 = 2.0;
+/// fast pointer moves with tiny brushes must not flood the painting systems
+const MAX_DABS_PER_MOVE: usize = 256;
 // ---------------------------------------------------------------------------
-use bevy::prelude::Color;
+use std::sync::Arc;
+
+use bevy::prelude::{Color, Vec2};
+
+use crate::terrain_painting::{BrushFalloff, BrushShape, BrushStamp, StampTransform, StrokeId};
 
 use super::OverwriteProbability;
 // ---------------------------------------------------------------------------
 #[derive(Clone, Copy)]
 pub(super) struct BrushSize(u8);
 // ----------------------------------------------------------------------------
+/// shared by all painting brushes (like brush size)
+pub(super) struct BrushShapeSettings {
+    pub use_stamp: bool,
+    pub falloff: BrushFalloff,
+    pub stamp: Option<Arc<BrushStamp>>,
+    pub stamp_filepath: String,
+    pub stamp_transform: StampTransform,
+    /// distance between brush placements along the stroke relative to radius
+    pub spacing: f32,
+}
+// ----------------------------------------------------------------------------
+/// session seed of randomized paint commands (see PaintingRng)
+#[derive(Default)]
+pub(super) struct RandomizationSettings {
+    /// current session seed
+    pub seed: u64,
+    pub seed_input: String,
+}
+// ----------------------------------------------------------------------------
+/// pointer path of the current stroke: brush placements (dabs) are
+/// interpolated along the path so fast pointer moves don't leave gaps
+#[derive(Default)]
+pub(super) struct StrokePath {
+    stroke: Option<StrokeId>,
+    /// world position of last dab
+    last_dab: Vec2,
+}
+// ----------------------------------------------------------------------------
 pub(super) struct PointerSettings {
     pub size: BrushSize,
     pub ring_width: f32,
@@ -52,9 +89,7 @@ impl BrushSize {
     }
     // ------------------------------------------------------------------------
     pub fn ring_width(&self) -> f32 {
-        ((self.0 as f32 / BRUSH_SIZE_MAX as f32) * 10.0)
-            .max(0.5)
-            .min(10.0)
+        ((self.0 as f32 / BRUSH_SIZE_MAX as f32) * 10.0).clamp(0.5, 10.0)
     }
     // ------------------------------------------------------------------------
     pub(super) fn as_mut(&mut self) -> &mut u8 {
@@ -63,6 +98,55 @@ impl BrushSize {
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
+impl BrushShapeSettings {
+    // ------------------------------------------------------------------------
+    pub fn shape(&self) -> BrushShape {
+        match &self.stamp {
+            Some(stamp) if self.use_stamp => BrushShape::Stamp(stamp.clone(), self.stamp_transform),
+            _ => BrushShape::Round(self.falloff),
+        }
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl StrokePath {
+    // ------------------------------------------------------------------------
+    /// world positions of all dabs between last dab and current pointer
+    /// position with the given distance. first position of a stroke is always
+    /// a dab. a pointer which is not moved (far enough) doesn't add any dabs.
+    pub fn dabs(&mut self, stroke: StrokeId, pos: Vec2, distance: f32) -> Vec<Vec2> {
+        if self.stroke != Some(stroke) {
+            self.stroke = Some(stroke);
+            self.last_dab = pos;
+            return vec![pos];
+        }
+        let delta = pos - self.last_dab;
+        let length = delta.length();
+        let distance = distance.max(f32::EPSILON);
+
+        let count = (length / distance) as usize;
+        if count == 0 {
+            return Vec::default();
+        }
+        // enlarge spacing instead of dropping the end of the path
+        let (count, distance) = if count > MAX_DABS_PER_MOVE {
+            (MAX_DABS_PER_MOVE, length / MAX_DABS_PER_MOVE as f32)
+        } else {
+            (count, distance)
+        };
+        let step = delta / length * distance;
+        let start = self.last_dab;
+
+        let dabs = (1..=count)
+            .map(|i| start + step * i as f32)
+            .collect::<Vec<_>>();
+        // remaining distance is carried over to the next pointer move
+        self.last_dab = dabs[count - 1];
+        dabs
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
 impl From<u8> for OverwriteProbability {
     fn from(v: u8) -> Self {
         Self(v as f32 / 100.0)
@@ -77,6 +161,19 @@ impl Default for BrushSize {
     }
 }
 // ----------------------------------------------------------------------------
+impl Default for BrushShapeSettings {
+    fn default() -> Self {
+        Self {
+            use_stamp: false,
+            falloff: BrushFalloff::default(),
+            stamp: None,
+            stamp_filepath: String::default(),
+            stamp_transform: StampTransform::default(),
+            spacing: 0.25,
+        }
+    }
+}
+// ----------------------------------------------------------------------------
 impl Default for PointerSettings {
     fn default() -> Self {
         Self {
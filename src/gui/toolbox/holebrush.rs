@@ -0,0 +1,32 @@
+// ----------------------------------------------------------------------------
+// terrain hole brush operations
+// ----------------------------------------------------------------------------
+use bevy::prelude::Color;
+
+use crate::terrain_render::TerrainRenderSettings;
+
+use super::ToolSettings;
+// ----------------------------------------------------------------------------
+/// holes are filled with the overlay and background material of the texture
+/// brush
+#[derive(Default)]
+pub(super) struct BrushSettings {
+    pub picker_activated: bool,
+}
+// ----------------------------------------------------------------------------
+impl ToolSettings for BrushSettings {
+    // ------------------------------------------------------------------------
+    fn pointer_color(&self) -> Color {
+        Color::ORANGE_RED
+    }
+    // ------------------------------------------------------------------------
+    fn sync_rendersettings(&mut self, settings: &mut TerrainRenderSettings) {
+        settings.reset_exclusive_view();
+
+        // holes (and filled holes) must be visible
+        settings.ignore_overlay_texture = false;
+        settings.ignore_bkgrnd_texture = false;
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
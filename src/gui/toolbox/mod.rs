@@ -1,18 +1,26 @@
 // ----------------------------------------------------------------------------
+use std::sync::Arc;
+
 use bevy::input::mouse::MouseWheel;
 use bevy::prelude::*;
 
+use crate::autofly_camera::CameraPathEditor;
+use crate::cmds::TrackedProgress;
 use crate::terrain_material::MaterialSlot;
 use crate::terrain_painting::{
-    BrushPlacement, OverwriteProbability, PaintingEvent, PickedType, PickerEvent, PickerResult,
-    PickerResultEvent, SlopeBlendThreshold, TextureScale, Variance,
+    AutoTexturingEvent, AutoTexturingRule, BrushPlacement, BrushStamp, HeightDelta,
+    MaterialRemapEvent, MaterialRemapResultEvent, MaterialUsageEvent, MaterialUsageResultEvent,
+    OverwriteProbability, PaintingEvent, PaintingRng, PickedType, PickerEvent, PickerResult,
+    PickerResultEvent, SculptStrength, SelectionEvent, SelectionResultEvent, SlopeBlendThreshold,
+    StrokeId, TextureScale, TintColor, TintJitter, TintStrength, Variance,
 };
 use crate::terrain_render::{
     BrushPointer, BrushPointerEventData, BrushPointerEventReceiver, TerrainMaterialSet,
     TerrainRenderSettings,
 };
+use crate::EditorEvent;
 
-use common::{BrushSize, PointerSettings};
+use common::{BrushShapeSettings, BrushSize, PointerSettings, RandomizationSettings, StrokePath};
 
 use super::{GuiAction, UiState};
 // ----------------------------------------------------------------------------
@@ -30,7 +38,11 @@ impl Plugin for TexturingToolboxPlugin {
                     .after("gui_actions"),
             )
             .add_system(process_brush_clicks.before("handle_ui_actions"))
-            .add_system(process_picker_results);
+            .add_system(sync_painting_seed)
+            .add_system(process_picker_results)
+            .add_system(process_remap_results)
+            .add_system(process_usage_results)
+            .add_system(process_selection_results);
     }
     // ------------------------------------------------------------------------
 }
@@ -42,8 +54,22 @@ pub struct ToolboxState {
     texture_brush: texturebrush::BrushSettings,
     blending_brush: blendingbrush::BrushSettings,
     scaling_brush: scalingbrush::BrushSettings,
+    tint_brush: tintbrush::BrushSettings,
+    sculpt_brush: sculptbrush::BrushSettings,
+    hole_brush: holebrush::BrushSettings,
+    autotexturing: autotexturing::AutoTexturingSettings,
+    remapping: remapping::MaterialRemapSettings,
+    usage: usage::MaterialUsageSettings,
+    selection_tool: selectiontool::SelectionSettings,
 
     brush_size: BrushSize,
+    brush_shape: BrushShapeSettings,
+    randomization: RandomizationSettings,
+
+    /// id of current (or last) brush stroke: a stroke starts with a paint
+    /// click and continues while the mouse button is pressed
+    stroke: StrokeId,
+    stroke_active: bool,
 }
 // ---------------------------------------------------------------------------
 #[derive(Debug)]
@@ -51,6 +77,8 @@ pub struct ToolboxState {
 pub enum ToolboxAction {
     ChangedToolSelection,
     UpdateBrushSettings,
+    LoadBrushStamp(String),
+    SetPaintingSeed(u64),
     SelectOverlayTexture(MaterialSlot),
     SelectBackgroundTexture(MaterialSlot),
     UpdateMaterial(MaterialSlot, MaterialSetting),
@@ -61,6 +89,18 @@ pub enum ToolboxAction {
     TexturePickerSelected(bool),
     SlopeBlendThresholdPickerSelected(bool),
     BkgrndScalingPickerSelected(bool),
+    ShowTintMap(bool),
+    TintPickerSelected(bool),
+    HolePickerSelected(bool),
+    PreviewAutoTexturing,
+    ApplyAutoTexturing,
+    DiscardAutoTexturing,
+    PreviewMaterialRemapping,
+    ApplyMaterialRemapping,
+    RefreshMaterialUsage,
+    HighlightMaterialCoverage(Option<MaterialSlot>),
+    CopySelection,
+    ClearSelection,
 }
 // ---------------------------------------------------------------------------
 #[derive(Debug)]
@@ -75,19 +115,34 @@ pub enum MaterialSetting {
     SetFalloff(f32),
 }
 // ---------------------------------------------------------------------------
-#[derive(Eq, PartialEq, Clone, Copy)]
+#[derive(Eq, PartialEq, Clone, Copy, Default)]
 enum ToolSelection {
+    #[default]
     Texturing,
     Blending,
     Scaling,
+    Tinting,
+    Sculpting,
+    Holes,
+    Selection,
+    AutoTexturing,
+    MaterialRemapping,
+    MaterialUsage,
     MaterialParameters,
 }
 // ----------------------------------------------------------------------------
 mod common;
 
+mod autotexturing;
 mod blendingbrush;
+mod holebrush;
+mod remapping;
 mod scalingbrush;
+mod sculptbrush;
+mod selectiontool;
 mod texturebrush;
+mod tintbrush;
+mod usage;
 
 mod update;
 pub(super) mod view;
@@ -97,8 +152,10 @@ pub(super) mod view;
 fn process_brush_clicks(
     receiver: Res<BrushPointerEventReceiver>,
     ui_state: Res<UiState>,
+    mut stroke_path: Local<StrokePath>,
     mut painting_events: EventWriter<PaintingEvent>,
     mut picker_events: EventWriter<PickerEvent>,
+    mut selection_events: EventWriter<SelectionEvent>,
 ) {
     use PickedType::*;
     use ToolSelection::*;
@@ -107,33 +164,59 @@ fn process_brush_clicks(
         let settings = &ui_state.toolbox;
 
         if let Some(selection) = settings.selection {
+            // pickers always use the (hard) brush center
             let placement = BrushPlacement::new(pos, radius);
-            match selection {
+
+            let cmds = match selection {
                 // -- picker
                 Texturing if settings.texture_brush.picker_activated => {
                     let cmds = update::create_texture_picker_cmds(button, &settings.texture_brush);
                     picker_events.send(PickerEvent::new(placement, cmds));
+                    continue;
                 }
                 Blending if settings.blending_brush.picker_activated => {
                     picker_events.send(PickerEvent::new(placement, vec![SlopeBlendThreshold]));
+                    continue;
                 }
                 Scaling if settings.scaling_brush.picker_activated => {
                     picker_events.send(PickerEvent::new(placement, vec![BackgroundScaling]));
+                    continue;
                 }
-                // -- painting
-                Texturing => {
-                    let cmds = update::create_texture_paint_cmds(button, &settings.texture_brush);
-                    painting_events.send(PaintingEvent::new(placement, cmds));
+                Tinting if settings.tint_brush.picker_activated => {
+                    picker_events.send(PickerEvent::new(placement, vec![TintColor]));
+                    continue;
                 }
-                Blending => {
-                    let cmds = update::create_blending_paint_cmds(button, &settings.blending_brush);
-                    painting_events.send(PaintingEvent::new(placement, cmds));
+                Holes if settings.hole_brush.picker_activated => {
+                    let cmds = update::create_hole_picker_cmds(button);
+                    picker_events.send(PickerEvent::new(placement, cmds));
+                    continue;
                 }
-                Scaling => {
-                    let cmds = update::create_scaling_paint_cmds(button, &settings.scaling_brush);
-                    painting_events.send(PaintingEvent::new(placement, cmds));
+                // -- painting
+                Texturing => update::create_texture_paint_cmds(button, &settings.texture_brush),
+                Blending => update::create_blending_paint_cmds(button, &settings.blending_brush),
+                Scaling => update::create_scaling_paint_cmds(button, &settings.scaling_brush),
+                Tinting => update::create_tint_paint_cmds(button, &settings.tint_brush),
+                Sculpting => update::create_sculpt_paint_cmds(button, &settings.sculpt_brush),
+                Holes => update::create_hole_paint_cmds(button, &settings.texture_brush),
+                // -- selection
+                Selection => {
+                    let tool = &settings.selection_tool;
+                    if let Some(event) =
+                        update::create_selection_event(button, settings.stroke, pos, tool)
+                    {
+                        selection_events.send(event);
+                    }
+                    continue;
                 }
-                MaterialParameters => continue,
+                AutoTexturing | MaterialRemapping | MaterialUsage | MaterialParameters => continue,
+            };
+
+            // fast pointer moves are filled with brush placements along the path
+            let spacing = settings.brush_shape.spacing * radius;
+            for pos in stroke_path.dabs(settings.stroke, pos, spacing) {
+                let placement =
+                    BrushPlacement::new(pos, radius).with_shape(settings.brush_shape.shape());
+                painting_events.send(PaintingEvent::new(settings.stroke, placement, cmds.clone()));
             }
         }
     }
@@ -155,8 +238,8 @@ fn process_picker_results(
                 ui_state.toolbox.texture_brush.overlay_texture = material_slot;
                 update::update_brush_on_material_selection(
                     &mut ui_state.toolbox,
-                    &mut *brush,
-                    &mut *rendersettings,
+                    &mut brush,
+                    &mut rendersettings,
                     true,
                 );
             }
@@ -164,20 +247,26 @@ fn process_picker_results(
                 ui_state.toolbox.texture_brush.bkgrnd_texture = material_slot;
                 update::update_brush_on_material_selection(
                     &mut ui_state.toolbox,
-                    &mut *brush,
-                    &mut *rendersettings,
+                    &mut brush,
+                    &mut rendersettings,
                     false,
                 );
             }
             BlendThreshold(value) => {
                 update::update_brush_on_blendthreshold_pick(
                     &mut ui_state.toolbox,
-                    &mut *brush,
+                    &mut brush,
                     value,
                 );
             }
             BackgroundScaling(value) => {
-                update::update_brush_on_scaling_pick(&mut ui_state.toolbox, &mut *brush, value);
+                update::update_brush_on_scaling_pick(&mut ui_state.toolbox, &mut brush, value);
+            }
+            Tint(color) => {
+                update::update_brush_on_tint_pick(&mut ui_state.toolbox, &mut brush, color);
+            }
+            Hole => {
+                info!("picked terrain hole: no material available");
             }
         }
     }
@@ -187,12 +276,56 @@ fn process_picker_results(
     }
 }
 // ----------------------------------------------------------------------------
+fn process_remap_results(
+    mut ui_state: ResMut<UiState>,
+    mut remap_results: EventReader<MaterialRemapResultEvent>,
+) {
+    for result in remap_results.iter() {
+        ui_state.toolbox.remapping.result = Some((result.table.clone(), result.stats.clone()));
+    }
+}
+// ----------------------------------------------------------------------------
+fn process_usage_results(
+    mut ui_state: ResMut<UiState>,
+    mut usage_results: EventReader<MaterialUsageResultEvent>,
+) {
+    for result in usage_results.iter() {
+        ui_state.toolbox.usage.usage = Some(result.0.clone());
+    }
+}
+// ----------------------------------------------------------------------------
+fn process_selection_results(
+    mut ui_state: ResMut<UiState>,
+    mut selection_results: EventReader<SelectionResultEvent>,
+    mut rendersettings: ResMut<TerrainRenderSettings>,
+) {
+    for result in selection_results.iter() {
+        let tool = &mut ui_state.toolbox.selection_tool;
+        match result {
+            SelectionResultEvent::Selection(bounds) => {
+                tool.selected = bounds.is_some();
+                rendersettings.selection = *bounds;
+            }
+            SelectionResultEvent::Clipboard(size, tint) => {
+                tool.clipboard = Some((*size, *tint));
+            }
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+#[allow(clippy::too_many_arguments)]
 fn handle_ui_actions(
     mut ui_state: ResMut<UiState>,
     mut ui_action: EventReader<GuiAction>,
     mut brush: ResMut<BrushPointer>,
     mut materialset: ResMut<TerrainMaterialSet>,
     mut rendersettings: ResMut<TerrainRenderSettings>,
+    mut autotexturing_events: EventWriter<AutoTexturingEvent>,
+    mut remap_events: EventWriter<MaterialRemapEvent>,
+    mut usage_events: EventWriter<MaterialUsageEvent>,
+    mut selection_events: EventWriter<SelectionEvent>,
+    mut editor_events: EventWriter<EditorEvent>,
+    mut painting_rng: ResMut<PaintingRng>,
 ) {
     use ToolboxAction::*;
 
@@ -202,22 +335,43 @@ fn handle_ui_actions(
                 ChangedToolSelection => {
                     update::on_changed_tool_selection(
                         &mut ui_state.toolbox,
-                        &mut *brush,
-                        &mut *rendersettings,
+                        &mut brush,
+                        &mut rendersettings,
                     );
+                    // stats are refreshed on demand only
+                    let toolbox = &ui_state.toolbox;
+                    if toolbox.selection == Some(ToolSelection::MaterialUsage)
+                        && toolbox.usage.usage.is_none()
+                    {
+                        usage_events.send(MaterialUsageEvent);
+                    }
                 }
                 UpdateBrushSettings if !ui_state.toolbox.has_projected_pointer() => {
                     // ignore update if there is no pointer for currently selected tool
                 }
                 UpdateBrushSettings => {
-                    update::update_brush_pointer(&ui_state.toolbox.pointer_settings(), &mut *brush);
+                    update::update_brush_pointer(&ui_state.toolbox.pointer_settings(), &mut brush);
+                }
+                LoadBrushStamp(filepath) => match BrushStamp::load(filepath) {
+                    Ok(stamp) => {
+                        info!("loaded brush stamp {:?}", stamp);
+                        let shape = &mut ui_state.toolbox.brush_shape;
+                        shape.stamp = Some(Arc::new(stamp));
+                        shape.use_stamp = true;
+                    }
+                    Err(msg) => error!("{}", msg),
+                },
+                SetPaintingSeed(seed) => {
+                    painting_rng.set_session_seed(*seed);
+                    // replaying recorded strokes requires the same stroke ids
+                    ui_state.toolbox.stroke = StrokeId::default();
                 }
                 SelectOverlayTexture(material_slot) => {
                     ui_state.toolbox.texture_brush.overlay_texture = *material_slot;
                     update::update_brush_on_material_selection(
                         &mut ui_state.toolbox,
-                        &mut *brush,
-                        &mut *rendersettings,
+                        &mut brush,
+                        &mut rendersettings,
                         true,
                     );
                 }
@@ -225,59 +379,133 @@ fn handle_ui_actions(
                     ui_state.toolbox.texture_brush.bkgrnd_texture = *material_slot;
                     update::update_brush_on_material_selection(
                         &mut ui_state.toolbox,
-                        &mut *brush,
-                        &mut *rendersettings,
+                        &mut brush,
+                        &mut rendersettings,
                         false,
                     );
                 }
                 UpdateMaterial(slot, setting) => {
-                    update::update_material_settings(*slot, setting, &mut *materialset);
+                    update::update_material_settings(*slot, setting, &mut materialset);
                 }
                 ShowOnlyOverlayTexture(only_overlay) => {
                     update::render_only_overlay_material(
                         &mut ui_state.toolbox.texture_brush,
-                        &mut *rendersettings,
+                        &mut rendersettings,
                         *only_overlay,
                     );
                 }
                 ShowOnlyBackgroundTexture(only_bkgrnd) => {
                     update::render_only_bkgrnd_material(
                         &mut ui_state.toolbox.texture_brush,
-                        &mut *rendersettings,
+                        &mut rendersettings,
                         *only_bkgrnd,
                     );
                 }
                 ShowBackgroundScaling(show) => {
                     update::render_only_bkgrnd_scaling(
                         &mut ui_state.toolbox.scaling_brush,
-                        &mut *rendersettings,
+                        &mut rendersettings,
                         *show,
                     );
                 }
                 ShowSlopeBlendThreshold(show) => {
                     update::render_only_slopeblend_threshold(
                         &mut ui_state.toolbox.blending_brush,
-                        &mut *rendersettings,
+                        &mut rendersettings,
                         *show,
                     );
                 }
                 TexturePickerSelected(selected) => {
                     ui_state.toolbox.texture_brush.picker_activated = *selected;
-                    update::picker_selection(&mut ui_state.toolbox, &mut *brush, *selected);
+                    update::picker_selection(&mut ui_state.toolbox, &mut brush, *selected);
                 }
                 SlopeBlendThresholdPickerSelected(selected) => {
                     ui_state.toolbox.blending_brush.picker_activated = *selected;
-                    update::picker_selection(&mut ui_state.toolbox, &mut *brush, *selected);
+                    update::picker_selection(&mut ui_state.toolbox, &mut brush, *selected);
                 }
                 BkgrndScalingPickerSelected(selected) => {
                     ui_state.toolbox.scaling_brush.picker_activated = *selected;
-                    update::picker_selection(&mut ui_state.toolbox, &mut *brush, *selected);
+                    update::picker_selection(&mut ui_state.toolbox, &mut brush, *selected);
+                }
+                ShowTintMap(show) => {
+                    update::render_only_tint_map(
+                        &mut ui_state.toolbox.tint_brush,
+                        &mut rendersettings,
+                        *show,
+                    );
+                }
+                TintPickerSelected(selected) => {
+                    ui_state.toolbox.tint_brush.picker_activated = *selected;
+                    update::picker_selection(&mut ui_state.toolbox, &mut brush, *selected);
+                }
+                HolePickerSelected(selected) => {
+                    ui_state.toolbox.hole_brush.picker_activated = *selected;
+                    update::picker_selection(&mut ui_state.toolbox, &mut brush, *selected);
+                }
+                PreviewAutoTexturing => {
+                    let settings = &mut ui_state.toolbox.autotexturing;
+                    settings.preview_active = true;
+                    autotexturing_events.send(AutoTexturingEvent::Preview(
+                        settings.rules.clone(),
+                        settings.region(),
+                    ));
+                    editor_events.send(EditorEvent::ProgressTrackingStart(
+                        "Auto Texturing".into(),
+                        vec![TrackedProgress::GeneratedAutoTexturing(0, 1)],
+                    ));
+                }
+                ApplyAutoTexturing => {
+                    ui_state.toolbox.autotexturing.preview_active = false;
+                    autotexturing_events.send(AutoTexturingEvent::Apply);
+                }
+                DiscardAutoTexturing => {
+                    ui_state.toolbox.autotexturing.preview_active = false;
+                    autotexturing_events.send(AutoTexturingEvent::Discard);
+                }
+                PreviewMaterialRemapping => {
+                    let table = ui_state.toolbox.remapping.table.clone();
+                    remap_events.send(MaterialRemapEvent::Preview(table));
+                    editor_events.send(EditorEvent::ProgressTrackingStart(
+                        "Material Remapping Preview".into(),
+                        vec![TrackedProgress::RemappedMaterials(0, 1)],
+                    ));
+                }
+                ApplyMaterialRemapping => {
+                    let table = ui_state.toolbox.remapping.table.clone();
+                    remap_events.send(MaterialRemapEvent::Apply(table));
+                    editor_events.send(EditorEvent::ProgressTrackingStart(
+                        "Material Remapping".into(),
+                        vec![TrackedProgress::RemappedMaterials(0, 1)],
+                    ));
+                }
+                RefreshMaterialUsage => {
+                    usage_events.send(MaterialUsageEvent);
+                }
+                HighlightMaterialCoverage(slot) => {
+                    update::render_only_material_coverage(
+                        &mut ui_state.toolbox.usage,
+                        &mut rendersettings,
+                        *slot,
+                    );
+                }
+                CopySelection => {
+                    let include_tint = ui_state.toolbox.selection_tool.include_tint;
+                    selection_events.send(SelectionEvent::Copy { include_tint });
+                }
+                ClearSelection => {
+                    selection_events.send(SelectionEvent::Clear);
                 }
             }
         }
     }
 }
 // ----------------------------------------------------------------------------
+fn sync_painting_seed(painting_rng: Res<PaintingRng>, mut ui_state: ResMut<UiState>) {
+    if painting_rng.is_changed() {
+        ui_state.toolbox.randomization.seed = painting_rng.session_seed();
+    }
+}
+// ----------------------------------------------------------------------------
 fn init_brush_pointer(mut brush_pointer: ResMut<BrushPointer>) {
     update::update_brush_pointer(&PointerSettings::default(), &mut brush_pointer);
 }
@@ -288,17 +516,36 @@ fn update_brush_pointer_info(
     mouse_input: Res<Input<MouseButton>>,
     mut mouse_wheel: EventReader<MouseWheel>,
     windows: Res<Windows>,
+    campath_editor: Res<CameraPathEditor>,
 ) {
+    // pointer is used for dragging camera path gizmos while editing paths
+    let wants_input = ui_state.wants_input() || campath_editor.is_active();
     let toolbox = &mut ui_state.toolbox;
+
+    if mouse_input.any_just_released([MouseButton::Left, MouseButton::Right]) {
+        toolbox.stroke_active = false;
+    }
     // check if cursor is not over gui or used by gui (slider draging into 3d area)
-    if toolbox.enabled && toolbox.has_projected_pointer() && !ui_state.wants_input() {
+    if toolbox.enabled && toolbox.has_projected_pointer() && !wants_input {
         let win = windows.get_primary().expect("no primary window");
 
         brush_pointer.active = true;
         if let Some(mouse_pos) = win.cursor_position() {
+            let (primary, secondary) = (MouseButton::Left, MouseButton::Right);
+
+            // pickers do not paint: they are single click only and do not
+            // start a stroke
+            if mouse_input.any_just_pressed([primary, secondary]) && !toolbox.is_picker_active() {
+                toolbox.stroke = toolbox.stroke.next();
+                toolbox.stroke_active = true;
+            }
+            let stroke_active = toolbox.stroke_active;
+
             brush_pointer.pos = mouse_pos * win.scale_factor() as f32;
-            brush_pointer.click_primary = mouse_input.just_pressed(MouseButton::Left);
-            brush_pointer.click_secondary = mouse_input.just_pressed(MouseButton::Right);
+            brush_pointer.click_primary = mouse_input.just_pressed(primary)
+                || (stroke_active && mouse_input.pressed(primary));
+            brush_pointer.click_secondary = mouse_input.just_pressed(secondary)
+                || (stroke_active && mouse_input.pressed(secondary));
 
             for e in mouse_wheel.iter() {
                 ui_state.toolbox.rescale_pointer(e.y);
@@ -326,8 +573,13 @@ impl ToolboxState {
     fn has_projected_pointer(&self) -> bool {
         use ToolSelection::*;
         match self.selection {
-            Some(MaterialParameters) | None => false,
-            Some(Texturing) | Some(Scaling) | Some(Blending) => true,
+            Some(AutoTexturing)
+            | Some(MaterialRemapping)
+            | Some(MaterialUsage)
+            | Some(MaterialParameters)
+            | None => false,
+            Some(Texturing) | Some(Scaling) | Some(Blending) | Some(Tinting) | Some(Sculpting)
+            | Some(Holes) | Some(Selection) => true,
         }
     }
     // ------------------------------------------------------------------------
@@ -335,13 +587,39 @@ impl ToolboxState {
         self.brush_size.scale(scale);
     }
     // ------------------------------------------------------------------------
+    fn is_picker_active(&self) -> bool {
+        use ToolSelection::*;
+        match self.selection {
+            Some(Texturing) => self.texture_brush.picker_activated,
+            Some(Blending) => self.blending_brush.picker_activated,
+            Some(Scaling) => self.scaling_brush.picker_activated,
+            Some(Tinting) => self.tint_brush.picker_activated,
+            Some(Holes) => self.hole_brush.picker_activated,
+            Some(Sculpting)
+            | Some(Selection)
+            | Some(AutoTexturing)
+            | Some(MaterialRemapping)
+            | Some(MaterialUsage)
+            | Some(MaterialParameters)
+            | None => false,
+        }
+    }
+    // ------------------------------------------------------------------------
     fn reset_picker(&mut self) {
         use ToolSelection::*;
         match self.selection {
             Some(Texturing) => self.texture_brush.picker_activated = false,
             Some(Blending) => self.blending_brush.picker_activated = false,
             Some(Scaling) => self.scaling_brush.picker_activated = false,
-            Some(MaterialParameters) | None => {}
+            Some(Tinting) => self.tint_brush.picker_activated = false,
+            Some(Holes) => self.hole_brush.picker_activated = false,
+            Some(Sculpting)
+            | Some(Selection)
+            | Some(AutoTexturing)
+            | Some(MaterialRemapping)
+            | Some(MaterialUsage)
+            | Some(MaterialParameters)
+            | None => {}
         }
     }
     // ------------------------------------------------------------------------
@@ -351,14 +629,27 @@ impl ToolboxState {
             Some(Texturing) => self.texture_brush.pointer_color(),
             Some(Blending) => self.blending_brush.pointer_color(),
             Some(Scaling) => self.scaling_brush.pointer_color(),
-            Some(MaterialParameters) | None => {
+            Some(Tinting) => self.tint_brush.pointer_color(),
+            Some(Sculpting) => self.sculpt_brush.pointer_color(),
+            Some(Holes) => self.hole_brush.pointer_color(),
+            Some(Selection) => self.selection_tool.pointer_color(),
+            Some(AutoTexturing)
+            | Some(MaterialRemapping)
+            | Some(MaterialUsage)
+            | Some(MaterialParameters)
+            | None => {
                 // pointer should be deactivated, see has_projected_pointer
                 unreachable!("pointer should have been deactivated!")
             }
         };
+        // selections and pastes use the (hard) pointer center only
+        let size = match self.selection {
+            Some(Selection) => BrushSize::minimal(),
+            _ => self.brush_size,
+        };
         PointerSettings {
-            size: self.brush_size,
-            ring_width: self.brush_size.ring_width(),
+            size,
+            ring_width: size.ring_width(),
             color,
         }
     }
@@ -369,7 +660,12 @@ impl ToolboxState {
             Some(Texturing) => self.texture_brush.sync_rendersettings(rendersettings),
             Some(Blending) => self.blending_brush.sync_rendersettings(rendersettings),
             Some(Scaling) => self.scaling_brush.sync_rendersettings(rendersettings),
-            Some(MaterialParameters) | None => {}
+            Some(Tinting) => self.tint_brush.sync_rendersettings(rendersettings),
+            Some(Sculpting) => self.sculpt_brush.sync_rendersettings(rendersettings),
+            Some(Holes) => self.hole_brush.sync_rendersettings(rendersettings),
+            Some(Selection) => self.selection_tool.sync_rendersettings(rendersettings),
+            Some(MaterialUsage) => self.usage.sync_rendersettings(rendersettings),
+            Some(AutoTexturing) | Some(MaterialRemapping) | Some(MaterialParameters) | None => {}
         }
     }
     // ------------------------------------------------------------------------
@@ -377,9 +673,4 @@ impl ToolboxState {
 // ----------------------------------------------------------------------------
 // Default impl
 // ----------------------------------------------------------------------------
-impl Default for ToolSelection {
-    fn default() -> Self {
-        Self::Texturing
-    }
-}
 // ----------------------------------------------------------------------------
@@ -0,0 +1,39 @@
+// ----------------------------------------------------------------------------
+// global material slot remapping settings
+// ----------------------------------------------------------------------------
+use crate::terrain_material::MaterialSlot;
+use crate::terrain_painting::{MaterialRemapStats, MaterialRemapTable, RemapTarget};
+// ----------------------------------------------------------------------------
+pub(super) struct MaterialRemapSettings {
+    pub table: MaterialRemapTable,
+    /// stats of last preview or remapping with the table used
+    pub result: Option<(MaterialRemapTable, MaterialRemapStats)>,
+}
+// ----------------------------------------------------------------------------
+impl MaterialRemapSettings {
+    // ------------------------------------------------------------------------
+    /// stats of last preview or remapping if they match the current table
+    pub fn stats(&self) -> Option<&MaterialRemapStats> {
+        self.result
+            .as_ref()
+            .filter(|(table, _)| *table == self.table)
+            .map(|(_, stats)| stats)
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+// Default
+// ----------------------------------------------------------------------------
+impl Default for MaterialRemapSettings {
+    fn default() -> Self {
+        Self {
+            table: MaterialRemapTable::replace_all(
+                RemapTarget::Both,
+                MaterialSlot::from(0),
+                MaterialSlot::from(1),
+            ),
+            result: None,
+        }
+    }
+}
+// ----------------------------------------------------------------------------
@@ -0,0 +1,48 @@
+// ----------------------------------------------------------------------------
+// all heightmap sculpt brush operations
+// ----------------------------------------------------------------------------
+use bevy::prelude::Color;
+
+use crate::terrain_render::TerrainRenderSettings;
+
+use super::{HeightDelta, SculptStrength, ToolSettings};
+// ----------------------------------------------------------------------------
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub(super) enum SculptOperation {
+    RaiseLower,
+    Smooth,
+    Flatten,
+    Noise,
+}
+// ----------------------------------------------------------------------------
+pub(super) struct BrushSettings {
+    pub operation: SculptOperation,
+    pub delta: HeightDelta,
+    pub strength: SculptStrength,
+}
+// ----------------------------------------------------------------------------
+impl ToolSettings for BrushSettings {
+    // ------------------------------------------------------------------------
+    fn pointer_color(&self) -> Color {
+        Color::ORANGE
+    }
+    // ------------------------------------------------------------------------
+    fn sync_rendersettings(&mut self, settings: &mut TerrainRenderSettings) {
+        // changes of the terrain shape must be visible
+        settings.reset_exclusive_view();
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+// Default
+// ----------------------------------------------------------------------------
+impl Default for BrushSettings {
+    fn default() -> Self {
+        Self {
+            operation: SculptOperation::RaiseLower,
+            delta: HeightDelta::default(),
+            strength: SculptStrength::default(),
+        }
+    }
+}
+// ----------------------------------------------------------------------------
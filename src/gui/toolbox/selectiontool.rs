@@ -0,0 +1,48 @@
+// ----------------------------------------------------------------------------
+// rectangle and lasso selection with copy and paste of selected regions
+// ----------------------------------------------------------------------------
+use bevy::prelude::{Color, UVec2};
+
+use crate::terrain_painting::{ClipboardTransform, SelectionShape};
+use crate::terrain_render::TerrainRenderSettings;
+
+use super::ToolSettings;
+// ----------------------------------------------------------------------------
+pub(super) struct SelectionSettings {
+    pub shape: SelectionShape,
+    /// copy tint map data in addition to texture control data
+    pub include_tint: bool,
+    /// applied to clipboard data on paste
+    pub transform: ClipboardTransform,
+    /// true if a region is selected
+    pub selected: bool,
+    /// size of copied region and if tint data was included
+    pub clipboard: Option<(UVec2, bool)>,
+}
+// ----------------------------------------------------------------------------
+impl ToolSettings for SelectionSettings {
+    // ------------------------------------------------------------------------
+    fn pointer_color(&self) -> Color {
+        Color::WHITE
+    }
+    // ------------------------------------------------------------------------
+    fn sync_rendersettings(&mut self, _settings: &mut TerrainRenderSettings) {
+        // selection outline is visible in all views
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+// Default
+// ----------------------------------------------------------------------------
+impl Default for SelectionSettings {
+    fn default() -> Self {
+        Self {
+            shape: SelectionShape::Rectangle,
+            include_tint: false,
+            transform: ClipboardTransform::default(),
+            selected: false,
+            clipboard: None,
+        }
+    }
+}
+// ----------------------------------------------------------------------------
@@ -27,10 +27,11 @@ pub(super) struct BrushSettings {
     pub picker_activated: bool,
 }
 // ----------------------------------------------------------------------------
-#[derive(Eq, PartialEq, Clone, Copy)]
+#[derive(Eq, PartialEq, Clone, Copy, Default)]
 pub(super) enum BrushTexturesUsed {
     Overlay,
     Background,
+    #[default]
     OverlayAndBackground,
 }
 // ----------------------------------------------------------------------------
@@ -126,9 +127,4 @@ impl Default for BrushSettings {
     }
 }
 // ----------------------------------------------------------------------------
-impl Default for BrushTexturesUsed {
-    fn default() -> Self {
-        Self::OverlayAndBackground
-    }
-}
 // ----------------------------------------------------------------------------
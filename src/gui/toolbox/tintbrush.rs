@@ -0,0 +1,64 @@
+// ----------------------------------------------------------------------------
+// all tint brush operations
+// ----------------------------------------------------------------------------
+use bevy::prelude::Color;
+
+use crate::terrain_render::TerrainRenderSettings;
+
+use super::{TintColor, TintJitter, TintStrength, ToolSettings};
+// ----------------------------------------------------------------------------
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub(super) enum TintOperation {
+    Set,
+    Multiply,
+    LightenDarken,
+    Jitter,
+}
+// ----------------------------------------------------------------------------
+pub(super) struct BrushSettings {
+    pub operation: TintOperation,
+    pub color: TintColor,
+    pub strength: TintStrength,
+    pub jitter: TintJitter,
+
+    pub show_tint_map: bool,
+    pub picker_activated: bool,
+}
+// ----------------------------------------------------------------------------
+impl ToolSettings for BrushSettings {
+    // ------------------------------------------------------------------------
+    fn pointer_color(&self) -> Color {
+        Color::FUCHSIA
+    }
+    // ------------------------------------------------------------------------
+    fn sync_rendersettings(&mut self, settings: &mut TerrainRenderSettings) {
+        settings.reset_exclusive_view();
+
+        // changes must be visible
+        settings.ignore_tint_map = false;
+
+        // if it was previously set, set it again
+        if self.show_tint_map {
+            settings.show_tint_map = true;
+        }
+        self.show_tint_map = settings.show_tint_map;
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+// Default
+// ----------------------------------------------------------------------------
+impl Default for BrushSettings {
+    fn default() -> Self {
+        Self {
+            operation: TintOperation::Set,
+            color: TintColor([128, 128, 128]),
+            strength: TintStrength::default(),
+            jitter: TintJitter::default(),
+
+            show_tint_map: false,
+            picker_activated: false,
+        }
+    }
+}
+// ----------------------------------------------------------------------------
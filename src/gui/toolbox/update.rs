@@ -2,14 +2,18 @@
 // toolbox::update - simple(r) actions for updating state, mapping to other actions
 //
 // ----------------------------------------------------------------------------
-use bevy::prelude::MouseButton;
+use bevy::prelude::{MouseButton, Vec2};
 
 use crate::terrain_material::MaterialSlot;
-use crate::terrain_painting::{PaintCommand, PickedType, SlopeBlendThreshold, TextureScale};
+use crate::terrain_painting::{
+    PaintCommand, PickedType, SelectionEvent, SlopeBlendThreshold, StrokeId, TextureScale,
+    TintColor,
+};
 use crate::terrain_render::{BrushPointer, TerrainMaterialSet, TerrainRenderSettings};
 
 use super::common::BrushSize;
-use super::{blendingbrush, scalingbrush, texturebrush};
+use super::{blendingbrush, scalingbrush, sculptbrush, texturebrush, tintbrush};
+use super::{selectiontool, usage};
 use super::{MaterialSetting, PointerSettings, ToolSelection, ToolboxState};
 // ----------------------------------------------------------------------------
 #[inline(always)]
@@ -108,6 +112,31 @@ pub(super) fn render_only_slopeblend_threshold(
     rendersettings.show_blend_threshold = show;
 }
 // ----------------------------------------------------------------------------
+pub(super) fn render_only_tint_map(
+    brush: &mut tintbrush::BrushSettings,
+    rendersettings: &mut TerrainRenderSettings,
+    show: bool,
+) {
+    brush.show_tint_map = show;
+
+    rendersettings.reset_exclusive_view();
+    rendersettings.show_tint_map = show;
+}
+// ----------------------------------------------------------------------------
+pub(super) fn render_only_material_coverage(
+    settings: &mut usage::MaterialUsageSettings,
+    rendersettings: &mut TerrainRenderSettings,
+    slot: Option<MaterialSlot>,
+) {
+    settings.highlighted = slot;
+
+    rendersettings.reset_exclusive_view();
+    if let Some(slot) = slot {
+        rendersettings.show_material_coverage = true;
+        rendersettings.highlighted_material = *slot;
+    }
+}
+// ----------------------------------------------------------------------------
 pub(super) fn on_changed_tool_selection(
     toolbox: &mut ToolboxState,
     brush_pointer: &mut BrushPointer,
@@ -135,10 +164,20 @@ pub(super) fn update_brush_on_material_selection(
     rendersettings: &mut TerrainRenderSettings,
     overlay_selected: bool,
 ) {
-    use ToolSelection::{MaterialParameters, Texturing};
+    use ToolSelection::{
+        AutoTexturing, Holes, MaterialParameters, MaterialRemapping, MaterialUsage, Texturing,
+    };
 
     match toolbox.selection {
-        Some(MaterialParameters) | None => {
+        Some(Holes) => {
+            // selected materials are used for filling holes -> no switch
+            update_brush_pointer(&toolbox.pointer_settings(), brush_pointer);
+        }
+        Some(AutoTexturing)
+        | Some(MaterialRemapping)
+        | Some(MaterialUsage)
+        | Some(MaterialParameters)
+        | None => {
             // texture is used in current tool -> no need to switch tool or
             // change active texture in brush
         }
@@ -190,6 +229,16 @@ pub(super) fn update_brush_on_scaling_pick(
     update_brush_pointer(&toolbox.pointer_settings(), &mut *brush_pointer);
 }
 // ----------------------------------------------------------------------------
+pub(super) fn update_brush_on_tint_pick(
+    toolbox: &mut ToolboxState,
+    brush_pointer: &mut BrushPointer,
+    color: TintColor,
+) {
+    toolbox.tint_brush.color = color;
+    toolbox.tint_brush.operation = tintbrush::TintOperation::Set;
+    update_brush_pointer(&toolbox.pointer_settings(), &mut *brush_pointer);
+}
+// ----------------------------------------------------------------------------
 pub(super) fn create_texture_picker_cmds(
     button: MouseButton,
     settings: &texturebrush::BrushSettings,
@@ -274,6 +323,35 @@ pub(super) fn create_texture_paint_cmds(
 }
 // ----------------------------------------------------------------------------
 #[inline(always)]
+pub(super) fn create_hole_picker_cmds(button: MouseButton) -> Vec<PickedType> {
+    use PickedType::*;
+
+    // other buttons are ignored
+    match button {
+        MouseButton::Left => vec![OverlayTexture, BackgroundTexture],
+        _ => Vec::default(),
+    }
+}
+// ----------------------------------------------------------------------------
+#[inline(always)]
+pub(super) fn create_hole_paint_cmds(
+    button: MouseButton,
+    texture_brush: &texturebrush::BrushSettings,
+) -> Vec<PaintCommand> {
+    use PaintCommand::*;
+
+    // holes are filled with the currently selected palette materials
+    match button {
+        MouseButton::Left => vec![CutHole],
+        MouseButton::Right => vec![FillHole(
+            texture_brush.overlay_texture,
+            texture_brush.bkgrnd_texture,
+        )],
+        _ => Vec::default(),
+    }
+}
+// ----------------------------------------------------------------------------
+#[inline(always)]
 pub(super) fn create_blending_paint_cmds(
     button: MouseButton,
     settings: &blendingbrush::BrushSettings,
@@ -393,3 +471,61 @@ pub(super) fn create_scaling_paint_cmds(
     cmds
 }
 // ----------------------------------------------------------------------------
+#[inline(always)]
+pub(super) fn create_tint_paint_cmds(
+    button: MouseButton,
+    settings: &tintbrush::BrushSettings,
+) -> Vec<PaintCommand> {
+    use tintbrush::TintOperation::*;
+    use PaintCommand::*;
+
+    let mut cmds = Vec::default();
+
+    match (button, settings.operation) {
+        (MouseButton::Left, Set) => cmds.push(SetTint(settings.color)),
+        (MouseButton::Left, Multiply) => cmds.push(MultiplyTint(settings.color)),
+        (MouseButton::Left, LightenDarken) => cmds.push(LightenTint(settings.strength)),
+        (MouseButton::Right, LightenDarken) => cmds.push(DarkenTint(settings.strength)),
+        (MouseButton::Left, Jitter) => cmds.push(JitterTint(settings.jitter)),
+        _ => {}
+    }
+    cmds
+}
+// ----------------------------------------------------------------------------
+#[inline(always)]
+pub(super) fn create_sculpt_paint_cmds(
+    button: MouseButton,
+    settings: &sculptbrush::BrushSettings,
+) -> Vec<PaintCommand> {
+    use sculptbrush::SculptOperation::*;
+    use PaintCommand::*;
+
+    let mut cmds = Vec::default();
+
+    match (button, settings.operation) {
+        (MouseButton::Left, RaiseLower) => cmds.push(RaiseHeight(settings.delta)),
+        (MouseButton::Right, RaiseLower) => cmds.push(LowerHeight(settings.delta)),
+        (MouseButton::Left, Smooth) => cmds.push(SmoothHeight(settings.strength)),
+        (MouseButton::Left, Flatten) => cmds.push(FlattenHeight(settings.strength)),
+        (MouseButton::Left, Noise) => cmds.push(NoiseHeight(settings.delta)),
+        _ => {}
+    }
+    cmds
+}
+// ----------------------------------------------------------------------------
+#[inline(always)]
+pub(super) fn create_selection_event(
+    button: MouseButton,
+    stroke: StrokeId,
+    pos: Vec2,
+    settings: &selectiontool::SelectionSettings,
+) -> Option<SelectionEvent> {
+    match button {
+        MouseButton::Left => Some(SelectionEvent::Select(stroke, settings.shape, pos)),
+        MouseButton::Right if settings.clipboard.is_some() => {
+            Some(SelectionEvent::Paste(stroke, pos, settings.transform))
+        }
+        _ => None,
+    }
+}
+// ----------------------------------------------------------------------------
@@ -0,0 +1,29 @@
+// ----------------------------------------------------------------------------
+// material usage statistics and coverage visualization
+// ----------------------------------------------------------------------------
+use crate::terrain_material::MaterialSlot;
+use crate::terrain_painting::MaterialUsage;
+use crate::terrain_render::TerrainRenderSettings;
+// ----------------------------------------------------------------------------
+#[derive(Default)]
+pub(super) struct MaterialUsageSettings {
+    /// stats of last refresh
+    pub usage: Option<MaterialUsage>,
+    /// material slot with highlighted coverage
+    pub highlighted: Option<MaterialSlot>,
+}
+// ----------------------------------------------------------------------------
+impl MaterialUsageSettings {
+    // ------------------------------------------------------------------------
+    pub fn sync_rendersettings(&mut self, settings: &mut TerrainRenderSettings) {
+        settings.reset_exclusive_view();
+
+        // if it was previously set, set it again
+        if let Some(slot) = self.highlighted {
+            settings.show_material_coverage = true;
+            settings.highlighted_material = *slot;
+        }
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
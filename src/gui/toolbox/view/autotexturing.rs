@@ -0,0 +1,255 @@
+// ----------------------------------------------------------------------------
+#[rustfmt::skip]
+#[inline]
+pub(super) fn show(
+    ui: &mut egui::Ui,
+    settings: &mut AutoTexturingSettings,
+    gui_event: &mut EventWriter<GuiAction>,
+) {
+    use GuiAction::Toolbox;
+
+    // --- rules
+    let mut rule_action = None;
+    let rule_count = settings.rules.len();
+
+    for (i, rule) in settings.rules.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut rule.enabled, format!("Rule #{}", i + 1));
+
+            ui.with_layout(egui::Layout::right_to_left(), |ui| {
+                if ui.small_button("x").on_hover_text("remove rule").clicked() {
+                    rule_action = Some(RuleAction::Remove(i));
+                }
+                if ui.add_enabled(i + 1 < rule_count, egui::Button::new("v").small())
+                    .on_hover_text("move rule down (later rules overwrite earlier rules)")
+                    .clicked()
+                {
+                    rule_action = Some(RuleAction::Swap(i, i + 1));
+                }
+                if ui.add_enabled(i > 0, egui::Button::new("^").small())
+                    .on_hover_text("move rule up")
+                    .clicked()
+                {
+                    rule_action = Some(RuleAction::Swap(i - 1, i));
+                }
+            });
+        });
+
+        ui.add_enabled_ui(rule.enabled, |ui| {
+            egui::Grid::new(format!("autotexturing.rule.{}", i))
+                .num_columns(2)
+                .show(ui, |ui| {
+                    rule_filters(ui, rule);
+                    rule_actions(ui, rule);
+                });
+        });
+        ui.separator();
+    }
+
+    match rule_action {
+        Some(RuleAction::Remove(i)) => { settings.rules.remove(i); }
+        Some(RuleAction::Swap(a, b)) => settings.rules.swap(a, b),
+        None => {}
+    }
+
+    if ui.button("Add rule").clicked() {
+        settings.rules.push(AutoTexturingRule::default());
+    }
+    ui.separator();
+
+    // --- region
+    ui.horizontal(|ui| {
+        ui.label("Region:");
+        ui.radio_value(&mut settings.region, AutoTexturingRegion::CompleteMap, "complete map");
+        ui.radio_value(&mut settings.region, AutoTexturingRegion::Rectangle, "rectangle");
+    });
+    ui.add_enabled_ui(settings.region == AutoTexturingRegion::Rectangle, |ui| {
+        ui.horizontal(|ui| {
+            ui.label("pos");
+            ui.add(DragValue::new(&mut settings.region_pos.x).prefix("x: "));
+            ui.add(DragValue::new(&mut settings.region_pos.y).prefix("y: "));
+        });
+        ui.horizontal(|ui| {
+            ui.label("size");
+            ui.add(DragValue::new(&mut settings.region_size.x).clamp_range(1..=u16::MAX as u32).prefix("w: "));
+            ui.add(DragValue::new(&mut settings.region_size.y).clamp_range(1..=u16::MAX as u32).prefix("h: "));
+        });
+    });
+    ui.separator();
+
+    // --- preview / apply / discard
+    ui.horizontal(|ui| {
+        let has_active_rules = settings.rules.iter().any(|r| r.enabled);
+
+        if ui.add_enabled(has_active_rules, egui::Button::new("Preview"))
+            .on_hover_text("generate auto texturing for region (replaces current preview)")
+            .clicked()
+        {
+            gui_event.send(Toolbox(ToolboxAction::PreviewAutoTexturing));
+        }
+        if ui.add_enabled(settings.preview_active, egui::Button::new("Apply"))
+            .on_hover_text("commit previewed auto texturing (undoable)")
+            .clicked()
+        {
+            gui_event.send(Toolbox(ToolboxAction::ApplyAutoTexturing));
+        }
+        if ui.add_enabled(settings.preview_active, egui::Button::new("Discard"))
+            .on_hover_text("restore texturing before preview")
+            .clicked()
+        {
+            gui_event.send(Toolbox(ToolboxAction::DiscardAutoTexturing));
+        }
+    });
+
+    ui.separator();
+}
+// ----------------------------------------------------------------------------
+enum RuleAction {
+    Remove(usize),
+    Swap(usize, usize),
+}
+// ----------------------------------------------------------------------------
+#[inline]
+fn rule_filters(ui: &mut Ui, rule: &mut AutoTexturingRule) {
+    range_filter(
+        ui,
+        "height",
+        "m",
+        -4096.0..=4096.0,
+        &mut rule.height,
+        (0.0, 100.0),
+    );
+    range_filter(ui, "slope", "°", 0.0..=90.0, &mut rule.slope, (0.0, 30.0));
+    range_filter(
+        ui,
+        "curvature",
+        "m",
+        -100.0..=100.0,
+        &mut rule.curvature,
+        (0.0, 10.0),
+    );
+
+    let mut enabled = rule.noise.is_some();
+    ui.checkbox(&mut enabled, "noise")
+        .on_hover_text("restricts rule to randomized patches");
+    ui.horizontal(|ui| match (enabled, rule.noise.as_mut()) {
+        (true, Some(noise)) => {
+            ui.add(
+                DragValue::new(&mut noise.scale)
+                    .clamp_range(1.0..=1000.0)
+                    .suffix("m"),
+            )
+            .on_hover_text("size of noise patches");
+            ui.add(
+                DragValue::new(&mut noise.coverage)
+                    .clamp_range(0.0..=1.0)
+                    .speed(0.01),
+            )
+            .on_hover_text("fraction of area covered by noise patches");
+            ui.add(DragValue::new(&mut noise.seed).prefix("seed: "));
+        }
+        (true, None) => {
+            rule.noise = Some(NoiseFilter {
+                scale: 20.0,
+                coverage: 0.5,
+                seed: 0,
+            });
+        }
+        (false, _) => rule.noise = None,
+    });
+    ui.end_row();
+}
+// ----------------------------------------------------------------------------
+#[inline]
+fn rule_actions(ui: &mut Ui, rule: &mut AutoTexturingRule) {
+    slot_action(ui, "overlay", &mut rule.overlay);
+    slot_action(ui, "background", &mut rule.background);
+
+    let mut blend = rule.slope_blend.map(|b| b.0);
+    value_action(ui, "blending", &mut blend);
+    rule.slope_blend = blend.map(SlopeBlendThreshold);
+
+    let mut scaling = rule.scaling.map(|s| s.0);
+    value_action(ui, "scaling", &mut scaling);
+    rule.scaling = scaling.map(TextureScale);
+}
+// ----------------------------------------------------------------------------
+#[inline]
+fn range_filter(
+    ui: &mut Ui,
+    label: &str,
+    unit: &str,
+    range: RangeInclusive<f32>,
+    filter: &mut Option<RangeFilter>,
+    default: (f32, f32),
+) {
+    let mut enabled = filter.is_some();
+    ui.checkbox(&mut enabled, label);
+    ui.horizontal(|ui| match (enabled, filter.as_mut()) {
+        (true, Some(f)) => {
+            ui.add(
+                DragValue::new(&mut f.min)
+                    .clamp_range(*range.start()..=f.max)
+                    .suffix(unit),
+            );
+            ui.label("-");
+            ui.add(
+                DragValue::new(&mut f.max)
+                    .clamp_range(f.min..=*range.end())
+                    .suffix(unit),
+            );
+        }
+        (true, None) => {
+            *filter = Some(RangeFilter {
+                min: default.0,
+                max: default.1,
+            })
+        }
+        (false, _) => *filter = None,
+    });
+    ui.end_row();
+}
+// ----------------------------------------------------------------------------
+#[inline]
+fn slot_action(ui: &mut Ui, label: &str, slot: &mut Option<MaterialSlot>) {
+    // slots are shown 1-based like in material palette
+    let mut value = slot.map(|s| *s + 1);
+    let mut enabled = value.is_some();
+    ui.checkbox(&mut enabled, label);
+    if enabled {
+        let v = value.get_or_insert(1);
+        ui.add(DragValue::new(v).clamp_range(1..=31).prefix("texture "));
+    } else {
+        value = None;
+    }
+    *slot = value.map(|v| MaterialSlot::from(v - 1));
+    ui.end_row();
+}
+// ----------------------------------------------------------------------------
+#[inline]
+fn value_action(ui: &mut Ui, label: &str, value: &mut Option<u8>) {
+    let mut enabled = value.is_some();
+    ui.checkbox(&mut enabled, label);
+    if enabled {
+        let v = value.get_or_insert(0);
+        ui.add(DragValue::new(v).clamp_range(0..=7));
+    } else {
+        *value = None;
+    }
+    ui.end_row();
+}
+// ----------------------------------------------------------------------------
+use std::ops::RangeInclusive;
+
+use bevy::prelude::*;
+use bevy_egui::egui::{self, DragValue, Ui};
+
+use crate::gui::toolbox::autotexturing::{AutoTexturingRegion, AutoTexturingSettings};
+use crate::gui::GuiAction;
+use crate::terrain_material::MaterialSlot;
+use crate::terrain_painting::{
+    AutoTexturingRule, NoiseFilter, RangeFilter, SlopeBlendThreshold, TextureScale,
+};
+
+use super::ToolboxAction;
+// ----------------------------------------------------------------------------
@@ -23,9 +23,138 @@ pub(super) fn show_brushsize_control(
     }
 }
 // ----------------------------------------------------------------------------
-use bevy_egui::egui::{Slider, Ui};
+#[rustfmt::skip]
+pub(super) fn show_brushshape_control(
+    ui: &mut Ui,
+    shape: &mut BrushShapeSettings,
+) -> Option<ToolboxAction> {
+    use FalloffCurve::*;
+
+    let mut result = None;
+
+    egui::Grid::new("brush.shape.settings")
+        .min_col_width(60.0)
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label("Shape:");
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut shape.use_stamp, false, "round");
+                ui.add_enabled_ui(shape.stamp.is_some(), |ui| {
+                    ui.radio_value(&mut shape.use_stamp, true, "stamp")
+                        .on_hover_text("use grayscale alpha mask as brush");
+                });
+            });
+            ui.end_row();
+
+            if shape.use_stamp {
+                ui.label("");
+                ui.vertical(|ui| {
+                    let rotation = shape.stamp_transform.rotation;
+                    let scale = shape.stamp_transform.scale;
+                    ui.add(Slider::new(&mut shape.stamp_transform.rotation, 0.0..=360.0)
+                        .show_value(false)
+                        .text(format!("{:.0}° rotation", rotation)));
+                    ui.add(Slider::new(&mut shape.stamp_transform.scale, 0.1..=2.0)
+                        .show_value(false)
+                        .text(format!("{:.2} scale", scale)))
+                        .on_hover_text("stamp size relative to brush size");
+                });
+            } else {
+                ui.label("Falloff:");
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut shape.falloff.curve, Hard, "hard");
+                        ui.radio_value(&mut shape.falloff.curve, Linear, "linear");
+                        ui.radio_value(&mut shape.falloff.curve, Smooth, "smooth");
+                        ui.radio_value(&mut shape.falloff.curve, Gaussian, "gaussian");
+                    });
+                    let hardness = shape.falloff.hardness * 100.0;
+                    ui.add_enabled(shape.falloff.curve != Hard,
+                        Slider::new(&mut shape.falloff.hardness, 0.0..=1.0)
+                            .show_value(false)
+                            .text(format!("{:.0}% hardness", hardness)))
+                        .on_hover_text("inner part of brush with full strength. \
+                            for texturing, blending and scaling the falloff reduces the \
+                            probability to change a pixel.");
+                });
+            }
+            ui.end_row();
+
+            ui.label("Spacing:");
+            let spacing = shape.spacing * 100.0;
+            ui.add(Slider::new(&mut shape.spacing, BRUSH_SPACING_MIN..=BRUSH_SPACING_MAX)
+                .show_value(false)
+                .text(format!("{:.0}% of radius", spacing)))
+                .on_hover_text("distance between brush placements along a stroke. \
+                    randomized operations change every pixel only once per stroke.");
+            ui.end_row();
+
+            ui.label("Stamp:");
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut shape.stamp_filepath)
+                    .hint_text("grayscale png filepath")
+                    .desired_width(120.0));
+                let filepath = shape.stamp_filepath.trim();
+                if ui.add_enabled(!filepath.is_empty(), egui::Button::new("Load")).clicked() {
+                    result = Some(ToolboxAction::LoadBrushStamp(filepath.to_string()));
+                }
+            });
+            ui.end_row();
+
+            if let Some(stamp) = &shape.stamp {
+                ui.label("");
+                ui.small(format!("loaded: {}", stamp.name()));
+                ui.end_row();
+            }
+        });
+
+    result
+}
+// ----------------------------------------------------------------------------
+#[rustfmt::skip]
+pub(super) fn show_randomization_control(
+    ui: &mut Ui,
+    settings: &mut RandomizationSettings,
+    stroke: StrokeId,
+) -> Option<ToolboxAction> {
+    let mut result = None;
+
+    egui::Grid::new("brush.randomization.settings")
+        .min_col_width(60.0)
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label("Seed:");
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut settings.seed_input)
+                    .hint_text(settings.seed.to_string())
+                    .desired_width(120.0));
+                let seed = settings.seed_input.trim().parse::<u64>();
+                if ui.add_enabled(seed.is_ok(), egui::Button::new("Set"))
+                    .on_hover_text("session seed of randomized operations. setting the seed \
+                        restarts the stroke counter so recorded strokes are replayed identically.")
+                    .clicked()
+                {
+                    result = seed.ok().map(ToolboxAction::SetPaintingSeed);
+                    settings.seed_input.clear();
+                }
+            });
+            ui.end_row();
+
+            ui.label("");
+            ui.small(format!("current: {} (stroke {})", settings.seed, stroke.id()));
+            ui.end_row();
+        });
+
+    result
+}
+// ----------------------------------------------------------------------------
+use bevy_egui::egui::{self, Slider, Ui};
 
-use crate::gui::toolbox::common::{BRUSH_SIZE_MAX, BRUSH_SIZE_MIN};
+use crate::gui::toolbox::common::{
+    BrushShapeSettings, RandomizationSettings, BRUSH_SIZE_MAX, BRUSH_SIZE_MIN, BRUSH_SPACING_MAX,
+    BRUSH_SPACING_MIN,
+};
+use crate::terrain_painting::{FalloffCurve, StrokeId};
 
 use super::ToolboxAction;
 // ----------------------------------------------------------------------------
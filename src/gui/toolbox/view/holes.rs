@@ -0,0 +1,74 @@
+// ----------------------------------------------------------------------------
+#[rustfmt::skip]
+#[inline]
+pub(super) fn show(
+    ui: &mut egui::Ui,
+    brush_size: &mut BrushSize,
+    brush: &mut BrushSettings,
+    texture_brush: &texturebrush::BrushSettings,
+    gui_event: &mut EventWriter<GuiAction>,
+) {
+    const CAPTION_COLUMN_WIDTH: f32 = 60.0;
+
+    use GuiAction::Toolbox;
+
+    // --- Brush settings
+    // 2 column grid like in texture brush so it's look is somewhat similar
+    egui::Grid::new("holes.brush.settings")
+        .min_col_width(CAPTION_COLUMN_WIDTH)
+        .num_columns(2)
+        .show(ui, |ui| {
+            // wrapped in vertical to align label in column to top
+            ui.vertical(|ui|{
+                ui.label("Holes:");
+            });
+
+            ui.vertical(|ui| {
+                ui.horizontal(|ui| {
+                    ui.small("left click: cut hole");
+
+                    //FIXME this "padding" should calculated based on min siderbar size
+                    ui.add_space(10.0);
+
+                    if ui
+                        .add(ui.small_selectable_button(brush.picker_activated, "P"))
+                        .on_hover_text("Pick overlay and background material used for filling holes.")
+                        .clicked()
+                    {
+                        gui_event.send(Toolbox(ToolboxAction::HolePickerSelected(!brush.picker_activated)));
+                    }
+                });
+                // slots are shown 1-based like in material palette
+                ui.small(format!(
+                    "right click: fill hole with #{} (overlay) / #{} (background)",
+                    *texture_brush.overlay_texture + 1,
+                    *texture_brush.bkgrnd_texture + 1,
+                ));
+            });
+        });
+
+    ui.separator();
+    // ------------------------------------------------------------------------
+    // --- Brush size
+    egui::Grid::new("holes.brush.settings.size")
+        .min_col_width(CAPTION_COLUMN_WIDTH)
+        .num_columns(2)
+        .show(ui, |ui| {
+            if let Some(action) = common::show_brushsize_control(ui, brush_size) {
+                gui_event.send(Toolbox(action));
+            }
+        });
+
+    ui.separator();
+}
+// ----------------------------------------------------------------------------
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+use crate::gui::toolbox::holebrush::BrushSettings;
+use crate::gui::toolbox::texturebrush;
+use crate::gui::{GuiAction, UiExtension};
+
+use super::common;
+use super::{BrushSize, ToolboxAction};
+// ----------------------------------------------------------------------------
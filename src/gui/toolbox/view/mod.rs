@@ -24,6 +24,33 @@ pub fn show_ui(
         ui.deselectable_value(&mut toolbox.selection, Scaling, egui::RichText::new("Scaling").small())
             .on_hover_text("Scaling Brush: adjusting or overwriting of background texture scaling.");
 
+        ui.deselectable_value(&mut toolbox.selection, Tinting, egui::RichText::new("Tinting").small())
+            .on_hover_text("Tint Brush: overwriting, multiplying, lightening/darkening or jittering of \
+                tint map colors.");
+
+        ui.deselectable_value(&mut toolbox.selection, Sculpting, egui::RichText::new("Sculpting").small())
+            .on_hover_text("Sculpt Brush: raising/lowering, smoothing, flattening or adding noise to \
+                the heightmap.");
+
+        ui.deselectable_value(&mut toolbox.selection, Holes, egui::RichText::new("Holes").small())
+            .on_hover_text("Hole Brush: cutting holes into the terrain (e.g. for cave entrances) or filling \
+                them with the selected materials.");
+
+        ui.deselectable_value(&mut toolbox.selection, Selection, egui::RichText::new("Selection").small())
+            .on_hover_text("Selection: rectangle or lasso selection restricting all brushes. copy and \
+                paste (rotated/mirrored) of texturing and tint.");
+
+        ui.deselectable_value(&mut toolbox.selection, AutoTexturing, egui::RichText::new("Auto Texturing").small())
+            .on_hover_text("Auto Texturing: rule based texturing by height, slope, curvature and noise.");
+
+        ui.deselectable_value(&mut toolbox.selection, MaterialRemapping, egui::RichText::new("Material Remapping").small())
+            .on_hover_text("Material Remapping: replaces material slots on the complete map, e.g. after \
+                reordering the material set.");
+
+        ui.deselectable_value(&mut toolbox.selection, MaterialUsage, egui::RichText::new("Material Usage").small())
+            .on_hover_text("Material Usage: usage statistics of material slots and blend control values. \
+                highlights coverage of a material slot on the terrain.");
+
         ui.deselectable_value(&mut toolbox.selection, MaterialParameters, ui.small_text("Material Parameters"));
     });
     if selected_tool != toolbox.selection {
@@ -42,6 +69,27 @@ pub fn show_ui(
         Some(Scaling) => {
             scaling::show(ui, brush_size, &mut toolbox.scaling_brush, gui_event);
         }
+        Some(Tinting) => {
+            tinting::show(ui, brush_size, &mut toolbox.tint_brush, gui_event);
+        }
+        Some(Sculpting) => {
+            sculpting::show(ui, brush_size, &mut toolbox.sculpt_brush, gui_event);
+        }
+        Some(Holes) => {
+            holes::show(ui, brush_size, &mut toolbox.hole_brush, &toolbox.texture_brush, gui_event);
+        }
+        Some(Selection) => {
+            selection::show(ui, &mut toolbox.selection_tool, gui_event);
+        }
+        Some(AutoTexturing) => {
+            autotexturing::show(ui, &mut toolbox.autotexturing, gui_event);
+        }
+        Some(MaterialRemapping) => {
+            remapping::show(ui, &mut toolbox.remapping, gui_event);
+        }
+        Some(MaterialUsage) => {
+            usage::show(ui, &toolbox.usage, gui_event);
+        }
         Some(MaterialParameters) => {
             materialsettings::show(
                 ui,
@@ -59,6 +107,17 @@ pub fn show_ui(
         }
     }
 
+    if matches!(toolbox.selection, Some(Texturing | Blending | Scaling | Tinting | Sculpting | Holes)) {
+        if let Some(action) = common::show_brushshape_control(ui, &mut toolbox.brush_shape) {
+            gui_event.send(GuiAction::Toolbox(action));
+        }
+        ui.separator();
+        if let Some(action) = common::show_randomization_control(ui, &mut toolbox.randomization, toolbox.stroke) {
+            gui_event.send(GuiAction::Toolbox(action));
+        }
+        ui.separator();
+    }
+
     materialpalette::show(ui, ui_images, toolbox, &materialset, gui_event);
 }
 // ----------------------------------------------------------------------------
@@ -74,9 +133,16 @@ use super::{ToolSelection::*, ToolboxAction, ToolboxState};
 // ----------------------------------------------------------------------------
 mod common;
 
+mod autotexturing;
 mod blending;
+mod holes;
 mod materialpalette;
 mod materialsettings;
+mod remapping;
 mod scaling;
+mod sculpting;
+mod selection;
 mod textures;
+mod tinting;
+mod usage;
 // ----------------------------------------------------------------------------
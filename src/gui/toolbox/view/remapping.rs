@@ -0,0 +1,115 @@
+// ----------------------------------------------------------------------------
+#[rustfmt::skip]
+#[inline]
+pub(super) fn show(
+    ui: &mut egui::Ui,
+    settings: &mut MaterialRemapSettings,
+    gui_event: &mut EventWriter<GuiAction>,
+) {
+    use GuiAction::Toolbox;
+
+    // --- target
+    ui.horizontal(|ui| {
+        ui.label("Remap:");
+        ui.radio_value(&mut settings.table.target, RemapTarget::Overlay, "overlay");
+        ui.radio_value(&mut settings.table.target, RemapTarget::Background, "background");
+        ui.radio_value(&mut settings.table.target, RemapTarget::Both, "both");
+    });
+    ui.separator();
+
+    // --- remap table
+    let stats = settings.stats().cloned();
+    let mut removed = None;
+
+    egui::Grid::new("remapping.table")
+        .num_columns(5)
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("from");
+            ui.label("");
+            ui.label("to");
+            ui.label("changes");
+            ui.end_row();
+
+            for (i, (from, to)) in settings.table.entries.iter_mut().enumerate() {
+                slot_value(ui, from);
+                ui.label("->");
+                slot_value(ui, to);
+
+                match stats.as_ref().map(|s| s.entry_changes(i)) {
+                    Some((overlay, background)) => {
+                        ui.label(format!("{} / {}", overlay, background))
+                            .on_hover_text("changed overlay / background datapoints");
+                    }
+                    None => { ui.label("-"); }
+                }
+                if ui.small_button("x").on_hover_text("remove mapping").clicked() {
+                    removed = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+
+    if let Some(i) = removed {
+        settings.table.entries.remove(i);
+    }
+    if ui.button("Add mapping").clicked() {
+        let next = settings.table.entries.last().map_or(0, |(_, to)| (**to + 1).min(30));
+        settings.table.entries.push((MaterialSlot::from(next), MaterialSlot::from(next)));
+    }
+    ui.label(ui.small_text(
+        "all mappings are applied at once, e.g. two materials can be swapped",
+    ));
+    ui.separator();
+
+    // --- preview / apply
+    ui.horizontal(|ui| {
+        let has_changes = settings.table.has_changes();
+
+        if ui.add_enabled(has_changes, egui::Button::new("Preview"))
+            .on_hover_text("count changed datapoints without changing the map")
+            .clicked()
+        {
+            gui_event.send(Toolbox(ToolboxAction::PreviewMaterialRemapping));
+        }
+        if ui.add_enabled(has_changes, egui::Button::new("Apply"))
+            .on_hover_text("remap materials on the complete map (undoable)")
+            .clicked()
+        {
+            gui_event.send(Toolbox(ToolboxAction::ApplyMaterialRemapping));
+        }
+    });
+
+    if let Some(stats) = stats {
+        let msg = if stats.applied {
+            format!("remapped {} datapoints", stats.datapoints)
+        } else {
+            format!("preview: {} datapoints will change", stats.datapoints)
+        };
+        ui.label(msg);
+    }
+    ui.separator();
+}
+// ----------------------------------------------------------------------------
+#[inline]
+fn slot_value(ui: &mut Ui, slot: &mut MaterialSlot) {
+    // slots are shown 1-based like in material palette
+    let mut value = **slot + 1;
+    if ui
+        .add(DragValue::new(&mut value).clamp_range(1..=31))
+        .changed()
+    {
+        *slot = MaterialSlot::from(value - 1);
+    }
+}
+// ----------------------------------------------------------------------------
+use bevy::prelude::*;
+use bevy_egui::egui::{self, DragValue, Ui};
+
+use crate::gui::toolbox::remapping::MaterialRemapSettings;
+use crate::gui::{GuiAction, UiExtension};
+use crate::terrain_material::MaterialSlot;
+use crate::terrain_painting::RemapTarget;
+
+use super::ToolboxAction;
+// ----------------------------------------------------------------------------
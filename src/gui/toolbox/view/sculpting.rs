@@ -0,0 +1,98 @@
+// ----------------------------------------------------------------------------
+#[rustfmt::skip]
+#[inline]
+pub(super) fn show(
+    ui: &mut egui::Ui,
+    brush_size: &mut BrushSize,
+    brush: &mut BrushSettings,
+    gui_event: &mut EventWriter<GuiAction>,
+) {
+    const CAPTION_COLUMN_WIDTH: f32 = 60.0;
+
+    use GuiAction::Toolbox;
+
+    // --- Brush settings
+    // 2 column grid like in texture brush so it's look is somewhat similar
+    egui::Grid::new("sculpt.brush.settings")
+        .min_col_width(CAPTION_COLUMN_WIDTH)
+        .num_columns(2)
+        .show(ui, |ui| {
+            // --- sculpt operation
+            // wrapped in vertical to align label in column to top
+            ui.vertical(|ui|{
+                ui.label("Sculpt:");
+            });
+
+            ui.vertical(|ui| {
+                operation_settings(ui, brush);
+                value_settings(ui, brush);
+            });
+        });
+
+    ui.separator();
+    // ------------------------------------------------------------------------
+    // --- Brush size
+    egui::Grid::new("sculpt.brush.settings.size")
+        .min_col_width(CAPTION_COLUMN_WIDTH)
+        .num_columns(2)
+        .show(ui, |ui| {
+            if let Some(action) = common::show_brushsize_control(ui, brush_size) {
+                gui_event.send(Toolbox(action));
+            }
+        });
+
+    ui.separator();
+}
+// ----------------------------------------------------------------------------
+#[inline]
+fn operation_settings(ui: &mut Ui, brush: &mut BrushSettings) {
+    use SculptOperation::*;
+
+    ui.horizontal(|ui| {
+        ui.radio_value(&mut brush.operation, RaiseLower, "raise/lower")
+            .on_hover_text("left mouse button raises, right mouse button lowers terrain");
+        ui.radio_value(&mut brush.operation, Noise, "noise")
+            .on_hover_text("randomly raise or lower terrain");
+    });
+    ui.horizontal(|ui| {
+        ui.radio_value(&mut brush.operation, Smooth, "smooth")
+            .on_hover_text("average terrain height with neighboring heights");
+        ui.radio_value(&mut brush.operation, Flatten, "flatten")
+            .on_hover_text("adjust terrain height towards average height of brush area");
+    });
+}
+// ----------------------------------------------------------------------------
+#[inline]
+fn value_settings(ui: &mut Ui, brush: &mut BrushSettings) {
+    use SculptOperation::*;
+
+    // copy values (borrow checker)
+    let delta = brush.delta.0;
+    let strength = brush.strength.0;
+
+    ui.add_enabled(
+        matches!(brush.operation, RaiseLower | Noise),
+        Slider::new(&mut brush.delta.0, 1..=4096)
+            .logarithmic(true)
+            .show_value(false)
+            .text(format!("{} height", delta)),
+    )
+    .on_hover_text("height change (in heightmap units) per brush application.");
+
+    ui.add_enabled(
+        matches!(brush.operation, Smooth | Flatten),
+        Slider::new(&mut brush.strength.0, 1..=100)
+            .show_value(false)
+            .text(format!("{}% strength", strength)),
+    );
+}
+// ----------------------------------------------------------------------------
+use bevy::prelude::*;
+use bevy_egui::egui::{self, Slider, Ui};
+
+use crate::gui::toolbox::sculptbrush::{BrushSettings, SculptOperation};
+use crate::gui::GuiAction;
+
+use super::common;
+use super::BrushSize;
+// ----------------------------------------------------------------------------
@@ -0,0 +1,74 @@
+// ----------------------------------------------------------------------------
+#[rustfmt::skip]
+#[inline]
+pub(super) fn show(
+    ui: &mut egui::Ui,
+    settings: &mut SelectionSettings,
+    gui_event: &mut EventWriter<GuiAction>,
+) {
+    use GuiAction::Toolbox;
+
+    // --- selection shape
+    ui.horizontal(|ui| {
+        ui.label("Select:");
+        ui.radio_value(&mut settings.shape, SelectionShape::Rectangle, "rectangle")
+            .on_hover_text("drag a rectangle with the left mouse button");
+        ui.radio_value(&mut settings.shape, SelectionShape::Lasso, "lasso")
+            .on_hover_text("draw a freehand outline with the left mouse button (closed automatically)");
+    });
+    ui.label(ui.small_text(
+        "all brushes paint only inside the selection. a click without dragging clears it.",
+    ));
+
+    ui.horizontal(|ui| {
+        if ui.add_enabled(settings.selected, egui::Button::new("Copy"))
+            .on_hover_text("copy selected texturing (and tint) into the clipboard")
+            .clicked()
+        {
+            gui_event.send(Toolbox(ToolboxAction::CopySelection));
+        }
+        if ui.add_enabled(settings.selected, egui::Button::new("Clear"))
+            .on_hover_text("remove selection (brushes paint everywhere again)")
+            .clicked()
+        {
+            gui_event.send(Toolbox(ToolboxAction::ClearSelection));
+        }
+        ui.checkbox(&mut settings.include_tint, "include tint")
+            .on_hover_text("copy tint map colors in addition to texturing");
+    });
+    ui.separator();
+
+    // --- paste
+    match settings.clipboard {
+        Some((size, tint)) => {
+            let tint = if tint { " + tint" } else { "" };
+            ui.label(format!("Clipboard: {}x{} datapoints{}", size.x, size.y, tint));
+        }
+        None => { ui.label("Clipboard: empty"); }
+    }
+
+    ui.add_enabled_ui(settings.clipboard.is_some(), |ui| {
+        let transform = &mut settings.transform;
+        ui.horizontal(|ui| {
+            ui.label("Rotation:");
+            ui.radio_value(&mut transform.rotation, 0, "0°");
+            ui.radio_value(&mut transform.rotation, 1, "90°");
+            ui.radio_value(&mut transform.rotation, 2, "180°");
+            ui.radio_value(&mut transform.rotation, 3, "270°");
+        });
+        ui.checkbox(&mut transform.mirror, "mirror")
+            .on_hover_text("mirror clipboard content (before rotation)");
+        ui.label(ui.small_text("right click: paste centered at pointer (undoable)"));
+    });
+    ui.separator();
+}
+// ----------------------------------------------------------------------------
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+use crate::gui::toolbox::selectiontool::SelectionSettings;
+use crate::gui::{GuiAction, UiExtension};
+use crate::terrain_painting::SelectionShape;
+
+use super::ToolboxAction;
+// ----------------------------------------------------------------------------
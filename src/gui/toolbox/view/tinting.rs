@@ -0,0 +1,132 @@
+// ----------------------------------------------------------------------------
+#[rustfmt::skip]
+#[inline]
+pub(super) fn show(
+    ui: &mut egui::Ui,
+    brush_size: &mut BrushSize,
+    brush: &mut BrushSettings,
+    gui_event: &mut EventWriter<GuiAction>,
+) {
+    const CAPTION_COLUMN_WIDTH: f32 = 60.0;
+
+    use GuiAction::Toolbox;
+
+    // --- Brush settings
+    // 2 column grid like in texture brush so it's look is somewhat similar
+    egui::Grid::new("tint.brush.settings")
+        .min_col_width(CAPTION_COLUMN_WIDTH)
+        .num_columns(2)
+        .show(ui, |ui| {
+            // --- tint operation
+            // wrapped in vertical to align label in column to top
+            ui.vertical(|ui|{
+                ui.label("Tint:");
+            });
+
+            ui.vertical(|ui| {
+                if let Some(action) = operation_settings(ui, brush) {
+                    gui_event.send(Toolbox(action));
+                }
+                value_settings(ui, brush);
+            });
+        });
+
+    ui.separator();
+    // ------------------------------------------------------------------------
+    // --- Brush size
+    egui::Grid::new("tint.brush.settings.size")
+        .min_col_width(CAPTION_COLUMN_WIDTH)
+        .num_columns(2)
+        .show(ui, |ui| {
+            if let Some(action) = common::show_brushsize_control(ui, brush_size) {
+                gui_event.send(Toolbox(action));
+            }
+        });
+
+    ui.separator();
+}
+// ----------------------------------------------------------------------------
+#[inline]
+fn operation_settings(ui: &mut Ui, brush: &mut BrushSettings) -> Option<ToolboxAction> {
+    use TintOperation::*;
+    use ToolboxAction::{ShowTintMap, TintPickerSelected};
+
+    let mut result = None;
+
+    ui.horizontal(|ui| {
+        ui.radio_value(&mut brush.operation, Set, "set")
+            .on_hover_text("overwrite tint with color");
+        ui.radio_value(&mut brush.operation, Multiply, "multiply")
+            .on_hover_text("multiply tint with color (gray is neutral)");
+
+        //FIXME this "padding" should calculated based on min siderbar size
+        ui.add_space(10.0);
+
+        // reduce spacing between "hotkey" buttons
+        ui.spacing_mut().item_spacing.x /= 4.0;
+
+        if ui
+            .add(ui.small_selectable_button(brush.show_tint_map, "T"))
+            .on_hover_text("Show tint map.")
+            .clicked()
+        {
+            result = Some(ShowTintMap(!brush.show_tint_map));
+        }
+        if ui
+            .add(ui.small_selectable_button(brush.picker_activated, "P"))
+            .on_hover_text("Pick tint color.")
+            .clicked()
+        {
+            result = Some(TintPickerSelected(!brush.picker_activated));
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.radio_value(&mut brush.operation, LightenDarken, "lighten/darken")
+            .on_hover_text("left mouse button lightens, right mouse button darkens tint");
+        ui.radio_value(&mut brush.operation, Jitter, "jitter")
+            .on_hover_text("randomly lighten or darken tint");
+    });
+    result
+}
+// ----------------------------------------------------------------------------
+#[inline]
+fn value_settings(ui: &mut Ui, brush: &mut BrushSettings) {
+    use TintOperation::*;
+
+    // copy values (borrow checker)
+    let strength = brush.strength.0;
+    let jitter = brush.jitter.0;
+
+    ui.add_enabled_ui(matches!(brush.operation, Set | Multiply), |ui| {
+        ui.horizontal(|ui| {
+            ui.color_edit_button_srgb(&mut brush.color.0);
+            let [r, g, b] = brush.color.0;
+            ui.label(format!("{} {} {}", r, g, b));
+        });
+    });
+
+    ui.add_enabled(
+        brush.operation == LightenDarken,
+        Slider::new(&mut brush.strength.0, 1..=64)
+            .show_value(false)
+            .text(format!("{} strength", strength)),
+    );
+
+    ui.add_enabled(
+        brush.operation == Jitter,
+        Slider::new(&mut brush.jitter.0, 1..=64)
+            .show_value(false)
+            .text(format!("{} jitter", jitter)),
+    )
+    .on_hover_text("maximum random value added/subtracted to/from tint.");
+}
+// ----------------------------------------------------------------------------
+use bevy::prelude::*;
+use bevy_egui::egui::{self, Slider, Ui};
+
+use crate::gui::toolbox::tintbrush::{BrushSettings, TintOperation};
+use crate::gui::{GuiAction, UiExtension};
+
+use super::common;
+use super::{BrushSize, ToolboxAction};
+// ----------------------------------------------------------------------------
@@ -0,0 +1,90 @@
+// ----------------------------------------------------------------------------
+#[rustfmt::skip]
+#[inline]
+pub(super) fn show(
+    ui: &mut egui::Ui,
+    settings: &MaterialUsageSettings,
+    gui_event: &mut EventWriter<GuiAction>,
+) {
+    use GuiAction::Toolbox;
+
+    ui.horizontal(|ui| {
+        if ui.button("Refresh").on_hover_text("recount usage on the complete map").clicked() {
+            gui_event.send(Toolbox(ToolboxAction::RefreshMaterialUsage));
+        }
+        if let Some(usage) = settings.usage.as_ref() {
+            ui.label(ui.small_text(format!("{} datapoints", usage.datapoints())));
+        }
+    });
+    ui.separator();
+
+    let usage = match settings.usage.as_ref() {
+        Some(usage) => usage,
+        None => {
+            ui.label("no statistics available.");
+            return;
+        }
+    };
+    let datapoints = usage.datapoints().max(1) as f32;
+    let percent = |count: usize| format!("{:.2}%", 100.0 * count as f32 / datapoints);
+
+    // --- per slot usage
+    ui.small("Material slots (overlay / background):");
+    egui::Grid::new("usage.materials")
+        .num_columns(3)
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("holes");
+            ui.label(percent(usage.overlay[0]));
+            ui.label(percent(usage.background[0]));
+            ui.end_row();
+
+            for slot in (0..31u8).map(MaterialSlot::from) {
+                let (overlay, background) = usage.slot_usage(slot);
+                if overlay == 0 && background == 0 {
+                    continue;
+                }
+                // slots are shown 1-based like in material palette
+                let highlighted = settings.highlighted == Some(slot);
+                if ui.selectable_label(highlighted, format!("#{}", *slot + 1))
+                    .on_hover_text("highlight coverage on terrain (overlay: red, background: green)")
+                    .clicked()
+                {
+                    let slot = if highlighted { None } else { Some(slot) };
+                    gui_event.send(Toolbox(ToolboxAction::HighlightMaterialCoverage(slot)));
+                }
+                ui.label(percent(overlay));
+                ui.label(percent(background));
+                ui.end_row();
+            }
+        });
+    ui.separator();
+
+    // --- blend control histograms
+    histogram(ui, "Slope blend threshold:", &usage.blend_threshold, datapoints);
+    histogram(ui, "Background uv scaling:", &usage.uv_scaling, datapoints);
+}
+// ----------------------------------------------------------------------------
+#[inline]
+fn histogram(ui: &mut Ui, label: &str, counts: &[usize], datapoints: f32) {
+    ui.small(label);
+    egui::Grid::new(label).num_columns(2).show(ui, |ui| {
+        for (value, count) in counts.iter().enumerate() {
+            let fraction = *count as f32 / datapoints;
+            ui.label(value.to_string());
+            ui.add(ProgressBar::new(fraction).text(format!("{:.2}%", 100.0 * fraction)));
+            ui.end_row();
+        }
+    });
+    ui.separator();
+}
+// ----------------------------------------------------------------------------
+use bevy::prelude::*;
+use bevy_egui::egui::{self, ProgressBar, Ui};
+
+use crate::gui::toolbox::usage::MaterialUsageSettings;
+use crate::gui::{GuiAction, UiExtension};
+use crate::terrain_material::MaterialSlot;
+
+use super::ToolboxAction;
+// ----------------------------------------------------------------------------
@@ -5,11 +5,17 @@
 use bevy::prelude::*;
 
 use crate::atmosphere::AtmosphereMat;
+use crate::autofly_camera::{CameraPath, CameraPathEditor, FrameSequenceRenderer};
 use crate::cmds;
-use crate::environment::{DayNightCycle, SunPositionSettings};
+use crate::environment::{
+    DayNightCycle, EnvironmentConfig, EnvironmentSettings, SunPositionSettings,
+};
 use crate::terrain_tiles::TerrainMeshSettings;
 
-use super::{AtmosphereSetting, DayNightCycleSetting, MeshSetting, RenderSetting, SunSetting};
+use super::{
+    AtmosphereSetting, CameraPathSetting, DayNightCycleSetting, EnvironmentBlendSetting,
+    EnvironmentCurveSetting, FrameSequenceSetting, MeshSetting, RenderSetting, SunSetting,
+};
 // ----------------------------------------------------------------------------
 pub(super) fn update_daynight_cycle_settings(
     action: &DayNightCycleSetting,
@@ -68,6 +74,88 @@ pub(super) fn update_atmosphere_settings(
     }
 }
 // ----------------------------------------------------------------------------
+pub(super) fn update_environment_curve(
+    action: &EnvironmentCurveSetting,
+    env_config: &mut ResMut<EnvironmentConfig>,
+) {
+    use EnvironmentCurveSetting::*;
+
+    match action {
+        AddKey(curve, time, value) => {
+            env_config.insert_key(*curve, *time, *value);
+        }
+        UpdateKey(curve, index, time, value) => {
+            env_config.update_key(*curve, *index, *time, *value)
+        }
+        RemoveKey(curve, index) => env_config.remove_key(*curve, *index),
+    }
+}
+// ----------------------------------------------------------------------------
+pub(super) fn update_environment_blending(
+    action: &EnvironmentBlendSetting,
+    env_settings: &mut ResMut<EnvironmentSettings>,
+) {
+    use EnvironmentBlendSetting::*;
+
+    match action {
+        LoadSecondary(filepath) => match EnvironmentConfig::load(filepath) {
+            Ok(definition) => env_settings.set_secondary(Some(definition)),
+            Err(e) => error!("{}", e),
+        },
+        ClearSecondary => env_settings.set_secondary(None),
+        SetWeight(weight) => {
+            env_settings.set_blend_weight(*weight);
+            env_settings.set_blend_animation(None);
+        }
+        SetAnimation(duration) => env_settings.set_blend_animation(*duration),
+    }
+}
+// ----------------------------------------------------------------------------
+pub(super) fn update_camera_path(
+    action: &CameraPathSetting,
+    campaths: &mut ResMut<Assets<CameraPath>>,
+    editor: &mut ResMut<CameraPathEditor>,
+) {
+    use CameraPathSetting::*;
+
+    match action {
+        SetDuration(id, duration) => {
+            if let Some(path) = campaths.get_mut(*id) {
+                path.update_duration(*duration);
+            }
+        }
+        SetKeypointShare(id, keypoint, share) => {
+            if let Some(path) = campaths.get_mut(*id) {
+                path.set_keypoint_share(*keypoint, *share);
+            }
+        }
+        InsertKeypoint(id, keypoint) => {
+            if let Some(path) = campaths.get_mut(*id) {
+                path.insert_keypoint(*keypoint);
+                editor.select_keypoint(Some(keypoint + 1));
+            }
+        }
+        RemoveKeypoint(id, keypoint) => {
+            if let Some(path) = campaths.get_mut(*id) {
+                if path.remove_keypoint(*keypoint) {
+                    editor.select_keypoint(Some((*keypoint).min(path.keypoint_count() - 1)));
+                }
+            }
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+pub(super) fn update_frame_sequence_settings(
+    action: &FrameSequenceSetting,
+    renderer: &mut ResMut<FrameSequenceRenderer>,
+) {
+    use FrameSequenceSetting::*;
+    match action {
+        SetFps(fps) => renderer.set_fps(*fps),
+        SetTimeOfDayAnimation(time_of_day) => renderer.set_time_of_day_animation(*time_of_day),
+    }
+}
+// ----------------------------------------------------------------------------
 #[rustfmt::skip]
 pub(super) fn update_mesh_settings(
     action: &MeshSetting,
@@ -92,7 +180,7 @@ pub(super) fn update_render_settings(
     task_manager: &mut cmds::AsyncCommandManager,
 ) {
     match action {
-        RenderSetting::OverlayWireframe(_) => {
+        RenderSetting::OverlayWireframe => {
             task_manager.add_new(cmds::GenerateTerrainMeshes.into());
         }
     }
@@ -0,0 +1,297 @@
+// ----------------------------------------------------------------------------
+#[inline]
+pub(super) fn show_camera_paths(
+    ui: &mut egui::Ui,
+    campaths: &CameraPathsCollection,
+    paths: &Assets<CameraPath>,
+    editor: &CameraPathEditor,
+    can_fly: bool,
+    gui_event: &mut EventWriter<GuiAction>,
+) {
+    use GuiAction::*;
+
+    egui::CollapsingHeader::new("Camera paths")
+        .default_open(false)
+        .show(ui, |ui| {
+            let mut loaded = campaths.loaded(paths).collect::<Vec<_>>();
+            if loaded.is_empty() {
+                ui.label("no camera paths found in assets/campaths");
+                return;
+            }
+            loaded.sort_by(|a, b| a.1.name().cmp(b.1.name()));
+
+            egui::Grid::new("camera.paths")
+                .num_columns(6)
+                .striped(true)
+                .show(ui, |ui| {
+                    for (id, path) in loaded {
+                        let selected = campaths.selected() == Some(id);
+                        ui.selectable_label(selected, path.name())
+                            .on_hover_text(format!("{} keypoints", path.keypoint_count()));
+
+                        let mut duration = path.duration();
+                        if ui
+                            .add(
+                                egui::DragValue::new(&mut duration)
+                                    .speed(0.5)
+                                    .clamp_range(1.0..=3600.0)
+                                    .suffix(" s"),
+                            )
+                            .changed()
+                        {
+                            gui_event.send(UpdateCameraPath(SetDuration(id, duration)));
+                        }
+                        if ui.add_enabled(can_fly, egui::Button::new("Fly")).clicked() {
+                            gui_event.send(StartAutoFly(id));
+                        }
+                        if ui
+                            .add_enabled(can_fly, egui::Button::new("Render"))
+                            .on_hover_text("renders path as png frame sequence")
+                            .clicked()
+                        {
+                            gui_event.send(RenderCameraPath(id));
+                        }
+                        let edited = editor.edited() == Some(id);
+                        if ui
+                            .add_enabled(can_fly, egui::SelectableLabel::new(edited, "Edit"))
+                            .clicked()
+                        {
+                            gui_event.send(EditCameraPath((!edited).then_some(id)));
+                        }
+                        if ui.button("Save").clicked() {
+                            gui_event.send(SaveCameraPath(id));
+                        }
+                        ui.end_row();
+                    }
+                });
+            ui.label(ui.small_text("Esc or Left-Ctrl stops flying"));
+
+            if let Some((id, path)) = editor
+                .edited()
+                .and_then(|id| paths.get(id).map(|p| (id, p)))
+            {
+                show_keypoint_settings(ui, id, path, editor, gui_event);
+            }
+        });
+}
+// ----------------------------------------------------------------------------
+#[inline]
+pub(super) fn show_recording_settings(
+    ui: &mut egui::Ui,
+    recorder: &CameraPathRecorder,
+    gui_event: &mut EventWriter<GuiAction>,
+) {
+    egui::CollapsingHeader::new("Camera path recording")
+        .default_open(false)
+        .show(ui, |ui| {
+            ui.add_enabled_ui(!recorder.is_recording(), |ui| {
+                let mut mode = recorder.mode();
+                let mut interval = match mode {
+                    RecordingMode::Interval(seconds) => seconds,
+                    RecordingMode::Keypress => 2.0,
+                };
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut mode, RecordingMode::Interval(interval), "every");
+                    let changed = ui
+                        .add(
+                            egui::DragValue::new(&mut interval)
+                                .speed(0.1)
+                                .clamp_range(0.1..=60.0)
+                                .suffix(" s"),
+                        )
+                        .changed();
+                    if changed {
+                        mode = RecordingMode::Interval(interval);
+                    }
+                    ui.radio_value(&mut mode, RecordingMode::Keypress, "on keypress (Space)");
+                });
+                if mode != recorder.mode() {
+                    gui_event.send(GuiAction::SetCameraPathRecordingMode(mode));
+                }
+            });
+
+            if recorder.is_recording() {
+                ui.label(format!("recording: {} keypoints", recorder.sample_count()));
+            } else {
+                ui.label(ui.small_text(
+                    "free cam: R starts/stops recording, V toggles path visualization",
+                ));
+            }
+        });
+}
+// ----------------------------------------------------------------------------
+#[inline]
+pub(super) fn show_rendering_settings(
+    ui: &mut egui::Ui,
+    renderer: &FrameSequenceRenderer,
+    gui_event: &mut EventWriter<GuiAction>,
+) {
+    use GuiAction::UpdateFrameSequenceRendering;
+
+    egui::CollapsingHeader::new("Camera path rendering")
+        .default_open(false)
+        .show(ui, |ui| {
+            if let Some((frame, frame_count)) = renderer.progress() {
+                ui.label(format!("rendering: frame {}/{}", frame, frame_count));
+                ui.label(ui.small_text("Esc aborts rendering"));
+                return;
+            }
+
+            egui::Grid::new("camera.path.rendering")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    let mut fps = renderer.fps();
+                    ui.label("frame rate");
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut fps)
+                                .clamp_range(1..=120)
+                                .suffix(" fps"),
+                        )
+                        .changed()
+                    {
+                        gui_event.send(UpdateFrameSequenceRendering(SetFps(fps)));
+                    }
+                    ui.end_row();
+
+                    let animation = renderer.time_of_day_animation();
+                    let mut animated = animation.is_some();
+                    // shown as hours
+                    let (mut start, mut end) = animation
+                        .map(|(start, end)| (start * 24.0, end * 24.0))
+                        .unwrap_or((12.0, 18.0));
+
+                    ui.checkbox(&mut animated, "time of day");
+                    let changed = ui
+                        .add_enabled_ui(animated, |ui| {
+                            ui.horizontal(|ui| {
+                                let start_changed = ui
+                                    .add(time_of_day_value(&mut start))
+                                    .on_hover_text("time of day of first frame")
+                                    .changed();
+                                ui.label("to");
+                                let end_changed = ui
+                                    .add(time_of_day_value(&mut end))
+                                    .on_hover_text("time of day of last frame")
+                                    .changed();
+                                start_changed || end_changed
+                            })
+                            .inner
+                        })
+                        .inner;
+                    ui.end_row();
+
+                    if changed || animated != animation.is_some() {
+                        let animation = animated.then(|| (start / 24.0, end / 24.0));
+                        gui_event.send(UpdateFrameSequenceRendering(SetTimeOfDayAnimation(
+                            animation,
+                        )));
+                    }
+                });
+            ui.label(
+                ui.small_text("frames are saved as png files in renderings/<camera path name>"),
+            );
+        });
+}
+// ----------------------------------------------------------------------------
+// helper
+// ----------------------------------------------------------------------------
+#[inline]
+fn time_of_day_value(hours: &mut f32) -> egui::DragValue<'_> {
+    egui::DragValue::new(hours)
+        .speed(0.05)
+        .clamp_range(0.0..=23.99)
+        .fixed_decimals(2)
+        .suffix(" h")
+}
+// ----------------------------------------------------------------------------
+#[inline]
+fn show_keypoint_settings(
+    ui: &mut egui::Ui,
+    id: CameraPathId,
+    path: &CameraPath,
+    editor: &CameraPathEditor,
+    gui_event: &mut EventWriter<GuiAction>,
+) {
+    use GuiAction::*;
+
+    ui.separator();
+    ui.label(format!("Keypoints of {}", path.name()));
+
+    let shares = path.keypoint_shares();
+    let total = shares.iter().sum::<f32>();
+    let selected = editor.selected_keypoint();
+
+    egui::Grid::new("camera.path.keypoints")
+        .num_columns(3)
+        .striped(true)
+        .show(ui, |ui| {
+            for (i, share) in shares.iter().enumerate() {
+                let is_selected = selected == Some(i);
+                if ui
+                    .selectable_label(is_selected, format!("#{}", i))
+                    .clicked()
+                {
+                    gui_event.send(SelectCameraPathKeypoint((!is_selected).then_some(i)));
+                }
+                let mut share = *share;
+                let hint = if i == 0 {
+                    "relative duration of approach from current camera"
+                } else {
+                    "relative duration of segment from previous keypoint"
+                };
+                if ui
+                    .add(
+                        egui::DragValue::new(&mut share)
+                            .speed(0.01)
+                            .clamp_range(0.05..=10.0),
+                    )
+                    .on_hover_text(hint)
+                    .changed()
+                {
+                    gui_event.send(UpdateCameraPath(SetKeypointShare(id, i, share)));
+                }
+                ui.label(format!("{:.1} s", share / total * path.duration()));
+                ui.end_row();
+            }
+        });
+
+    ui.horizontal(|ui| {
+        let selected = selected.filter(|i| *i < path.keypoint_count());
+        if ui
+            .add_enabled(selected.is_some(), egui::Button::new("Insert after"))
+            .clicked()
+        {
+            if let Some(i) = selected {
+                gui_event.send(UpdateCameraPath(InsertKeypoint(id, i)));
+            }
+        }
+        let can_remove = selected.is_some() && path.keypoint_count() > 2;
+        if ui
+            .add_enabled(can_remove, egui::Button::new("Remove"))
+            .clicked()
+        {
+            if let Some(i) = selected {
+                gui_event.send(UpdateCameraPath(RemoveKeypoint(id, i)));
+            }
+        }
+    });
+    ui.label(ui.small_text(
+        "drag keypoints and the control point of the selected keypoint in the viewport",
+    ));
+}
+// ----------------------------------------------------------------------------
+use bevy::prelude::{Assets, EventWriter};
+use bevy_egui::egui;
+
+use crate::autofly_camera::{
+    CameraPath, CameraPathEditor, CameraPathId, CameraPathRecorder, CameraPathsCollection,
+    FrameSequenceRenderer, RecordingMode,
+};
+use crate::gui::CameraPathSetting::{
+    InsertKeypoint, RemoveKeypoint, SetDuration, SetKeypointShare,
+};
+use crate::gui::FrameSequenceSetting::{SetFps, SetTimeOfDayAnimation};
+
+use super::{GuiAction, UiExtension};
+// ----------------------------------------------------------------------------
@@ -0,0 +1,463 @@
+// ----------------------------------------------------------------------------
+#[inline]
+pub(super) fn show_curve_editor(
+    ui: &mut egui::Ui,
+    editor: &mut EnvironmentCurveEditor,
+    env_config: &EnvironmentConfig,
+    daynight_cycle: &DayNightCycle,
+    gui_event: &mut EventWriter<GuiAction>,
+) {
+    egui::CollapsingHeader::new("Environment curves")
+        .default_open(false)
+        .show(ui, |ui| {
+            let previous_curve = editor.curve;
+            egui::ComboBox::from_label("curve")
+                .selected_text(curve_caption(CURVES[editor.curve].id))
+                .width(ui.available_width() - 50.0)
+                .show_ui(ui, |ui| {
+                    for (i, curve) in CURVES.iter().enumerate() {
+                        ui.selectable_value(&mut editor.curve, i, curve_caption(curve.id));
+                    }
+                });
+            if editor.curve != previous_curve {
+                editor.selected_key = None;
+                editor.dragged_key = None;
+                editor.drag_range = None;
+            }
+
+            let curve = CURVES[editor.curve].id;
+            let keys = curve_keys(env_config, curve);
+            if keys.is_empty() {
+                return;
+            }
+            // selection may be invalid after changes
+            editor.selected_key = editor.selected_key.filter(|i| *i < keys.len());
+
+            show_curve_plot(ui, editor, curve, &keys, daynight_cycle, gui_event);
+            ui.label(ui.small_text("click: select, drag: move, double click: add key"));
+
+            show_key_settings(ui, editor, curve, &keys, daynight_cycle, gui_event);
+
+            ui.separator();
+            if editor.filepath.is_empty() {
+                editor.filepath = env_config
+                    .filepath()
+                    .map(|path| Path::new(path).with_extension("json"))
+                    .map(|path| path.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "environment.env.json".to_string());
+            }
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut editor.filepath)
+                        .desired_width(ui.available_width() - 50.0),
+                );
+                if ui
+                    .add_enabled(!editor.filepath.is_empty(), egui::Button::new("Save"))
+                    .clicked()
+                {
+                    gui_event.send(SaveEnvironmentDefinition(editor.filepath.clone()));
+                }
+            });
+            ui.separator();
+        });
+}
+// ----------------------------------------------------------------------------
+#[inline]
+pub(super) fn show_blend_settings(
+    ui: &mut egui::Ui,
+    blending: &mut EnvironmentBlending,
+    env_settings: &EnvironmentSettings,
+    gui_event: &mut EventWriter<GuiAction>,
+) {
+    egui::CollapsingHeader::new("Environment blending")
+        .default_open(false)
+        .show(ui, |ui| {
+            ui.label(format!(
+                "secondary definition: {}",
+                env_settings.secondary().unwrap_or("none")
+            ));
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut blending.filepath)
+                        .hint_text("path to .env.json / .env.xml")
+                        .desired_width(ui.available_width() - 100.0),
+                );
+                if ui
+                    .add_enabled(!blending.filepath.is_empty(), egui::Button::new("Load"))
+                    .clicked()
+                {
+                    gui_event.send(UpdateEnvironmentBlending(LoadSecondary(
+                        blending.filepath.clone(),
+                    )));
+                }
+                if ui
+                    .add_enabled(
+                        env_settings.secondary().is_some(),
+                        egui::Button::new("Clear"),
+                    )
+                    .clicked()
+                {
+                    gui_event.send(UpdateEnvironmentBlending(ClearSecondary));
+                }
+            });
+
+            ui.add_enabled_ui(env_settings.secondary().is_some(), |ui| {
+                let mut weight = env_settings.blend_weight();
+                if ui
+                    .add(egui::Slider::new(&mut weight, 0.0..=1.0).text("blend weight"))
+                    .changed()
+                {
+                    gui_event.send(UpdateEnvironmentBlending(SetWeight(weight)));
+                }
+
+                ui.horizontal(|ui| {
+                    let mut animated = env_settings.blend_animation().is_some();
+                    let mut changed = ui.checkbox(&mut animated, "animate").changed();
+                    changed |= ui
+                        .add(
+                            egui::DragValue::new(&mut blending.duration)
+                                .speed(0.1)
+                                .clamp_range(0.5..=600.0),
+                        )
+                        .changed();
+                    ui.label("transition [s]");
+
+                    if changed {
+                        let duration = animated.then_some(blending.duration);
+                        gui_event.send(UpdateEnvironmentBlending(SetAnimation(duration)));
+                    }
+                });
+            });
+            ui.separator();
+        });
+}
+// ----------------------------------------------------------------------------
+// curve plot
+// ----------------------------------------------------------------------------
+const PLOT_HEIGHT: f32 = 120.0;
+const COLOR_STRIP_HEIGHT: f32 = 10.0;
+const KEY_RADIUS: f32 = 4.0;
+const KEY_PICK_DISTANCE: f32 = 8.0;
+// ----------------------------------------------------------------------------
+/// key with normalized time, plotted value (intensity for colors) and color
+struct Key {
+    time: f32,
+    value: f32,
+    color: Option<[f32; 3]>,
+}
+// ----------------------------------------------------------------------------
+fn show_curve_plot(
+    ui: &mut egui::Ui,
+    editor: &mut EnvironmentCurveEditor,
+    curve: CurveId,
+    keys: &[Key],
+    daynight_cycle: &DayNightCycle,
+    gui_event: &mut EventWriter<GuiAction>,
+) {
+    let (response, painter) = ui.allocate_painter(
+        egui::vec2(ui.available_width(), PLOT_HEIGHT),
+        Sense::click_and_drag(),
+    );
+    let rect = response.rect;
+    let is_color = keys[0].color.is_some();
+
+    let (min, max) = editor.drag_range.unwrap_or_else(|| value_range(keys));
+    let plot_bottom = if is_color {
+        rect.bottom() - COLOR_STRIP_HEIGHT
+    } else {
+        rect.bottom()
+    };
+
+    let to_screen = |time: f32, value: f32| {
+        pos2(
+            rect.left() + time * rect.width(),
+            plot_bottom - (value - min) / (max - min) * (plot_bottom - rect.top()),
+        )
+    };
+    let from_screen = |pos: Pos2| {
+        (
+            ((pos.x - rect.left()) / rect.width()).clamp(0.0, 1.0),
+            min + (plot_bottom - pos.y) / (plot_bottom - rect.top()) * (max - min),
+        )
+    };
+
+    // background with grid lines every 3 hours
+    painter.rect_filled(rect, 0.0, Color32::from_gray(20));
+    for hour in (3..24).step_by(3) {
+        let x = rect.left() + hour as f32 / 24.0 * rect.width();
+        let gray = if hour % 12 == 0 { 80 } else { 50 };
+        painter.line_segment(
+            [pos2(x, rect.top()), pos2(x, rect.bottom())],
+            Stroke::new(1.0, Color32::from_gray(gray)),
+        );
+    }
+
+    // key colors (color of key is used until the next key)
+    let (first, last) = (&keys[0], &keys[keys.len() - 1]);
+    if is_color {
+        let strip = |from: f32, to: f32, rgb: [f32; 3]| {
+            let strip = Rect::from_min_max(
+                pos2(rect.left() + from * rect.width(), plot_bottom),
+                pos2(rect.left() + to * rect.width(), rect.bottom()),
+            );
+            painter.rect_filled(strip, 0.0, to_color32(rgb));
+        };
+        // wrapped color of last key before the first key
+        if let Some(rgb) = last.color {
+            strip(0.0, first.time, rgb);
+        }
+        for (i, key) in keys.iter().enumerate() {
+            if let Some(rgb) = key.color {
+                strip(
+                    key.time,
+                    keys.get(i + 1).map(|k| k.time).unwrap_or(1.0),
+                    rgb,
+                );
+            }
+        }
+    }
+
+    // curve is cyclic: connect last key with first key of next day
+    // (linear approximation of the interpolated curve)
+    let line = std::iter::once(to_screen(last.time - 1.0, last.value))
+        .chain(keys.iter().map(|k| to_screen(k.time, k.value)))
+        .chain(std::iter::once(to_screen(first.time + 1.0, first.value)))
+        .collect::<Vec<_>>();
+    painter.add(egui::Shape::line(
+        line,
+        Stroke::new(1.5, Color32::LIGHT_BLUE),
+    ));
+
+    // current time marker
+    let now = rect.left() + daynight_cycle.time_of_day().normalized() * rect.width();
+    painter.line_segment(
+        [pos2(now, rect.top()), pos2(now, rect.bottom())],
+        Stroke::new(1.0, Color32::YELLOW),
+    );
+
+    for (i, key) in keys.iter().enumerate() {
+        let pos = to_screen(key.time, key.value);
+        if editor.selected_key == Some(i) {
+            painter.circle_filled(pos, KEY_RADIUS + 1.0, Color32::YELLOW);
+        } else {
+            painter.circle_filled(pos, KEY_RADIUS, Color32::WHITE);
+        }
+    }
+
+    // interaction
+    let key_at = |pointer: Pos2| {
+        keys.iter()
+            .enumerate()
+            .map(|(i, k)| (i, to_screen(k.time, k.value).distance(pointer)))
+            .filter(|(_, distance)| *distance <= KEY_PICK_DISTANCE)
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(i, _)| i)
+    };
+
+    if let Some(pointer) = response.interact_pointer_pos() {
+        if response.double_clicked() && key_at(pointer).is_none() {
+            let (time, value) = from_screen(pointer);
+            // new color keys use the color of the previous key
+            let value = match previous_key(keys, time).color {
+                Some(rgb) => CurveValue::Color(to_rgb255(rgb), value),
+                None => CurveValue::Scalar(value),
+            };
+            gui_event.send(UpdateEnvironmentCurve(AddKey(curve, time, value)));
+        } else if response.clicked() {
+            editor.selected_key = key_at(pointer);
+        } else if response.drag_started() {
+            editor.dragged_key = key_at(pointer);
+            if editor.dragged_key.is_some() {
+                editor.selected_key = editor.dragged_key;
+                editor.drag_range = Some((min, max));
+            }
+        } else if response.dragged() {
+            if let Some(i) = editor.dragged_key.filter(|i| *i < keys.len()) {
+                let (time, value) = from_screen(pointer);
+                let time = clamp_time(keys, i, time);
+                let value = match keys[i].color {
+                    Some(rgb) => CurveValue::Color(to_rgb255(rgb), value),
+                    None => CurveValue::Scalar(value),
+                };
+                gui_event.send(UpdateEnvironmentCurve(UpdateKey(curve, i, time, value)));
+            }
+        }
+    }
+    if response.drag_released() {
+        editor.dragged_key = None;
+        editor.drag_range = None;
+    }
+}
+// ----------------------------------------------------------------------------
+fn show_key_settings(
+    ui: &mut egui::Ui,
+    editor: &mut EnvironmentCurveEditor,
+    curve: CurveId,
+    keys: &[Key],
+    daynight_cycle: &DayNightCycle,
+    gui_event: &mut EventWriter<GuiAction>,
+) {
+    if let Some(i) = editor.selected_key {
+        let key = &keys[i];
+        let (mut time, mut value, mut rgb) = (key.time, key.value, key.color);
+
+        let caption = format!(
+            "{} key time [HH:mm]",
+            TimeOfDay::from_normalized(time).as_str()
+        );
+        let mut changed = ui
+            .add(
+                egui::Slider::new(&mut time, 0.0..=1.0)
+                    .show_value(false)
+                    .text(caption),
+            )
+            .changed();
+
+        ui.horizontal(|ui| {
+            if let Some(rgb) = rgb.as_mut() {
+                changed |= ui.color_edit_button_rgb(rgb).changed();
+                ui.label("color");
+            }
+            changed |= ui
+                .add(egui::DragValue::new(&mut value).speed(value_speed(keys)))
+                .changed();
+            ui.label(if rgb.is_some() { "intensity" } else { "value" });
+        });
+
+        if changed {
+            let time = clamp_time(keys, i, time);
+            let value = match rgb {
+                Some(rgb) => CurveValue::Color(to_rgb255(rgb), value),
+                None => CurveValue::Scalar(value),
+            };
+            gui_event.send(UpdateEnvironmentCurve(UpdateKey(curve, i, time, value)));
+        }
+    }
+
+    ui.horizontal(|ui| {
+        if ui.button("Add key at current time").clicked() {
+            let now = daynight_cycle.time_of_day().normalized();
+            let previous = previous_key(keys, now);
+            let value = match previous.color {
+                Some(rgb) => CurveValue::Color(to_rgb255(rgb), previous.value),
+                None => CurveValue::Scalar(previous.value),
+            };
+            gui_event.send(UpdateEnvironmentCurve(AddKey(curve, now, value)));
+        }
+        if ui
+            .add_enabled(
+                editor.selected_key.is_some() && keys.len() > 1,
+                egui::Button::new("Delete key"),
+            )
+            .clicked()
+        {
+            if let Some(i) = editor.selected_key.take() {
+                gui_event.send(UpdateEnvironmentCurve(RemoveKey(curve, i)));
+            }
+        }
+    });
+}
+// ----------------------------------------------------------------------------
+// helper
+// ----------------------------------------------------------------------------
+fn curve_caption((group, name): CurveId) -> String {
+    format!("{}.{}", group, name)
+}
+// ----------------------------------------------------------------------------
+fn curve_keys(env_config: &EnvironmentConfig, curve: CurveId) -> Vec<Key> {
+    match env_config.curve_keys(curve) {
+        Some(CurveKeys::Scalar(keys)) => keys
+            .iter()
+            .map(|k| Key {
+                time: k.time().normalized(),
+                value: k.value(),
+                color: None,
+            })
+            .collect(),
+        Some(CurveKeys::Color(keys)) => keys
+            .iter()
+            .map(|k| {
+                let c = k.color().as_rgba_f32();
+                Key {
+                    time: k.time().normalized(),
+                    value: k.intensity(),
+                    color: Some([c[0], c[1], c[2]]),
+                }
+            })
+            .collect(),
+        None => Vec::new(),
+    }
+}
+// ----------------------------------------------------------------------------
+/// value range of keys with some margin
+fn value_range(keys: &[Key]) -> (f32, f32) {
+    let (min, max) = keys.iter().fold((f32::MAX, f32::MIN), |(min, max), k| {
+        (min.min(k.value), max.max(k.value))
+    });
+    let margin = ((max - min) * 0.1).max(0.5);
+    (min - margin, max + margin)
+}
+// ----------------------------------------------------------------------------
+fn value_speed(keys: &[Key]) -> f32 {
+    let (min, max) = value_range(keys);
+    ((max - min) / 200.0).max(0.001)
+}
+// ----------------------------------------------------------------------------
+/// key at or before time (wraps to last key)
+fn previous_key(keys: &[Key], time: f32) -> &Key {
+    keys.iter()
+        .rev()
+        .find(|k| k.time <= time)
+        .unwrap_or(&keys[keys.len() - 1])
+}
+// ----------------------------------------------------------------------------
+/// keeps key order by restricting time to the range between the neighbours
+fn clamp_time(keys: &[Key], i: usize, time: f32) -> f32 {
+    const MINUTE: f32 = 1.0 / (24.0 * 60.0);
+
+    let min = if i > 0 {
+        keys[i - 1].time + MINUTE
+    } else {
+        0.0
+    };
+    let max = keys
+        .get(i + 1)
+        .map(|k| k.time - MINUTE)
+        .unwrap_or(1.0 - MINUTE);
+
+    time.clamp(min, max.max(min))
+}
+// ----------------------------------------------------------------------------
+#[inline]
+fn to_rgb255(rgb: [f32; 3]) -> [f32; 3] {
+    [rgb[0] * 255.0, rgb[1] * 255.0, rgb[2] * 255.0]
+}
+// ----------------------------------------------------------------------------
+#[inline]
+fn to_color32(rgb: [f32; 3]) -> Color32 {
+    Color32::from_rgb(
+        (rgb[0] * 255.0) as u8,
+        (rgb[1] * 255.0) as u8,
+        (rgb[2] * 255.0) as u8,
+    )
+}
+// ----------------------------------------------------------------------------
+use std::path::Path;
+
+use bevy::prelude::EventWriter;
+use bevy_egui::egui::{self, pos2, Color32, Pos2, Rect, Sense, Stroke};
+
+use crate::environment::{
+    CurveId, CurveKeys, CurveValue, DayNightCycle, EnvironmentConfig, EnvironmentSettings,
+    TimeOfDay, CURVES,
+};
+
+use crate::gui::environment::{EnvironmentBlending, EnvironmentCurveEditor};
+use crate::gui::EnvironmentBlendSetting::*;
+use crate::gui::EnvironmentCurveSetting::*;
+
+use super::GuiAction::{
+    self, SaveEnvironmentDefinition, UpdateEnvironmentBlending, UpdateEnvironmentCurve,
+};
+use super::UiExtension;
+// ----------------------------------------------------------------------------
@@ -10,6 +10,66 @@ pub fn show(
             ui.set_enabled(ui_state.enabled);
 
             ui.menu_button("Project", |ui| {
+                if ui
+                    .add_enabled(
+                        !ui_state.project_open && !ui_state.project_is_loading,
+                        egui::Button::new("Open Project..."),
+                    )
+                    .clicked()
+                {
+                    ui.close_menu();
+                    gui_event.send(GuiAction::ShowProjectDialog(ProjectFileAction::Open));
+                }
+                if ui
+                    .add_enabled(ui_state.project_open, egui::Button::new("Save Project..."))
+                    .clicked()
+                {
+                    ui.close_menu();
+                    gui_event.send(GuiAction::ShowProjectDialog(ProjectFileAction::Save));
+                }
+                ui.separator();
+                if ui
+                    .add_enabled(ui_state.project_open, egui::Button::new("Save Texturing"))
+                    .clicked()
+                {
+                    ui.close_menu();
+                    gui_event.send(GuiAction::SaveTextureMaps);
+                }
+                if ui
+                    .add_enabled(ui_state.project_open, egui::Button::new("Save Tint Map"))
+                    .clicked()
+                {
+                    ui.close_menu();
+                    gui_event.send(GuiAction::SaveTintMap);
+                }
+                if ui
+                    .add_enabled(ui_state.project_open, egui::Button::new("Save Heightmap"))
+                    .clicked()
+                {
+                    ui.close_menu();
+                    gui_event.send(GuiAction::SaveHeightmap);
+                }
+                if ui
+                    .add_enabled(
+                        ui_state.project_open,
+                        egui::Button::new("Export Heightmap (r16)"),
+                    )
+                    .clicked()
+                {
+                    ui.close_menu();
+                    gui_event.send(GuiAction::ExportHeightmapR16);
+                }
+                if ui
+                    .add_enabled(
+                        ui_state.project_open,
+                        egui::Button::new("Export Terrain Tiles (w3)..."),
+                    )
+                    .clicked()
+                {
+                    ui.close_menu();
+                    gui_event.send(GuiAction::ShowTileExportDialog);
+                }
+                ui.separator();
                 if ui.button("Quit").clicked() {
                     ui.close_menu();
                     gui_event.send(GuiAction::QuitRequest);
@@ -28,5 +88,5 @@ pub fn show(
 use bevy::prelude::EventWriter;
 use bevy_egui::{egui, EguiContext};
 
-use super::{GuiAction, UiState};
+use super::{GuiAction, ProjectFileAction, UiState};
 // ----------------------------------------------------------------------------
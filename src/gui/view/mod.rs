@@ -1,6 +1,6 @@
 // ----------------------------------------------------------------------------
 #[rustfmt::skip]
-#[allow(clippy::too_many_arguments)]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub(super) fn show_ui(
     mut egui_ctx: ResMut<EguiContext>,
     mut ui_state: ResMut<UiState>,
@@ -8,9 +8,14 @@ pub(super) fn show_ui(
     materialset: Res<TerrainMaterialSet>,
     mesh_settings: Option<Res<TerrainMeshSettings>>,
     daynight_cycle: Res<DayNightCycle>,
+    env_config: Res<EnvironmentConfig>,
+    env_settings: Res<EnvironmentSettings>,
     sun_settings: Option<Res<SunPositionSettings>>,
     atmosphere_settings: Option<Res<AtmosphereMat>>,
     mesh_stats: Res<TerrainStats>,
+    (campaths, campath_assets, campath_recorder, campath_editor, sequence_renderer): (
+        Res<CameraPathsCollection>, Res<Assets<CameraPath>>, Res<CameraPathRecorder>,
+        Res<CameraPathEditor>, Res<FrameSequenceRenderer>),
     mut render_settings: ResMut<TerrainRenderSettings>,
     mut shadow_settings: ResMut<TerrainShadowsRenderSettings>,
     mut gui_event: EventWriter<GuiAction>,
@@ -19,6 +24,8 @@ pub(super) fn show_ui(
         return;
     }
     menu::show(&mut egui_ctx, &ui_state, &mut gui_event);
+    project::show_dialog(&mut egui_ctx, &mut ui_state.project, &mut gui_event);
+    tileexport::show_dialog(&mut egui_ctx, &mut ui_state.tile_export, &mut gui_event);
 
     egui::SidePanel::right("side_panel")
         .resizable(ui_state.enabled)
@@ -29,10 +36,10 @@ pub(super) fn show_ui(
             egui::ScrollArea::vertical()
                 .max_height(ui.available_height() - 45.0)
                 .show(ui, |ui| {
-                    rendersettings::show_settings(ui, &mut *render_settings, &mut gui_event);
+                    rendersettings::show_settings(ui, &mut render_settings, &mut gui_event);
 
                     rendersettings::show_terrain_shadows_settings(
-                        ui, &mut *render_settings, &mut shadow_settings);
+                        ui, &mut render_settings, &mut shadow_settings);
 
                     if let Some(settings) = mesh_settings {
                         mesh::show_settings(ui, &settings, &mesh_stats, &mut gui_event);
@@ -40,6 +47,20 @@ pub(super) fn show_ui(
 
                     daynight::show_settings(ui, &daynight_cycle, &mut gui_event);
 
+                    campaths::show_camera_paths(
+                        ui, &campaths, &campath_assets, &campath_editor, ui_state.project_open,
+                        &mut gui_event);
+
+                    campaths::show_recording_settings(ui, &campath_recorder, &mut gui_event);
+
+                    campaths::show_rendering_settings(ui, &sequence_renderer, &mut gui_event);
+
+                    environment::show_curve_editor(
+                        ui, &mut ui_state.environment, &env_config, &daynight_cycle, &mut gui_event);
+
+                    environment::show_blend_settings(
+                        ui, &mut ui_state.environment_blending, &env_settings, &mut gui_event);
+
                     if let Some(settings) = sun_settings {
                         atmosphere::show_sun_settings(ui, &settings, &mut gui_event);
                     }
@@ -60,6 +81,9 @@ pub(super) fn show_ui(
                             .text(task_tracking.last_msg()),
                     );
                 }
+                if let Some(error) = ui_state.progress.error() {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
             });
         });
 }
@@ -68,16 +92,27 @@ use bevy::prelude::*;
 use bevy_egui::{egui, EguiContext};
 
 use crate::atmosphere::AtmosphereMat;
-use crate::environment::{DayNightCycle, SunPositionSettings};
+use crate::autofly_camera::{
+    CameraPath, CameraPathEditor, CameraPathRecorder, CameraPathsCollection, FrameSequenceRenderer,
+};
+use crate::environment::{
+    DayNightCycle, EnvironmentConfig, EnvironmentSettings, SunPositionSettings,
+};
 use crate::terrain_material::TerrainMaterialSet;
 use crate::terrain_render::{TerrainRenderSettings, TerrainShadowsRenderSettings};
 use crate::terrain_tiles::{TerrainMeshSettings, TerrainStats};
 
-use super::{GuiAction, UiExtension, UiImages, UiState};
+use super::{
+    GuiAction, ProjectDialog, ProjectFileAction, TileExportDialog, UiExtension, UiImages, UiState,
+};
 // ----------------------------------------------------------------------------
 mod atmosphere;
+mod campaths;
 mod daynight;
+mod environment;
 mod menu;
 mod mesh;
+mod project;
 mod rendersettings;
+mod tileexport;
 // ----------------------------------------------------------------------------
@@ -0,0 +1,60 @@
+// ----------------------------------------------------------------------------
+#[rustfmt::skip]
+pub(super) fn show_dialog(
+    egui_ctx: &mut EguiContext,
+    dialog: &mut ProjectDialog,
+    gui_event: &mut EventWriter<GuiAction>,
+) {
+    let action = match dialog.show {
+        Some(action) => action,
+        None => return,
+    };
+    let (title, button) = match action {
+        ProjectFileAction::Open => ("Open Project", "Open"),
+        ProjectFileAction::Save => ("Save Project", "Save"),
+    };
+
+    let mut opened = true;
+    egui::Window::new(title)
+        .open(&mut opened)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(egui_ctx.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label("project file:");
+                ui.add(egui::TextEdit::singleline(&mut dialog.filepath)
+                    .hint_text("projects/terrain.ron")
+                    .desired_width(350.0));
+            });
+
+            if let Some(error) = dialog.error.as_ref() {
+                ui.separator();
+                ui.colored_label(egui::Color32::RED, error);
+            }
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                let filepath = dialog.filepath.trim();
+                if ui.add_enabled(!filepath.is_empty(), egui::Button::new(button)).clicked() {
+                    gui_event.send(match action {
+                        ProjectFileAction::Open => GuiAction::OpenProject(filepath.to_string()),
+                        ProjectFileAction::Save => GuiAction::SaveProject(filepath.to_string()),
+                    });
+                }
+                if ui.button("Cancel").clicked() {
+                    gui_event.send(GuiAction::CloseProjectDialog);
+                }
+            });
+        });
+
+    if !opened {
+        gui_event.send(GuiAction::CloseProjectDialog);
+    }
+}
+// ----------------------------------------------------------------------------
+use bevy::prelude::EventWriter;
+use bevy_egui::{egui, EguiContext};
+
+use super::{GuiAction, ProjectDialog, ProjectFileAction};
+// ----------------------------------------------------------------------------
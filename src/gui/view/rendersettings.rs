@@ -34,7 +34,7 @@ pub(super) fn show_settings(
                     .min_col_width(column_min_width)
                     .show(ui, |ui| {
                         if ui.checkbox(&mut settings.overlay_wireframe, "wireframe").changed() {
-                            gui_event.send(UpdateRenderSettings(OverlayWireframe(settings.overlay_wireframe)));
+                            gui_event.send(UpdateRenderSettings(OverlayWireframe));
                         }
                         ui.checkbox(&mut settings.overlay_clipmap_level, "clipmap level");
                     });
@@ -85,6 +85,13 @@ pub(super) fn show_settings(
                     if ui.checkbox(&mut settings.show_lightheight_map, "lightheight map").clicked() {
                         select_exclusive_view(settings, LightheightMap, settings.show_lightheight_map);
                     }
+                    ui.end_row_if(6 % columns == 0);
+                    if ui.checkbox(&mut settings.show_material_coverage, "material coverage")
+                        .on_hover_text("coverage of material slot selected in material usage tool")
+                        .clicked()
+                    {
+                        select_exclusive_view(settings, MaterialCoverage, settings.show_material_coverage);
+                    }
                 });
             ui.separator();
         });
@@ -156,6 +163,7 @@ enum ExclusiveViewSelection {
     UvScaling,
     TintMap,
     LightheightMap,
+    MaterialCoverage,
 }
 // ----------------------------------------------------------------------------
 fn select_exclusive_view(
@@ -175,6 +183,7 @@ fn select_exclusive_view(
         UvScaling => settings.show_bkgrnd_scaling = value,
         TintMap => settings.show_tint_map = value,
         LightheightMap => settings.show_lightheight_map = value,
+        MaterialCoverage => settings.show_material_coverage = value,
     }
 }
 // ----------------------------------------------------------------------------
@@ -0,0 +1,67 @@
+// ----------------------------------------------------------------------------
+#[rustfmt::skip]
+pub(super) fn show_dialog(
+    egui_ctx: &mut EguiContext,
+    dialog: &mut TileExportDialog,
+    gui_event: &mut EventWriter<GuiAction>,
+) {
+    if !dialog.show {
+        return;
+    }
+
+    let mut opened = true;
+    egui::Window::new("Export Terrain Tiles (w3)")
+        .open(&mut opened)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(egui_ctx.ctx_mut(), |ui| {
+            let (tiles, resolution) = dialog.layout;
+            ui.label(format!(
+                "{0}x{0} tiles with {1}x{1} datapoints (including shared border)",
+                tiles, resolution + 1
+            ));
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.label("directory:");
+                ui.add(egui::TextEdit::singleline(&mut dialog.settings.directory)
+                    .desired_width(350.0));
+            });
+            ui.horizontal(|ui| {
+                ui.label("maps:");
+                ui.checkbox(&mut dialog.settings.heightmap, "heightmap");
+                ui.checkbox(&mut dialog.settings.controlmap, "control map");
+                ui.checkbox(&mut dialog.settings.tintmap, "tint map");
+            });
+
+            if let Some(error) = dialog.error.as_ref() {
+                ui.separator();
+                ui.colored_label(egui::Color32::RED, error);
+            }
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                let settings = &dialog.settings;
+                let enabled = !settings.directory.trim().is_empty()
+                    && (settings.heightmap || settings.controlmap || settings.tintmap);
+
+                if ui.add_enabled(enabled, egui::Button::new("Export")).clicked() {
+                    gui_event.send(GuiAction::ExportTiles(settings.clone()));
+                }
+                if ui.button("Cancel").clicked() {
+                    gui_event.send(GuiAction::CloseTileExportDialog);
+                }
+            });
+        });
+
+    if !opened {
+        gui_event.send(GuiAction::CloseTileExportDialog);
+    }
+}
+// ----------------------------------------------------------------------------
+use bevy::prelude::EventWriter;
+use bevy_egui::{egui, EguiContext};
+
+use super::{GuiAction, TileExportDialog};
+// ----------------------------------------------------------------------------
@@ -1,34 +1,58 @@
 // ----------------------------------------------------------------------------
 // max (default) storage binding size 128mb //TODO is this guaranteed?
 // mapsize 16384 * 512 rows * 12 byte (result buf with normals) = 96MB
+#[cfg(feature = "editor")]
 const COMPUTE_NORMALS_MAX_ROWS: usize = 1024;
+/// compute normals shader workgroup size in y direction
+#[cfg(feature = "editor")]
+const COMPUTE_NORMALS_ROW_ALIGNMENT: usize = 8;
 // ----------------------------------------------------------------------------
 use std::sync::Arc;
 
-use bevy::ecs::schedule::StateData;
-use bevy::math::uvec2;
-use bevy::prelude::*;
+use glam::{uvec2, vec3, UVec2, Vec3};
 
-use crate::cmds::{AsyncTaskFinishedEvent, AsyncTaskStartEvent, TrackedProgress};
-use crate::compute::{AppComputeNormalsTask, ComputeResultData, ComputeResults};
+use crate::clipmap::Rectangle;
 use crate::config::{TerrainConfig, TILE_SIZE};
-use crate::EditorEvent;
+
+#[cfg(feature = "editor")]
+use {
+    crate::cmds::{
+        self, AsyncCommandManager, AsyncTaskFinishedEvent, AsyncTaskStartEvent, TrackedProgress,
+    },
+    crate::compute::{AppComputeNormalsTask, ComputeResultData, ComputeResults},
+    crate::EditorEvent,
+    bevy::{ecs::schedule::StateData, prelude::*},
+};
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 pub struct HeightmapPlugin;
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 impl HeightmapPlugin {
     // ------------------------------------------------------------------------
     pub fn generate_heightmap_normals<T: StateData>(state: T) -> SystemSet {
         SystemSet::on_update(state).with_system(generate_heightmap_normals)
     }
     // ------------------------------------------------------------------------
+    /// triggers (partial) regeneration of normals, errormaps and meshes for
+    /// heightmap regions changed by editing
+    pub fn process_heightmap_changes<T: StateData>(state: T) -> SystemSet {
+        SystemSet::on_update(state).with_system(start_heightmap_changes_processing)
+    }
+    // ------------------------------------------------------------------------
+    pub fn reset_data<T: StateData>(state: T) -> SystemSet {
+        SystemSet::on_enter(state).with_system(reset_heightmap_changes)
+    }
+    // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 impl Plugin for HeightmapPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<ComputeNormalsTaskQueue>()
             .init_resource::<TerrainHeightMap>()
-            .init_resource::<TerrainNormals>();
+            .init_resource::<TerrainNormals>()
+            .init_resource::<HeightmapChanges>();
     }
 }
 // ----------------------------------------------------------------------------
@@ -41,10 +65,20 @@ pub struct TerrainHeightMap {
 // ----------------------------------------------------------------------------
 #[derive(Default)]
 pub struct TerrainNormals {
-    _size: u32,
+    size: u32,
     data: Vec<u32>,
 }
 // ----------------------------------------------------------------------------
+/// [Resource] Tracks heightmap regions (map coordinates) changed by editing.
+/// Changes are collected while a previous change is still processed and are
+/// merged into one region for the next update of normals, errormaps and meshes.
+#[cfg(feature = "editor")]
+#[derive(Default)]
+pub struct HeightmapChanges {
+    pending: Option<Rectangle>,
+    processing: Option<Rectangle>,
+}
+// ----------------------------------------------------------------------------
 #[derive(Clone, Copy)]
 pub struct MinHeight(u16);
 #[derive(Clone, Copy)]
@@ -84,14 +118,217 @@ impl TerrainHeightMap {
         self.height_scaling = new_heightmap.height_scaling;
     }
     // ------------------------------------------------------------------------
+    /// overwrites heightmap data within rectangle. Note: the heightmap clipmap
+    /// holds its own copy of the data and must be updated separately.
+    pub(crate) fn update_rectangle(&mut self, rectangle: &Rectangle, new_data: &[u16]) {
+        assert!(rectangle.pos.x + rectangle.size.x <= self.size);
+        assert!(rectangle.pos.y + rectangle.size.y <= self.size);
+        assert!((rectangle.size.x * rectangle.size.y) as usize == new_data.len());
+
+        let width = rectangle.size.x as usize;
+        let mut offset = (rectangle.pos.y * self.size + rectangle.pos.x) as usize;
+
+        for src in new_data.chunks_exact(width) {
+            self.data[offset..offset + width].copy_from_slice(src);
+            offset += self.size as usize;
+        }
+    }
+    // ------------------------------------------------------------------------
+    pub(crate) fn size(&self) -> u32 {
+        self.size
+    }
+    // ------------------------------------------------------------------------
+    /// copy of heightmap data within rectangle
+    pub(crate) fn extract(&self, rectangle: &Rectangle) -> Vec<u16> {
+        extract_rectangle(&self.data, self.size, rectangle)
+    }
+    // ------------------------------------------------------------------------
+    /// heightmap data as 16bit big endian byte stream (as expected by 16bit
+    /// grayscale png files).
+    pub(crate) fn to_be_bytes(&self) -> Vec<u8> {
+        self.data.iter().flat_map(|h| h.to_be_bytes()).collect()
+    }
+    // ------------------------------------------------------------------------
+    /// heightmap data as 16bit little endian byte stream (headerless raw/r16
+    /// format used by most external terrain tools).
+    pub(crate) fn to_le_bytes(&self) -> Vec<u8> {
+        self.data.iter().flat_map(|h| h.to_le_bytes()).collect()
+    }
+    // ------------------------------------------------------------------------
+    /// number of datapoints at the lower and upper bound of the u16 range
+    /// (sculpting clamps heights to min/max height of the terrain).
+    pub(crate) fn clipped_values(&self) -> (usize, usize) {
+        self.data.iter().fold((0, 0), |(min, max), h| match *h {
+            u16::MIN => (min + 1, max),
+            u16::MAX => (min, max + 1),
+            _ => (min, max),
+        })
+    }
+    // ------------------------------------------------------------------------
+    pub(crate) fn tile_min_max_height(
+        &self,
+        tile: TerrainTileId<TILE_SIZE>,
+    ) -> (MinHeight, MaxHeight) {
+        let start = tile.sampling_offset();
+        let (min, max) = self
+            .data
+            .chunks_exact(self.size as usize)
+            .skip(start.y as usize)
+            .take(TILE_SIZE as usize)
+            .flat_map(|row| &row[start.x as usize..(start.x + TILE_SIZE) as usize])
+            .fold((u16::MAX, u16::MIN), |(min, max), h| {
+                (min.min(*h), max.max(*h))
+            });
+
+        (MinHeight(min), MaxHeight(max))
+    }
+    // ------------------------------------------------------------------------
+    /// creates compute normals task for the provided rows. Note: two additional
+    /// rows are required: one before the data and one after the data to allow
+    /// "previous" and "next" row access. on the map borders the first/last row
+    /// is duplicated.
+    #[cfg(feature = "editor")]
+    fn compute_normals_task(
+        &self,
+        terrain_config: &TerrainConfig,
+        first_row: usize,
+        rows: usize,
+    ) -> AppComputeNormalsTask {
+        let data_width = self.size as usize;
+        let last_row = first_row + rows - 1;
+
+        let prev_row = first_row.saturating_sub(1);
+        let next_row = (last_row + 1).min(data_width - 1);
+
+        let mut data = Vec::with_capacity(data_width * (rows + 2));
+        data.extend_from_slice(&self.data[prev_row * data_width..(prev_row + 1) * data_width]);
+        data.extend_from_slice(&self.data[first_row * data_width..(last_row + 1) * data_width]);
+        data.extend_from_slice(&self.data[next_row * data_width..(next_row + 1) * data_width]);
+
+        AppComputeNormalsTask {
+            map_resolution: terrain_config.resolution(),
+            map_height_scaling: terrain_config.height_scaling(),
+            data_width: self.size,
+            data_rows: rows as u32,
+            data_offset: first_row * data_width,
+            data: Some(data),
+        }
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl TerrainNormals {
+    // ------------------------------------------------------------------------
+    /// cpu version of the compute normals shader (see compute/normals.wgsl)
+    /// for usage without gpu. generates the same packed (11:10:11) normals.
+    pub(crate) fn generate(heightmap: &TerrainHeightMap, terrain_config: &TerrainConfig) -> Self {
+        let size = heightmap.size as usize;
+        let scale = vec3(
+            terrain_config.resolution(),
+            terrain_config.height_scaling(),
+            terrain_config.resolution(),
+        );
+        let sample = |x: usize, y: usize| heightmap.data[y * size + x] as f32;
+
+        let mut data = Vec::with_capacity(size * size);
+        for y in 0..size {
+            // first/last row is duplicated on map borders
+            let y_prev = y.saturating_sub(1);
+            let y_next = (y + 1).min(size - 1);
+
+            for x in 0..size {
+                let x_next = (x + 1).clamp(1, size - 1);
+
+                let ve = vec3(0.0, sample(x, y), 0.0) * scale;
+                let vb = vec3(0.0, sample(x, y_prev), -1.0) * scale;
+                let vf = vec3(1.0, sample(x_next, y), 0.0) * scale;
+                let vh = vec3(0.0, sample(x, y_next), 1.0) * scale;
+
+                let normal = ((vh - ve).cross(vf - ve).normalize()
+                    + (vf - ve).cross(vb - ve).normalize())
+                .normalize();
+
+                data.push(pack_unit_direction_11_10_11(normal));
+            }
+        }
+        Self {
+            size: heightmap.size,
+            data,
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// copy of packed normals within rectangle
+    pub(crate) fn extract(&self, rectangle: &Rectangle) -> Vec<u32> {
+        extract_rectangle(&self.data, self.size, rectangle)
+    }
+    // ------------------------------------------------------------------------
+    /// y component of a packed (11:10:11) normal, see compute normals shader
+    #[inline(always)]
+    pub(crate) fn unpack_y(packed: u32) -> f32 {
+        ((packed >> 11) & 0b11_1111_1111) as f32 / 1023.0 * 2.0 - 1.0
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+impl HeightmapChanges {
+    // ------------------------------------------------------------------------
+    /// adds changed region (will be merged with all other pending changes)
+    pub fn add(&mut self, rectangle: &Rectangle) {
+        self.pending = Some(match self.pending.take() {
+            Some(pending) => {
+                let min = pending.pos.min(rectangle.pos);
+                let max = (pending.pos + pending.size).max(rectangle.pos + rectangle.size);
+                Rectangle {
+                    pos: min,
+                    size: max - min,
+                }
+            }
+            None => rectangle.clone(),
+        });
+    }
+    // ------------------------------------------------------------------------
+    /// currently processed region grown by one datapoint in every direction
+    /// (clamped to map size): normals and errormaps depend on neighboring
+    /// heights. None means full map (e.g. while loading).
+    pub fn processing_affected(&self, map_size: u32) -> Option<Rectangle> {
+        self.processing.as_ref().map(|r| {
+            let min = uvec2(r.pos.x.saturating_sub(1), r.pos.y.saturating_sub(1));
+            let max = (r.pos + r.size + UVec2::ONE).min(UVec2::splat(map_size));
+            Rectangle {
+                pos: min,
+                size: max - min,
+            }
+        })
+    }
+    // ------------------------------------------------------------------------
+    /// marks processing as finished so the next pending changes can be
+    /// processed
+    pub fn finish_processing(&mut self) {
+        self.processing = None;
+    }
+    // ------------------------------------------------------------------------
+    fn start_processing(&mut self) -> bool {
+        if self.processing.is_none() && self.pending.is_some() {
+            self.processing = self.pending.take();
+            true
+        } else {
+            false
+        }
+    }
+    // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 #[derive(Default)]
 struct ComputeNormalsTaskQueue {
     queue: Vec<AppComputeNormalsTask>,
     pending: usize,
+    /// number of tasks for current generation (progress tracking)
+    total: usize,
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 impl ComputeNormalsTaskQueue {
     // ------------------------------------------------------------------------
     /// returns true if last pending was "finished" and queue is empty
@@ -108,6 +345,7 @@ impl ComputeNormalsTaskQueue {
 // ----------------------------------------------------------------------------
 // systems
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 #[allow(clippy::too_many_arguments)]
 /// system for any heightmap generation
 fn generate_heightmap_normals(
@@ -118,6 +356,7 @@ fn generate_heightmap_normals(
     mut terrain_normals: ResMut<TerrainNormals>,
     mut compute_queue: ResMut<ComputeNormalsTaskQueue>,
     terrain_heightmap: Res<TerrainHeightMap>,
+    heightmap_changes: Res<HeightmapChanges>,
     terrain_config: Res<TerrainConfig>,
     compute_results: Res<ComputeResults>,
 ) {
@@ -135,58 +374,36 @@ fn generate_heightmap_normals(
             // scale down with map size (mostly for dev envs)
             let max_rows = data_width.min(COMPUTE_NORMALS_MAX_ROWS);
 
-            let last_slice = data_width / max_rows - 1;
-
-            for slice in 0..=last_slice {
-                // Note: two additional rows are required: one before the data
-                // and one after the data to allow "previous" and "next" row access
-                let mut data = vec![0u16; data_width * (max_rows + 2)];
-                let heightmap = &terrain_heightmap.data;
-
-                if last_slice == 0 {
-                    // only one slice -> duplicate first and last row
-                    let first = 0..data_width;
-                    let data_segment = first.end..first.end + data_width * max_rows;
-                    let last = data_segment.end..;
-
-                    data[first].copy_from_slice(&heightmap[0..data_width]);
-                    data[data_segment].copy_from_slice(&heightmap[..]);
-                    data[last].copy_from_slice(&heightmap[heightmap.len() - data_width..]);
-                } else if slice == 0 {
-                    // first slice -> duplicate first line
-                    let start = 0;
-                    let end = start + data_width * (max_rows + 1);
-
-                    data[0..data_width].copy_from_slice(&heightmap[0..data_width]);
-                    data[data_width..].copy_from_slice(&heightmap[start..end]);
-                } else if slice == last_slice {
-                    // last -> duplicate last line
-                    let len = data_width * (max_rows + 1);
-                    let start = slice * data_width * max_rows - data_width;
-                    let end = start + len;
-
-                    data[0..len].copy_from_slice(&heightmap[start..end]);
-                    data[len..].copy_from_slice(&heightmap[end - data_width..]);
-                } else {
-                    let start = slice * data_width * max_rows - data_width;
-                    let end = start + data_width * (max_rows + 2);
-
-                    data.copy_from_slice(&heightmap[start..end]);
+            let partial_update = heightmap_changes.processing_affected(terrain_heightmap.size);
+
+            let (first_row, end_row) = match &partial_update {
+                // only changed rows have to be recomputed but compute shader
+                // requires the row count to be a multiple of its workgroup size
+                Some(region) => {
+                    let align = COMPUTE_NORMALS_ROW_ALIGNMENT;
+                    let first_row = region.pos.y as usize / align * align;
+                    let end_row = (region.pos.y + region.size.y) as usize;
+                    (first_row, end_row.div_ceil(align) * align)
                 }
+                None => (0, data_width),
+            };
+
+            for start in (first_row..end_row).step_by(max_rows) {
+                let rows = max_rows.min(end_row - start);
+                compute_queue
+                    .queue
+                    .push(terrain_heightmap.compute_normals_task(&terrain_config, start, rows));
+            }
+            compute_queue.total = compute_queue.queue.len();
 
-                compute_queue.queue.push(AppComputeNormalsTask {
-                    map_resolution: terrain_config.resolution(),
-                    map_height_scaling: terrain_config.height_scaling(),
-                    data_width: terrain_heightmap.size,
-                    data_rows: max_rows as u32,
-                    data_offset: slice * data_width * max_rows,
-                    data: Some(data),
-                });
+            if partial_update.is_some() {
+                // normals of all other rows are still valid
+                continue;
             }
 
             // intialize current normals to new size
             *terrain_normals = TerrainNormals {
-                _size: terrain_heightmap.size,
+                size: terrain_heightmap.size,
                 data: vec![
                     // packed(0, 1, 0)
                     1023u32 << 11;
@@ -225,24 +442,70 @@ fn generate_heightmap_normals(
         // cleanup finished compute task trigger
         commands.entity(taskid).despawn();
 
+        let finished = compute_queue.finished(1);
+
         // progress update for GUI
-        let data_width = terrain_heightmap.size as usize;
-        let max_tasks = data_width / data_width.min(COMPUTE_NORMALS_MAX_ROWS);
+        let max_tasks = compute_queue.total;
 
         editor_events.send(EditorEvent::ProgressTrackingUpdate(
             TrackedProgress::GeneratedHeightmapNormals(
-                max_tasks.saturating_sub(compute_queue.pending),
+                max_tasks.saturating_sub(compute_queue.queue.len() + compute_queue.pending),
                 max_tasks,
             ),
         ));
 
-        if compute_queue.finished(1) {
+        if finished {
             terrain_normals.set_changed();
             task_finished.send(AsyncTaskFinishedEvent::HeightmapNormalsGenerated);
         }
     }
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+fn start_heightmap_changes_processing(
+    mut heightmap_changes: ResMut<HeightmapChanges>,
+    mut task_manager: ResMut<AsyncCommandManager>,
+) {
+    // changes are processed one after another: the async tasks use the
+    // processed region to restrict the regeneration of normals, errormaps and
+    // meshes to the affected tiles
+    if heightmap_changes.start_processing() {
+        task_manager.add_new(cmds::GenerateHeightmapNormals.into());
+    }
+}
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+fn reset_heightmap_changes(mut heightmap_changes: ResMut<HeightmapChanges>) {
+    *heightmap_changes = HeightmapChanges::default();
+}
+// ----------------------------------------------------------------------------
+// helper
+// ----------------------------------------------------------------------------
+/// see compute/normals.wgsl
+#[inline(always)]
+fn pack_unit_direction_11_10_11(n: Vec3) -> u32 {
+    let x = ((n.x.clamp(-1.0, 1.0) * 0.5 + 0.5) * ((1u32 << 11) - 1) as f32) as u32;
+    let y = ((n.y.clamp(-1.0, 1.0) * 0.5 + 0.5) * ((1u32 << 10) - 1) as f32) as u32;
+    let z = ((n.z.clamp(-1.0, 1.0) * 0.5 + 0.5) * ((1u32 << 11) - 1) as f32) as u32;
+
+    (z << 21) | (y << 11) | x
+}
+// ----------------------------------------------------------------------------
+#[inline]
+fn extract_rectangle<T: Copy>(data: &[T], size: u32, rectangle: &Rectangle) -> Vec<T> {
+    assert!(rectangle.pos.x + rectangle.size.x <= size);
+    assert!(rectangle.pos.y + rectangle.size.y <= size);
+
+    let (x, width) = (rectangle.pos.x as usize, rectangle.size.x as usize);
+
+    data.chunks_exact(size as usize)
+        .skip(rectangle.pos.y as usize)
+        .take(rectangle.size.y as usize)
+        .flat_map(|row| &row[x..x + width])
+        .copied()
+        .collect()
+}
+// ----------------------------------------------------------------------------
 // reduced views on heightmap/normals
 // ----------------------------------------------------------------------------
 impl TerrainHeightMap {
@@ -424,6 +687,7 @@ impl<const TILE_SIZE: u32> TerrainTileId<TILE_SIZE> {
 // heightmap clipmap (required for terrain shadows)
 // ----------------------------------------------------------------------------
 use crate::clipmap::ClipmapData;
+#[cfg(feature = "editor")]
 use bevy::render::render_resource::TextureFormat;
 
 // TODO access to Heightmap data (for tile generation should use the high res
@@ -439,6 +703,7 @@ impl ClipmapData for TerrainHeightMap {
         1
     }
     // ------------------------------------------------------------------------
+    #[cfg(feature = "editor")]
     #[inline(always)]
     fn texture_format(&self) -> TextureFormat {
         TextureFormat::R16Uint
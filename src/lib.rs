@@ -1,50 +1,91 @@
+// helpers of the editor app are unused in the headless build
+#![cfg_attr(not(feature = "editor"), allow(dead_code))]
 // ----------------------------------------------------------------------------
-use bevy::{app::AppExit, prelude::*, render::render_resource::TextureFormat, tasks::Task};
-use bevy_egui::EguiContext;
-// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 pub struct EditorPlugin;
 // ----------------------------------------------------------------------------
-use camera::CameraPlugin;
-
-use cmds::AsyncTaskFinishedEvent;
-use gui::UiImages;
-
-use crate::autofly_camera::AutoFlyCameraPlugin;
-use crate::environment::EnvironmentPlugin;
-use crate::heightmap::HeightmapPlugin;
-use crate::terrain_clipmap::TerrainClipmapPlugin;
-use crate::terrain_material::MaterialSetPlugin;
-use crate::terrain_painting::TerrainPaintingPlugin;
-use crate::terrain_render::TerrainShadowsComputePlugin;
-use crate::terrain_tiles::TerrainTilesGeneratorPlugin;
+// headless api (usable without bevy app, e.g. for batch processing). builds
+// without the "editor" feature (no bevy dependency).
+pub use config::{TerrainConfig, TILE_SIZE};
+pub use document::TerrainDocument;
+pub use heightmap::TerrainTileId;
+pub use terrain_material::MaterialSlot;
+pub use terrain_painting::{
+    BrushFalloff, BrushPlacement, BrushShape, BrushStamp, ControlMapEntry, FalloffCurve,
+    HeightDelta, MaterialRemapStats, MaterialRemapTable, MaterialUsage, OverwriteProbability,
+    PaintCommand, PaintingRng, RemapTarget, SculptStrength, SlopeBlendThreshold, StampTransform,
+    StrokeId, TextureScale, TintColor, TintJitter, TintStrength, Variance,
+};
+pub use terrain_tiles::{TerrainMesh, TerrainMeshIndices, TerrainMeshStats, TerrainMeshVertexData};
+pub use tileexport::TileExportSettings;
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+use {
+    bevy::{app::AppExit, prelude::*, render::render_resource::TextureFormat, tasks::Task},
+    bevy_egui::EguiContext,
+    camera::CameraPlugin,
+    cmds::AsyncTaskFinishedEvent,
+    gui::UiImages,
+};
+
+#[cfg(feature = "editor")]
+use crate::{
+    autofly_camera::AutoFlyCameraPlugin, environment::EnvironmentPlugin,
+    heightmap::HeightmapPlugin, terrain_clipmap::TerrainClipmapPlugin,
+    terrain_material::MaterialSetPlugin, terrain_painting::TerrainPaintingPlugin,
+    terrain_render::TerrainShadowsComputePlugin, terrain_tiles::TerrainTilesGeneratorPlugin,
+};
 // ----------------------------------------------------------------------------
-mod atmosphere;
 mod config;
+mod document;
 mod loader;
+mod tileexport;
+mod writer;
 
 mod heightmap;
-mod terrain_clipmap;
 mod terrain_material;
 mod terrain_tiles;
 mod texturecontrol;
 mod tintmap;
 
-mod environment;
 mod terrain_painting;
+
+mod clipmap;
+// ----------------------------------------------------------------------------
+// editor app
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+mod atmosphere;
+
+#[cfg(feature = "editor")]
+mod terrain_clipmap;
+
+#[cfg(feature = "editor")]
+mod environment;
+#[cfg(feature = "editor")]
 mod terrain_render;
 
+#[cfg(feature = "editor")]
 mod autofly_camera;
+#[cfg(feature = "editor")]
 mod camera;
-mod clipmap;
+#[cfg(feature = "editor")]
 mod compute;
+#[cfg(feature = "editor")]
 mod mut_renderasset;
+#[cfg(feature = "editor")]
 mod resource;
+#[cfg(feature = "editor")]
 mod shapes;
+#[cfg(feature = "editor")]
 mod texturearray;
 
+#[cfg(feature = "editor")]
 mod cmds;
+#[cfg(feature = "editor")]
 mod gui;
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 #[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
 enum EditorState {
     Initialization,
@@ -56,19 +97,25 @@ enum EditorState {
 }
 // ----------------------------------------------------------------------------
 /// events triggered by editor and not user (e.g. to update something in GUI)
+#[cfg(feature = "editor")]
 enum EditorEvent {
     TerrainTextureUpdated(terrain_material::TextureUpdatedEvent),
     ProgressTrackingStart(cmds::TrackedTaskname, Vec<cmds::TrackedProgress>),
     ProgressTrackingUpdate(cmds::TrackedProgress),
+    ProgressTrackingCancel,
+    /// stops progress tracking and shows the error
+    ProgressTrackingFailed(String),
     ToggleGuiVisibility,
     StateChange(EditorState),
     Debug(DebugEvent),
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 enum DebugEvent {
     ClipmapUpdate(String, u8, Handle<texturearray::TextureArray>),
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 #[derive(Default)]
 struct DefaultResources {
     logo: Handle<Image>,
@@ -76,6 +123,7 @@ struct DefaultResources {
 }
 // ----------------------------------------------------------------------------
 // sync loader of essential files
+#[cfg(feature = "editor")]
 fn setup_default_assets(
     mut egui_ctx: ResMut<EguiContext>,
     mut ui_images: ResMut<UiImages>,
@@ -134,6 +182,7 @@ fn setup_default_assets(
     Ok(())
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 fn handle_setup_errors(
     In(result): In<Result<(), String>>,
     mut app_exit_events: EventWriter<AppExit>,
@@ -147,10 +196,12 @@ fn handle_setup_errors(
     }
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 fn finish_initialization(mut app_state: ResMut<State<EditorState>>) {
     app_state.overwrite_set(EditorState::NoTerrainData).unwrap();
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 fn signal_editor_state_change(
     app_state: Res<State<EditorState>>,
     mut editor_events: EventWriter<EditorEvent>,
@@ -158,14 +209,21 @@ fn signal_editor_state_change(
     editor_events.send(EditorEvent::StateChange(*app_state.current()));
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 type TaskResult = Task<Result<TaskResultData, String>>;
 // ----------------------------------------------------------------------------
 enum TaskResultData {
     HeightmapData(heightmap::TerrainHeightMap),
     TextureControl(texturecontrol::TextureControl),
     TintMap(tintmap::TintMap),
+    TextureMapSaved,
+    TintMapSaved,
+    HeightmapSaved,
+    HeightmapExported,
+    TilesExported,
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 fn setup_terrain_loading(
     terrain_config: Res<config::TerrainConfig>,
     mut mesh_settings: ResMut<terrain_tiles::TerrainMeshSettings>,
@@ -180,11 +238,11 @@ fn setup_terrain_loading(
     *terrain_render_info = terrain_render::TerrainMapInfo::from(terrain_config.as_ref());
 
     // queue loading tasks
-    task_manager.add_new(cmds::WaitForTerrainLoaded::default().into());
-    task_manager.add_new(cmds::LoadHeightmap::default().into());
-    task_manager.add_new(cmds::LoadTextureMap::default().into());
-    task_manager.add_new(cmds::LoadTintMap::default().into());
-    task_manager.add_new(cmds::LoadTerrainMaterialSet::default().into());
+    task_manager.add_new(cmds::WaitForTerrainLoaded.into());
+    task_manager.add_new(cmds::LoadHeightmap.into());
+    task_manager.add_new(cmds::LoadTextureMap.into());
+    task_manager.add_new(cmds::LoadTintMap.into());
+    task_manager.add_new(cmds::LoadTerrainMaterialSet.into());
 
     // bigger terrains may take > 10s of loading. show a progress bar by tracking
     // all longer running events
@@ -203,6 +261,7 @@ fn setup_terrain_loading(
     ));
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 fn watch_loading(
     mut app_state: ResMut<State<EditorState>>,
     mut tasks_finished: EventReader<AsyncTaskFinishedEvent>,
@@ -214,6 +273,7 @@ fn watch_loading(
     }
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 impl Plugin for EditorPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<DefaultResources>()
@@ -253,6 +313,7 @@ impl Plugin for EditorPlugin {
     }
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 impl EditorState {
     // ------------------------------------------------------------------------
     /// init of default resources/placeholders etc. with explicit ordering
@@ -275,7 +336,8 @@ impl EditorState {
         // there is no update phase in initialization, just transit to next state
         .add_startup_system(finish_initialization.after("init_ui"))
         // plugins
-        .add_startup_system_set(EnvironmentPlugin::startup());
+        .add_startup_system_set(EnvironmentPlugin::startup())
+        .add_startup_system_set(AutoFlyCameraPlugin::startup());
     }
     // ------------------------------------------------------------------------
     /// close project / unload terrain state
@@ -291,8 +353,10 @@ impl EditorState {
 
         app // plugins
             .add_system_set(TerrainClipmapPlugin::reset_data(NoTerrainData))
+            .add_system_set(HeightmapPlugin::reset_data(NoTerrainData))
             .add_system_set(TerrainShadowsComputePlugin::reset_data(NoTerrainData))
             .add_system_set(TerrainTilesGeneratorPlugin::reset_data(NoTerrainData))
+            .add_system_set(TerrainPaintingPlugin::reset_data(NoTerrainData))
             .add_system_set(MaterialSetPlugin::setup_default_materialset(NoTerrainData))
             .add_system_set(EnvironmentPlugin::activate_dynamic_updates(NoTerrainData))
             .add_system_set(EnvironmentPlugin::reset_data(NoTerrainData));
@@ -349,8 +413,12 @@ impl EditorState {
             .add_system_set(EnvironmentPlugin::activate_dynamic_updates(Editing))
             .add_system_set(MaterialSetPlugin::terrain_material_loading(Editing))
             .add_system_set(TerrainClipmapPlugin::update_tracker(Editing))
+            .add_system_set(HeightmapPlugin::generate_heightmap_normals(Editing))
+            .add_system_set(HeightmapPlugin::process_heightmap_changes(Editing))
             .add_system_set(TerrainTilesGeneratorPlugin::lazy_generation(Editing))
-            .add_system_set(TerrainPaintingPlugin::process_brush_operations(Editing));
+            .add_system_set(TerrainPaintingPlugin::process_brush_operations(Editing))
+            .add_system_set(AutoFlyCameraPlugin::edit_camera_path(Editing))
+            .add_system_set(AutoFlyCameraPlugin::stop_path_editing(Editing));
     }
     // ------------------------------------------------------------------------
     /// stacked state with active free cam (editing on hold)
@@ -366,6 +434,8 @@ impl EditorState {
             .add_system_set(CameraPlugin::start_free_camera(FreeCam))
             .add_system_set(CameraPlugin::active_free_camera(FreeCam))
             .add_system_set(CameraPlugin::stop_free_camera(FreeCam))
+            .add_system_set(AutoFlyCameraPlugin::record_camera_path(FreeCam))
+            .add_system_set(AutoFlyCameraPlugin::stop_recording(FreeCam))
             .add_system_set(EnvironmentPlugin::activate_dynamic_updates(FreeCam))
             .add_system_set(TerrainClipmapPlugin::update_tracker(FreeCam))
             .add_system_set(TerrainTilesGeneratorPlugin::lazy_generation(FreeCam));
@@ -393,43 +463,43 @@ impl EditorState {
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 #[allow(clippy::single_match, clippy::too_many_arguments)]
 fn global_hotkeys(
     keys: Res<Input<KeyCode>>,
     mut app_state: ResMut<State<EditorState>>,
     mut event: EventWriter<EditorEvent>,
-
-    mut autocam_paths: ResMut<autofly_camera::CameraPathsCollection>,
+    mut history_event: EventWriter<terrain_painting::PaintingHistoryEvent>,
+    // LControl is used for freecam toggling *and* as modifier
+    mut lctrl_used_as_modifier: Local<bool>,
 ) {
+    use terrain_painting::PaintingHistoryEvent::{Redo, Undo};
     use EditorState::*;
 
+    let ctrl = keys.any_pressed([KeyCode::LControl, KeyCode::RControl]);
+
     for key in keys.get_just_pressed() {
         match app_state.current() {
             FreeCam => match key {
                 KeyCode::F12 => event.send(EditorEvent::ToggleGuiVisibility),
-                KeyCode::LControl => app_state.overwrite_pop().unwrap(),
+                KeyCode::LControl => {
+                    // prevent reactivating freecam on release in editing state
+                    *lctrl_used_as_modifier = true;
+                    app_state.overwrite_pop().unwrap();
+                }
                 _ => {}
             },
             Editing => match key {
                 KeyCode::F12 => event.send(EditorEvent::ToggleGuiVisibility),
-                KeyCode::LControl => app_state.overwrite_push(FreeCam).unwrap(),
-                KeyCode::Key0 => {
-                    autocam_paths.select(0);
-                    app_state
-                        .overwrite_push(EditorState::AutoFlyCamera)
-                        .unwrap();
-                }
-                KeyCode::Key9 => {
-                    autocam_paths.select(1);
-                    app_state
-                        .overwrite_push(EditorState::AutoFlyCamera)
-                        .unwrap();
+                // freecam is activated on release if LControl was not used as modifier
+                KeyCode::LControl => *lctrl_used_as_modifier = false,
+                KeyCode::Z if ctrl => {
+                    *lctrl_used_as_modifier = true;
+                    history_event.send(Undo);
                 }
-                KeyCode::Key8 => {
-                    autocam_paths.select(2);
-                    app_state
-                        .overwrite_push(EditorState::AutoFlyCamera)
-                        .unwrap();
+                KeyCode::Y if ctrl => {
+                    *lctrl_used_as_modifier = true;
+                    history_event.send(Redo);
                 }
                 _ => (),
             },
@@ -450,10 +520,17 @@ fn global_hotkeys(
             Initialization => {}
         }
     }
+
+    if keys.just_released(KeyCode::LControl) && !*lctrl_used_as_modifier {
+        if let Editing = app_state.current() {
+            app_state.overwrite_push(FreeCam).unwrap();
+        }
+    }
 }
 // ----------------------------------------------------------------------------
 // helper
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 impl<'a> From<&'a config::TerrainConfig> for terrain_render::TerrainMapInfo {
     // ------------------------------------------------------------------------
     fn from(config: &'a config::TerrainConfig) -> Self {
@@ -1,7 +1,7 @@
 // ----------------------------------------------------------------------------
 use std::fs::File;
 
-use bevy::prelude::*;
+use tracing::debug;
 
 use futures_lite::Future;
 
@@ -190,12 +190,33 @@ impl LoaderPlugin {
 
         let img_data = Self::load_png_data(Indexed, Eight, resolution, filepath)?;
 
-        // check value range
-        for (i, pix) in img_data.iter().enumerate() {
-            if *pix > max {
+        match Self::check_value_range(&img_data, resolution, dataname, max, 1)
+            .into_iter()
+            .next()
+        {
+            Some(error) => Err(error),
+            None => Ok(img_data),
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// returns errors for (up to max_errors) datapoints outside of valid range
+    /// and a summary of all remaining invalid datapoints.
+    fn check_value_range(
+        img_data: &[u8],
+        resolution: u32,
+        dataname: &str,
+        max: u8,
+        max_errors: usize,
+    ) -> Vec<String> {
+        let mut errors = Vec::new();
+        let mut invalid = 0;
+
+        for (i, pix) in img_data.iter().enumerate().filter(|(_, pix)| **pix > max) {
+            invalid += 1;
+            if errors.len() < max_errors {
                 let y = i / resolution as usize;
                 let x = i - y * resolution as usize;
-                return Err(format!(
+                errors.push(format!(
                     "valid range for {} is [0..{}]. found: {} at line {} in pixel {}",
                     dataname,
                     max,
@@ -205,7 +226,74 @@ impl LoaderPlugin {
                 ));
             }
         }
-        Ok(img_data)
+        if invalid > errors.len() {
+            errors.push(format!(
+                "{}: {} more datapoints outside of valid range [0..{}]",
+                dataname,
+                invalid - errors.len(),
+                max
+            ));
+        }
+        errors
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+// validation
+// ----------------------------------------------------------------------------
+/// max reported out of range datapoints per map
+const MAX_REPORTED_RANGE_ERRORS: usize = 10;
+// ----------------------------------------------------------------------------
+impl LoaderPlugin {
+    // ------------------------------------------------------------------------
+    /// checks config and format/value ranges of all terrain maps defined in
+    /// config without stopping at the first error. maps with empty filepath
+    /// are skipped (they are replaced by defaults on loading).
+    pub(crate) fn validate_terrain_data(config: &config::TerrainConfig) -> Result<(), Vec<String>> {
+        use png::{
+            BitDepth::{Eight, Sixteen},
+            ColorType::{Grayscale, Indexed, Rgba},
+        };
+
+        let mut errors = config.validate().err().unwrap_or_default();
+        let size = config.map_size();
+        let texturing = config.texturemaps();
+
+        if !config.heightmap().is_empty() {
+            if let Err(e) = Self::load_png_data(Grayscale, Sixteen, size, config.heightmap()) {
+                errors.push(e);
+            }
+        }
+
+        for (filepath, dataname, max) in [
+            (texturing.background(), "background texture id", 31),
+            (texturing.overlay(), "overlay texture id", 31),
+            (texturing.blendcontrol(), "texture blendcontrol param", 63),
+        ] {
+            if filepath.is_empty() {
+                continue;
+            }
+            match Self::load_png_data(Indexed, Eight, size, filepath) {
+                Ok(data) => errors.extend(
+                    Self::check_value_range(&data, size, dataname, max, MAX_REPORTED_RANGE_ERRORS)
+                        .into_iter()
+                        .map(|e| format!("file {}: {}", filepath, e)),
+                ),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        if !config.tintmap().is_empty() {
+            if let Err(e) = Self::load_png_data(Rgba, Eight, size, config.tintmap()) {
+                errors.push(e);
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
     }
     // ------------------------------------------------------------------------
 }
@@ -126,18 +126,14 @@ impl<A: RenderResource> Plugin for RenderResourcePlugin<A> {
 }
 
 /// Temporarily stores the extracted and removed resources of the current frame.
+#[derive(Default)]
 pub enum UpdatedResource<A: RenderResource> {
+    #[default]
     Unchanged,
     Updated(Option<A::ExtractedResource>),
     Removed,
 }
 
-impl<A: RenderResource> Default for UpdatedResource<A> {
-    fn default() -> Self {
-        Self::Unchanged
-    }
-}
-
 /// Stores all GPU representations ([`RenderResource::PreparedResources`](RenderResource::PreparedResource))
 /// of [`RenderResources`](RenderResource) as long as they exist.
 pub type PreparedRenderResource<A> = Option<<A as RenderResource>::PreparedResource>;
@@ -121,9 +121,11 @@ mod tracker;
 pub fn enable_caching(
     mut texture_clipmap: ResMut<TextureControlClipmap>,
     mut tint_clipmap: ResMut<TintClipmap>,
+    mut heightmap_clipmap: ResMut<HeightmapClipmap>,
 ) {
     texture_clipmap.enable_cache();
     tint_clipmap.enable_cache();
+    heightmap_clipmap.enable_cache();
 }
 // ----------------------------------------------------------------------------
 #[allow(clippy::too_many_arguments)]
@@ -164,7 +166,7 @@ fn update_clipmaps(
             ] {
                 editor_events.send(crate::EditorEvent::Debug(crate::DebugEvent::ClipmapUpdate(
                     label.to_string(),
-                    level as u8,
+                    level,
                     handle.clone_weak(),
                 )));
             }
@@ -1,11 +1,8 @@
 // ----------------------------------------------------------------------------
-/// Restriction for clipmap level parameter (e.g. if clipmap size is smallish
-/// and map size is biggish).
-const MAX_SUPPORTED_CLIPMAP_LEVEL: u8 = 8;
-// ----------------------------------------------------------------------------
 use bevy::math::{uvec2, UVec2, Vec2};
 
 use crate::clipmap::Rectangle;
+use crate::config::MAX_SUPPORTED_CLIPMAP_LEVEL;
 use crate::terrain_render::{ClipmapInfo, ClipmapLayerInfo};
 
 use super::{ClipmapTracker, LayerRectangle, CLIPMAP_GRANULARITY, CLIPMAP_SIZE};
@@ -1,26 +1,35 @@
 // ----------------------------------------------------------------------------
-#[cfg(debug_assertions)]
+#[cfg(all(feature = "editor", debug_assertions))]
 const TERRAIN_TEXTURE_MIP_LEVELS: Option<u8> = None;
-#[cfg(not(debug_assertions))]
+#[cfg(all(feature = "editor", not(debug_assertions)))]
 const TERRAIN_TEXTURE_MIP_LEVELS: Option<u8> = Some(0);
 // ----------------------------------------------------------------------------
-use bevy::{
-    ecs::schedule::StateData,
-    prelude::*,
-    render::render_resource::TextureFormat,
-    tasks::{IoTaskPool, Task},
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "editor")]
+use {
+    bevy::{
+        ecs::schedule::StateData,
+        prelude::*,
+        render::render_resource::TextureFormat,
+        tasks::{IoTaskPool, Task},
+    },
+    futures_lite::Future,
 };
 
-use futures_lite::Future;
-
-use crate::cmds::{AsyncTaskFinishedEvent, AsyncTaskStartEvent};
-use crate::config::TerrainConfig;
-use crate::loader::LoaderPlugin;
-use crate::texturearray::{TextureArray, TextureArrayBuilder, TextureMipLevel};
-use crate::{DefaultResources, EditorEvent};
+#[cfg(feature = "editor")]
+use crate::{
+    cmds::{AsyncTaskFinishedEvent, AsyncTaskStartEvent},
+    config::TerrainConfig,
+    loader::LoaderPlugin,
+    texturearray::{TextureArray, TextureArrayBuilder, TextureMipLevel},
+    DefaultResources, EditorEvent,
+};
 
-pub use crate::terrain_render::{TerrainMaterialParam, TerrainMaterialSet};
+#[cfg(feature = "editor")]
+pub use crate::terrain_render::TerrainMaterialSet;
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 #[derive(Debug, Clone, Copy)]
 pub enum TextureType {
     Diffuse,
@@ -30,10 +39,26 @@ pub enum TextureType {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct MaterialSlot(u8);
 // ----------------------------------------------------------------------------
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TerrainMaterialParam {
+    pub blend_sharpness: f32,
+    pub slope_base_dampening: f32,
+    pub slope_normal_dampening: f32,
+    pub specularity_scale: f32,
+    pub specularity: f32,
+    pub specularity_base: f32,
+    pub _specularity_scale_copy: f32,
+    pub falloff: f32,
+}
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 pub struct TextureUpdatedEvent(pub MaterialSlot, pub TextureType);
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 pub struct MaterialSetPlugin;
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 impl MaterialSetPlugin {
     // ------------------------------------------------------------------------
     /// normal active cam operation
@@ -49,17 +74,20 @@ impl MaterialSetPlugin {
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 impl Plugin for MaterialSetPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<MaterialLoadingTaskQueue>();
     }
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 #[derive(Default)]
 struct MaterialLoadingTaskQueue {
     pending: usize,
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 impl MaterialLoadingTaskQueue {
     // ------------------------------------------------------------------------
     /// returns true if last pending was "finished"
@@ -74,6 +102,7 @@ impl MaterialLoadingTaskQueue {
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 struct TerrainTextureData {
     slot: MaterialSlot,
     ty: TextureType,
@@ -82,6 +111,7 @@ struct TerrainTextureData {
 // ----------------------------------------------------------------------------
 // systems
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 pub(super) fn setup_default_materialset(
     placeholder: Res<DefaultResources>,
     textures: Res<Assets<Image>>,
@@ -154,7 +184,7 @@ pub(super) fn setup_default_materialset(
     // -- normals -------------------------------------------------------------
     // Note: must not be loaded as SRGB!
     let mut normal_array =
-        TextureArrayBuilder::new(dim as u32, 32, Rgba8Unorm, TERRAIN_TEXTURE_MIP_LEVELS);
+        TextureArrayBuilder::new(dim, 32, Rgba8Unorm, TERRAIN_TEXTURE_MIP_LEVELS);
 
     let placeholder_normal =
         TextureArray::generate_mips(create_image(placeholder_normal), &mip_sizes);
@@ -177,6 +207,7 @@ pub(super) fn setup_default_materialset(
     debug!("generating default material pallete.end");
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 fn load_terrain_texture(
     slot: MaterialSlot,
     filepath: String,
@@ -197,6 +228,7 @@ fn load_terrain_texture(
     }
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 #[allow(clippy::too_many_arguments)]
 fn start_material_tasks(
     mut commands: Commands,
@@ -255,8 +287,10 @@ fn start_material_tasks(
     }
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 type TaskResult<T> = Task<Result<T, String>>;
 
+#[cfg(feature = "editor")]
 fn check_material_tasks(
     mut commands: Commands,
     mut loading_queue: ResMut<MaterialLoadingTaskQueue>,
@@ -328,6 +362,23 @@ impl std::ops::Deref for MaterialSlot {
     }
 }
 // ----------------------------------------------------------------------------
+impl Default for TerrainMaterialParam {
+    // ------------------------------------------------------------------------
+    fn default() -> Self {
+        Self {
+            blend_sharpness: 0.5,
+            slope_base_dampening: 0.5,
+            slope_normal_dampening: 0.5,
+            specularity_scale: 0.0,
+            specularity: 0.0,
+            specularity_base: 0.0,
+            _specularity_scale_copy: 0.0,
+            falloff: 0.0,
+        }
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
 // helper
 // ----------------------------------------------------------------------------
 use std::ops::{Index, IndexMut};
@@ -351,6 +402,7 @@ impl IndexMut<MaterialSlot> for [TerrainMaterialParam; 31] {
 // ----------------------------------------------------------------------------
 use std::fmt;
 
+#[cfg(feature = "editor")]
 impl fmt::Display for TextureType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
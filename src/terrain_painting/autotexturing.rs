@@ -0,0 +1,537 @@
+// ----------------------------------------------------------------------------
+// rule based automatic texturing
+// ----------------------------------------------------------------------------
+use bevy::math::uvec2;
+use bevy::prelude::*;
+use bevy::tasks::ComputeTaskPool;
+
+use crate::clipmap::Rectangle;
+use crate::cmds::{AsyncCommandManager, AsyncTaskFinishedEvent, AsyncTaskStartEvent};
+use crate::cmds::{GenerateAutoTexturing, TrackedProgress};
+use crate::config::TerrainConfig;
+use crate::heightmap::{TerrainHeightMap, TerrainNormals};
+use crate::terrain_clipmap::{ClipmapTracker, TextureControlClipmap};
+use crate::terrain_material::MaterialSlot;
+use crate::EditorEvent;
+
+use super::history::PaintingHistory;
+use super::{is_terrain_hole, set_bits};
+use super::{MaterialRemapEvent, PaintingEvent, PaintingHistoryEvent, SelectionEvent};
+use super::{SlopeBlendThreshold, TextureScale};
+use super::{BKGRND_TEXTURE_BITMASK, BKGRND_TEXTURE_BITPOS};
+use super::{BLENDING_BITMASK, BLENDING_BITPOS, SCALING_BITMASK, SCALING_BITPOS};
+use super::{OVERLAY_TEXTURE_BITMASK, OVERLAY_TEXTURE_BITPOS};
+// ----------------------------------------------------------------------------
+/// rows of the control map processed in one (parallelized) step
+const BAND_ROWS: u32 = 128;
+/// rows of a band processed by one thread
+const BAND_CHUNK_ROWS: u32 = 16;
+/// max time per frame spent in generation (multiple bands are processed until
+/// time is exceeded)
+const MAX_GENERATION_TIME_MS: instant::Duration = instant::Duration::from_millis(30);
+// ----------------------------------------------------------------------------
+#[derive(Debug)]
+pub enum AutoTexturingEvent {
+    /// generates a preview of the rules for the region (map coordinates).
+    /// None is the complete map.
+    Preview(Vec<AutoTexturingRule>, Option<Rectangle>),
+    /// commits current preview (as one undo step)
+    Apply,
+    /// restores texturing before preview
+    Discard,
+}
+// ----------------------------------------------------------------------------
+#[derive(Debug, Clone)]
+/// All defined filters must match for a datapoint to be changed by the rule.
+/// Rules are applied in order: later rules overwrite earlier ones.
+pub struct AutoTexturingRule {
+    pub enabled: bool,
+    /// terrain height in meters
+    pub height: Option<RangeFilter>,
+    /// slope in degrees (0 is flat)
+    pub slope: Option<RangeFilter>,
+    /// difference to average height of direct neighbors in meters: positive in
+    /// valleys/concave, negative on ridges/convex terrain
+    pub curvature: Option<RangeFilter>,
+    pub noise: Option<NoiseFilter>,
+
+    pub overlay: Option<MaterialSlot>,
+    pub background: Option<MaterialSlot>,
+    pub slope_blend: Option<SlopeBlendThreshold>,
+    pub scaling: Option<TextureScale>,
+}
+// ----------------------------------------------------------------------------
+#[derive(Debug, Clone, Copy)]
+pub struct RangeFilter {
+    pub min: f32,
+    pub max: f32,
+}
+// ----------------------------------------------------------------------------
+#[derive(Debug, Clone, Copy)]
+/// smooth value noise to break up uniform areas
+pub struct NoiseFilter {
+    /// size of noise features in meters
+    pub scale: f32,
+    /// fraction (0..1) of matching area
+    pub coverage: f32,
+    pub seed: u32,
+}
+// ----------------------------------------------------------------------------
+#[derive(Default)]
+pub(super) struct AutoTexturing {
+    request: Option<(Vec<AutoTexturingRule>, Option<Rectangle>)>,
+    generation: Option<Generation>,
+    preview: Option<Preview>,
+}
+// ----------------------------------------------------------------------------
+struct Generation {
+    rules: Vec<AutoTexturingRule>,
+    rectangle: Rectangle,
+    /// texture control data before generation started
+    backup: Vec<u16>,
+    /// original data of all bands changed so far
+    changed: Vec<(Rectangle, Vec<u16>)>,
+    next_row: u32,
+}
+// ----------------------------------------------------------------------------
+struct Preview {
+    /// texture control data of changed bands before preview
+    changed: Vec<(Rectangle, Vec<u16>)>,
+}
+// ----------------------------------------------------------------------------
+/// input data for generating a band of rows
+struct BandInput<'a> {
+    rules: &'a [AutoTexturingRule],
+    band: Rectangle,
+    control: &'a [u16],
+    /// heights of band grown by one datapoint in every direction (clamped to
+    /// map) for curvature calculation
+    heights: Vec<u16>,
+    heights_rectangle: Rectangle,
+    normals: Vec<u32>,
+
+    map_resolution: f32,
+    min_height: f32,
+    height_scaling: f32,
+}
+// ----------------------------------------------------------------------------
+struct Sample {
+    height: f32,
+    slope: f32,
+    curvature: f32,
+    /// world position (relative to map origin) in meters
+    pos: Vec2,
+}
+// ----------------------------------------------------------------------------
+// systems
+// ----------------------------------------------------------------------------
+#[allow(clippy::too_many_arguments)]
+pub(super) fn process_autotexturing_events(
+    mut events: EventReader<AutoTexturingEvent>,
+    mut paint_events: EventReader<PaintingEvent>,
+    mut history_events: EventReader<PaintingHistoryEvent>,
+    mut remap_events: EventReader<MaterialRemapEvent>,
+    mut selection_events: EventReader<SelectionEvent>,
+    mut autotexturing: ResMut<AutoTexturing>,
+    mut texture_clipmap: ResMut<TextureControlClipmap>,
+    mut clipmap_tracker: ResMut<ClipmapTracker>,
+    mut history: ResMut<PaintingHistory>,
+    mut task_manager: ResMut<AsyncCommandManager>,
+    mut task_finished: EventWriter<AsyncTaskFinishedEvent>,
+) {
+    // painting (or undo or remapping) on top of a not yet commited preview
+    // would be lost on discard and break the history -> discard preview first
+    // (count instead of any: all events must be consumed)
+    let remapping = remap_events
+        .iter()
+        .filter(|e| matches!(e, MaterialRemapEvent::Apply(_)))
+        .count()
+        > 0;
+    let pasting = selection_events
+        .iter()
+        .filter(|e| matches!(e, SelectionEvent::Paste(..)))
+        .count()
+        > 0;
+    let painting = !paint_events.is_empty() || !history_events.is_empty() || remapping || pasting;
+    paint_events.iter().for_each(drop);
+    history_events.iter().for_each(drop);
+
+    if painting && autotexturing.is_active() {
+        info!("auto texturing: painting operation discards preview.");
+        if autotexturing.discard(&mut texture_clipmap) {
+            task_finished.send(AsyncTaskFinishedEvent::AutoTexturingGenerated);
+        }
+        clipmap_tracker.force_update();
+    }
+
+    for event in events.iter() {
+        match event {
+            AutoTexturingEvent::Preview(rules, region) => {
+                if autotexturing.generation.is_some() {
+                    warn!("auto texturing: generation still in progress. ignoring request.");
+                    continue;
+                }
+                // new preview replaces old one
+                autotexturing.discard(&mut texture_clipmap);
+                autotexturing.request = Some((rules.clone(), region.clone()));
+                task_manager.add_new(GenerateAutoTexturing.into());
+            }
+            AutoTexturingEvent::Apply => match autotexturing.preview.take() {
+                Some(preview) => {
+                    if !preview.changed.is_empty() {
+                        history.record_texture_control_patches(preview.changed);
+                    }
+                }
+                None => warn!("auto texturing: no preview to apply."),
+            },
+            AutoTexturingEvent::Discard => {
+                if autotexturing.discard(&mut texture_clipmap) {
+                    task_finished.send(AsyncTaskFinishedEvent::AutoTexturingGenerated);
+                }
+                clipmap_tracker.force_update();
+            }
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+#[allow(clippy::too_many_arguments)]
+pub(super) fn generate_autotexturing(
+    config: Res<TerrainConfig>,
+    heightmap: Res<TerrainHeightMap>,
+    normals: Res<TerrainNormals>,
+    thread_pool: Res<ComputeTaskPool>,
+    mut autotexturing: ResMut<AutoTexturing>,
+    mut texture_clipmap: ResMut<TextureControlClipmap>,
+    mut clipmap_tracker: ResMut<ClipmapTracker>,
+    mut tasks_queued: EventReader<AsyncTaskStartEvent>,
+    mut task_finished: EventWriter<AsyncTaskFinishedEvent>,
+    mut editor_events: EventWriter<EditorEvent>,
+) {
+    use instant::Instant;
+
+    for task in tasks_queued.iter() {
+        if let AsyncTaskStartEvent::GenerateAutoTexturing = task {
+            match autotexturing.request.take() {
+                Some((rules, region)) => {
+                    let rectangle = clamp_region(region, config.map_size());
+                    debug!("generating auto texturing for {:?}...", rectangle);
+
+                    // see process_brush_operations
+                    texture_clipmap.disable_cache();
+
+                    autotexturing.generation = Some(Generation {
+                        rules,
+                        backup: texture_clipmap.extract_fullres(&rectangle),
+                        rectangle,
+                        changed: Vec::default(),
+                        next_row: 0,
+                    });
+                }
+                None => {
+                    // canceled (e.g. by painting)
+                    task_finished.send(AsyncTaskFinishedEvent::AutoTexturingGenerated);
+                }
+            }
+        }
+    }
+
+    let generation = match autotexturing.generation.as_mut() {
+        Some(generation) => generation,
+        None => return,
+    };
+
+    let start_time = Instant::now();
+    let rectangle = generation.rectangle.clone();
+    let width = rectangle.size.x as usize;
+
+    // ...measure duration after every band
+    while generation.next_row < rectangle.size.y
+        && Instant::now().duration_since(start_time) < MAX_GENERATION_TIME_MS
+    {
+        let rows = BAND_ROWS.min(rectangle.size.y - generation.next_row);
+        let band = Rectangle {
+            pos: rectangle.pos + uvec2(0, generation.next_row),
+            size: uvec2(rectangle.size.x, rows),
+        };
+        let start = generation.next_row as usize * width;
+        let control = &generation.backup[start..start + rows as usize * width];
+
+        let data = {
+            let input = BandInput::new(
+                &config,
+                &heightmap,
+                &normals,
+                &generation.rules,
+                band.clone(),
+                control,
+            );
+            let input = &input;
+
+            thread_pool
+                .scope(|s| {
+                    for first_row in (0..rows).step_by(BAND_CHUNK_ROWS as usize) {
+                        let chunk_rows = BAND_CHUNK_ROWS.min(rows - first_row);
+                        s.spawn(async move { input.generate(first_row, chunk_rows) });
+                    }
+                })
+                // Note: scope returns results in spawn order
+                .concat()
+        };
+        // only changed bands are updated and stored for undo/discard
+        if data != control {
+            texture_clipmap.update_fullres(&band, &data);
+            generation.changed.push((band, control.to_vec()));
+        }
+        generation.next_row += rows;
+    }
+
+    // progress update for GUI
+    editor_events.send(EditorEvent::ProgressTrackingUpdate(
+        TrackedProgress::GeneratedAutoTexturing(
+            generation.next_row as usize,
+            rectangle.size.y as usize,
+        ),
+    ));
+
+    if generation.next_row >= rectangle.size.y {
+        if let Some(generation) = autotexturing.generation.take() {
+            autotexturing.preview = Some(Preview {
+                changed: generation.changed,
+            });
+        }
+        clipmap_tracker.force_update();
+        task_finished.send(AsyncTaskFinishedEvent::AutoTexturingGenerated);
+    }
+}
+// ----------------------------------------------------------------------------
+pub(super) fn reset_autotexturing(mut autotexturing: ResMut<AutoTexturing>) {
+    *autotexturing = AutoTexturing::default();
+}
+// ----------------------------------------------------------------------------
+// helper
+// ----------------------------------------------------------------------------
+#[inline]
+fn clamp_region(region: Option<Rectangle>, map_size: u32) -> Rectangle {
+    let map = UVec2::splat(map_size);
+    match region {
+        Some(region) => {
+            let pos = region.pos.min(map - UVec2::ONE);
+            let max = (region.pos + region.size).min(map);
+            Rectangle {
+                pos,
+                size: (max - pos).max(UVec2::ONE),
+            }
+        }
+        None => Rectangle {
+            pos: UVec2::ZERO,
+            size: map,
+        },
+    }
+}
+// ----------------------------------------------------------------------------
+/// hash based (deterministic) value noise in range 0..1
+#[inline(always)]
+fn value_noise(pos: Vec2, seed: u32) -> f32 {
+    #[inline(always)]
+    fn lattice(x: i32, y: i32, seed: u32) -> f32 {
+        let mut h =
+            seed ^ (x as u32).wrapping_mul(0x27d4_eb2d) ^ (y as u32).wrapping_mul(0x1656_67b1);
+        h ^= h >> 15;
+        h = h.wrapping_mul(0x85eb_ca6b);
+        h ^= h >> 13;
+        h = h.wrapping_mul(0xc2b2_ae35);
+        h ^= h >> 16;
+        h as f32 / u32::MAX as f32
+    }
+    let p = pos.floor();
+    let f = pos - p;
+    // smoothstep interpolation
+    let w = f * f * (Vec2::splat(3.0) - 2.0 * f);
+    let (x, y) = (p.x as i32, p.y as i32);
+
+    let top = lattice(x, y, seed) + (lattice(x + 1, y, seed) - lattice(x, y, seed)) * w.x;
+    let bottom =
+        lattice(x, y + 1, seed) + (lattice(x + 1, y + 1, seed) - lattice(x, y + 1, seed)) * w.x;
+
+    top + (bottom - top) * w.y
+}
+// ----------------------------------------------------------------------------
+// impl
+// ----------------------------------------------------------------------------
+impl AutoTexturing {
+    // ------------------------------------------------------------------------
+    fn is_active(&self) -> bool {
+        self.generation.is_some() || self.preview.is_some()
+    }
+    // ------------------------------------------------------------------------
+    /// restores texturing before preview (or running generation). returns
+    /// true if a running generation was canceled.
+    fn discard(&mut self, texture_clipmap: &mut TextureControlClipmap) -> bool {
+        if let Some(preview) = self.preview.take() {
+            for (band, data) in &preview.changed {
+                texture_clipmap.update_fullres(band, data);
+            }
+        }
+        if let Some(generation) = self.generation.take() {
+            for (band, data) in &generation.changed {
+                texture_clipmap.update_fullres(band, data);
+            }
+            true
+        } else {
+            false
+        }
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl AutoTexturingRule {
+    // ------------------------------------------------------------------------
+    #[inline(always)]
+    fn matches(&self, sample: &Sample) -> bool {
+        self.height.is_none_or(|r| r.contains(sample.height))
+            && self.slope.is_none_or(|r| r.contains(sample.slope))
+            && self.curvature.is_none_or(|r| r.contains(sample.curvature))
+            && self.noise.is_none_or(|n| n.matches(sample.pos))
+    }
+    // ------------------------------------------------------------------------
+    #[inline(always)]
+    fn apply(&self, mut data: u16) -> u16 {
+        // holes are only changed by the hole brush
+        if is_terrain_hole(data) {
+            return data;
+        }
+        // zero is reserved for holes
+        if let Some(slot) = self.overlay {
+            data =
+                set_bits::<OVERLAY_TEXTURE_BITMASK, OVERLAY_TEXTURE_BITPOS>(data, *slot as u16 + 1);
+        }
+        if let Some(slot) = self.background {
+            data =
+                set_bits::<BKGRND_TEXTURE_BITMASK, BKGRND_TEXTURE_BITPOS>(data, *slot as u16 + 1);
+        }
+        if let Some(value) = self.slope_blend {
+            data = set_bits::<BLENDING_BITMASK, BLENDING_BITPOS>(data, value.0 as u16);
+        }
+        if let Some(value) = self.scaling {
+            data = set_bits::<SCALING_BITMASK, SCALING_BITPOS>(data, value.0 as u16);
+        }
+        data
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl RangeFilter {
+    #[inline(always)]
+    fn contains(&self, value: f32) -> bool {
+        self.min <= value && value <= self.max
+    }
+}
+// ----------------------------------------------------------------------------
+impl NoiseFilter {
+    #[inline(always)]
+    fn matches(&self, pos: Vec2) -> bool {
+        value_noise(pos / self.scale.max(0.01), self.seed) < self.coverage
+    }
+}
+// ----------------------------------------------------------------------------
+impl<'a> BandInput<'a> {
+    // ------------------------------------------------------------------------
+    fn new(
+        config: &TerrainConfig,
+        heightmap: &TerrainHeightMap,
+        normals: &TerrainNormals,
+        rules: &'a [AutoTexturingRule],
+        band: Rectangle,
+        control: &'a [u16],
+    ) -> Self {
+        let map_size = UVec2::splat(config.map_size());
+        let min = uvec2(band.pos.x.saturating_sub(1), band.pos.y.saturating_sub(1));
+        let max = (band.pos + band.size + UVec2::ONE).min(map_size);
+        let heights_rectangle = Rectangle {
+            pos: min,
+            size: max - min,
+        };
+
+        Self {
+            rules,
+            control,
+            heights: heightmap.extract(&heights_rectangle),
+            heights_rectangle,
+            normals: normals.extract(&band),
+            band,
+            map_resolution: config.resolution(),
+            min_height: config.min_height(),
+            height_scaling: config.height_scaling(),
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// height in meters at map position (clamped to available heights)
+    #[inline(always)]
+    fn height(&self, x: i64, y: i64) -> f32 {
+        let r = &self.heights_rectangle;
+        let x = (x - r.pos.x as i64).clamp(0, r.size.x as i64 - 1) as usize;
+        let y = (y - r.pos.y as i64).clamp(0, r.size.y as i64 - 1) as usize;
+        let value = self.heights[y * r.size.x as usize + x];
+
+        self.min_height + value as f32 * self.height_scaling
+    }
+    // ------------------------------------------------------------------------
+    fn generate(&self, first_row: u32, rows: u32) -> Vec<u16> {
+        let width = self.band.size.x as usize;
+        let mut result = Vec::with_capacity(width * rows as usize);
+
+        for y in first_row..first_row + rows {
+            for x in 0..self.band.size.x {
+                let offset = y as usize * width + x as usize;
+                let (map_x, map_y) = ((self.band.pos.x + x) as i64, (self.band.pos.y + y) as i64);
+
+                let height = self.height(map_x, map_y);
+                let neighbors = self.height(map_x - 1, map_y)
+                    + self.height(map_x + 1, map_y)
+                    + self.height(map_x, map_y - 1)
+                    + self.height(map_x, map_y + 1);
+
+                let normal_y = TerrainNormals::unpack_y(self.normals[offset]);
+
+                let sample = Sample {
+                    height,
+                    slope: normal_y.clamp(-1.0, 1.0).acos().to_degrees(),
+                    curvature: neighbors / 4.0 - height,
+                    pos: Vec2::new(map_x as f32, map_y as f32) * self.map_resolution,
+                };
+
+                let value = self
+                    .rules
+                    .iter()
+                    .filter(|rule| rule.enabled && rule.matches(&sample))
+                    .fold(self.control[offset], |value, rule| rule.apply(value));
+
+                result.push(value);
+            }
+        }
+        result
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+// Default
+// ----------------------------------------------------------------------------
+impl Default for AutoTexturingRule {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            height: None,
+            slope: Some(RangeFilter {
+                min: 0.0,
+                max: 30.0,
+            }),
+            curvature: None,
+            noise: None,
+            overlay: Some(MaterialSlot::from(0)),
+            background: None,
+            slope_blend: None,
+            scaling: None,
+        }
+    }
+}
+// ----------------------------------------------------------------------------
@@ -0,0 +1,193 @@
+// ----------------------------------------------------------------------------
+// brush shapes: weighted masks with falloff and alpha-mask stamps
+// ----------------------------------------------------------------------------
+use std::sync::Arc;
+
+use glam::{Mat2, Vec2};
+// ----------------------------------------------------------------------------
+#[derive(Clone, Debug)]
+pub enum BrushShape {
+    Round(BrushFalloff),
+    /// grayscale alpha mask covering a square with brush radius as half
+    /// side length (before scaling)
+    Stamp(Arc<BrushStamp>, StampTransform),
+}
+// ----------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FalloffCurve {
+    Hard,
+    Linear,
+    Smooth,
+    Gaussian,
+}
+// ----------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug)]
+pub struct BrushFalloff {
+    pub curve: FalloffCurve,
+    /// fraction (0..1) of radius with full weight
+    pub hardness: f32,
+}
+// ----------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug)]
+pub struct StampTransform {
+    /// rotation in degrees (clockwise)
+    pub rotation: f32,
+    /// scale relative to brush size
+    pub scale: f32,
+}
+// ----------------------------------------------------------------------------
+pub struct BrushStamp {
+    name: String,
+    width: u32,
+    height: u32,
+    /// normalized (0..1) weights
+    data: Vec<f32>,
+}
+// ----------------------------------------------------------------------------
+impl BrushShape {
+    // ------------------------------------------------------------------------
+    /// max distance (relative to brush radius) with non zero weight
+    pub(super) fn extent(&self) -> f32 {
+        match self {
+            BrushShape::Round(_) => 1.0,
+            // rotated square
+            BrushShape::Stamp(_, transform) => transform.scale * std::f32::consts::SQRT_2,
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// weight (0..1) for offset from brush center relative to brush radius
+    #[inline(always)]
+    pub(super) fn weight(&self, offset: Vec2) -> f32 {
+        match self {
+            BrushShape::Round(falloff) => falloff.weight(offset.length()),
+            BrushShape::Stamp(stamp, transform) => {
+                let scale = transform.scale.max(0.01);
+                // inverse transformation from brush space into stamp space
+                let offset = Mat2::from_angle(-transform.rotation.to_radians()) * offset / scale;
+                stamp.sample(offset)
+            }
+        }
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl BrushFalloff {
+    // ------------------------------------------------------------------------
+    #[inline(always)]
+    fn weight(&self, distance: f32) -> f32 {
+        use FalloffCurve::*;
+
+        if distance >= 1.0 {
+            return 0.0;
+        }
+        let hardness = self.hardness.clamp(0.0, 1.0);
+        if self.curve == Hard || distance <= hardness {
+            return 1.0;
+        }
+        // relative position in falloff ring
+        let t = ((distance - hardness) / (1.0 - hardness)).clamp(0.0, 1.0);
+        match self.curve {
+            Hard => 1.0,
+            Linear => 1.0 - t,
+            Smooth => 1.0 - t * t * (3.0 - 2.0 * t),
+            // ~0.01 at the border
+            Gaussian => (-4.5 * t * t).exp(),
+        }
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl BrushStamp {
+    // ------------------------------------------------------------------------
+    /// loads grayscale (or any other format which is converted into grayscale)
+    /// png as brush mask. white is full weight.
+    pub fn load(filepath: &str) -> Result<Self, String> {
+        let img = image::open(filepath)
+            .map_err(|e| format!("failed to load brush stamp {}: {}", filepath, e))?
+            .into_luma8();
+
+        if img.width() == 0 || img.height() == 0 {
+            return Err(format!("brush stamp {} is empty", filepath));
+        }
+
+        let name = std::path::Path::new(filepath)
+            .file_stem()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| filepath.to_string());
+
+        Ok(Self {
+            name,
+            width: img.width(),
+            height: img.height(),
+            data: img.as_raw().iter().map(|v| *v as f32 / 255.0).collect(),
+        })
+    }
+    // ------------------------------------------------------------------------
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    // ------------------------------------------------------------------------
+    /// bilinear sampled weight. pos is in -1..1 range (outside is zero).
+    #[inline(always)]
+    fn sample(&self, pos: Vec2) -> f32 {
+        if pos.x.abs() >= 1.0 || pos.y.abs() >= 1.0 {
+            return 0.0;
+        }
+        let (w, h) = (self.width as usize, self.height as usize);
+
+        // texel centers
+        let x = ((pos.x + 1.0) * 0.5 * w as f32 - 0.5).clamp(0.0, (w - 1) as f32);
+        let y = ((pos.y + 1.0) * 0.5 * h as f32 - 0.5).clamp(0.0, (h - 1) as f32);
+
+        let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+        let (x1, y1) = ((x0 + 1).min(w - 1), (y0 + 1).min(h - 1));
+        let (fx, fy) = (x.fract(), y.fract());
+
+        let top = self.data[y0 * w + x0] * (1.0 - fx) + self.data[y0 * w + x1] * fx;
+        let bottom = self.data[y1 * w + x0] * (1.0 - fx) + self.data[y1 * w + x1] * fx;
+
+        top * (1.0 - fy) + bottom * fy
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+// Default impl
+// ----------------------------------------------------------------------------
+impl Default for BrushShape {
+    fn default() -> Self {
+        Self::Round(BrushFalloff::default())
+    }
+}
+// ----------------------------------------------------------------------------
+impl Default for BrushFalloff {
+    fn default() -> Self {
+        Self {
+            curve: FalloffCurve::Hard,
+            hardness: 0.5,
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+impl Default for StampTransform {
+    fn default() -> Self {
+        Self {
+            rotation: 0.0,
+            scale: 1.0,
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+// fmt
+// ----------------------------------------------------------------------------
+use std::fmt;
+
+impl fmt::Debug for BrushStamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "BrushStamp({} {}x{})",
+            self.name, self.width, self.height
+        )
+    }
+}
+// ----------------------------------------------------------------------------
@@ -0,0 +1,193 @@
+// ----------------------------------------------------------------------------
+// per stroke bookkeeping of changed datapoints: overlapping brush placements
+// (interpolated dabs) of one stroke must not apply randomized commands to the
+// same datapoint more than once.
+// ----------------------------------------------------------------------------
+use rand::Rng;
+
+use bevy::math::uvec2;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::clipmap::Rectangle;
+
+use super::random::PaintRng;
+use super::StrokeId;
+// ----------------------------------------------------------------------------
+/// datapoints are tracked in sparse square chunks
+const CHUNK_SIZE: u32 = 64;
+/// marks a datapoint as changed within the current stroke
+const CHANGED: f32 = f32::INFINITY;
+// ----------------------------------------------------------------------------
+#[derive(Default)]
+pub(super) struct StrokeCoverage {
+    stroke: Option<StrokeId>,
+    /// highest brush weight offered to every datapoint in the current stroke
+    /// (or CHANGED)
+    chunks: HashMap<UVec2, Box<[f32]>>,
+}
+// ----------------------------------------------------------------------------
+impl StrokeCoverage {
+    // ------------------------------------------------------------------------
+    /// restricts the brush weights of randomized commands so every datapoint
+    /// is changed at most with its highest weight of the stroke:
+    /// - if the weights are probabilities for the commands a datapoint with a
+    ///   higher weight than in previous placements of the stroke is changed
+    ///   with the conditional probability of not having been changed before
+    ///   (weight is set to 1.0). overall a datapoint is changed with its
+    ///   highest weight as probability (like with a single placement).
+    /// - otherwise the weights are blending strengths: only the part of the
+    ///   weight that exceeds the highest weight of previous placements is
+    ///   applied (no random dropping of datapoints), so the accumulated
+    ///   blending of the stroke equals the highest weight.
+    ///
+    /// all other weights are set to zero.
+    pub(super) fn restrict(
+        &mut self,
+        stroke: StrokeId,
+        rng: &mut PaintRng,
+        rectangle: &Rectangle,
+        mask: &mut [f32],
+        weights_are_probabilities: bool,
+    ) {
+        if self.stroke != Some(stroke) {
+            self.stroke = Some(stroke);
+            self.chunks.clear();
+        }
+        let width = rectangle.size.x as usize;
+        for (i, weight) in mask.iter_mut().enumerate() {
+            if *weight <= 0.0 {
+                continue;
+            }
+            let pos = rectangle.pos + uvec2((i % width) as u32, (i / width) as u32);
+            let offered = self.offered_weight(pos);
+
+            let new_weight = weight.min(1.0);
+            if *offered >= new_weight {
+                // includes already changed datapoints
+                *weight = 0.0;
+                continue;
+            }
+            // offered is always below 1.0: a weight of 1.0 changes datapoint
+            let remaining = (new_weight - *offered) / (1.0 - *offered);
+
+            if !weights_are_probabilities {
+                *offered = new_weight;
+                *weight = remaining;
+            } else if remaining >= 1.0 || rng.gen::<f32>() < remaining {
+                *offered = CHANGED;
+                *weight = 1.0;
+            } else {
+                *offered = new_weight;
+                *weight = 0.0;
+            }
+        }
+    }
+    // ------------------------------------------------------------------------
+    #[inline(always)]
+    fn offered_weight(&mut self, pos: UVec2) -> &mut f32 {
+        let chunk = self
+            .chunks
+            .entry(pos / CHUNK_SIZE)
+            .or_insert_with(|| vec![0.0; (CHUNK_SIZE * CHUNK_SIZE) as usize].into_boxed_slice());
+
+        let offset = pos % CHUNK_SIZE;
+        &mut chunk[(offset.y * CHUNK_SIZE + offset.x) as usize]
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+
+    const SIZE: u32 = 256;
+
+    fn rectangle() -> Rectangle {
+        Rectangle {
+            pos: uvec2(100, 100),
+            size: uvec2(SIZE, SIZE),
+        }
+    }
+
+    /// applies placements with constant weight and returns the change count
+    /// per datapoint
+    fn changes(weights: &[f32]) -> Vec<u32> {
+        let mut coverage = StrokeCoverage::default();
+        let mut rng = PaintRng::seed_from_u64(42);
+        let mut changes = vec![0; (SIZE * SIZE) as usize];
+
+        for weight in weights {
+            let mut mask = vec![*weight; changes.len()];
+            coverage.restrict(StrokeId(1), &mut rng, &rectangle(), &mut mask, true);
+            for (count, changed) in changes.iter_mut().zip(mask.iter()) {
+                if *changed > 0.0 {
+                    assert_eq!(*changed, 1.0);
+                    *count += 1;
+                }
+            }
+        }
+        changes
+    }
+
+    fn changed_ratio(changes: &[u32]) -> f32 {
+        changes.iter().filter(|c| **c > 0).count() as f32 / changes.len() as f32
+    }
+
+    #[test]
+    fn datapoints_change_with_highest_weight_as_probability() {
+        for weights in [&[0.3][..], &[0.2, 0.5, 0.5, 0.8, 0.4], &[0.1; 10]] {
+            let changes = changes(weights);
+            let highest = weights.iter().copied().fold(0.0, f32::max);
+
+            assert!(changes.iter().all(|c| *c <= 1), "{:?}", weights);
+            assert!(
+                (changed_ratio(&changes) - highest).abs() < 0.01,
+                "{:?}: {}",
+                weights,
+                changed_ratio(&changes)
+            );
+        }
+    }
+
+    #[test]
+    fn full_weight_changes_every_datapoint_once() {
+        let changes = changes(&[0.5, 1.0, 1.0]);
+        assert!(changes.iter().all(|c| *c == 1));
+    }
+
+    #[test]
+    fn blending_strengths_accumulate_to_highest_weight() {
+        let mut coverage = StrokeCoverage::default();
+        let mut rng = PaintRng::seed_from_u64(42);
+        // blending of a constant target value: accumulated blend factor
+        let mut blended = vec![0.0f32; (SIZE * SIZE) as usize];
+
+        for weight in [0.25, 0.75, 0.5, 0.75] {
+            let mut mask = vec![weight; blended.len()];
+            coverage.restrict(StrokeId(1), &mut rng, &rectangle(), &mut mask, false);
+            for (b, w) in blended.iter_mut().zip(mask.iter()) {
+                *b += (1.0 - *b) * w;
+            }
+        }
+        // every datapoint is blended (no random drops) with the highest weight
+        assert!(blended.iter().all(|b| (b - 0.75).abs() < 1e-5));
+    }
+
+    #[test]
+    fn new_stroke_resets_coverage() {
+        let mut coverage = StrokeCoverage::default();
+        let mut rng = PaintRng::seed_from_u64(42);
+
+        let mut mask = vec![1.0; (SIZE * SIZE) as usize];
+        coverage.restrict(StrokeId(1), &mut rng, &rectangle(), &mut mask, true);
+        coverage.restrict(StrokeId(1), &mut rng, &rectangle(), &mut mask, true);
+        assert!(mask.iter().all(|w| *w == 0.0));
+
+        let mut mask = vec![1.0; (SIZE * SIZE) as usize];
+        coverage.restrict(StrokeId(2), &mut rng, &rectangle(), &mut mask, true);
+        assert!(mask.iter().all(|w| *w == 1.0));
+    }
+}
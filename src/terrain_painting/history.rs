@@ -0,0 +1,612 @@
+// ----------------------------------------------------------------------------
+use std::collections::VecDeque;
+
+use bevy::math::{uvec2, UVec2};
+use bevy::utils::HashMap;
+use tracing::warn;
+
+use crate::clipmap::Rectangle;
+use crate::heightmap::{HeightmapChanges, TerrainHeightMap};
+use crate::terrain_clipmap::{HeightmapClipmap, TextureControlClipmap, TintClipmap};
+use crate::texturecontrol::TerrainHoleChanges;
+
+use super::{is_terrain_hole, StrokeId};
+// ----------------------------------------------------------------------------
+/// max number of undo steps kept in history
+const MAX_HISTORY_STEPS: usize = 100;
+/// max memory (in bytes) of all stored undo + redo data
+const MAX_HISTORY_MEMORY: usize = 256 * 1024 * 1024;
+/// changed datapoints are stored in sparse square chunks
+const CHUNK_SIZE: u32 = 64;
+// ----------------------------------------------------------------------------
+/// bounded undo/redo history of texture control, tint map and heightmap changes.
+/// only the data *before* the first change of a datapoint within a step is
+/// stored.
+pub struct PaintingHistory {
+    undo: VecDeque<HistoryStep>,
+    redo: Vec<HistoryStep>,
+    /// step of currently running stroke (not yet pushed to undo stack)
+    current: Option<HistoryStep>,
+    /// memory of all undo, redo and current steps
+    memory: usize,
+    max_steps: usize,
+    max_memory: usize,
+}
+// ----------------------------------------------------------------------------
+#[derive(Default)]
+/// all changed chunks with the data *before* the change
+pub(super) struct HistoryStep {
+    stroke: Option<StrokeId>,
+    texture_control: HashMap<UVec2, Patch<u16>>,
+    tint: HashMap<UVec2, Patch<u8>>,
+    heightmap: HashMap<UVec2, Patch<u16>>,
+    memory: usize,
+}
+// ----------------------------------------------------------------------------
+/// stored datapoints of one chunk
+struct Patch<T> {
+    /// bounding box of stored datapoints (map coordinates)
+    bounds: Rectangle,
+    /// one bit per stored datapoint (one word per chunk row)
+    stored: [u64; CHUNK_SIZE as usize],
+    /// chunk data (channels per datapoint), only valid for stored datapoints
+    data: Box<[T]>,
+}
+// ----------------------------------------------------------------------------
+impl PaintingHistory {
+    // ------------------------------------------------------------------------
+    /// stores texture control rectangle data before it is changed.
+    pub(super) fn record_texture_control(
+        &mut self,
+        stroke: StrokeId,
+        rectangle: &Rectangle,
+        data: &[u16],
+    ) {
+        self.record(stroke, rectangle, |step| {
+            step.record_texture_control(rectangle, data)
+        });
+    }
+    // ------------------------------------------------------------------------
+    /// stores tint rectangle data before it is changed.
+    pub(super) fn record_tint(&mut self, stroke: StrokeId, rectangle: &Rectangle, data: &[u8]) {
+        self.record(stroke, rectangle, |step| step.record_tint(rectangle, data));
+    }
+    // ------------------------------------------------------------------------
+    /// stores heightmap rectangle data before it is changed.
+    pub(super) fn record_heightmap(
+        &mut self,
+        stroke: StrokeId,
+        rectangle: &Rectangle,
+        data: &[u16],
+    ) {
+        self.record(stroke, rectangle, |step| {
+            step.record_heightmap(rectangle, data)
+        });
+    }
+    // ------------------------------------------------------------------------
+    /// stores multiple texture control rectangles before they were changed as
+    /// one separate undo step (e.g. only changed parts of map wide operations).
+    pub(super) fn record_texture_control_patches(&mut self, patches: Vec<(Rectangle, Vec<u16>)>) {
+        self.finish_step();
+        self.clear_redo();
+
+        let mut step = HistoryStep::new(None);
+        for (rectangle, data) in &patches {
+            step.record_texture_control(rectangle, data);
+        }
+        self.push_undo(step);
+    }
+    // ------------------------------------------------------------------------
+    pub(super) fn pop_undo(&mut self) -> Option<HistoryStep> {
+        // a still running stroke is undone completely
+        self.finish_step();
+        let step = self.undo.pop_back()?;
+        self.memory -= step.memory;
+        Some(step)
+    }
+    // ------------------------------------------------------------------------
+    pub(super) fn pop_redo(&mut self) -> Option<HistoryStep> {
+        self.finish_step();
+        let step = self.redo.pop()?;
+        self.memory -= step.memory;
+        Some(step)
+    }
+    // ------------------------------------------------------------------------
+    pub(super) fn push_undo(&mut self, step: HistoryStep) {
+        if self.refuse(&step) {
+            return;
+        }
+        self.memory += step.memory;
+        self.undo.push_back(step);
+        self.enforce_limits();
+    }
+    // ------------------------------------------------------------------------
+    pub(super) fn push_redo(&mut self, step: HistoryStep) {
+        if self.refuse(&step) {
+            return;
+        }
+        self.memory += step.memory;
+        self.redo.push(step);
+        self.enforce_limits();
+    }
+    // ------------------------------------------------------------------------
+    pub(super) fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.current = None;
+        self.memory = 0;
+    }
+    // ------------------------------------------------------------------------
+    /// changes with the same stroke id are grouped into one undo step. a
+    /// stroke that exceeds the memory limit is split into multiple steps.
+    fn record(
+        &mut self,
+        stroke: StrokeId,
+        rectangle: &Rectangle,
+        record: impl FnOnce(&mut HistoryStep) -> usize,
+    ) {
+        if matches!(&self.current, Some(step) if step.stroke != Some(stroke)) {
+            self.finish_step();
+        }
+        self.clear_redo();
+
+        // upper bound of memory for the new chunks (chunks of the rectangle
+        // may already be stored)
+        let required = HistoryStep::max_memory(rectangle);
+        if required > self.max_memory {
+            warn!(
+                "painting history: change exceeds history memory limit ({} MB). history cleared.",
+                self.max_memory / (1024 * 1024)
+            );
+            self.clear();
+            return;
+        }
+        if matches!(&self.current, Some(step) if step.memory + required > self.max_memory) {
+            self.finish_step();
+        }
+
+        let step = self
+            .current
+            .get_or_insert_with(|| HistoryStep::new(Some(stroke)));
+        self.memory += record(step);
+        self.enforce_limits();
+    }
+    // ------------------------------------------------------------------------
+    /// steps larger than the memory limit cannot be stored. all previous steps
+    /// would restore an inconsistent state so the history is cleared.
+    fn refuse(&mut self, step: &HistoryStep) -> bool {
+        if step.memory > self.max_memory {
+            warn!(
+                "painting history: change ({} MB) exceeds history memory limit ({} MB). history cleared.",
+                step.memory / (1024 * 1024),
+                self.max_memory / (1024 * 1024)
+            );
+            self.clear();
+            true
+        } else {
+            false
+        }
+    }
+    // ------------------------------------------------------------------------
+    fn clear_redo(&mut self) {
+        // any new change invalidates the redo stack
+        for step in self.redo.drain(..) {
+            self.memory -= step.memory;
+        }
+    }
+    // ------------------------------------------------------------------------
+    fn finish_step(&mut self) {
+        if let Some(step) = self.current.take() {
+            // memory was already accounted for while recording
+            self.undo.push_back(step);
+            self.enforce_limits();
+        }
+    }
+    // ------------------------------------------------------------------------
+    fn enforce_limits(&mut self) {
+        while self.undo.len() > self.max_steps {
+            if let Some(step) = self.undo.pop_front() {
+                self.memory -= step.memory;
+            }
+        }
+        // drop oldest undo steps first, then the most distant redo steps.
+        // the running stroke is never dropped (it is split while recording).
+        while self.memory > self.max_memory {
+            let step = match self.undo.pop_front() {
+                Some(step) => step,
+                None if !self.redo.is_empty() => self.redo.remove(0),
+                None => break,
+            };
+            self.memory -= step.memory;
+        }
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl HistoryStep {
+    // ------------------------------------------------------------------------
+    fn new(stroke: Option<StrokeId>) -> Self {
+        Self {
+            stroke,
+            ..Default::default()
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// max memory required for storing all chunks of a rectangle (of the
+    /// largest data type)
+    fn max_memory(rectangle: &Rectangle) -> usize {
+        let first = rectangle.pos / CHUNK_SIZE;
+        let last = (rectangle.pos + rectangle.size - UVec2::ONE) / CHUNK_SIZE;
+        let chunks = ((last.x - first.x + 1) * (last.y - first.y + 1)) as usize;
+
+        chunks * Patch::<u8>::memory(4)
+    }
+    // ------------------------------------------------------------------------
+    /// returns additionally required memory
+    fn record_texture_control(&mut self, rectangle: &Rectangle, data: &[u16]) -> usize {
+        let memory = Patch::record(&mut self.texture_control, rectangle, data, 1);
+        self.memory += memory;
+        memory
+    }
+    // ------------------------------------------------------------------------
+    /// returns additionally required memory
+    fn record_tint(&mut self, rectangle: &Rectangle, data: &[u8]) -> usize {
+        let memory = Patch::record(&mut self.tint, rectangle, data, 4);
+        self.memory += memory;
+        memory
+    }
+    // ------------------------------------------------------------------------
+    /// returns additionally required memory
+    fn record_heightmap(&mut self, rectangle: &Rectangle, data: &[u16]) -> usize {
+        let memory = Patch::record(&mut self.heightmap, rectangle, data, 1);
+        self.memory += memory;
+        memory
+    }
+    // ------------------------------------------------------------------------
+    pub(super) fn changes_heightmap(&self) -> bool {
+        !self.heightmap.is_empty()
+    }
+    // ------------------------------------------------------------------------
+    /// restores stored data and returns the reverse step (containing the data
+    /// before restoring).
+    pub(super) fn restore(
+        mut self,
+        texture_clipmap: &mut TextureControlClipmap,
+        tint_clipmap: &mut TintClipmap,
+        heightmap_clipmap: &mut HeightmapClipmap,
+        heightmap: &mut TerrainHeightMap,
+        heightmap_changes: &mut HeightmapChanges,
+        hole_changes: &mut TerrainHoleChanges,
+    ) -> HistoryStep {
+        // every datapoint is stored at most once per step: restoring swaps
+        // stored and current data so the step becomes its own reverse step
+        for patch in self.texture_control.values_mut() {
+            let mut current = texture_clipmap.extract_fullres(&patch.bounds);
+            let mut holes = false;
+            patch.swap(&mut current, 1, |stored, current| {
+                holes |= is_terrain_hole(*stored) || is_terrain_hole(*current);
+            });
+            // meshes must be regenerated if holes are added or removed
+            if holes {
+                hole_changes.add(&patch.bounds);
+            }
+            texture_clipmap.update_fullres(&patch.bounds, &current);
+        }
+        for patch in self.tint.values_mut() {
+            let mut current = tint_clipmap.extract_fullres(&patch.bounds);
+            patch.swap(&mut current, 4, |_, _| {});
+            tint_clipmap.update_fullres(&patch.bounds, &current);
+        }
+        for patch in self.heightmap.values_mut() {
+            let mut current = heightmap_clipmap.extract_fullres(&patch.bounds);
+            patch.swap(&mut current, 1, |_, _| {});
+            heightmap_clipmap.update_fullres(&patch.bounds, &current);
+            heightmap.update_rectangle(&patch.bounds, &current);
+            heightmap_changes.add(&patch.bounds);
+        }
+        self.stroke = None;
+        self
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl<T: Copy + Default> Patch<T> {
+    // ------------------------------------------------------------------------
+    fn new(chunk: UVec2, channels: usize) -> Self {
+        Self {
+            bounds: Rectangle {
+                pos: chunk * CHUNK_SIZE,
+                size: UVec2::ZERO,
+            },
+            stored: [0; CHUNK_SIZE as usize],
+            data: vec![T::default(); (CHUNK_SIZE * CHUNK_SIZE) as usize * channels]
+                .into_boxed_slice(),
+        }
+    }
+    // ------------------------------------------------------------------------
+    fn memory(channels: usize) -> usize {
+        std::mem::size_of::<Self>()
+            + (CHUNK_SIZE * CHUNK_SIZE) as usize * channels * std::mem::size_of::<T>()
+    }
+    // ------------------------------------------------------------------------
+    /// stores all datapoints of the rectangle that are not stored yet and
+    /// returns memory of newly created chunks
+    fn record(
+        patches: &mut HashMap<UVec2, Patch<T>>,
+        rectangle: &Rectangle,
+        data: &[T],
+        channels: usize,
+    ) -> usize {
+        let first = rectangle.pos / CHUNK_SIZE;
+        let last = (rectangle.pos + rectangle.size - UVec2::ONE) / CHUNK_SIZE;
+        let width = rectangle.size.x as usize;
+
+        let mut memory = 0;
+        for chunk_y in first.y..=last.y {
+            for chunk_x in first.x..=last.x {
+                let chunk = uvec2(chunk_x, chunk_y);
+                let patch = patches.entry(chunk).or_insert_with(|| {
+                    memory += Self::memory(channels);
+                    Self::new(chunk, channels)
+                });
+                let origin = chunk * CHUNK_SIZE;
+
+                // intersection of chunk and rectangle
+                let min = rectangle.pos.max(origin);
+                let max = (rectangle.pos + rectangle.size).min(origin + UVec2::splat(CHUNK_SIZE));
+
+                for y in min.y..max.y {
+                    let row = &mut patch.stored[(y - origin.y) as usize];
+                    for x in min.x..max.x {
+                        let bit = 1u64 << (x - origin.x);
+                        if *row & bit != 0 {
+                            continue;
+                        }
+                        *row |= bit;
+
+                        let src = ((y - rectangle.pos.y) as usize * width
+                            + (x - rectangle.pos.x) as usize)
+                            * channels;
+                        let dst =
+                            ((y - origin.y) * CHUNK_SIZE + (x - origin.x)) as usize * channels;
+                        patch.data[dst..dst + channels].copy_from_slice(&data[src..src + channels]);
+                    }
+                }
+                patch.extend_bounds(min, max);
+            }
+        }
+        memory
+    }
+    // ------------------------------------------------------------------------
+    fn extend_bounds(&mut self, min: UVec2, max: UVec2) {
+        let bounds = &mut self.bounds;
+        let (min, max) = if bounds.size == UVec2::ZERO {
+            (min, max)
+        } else {
+            (min.min(bounds.pos), max.max(bounds.pos + bounds.size))
+        };
+        bounds.pos = min;
+        bounds.size = max - min;
+    }
+    // ------------------------------------------------------------------------
+    /// swaps stored datapoints with the data of the bounds rectangle
+    fn swap(&mut self, current: &mut [T], channels: usize, mut inspect: impl FnMut(&T, &T)) {
+        let origin = self.bounds.pos - self.bounds.pos % CHUNK_SIZE;
+        let (pos, size) = (self.bounds.pos, self.bounds.size);
+
+        for y in pos.y..pos.y + size.y {
+            let row = self.stored[(y - origin.y) as usize];
+            for x in pos.x..pos.x + size.x {
+                if row & (1u64 << (x - origin.x)) == 0 {
+                    continue;
+                }
+                let src = ((y - origin.y) * CHUNK_SIZE + (x - origin.x)) as usize * channels;
+                let dst = ((y - pos.y) * size.x + (x - pos.x)) as usize * channels;
+
+                for c in 0..channels {
+                    inspect(&self.data[src + c], &current[dst + c]);
+                    std::mem::swap(&mut self.data[src + c], &mut current[dst + c]);
+                }
+            }
+        }
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+// Default impl
+// ----------------------------------------------------------------------------
+impl Default for PaintingHistory {
+    fn default() -> Self {
+        Self {
+            undo: VecDeque::default(),
+            redo: Vec::default(),
+            current: None,
+            memory: 0,
+            max_steps: MAX_HISTORY_STEPS,
+            max_memory: MAX_HISTORY_MEMORY,
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rectangle(x: u32, y: u32, size: u32) -> Rectangle {
+        Rectangle {
+            pos: uvec2(x, y),
+            size: uvec2(size, size),
+        }
+    }
+
+    fn patches(step: &HistoryStep) -> usize {
+        step.texture_control.len() + step.tint.len() + step.heightmap.len()
+    }
+
+    /// memory of one chunk of texture control data
+    fn chunk() -> usize {
+        Patch::<u16>::memory(1)
+    }
+
+    fn with_limits(max_steps: usize, max_memory: usize) -> PaintingHistory {
+        PaintingHistory {
+            max_steps,
+            max_memory,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn changes_of_one_stroke_are_one_step() {
+        let mut history = PaintingHistory::default();
+
+        for _ in 0..3 {
+            history.record_texture_control(StrokeId(1), &rectangle(0, 0, 2), &[0; 4]);
+        }
+        history.record_tint(StrokeId(2), &rectangle(0, 0, 2), &[0; 16]);
+        history.record_heightmap(StrokeId(2), &rectangle(0, 0, 2), &[0; 4]);
+
+        // running stroke is finished by undo
+        let step = history.pop_undo().unwrap();
+        assert_eq!(step.stroke, Some(StrokeId(2)));
+        assert_eq!(patches(&step), 2);
+        assert!(step.changes_heightmap());
+
+        let step = history.pop_undo().unwrap();
+        assert_eq!(step.stroke, Some(StrokeId(1)));
+        assert_eq!(patches(&step), 1);
+        assert_eq!(step.memory, chunk());
+        assert!(!step.changes_heightmap());
+
+        assert!(history.pop_undo().is_none());
+        assert_eq!(history.memory, 0);
+    }
+
+    #[test]
+    fn only_first_change_of_datapoints_is_stored() {
+        let mut step = HistoryStep::new(None);
+
+        // overlapping rectangles spanning two chunks
+        step.record_texture_control(&rectangle(62, 0, 2), &[1; 4]);
+        step.record_texture_control(&rectangle(63, 0, 2), &[2; 4]);
+        assert_eq!(step.memory, 2 * chunk());
+
+        let first = &step.texture_control[&uvec2(0, 0)];
+        assert_eq!(first.bounds.pos, uvec2(62, 0));
+        assert_eq!(first.bounds.size, uvec2(2, 2));
+
+        let second = step.texture_control.get_mut(&uvec2(1, 0)).unwrap();
+        assert_eq!(second.bounds.pos, uvec2(64, 0));
+        assert_eq!(second.bounds.size, uvec2(1, 2));
+
+        let mut current = vec![7; 2];
+        second.swap(&mut current, 1, |_, _| {});
+        assert_eq!(current, vec![2, 2]);
+
+        // restoring the first chunk keeps the first recorded value (63, 0)
+        let first = step.texture_control.get_mut(&uvec2(0, 0)).unwrap();
+        let mut current = vec![7; 4];
+        first.swap(&mut current, 1, |_, _| {});
+        assert_eq!(current, vec![1; 4]);
+    }
+
+    #[test]
+    fn new_changes_clear_redo() {
+        let mut history = PaintingHistory::default();
+
+        history.record_texture_control(StrokeId(1), &rectangle(0, 0, 2), &[0; 4]);
+        let step = history.pop_undo().unwrap();
+        history.push_redo(step);
+        assert_eq!(history.memory, chunk());
+
+        history.record_texture_control(StrokeId(2), &rectangle(0, 0, 2), &[0; 4]);
+        assert!(history.pop_redo().is_none());
+        assert_eq!(history.memory, chunk());
+    }
+
+    #[test]
+    fn oldest_steps_are_dropped_above_step_limit() {
+        let mut history = PaintingHistory::default();
+
+        for i in 0..MAX_HISTORY_STEPS + 10 {
+            history.record_texture_control(StrokeId(i as u32), &rectangle(0, 0, 2), &[0; 4]);
+        }
+        history.finish_step();
+
+        assert_eq!(history.undo.len(), MAX_HISTORY_STEPS);
+        assert_eq!(history.undo.front().unwrap().stroke, Some(StrokeId(10)));
+        assert_eq!(history.memory, MAX_HISTORY_STEPS * chunk());
+    }
+
+    /// step with changes in the first chunks of the first chunk row
+    fn step(chunks: u32) -> HistoryStep {
+        let mut step = HistoryStep::new(None);
+        let rectangle = Rectangle {
+            pos: uvec2(0, 0),
+            size: uvec2(chunks * CHUNK_SIZE, 1),
+        };
+        step.record_texture_control(&rectangle, &vec![0; (chunks * CHUNK_SIZE) as usize]);
+        step
+    }
+
+    #[test]
+    fn memory_limit_is_never_exceeded() {
+        let mut history = with_limits(MAX_HISTORY_STEPS, 3 * chunk());
+
+        for i in 0..5 {
+            history.record_texture_control_patches(vec![(rectangle(i * 64, 0, 2), vec![0; 4])]);
+            assert!(history.memory <= 3 * chunk());
+        }
+        assert_eq!(history.undo.len(), 3);
+
+        // redo steps are limited, too: oldest undo steps are dropped first...
+        history.pop_undo().unwrap();
+        history.push_redo(step(2));
+        assert_eq!((history.undo.len(), history.redo.len()), (1, 1));
+        assert_eq!(history.memory, 3 * chunk());
+
+        // ...followed by the most distant redo steps
+        history.clear();
+        history.push_redo(step(2));
+        history.push_redo(step(1));
+        history.push_redo(step(1));
+        assert_eq!(history.redo.len(), 2);
+        assert_eq!(history.memory, 2 * chunk());
+
+        // steps larger than the limit are refused and clear the history
+        history.push_undo(step(4));
+        assert!(history.undo.is_empty() && history.redo.is_empty());
+        assert_eq!(history.memory, 0);
+
+        history.record_texture_control_patches(vec![(rectangle(0, 0, 64), vec![0; 64 * 64])]);
+        history.record_texture_control_patches(vec![(rectangle(0, 0, 256), vec![0; 256 * 256])]);
+        assert!(history.undo.is_empty());
+        assert_eq!(history.memory, 0);
+    }
+
+    #[test]
+    fn strokes_above_memory_limit_are_split() {
+        let mut history = with_limits(MAX_HISTORY_STEPS, 2 * Patch::<u8>::memory(4));
+
+        // every placement touches a new chunk
+        for i in 0..5 {
+            history.record_texture_control(StrokeId(1), &rectangle(i * 64, 0, 2), &[0; 4]);
+            assert!(history.memory <= 2 * Patch::<u8>::memory(4));
+        }
+        history.finish_step();
+        // only the latest parts of the stroke are kept
+        assert!(!history.undo.is_empty());
+        assert!(history
+            .undo
+            .iter()
+            .all(|step| step.stroke == Some(StrokeId(1))));
+        let stored = history.undo.iter().map(patches).sum::<usize>();
+        assert!((1..5).contains(&stored));
+        assert!(history
+            .undo
+            .back()
+            .unwrap()
+            .texture_control
+            .contains_key(&uvec2(4, 0)));
+    }
+}
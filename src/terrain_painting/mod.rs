@@ -1,38 +1,141 @@
 // ----------------------------------------------------------------------------
-use rand::{thread_rng, Rng};
+use rand::{Rng, SeedableRng};
 
-use bevy::math::{uvec2, vec2};
-use bevy::{ecs::schedule::StateData, prelude::*};
+use glam::{uvec2, vec2, Vec2};
 
 use crate::config::TerrainConfig;
-use crate::terrain_clipmap::{ClipmapTracker, TextureControlClipmap};
 use crate::terrain_material::MaterialSlot;
 
 use crate::clipmap::Rectangle;
+
+use random::PaintRng;
+
+pub use brush::{BrushFalloff, BrushShape, BrushStamp, FalloffCurve, StampTransform};
+pub use random::PaintingRng;
+pub use remapping::{MaterialRemapStats, MaterialRemapTable, RemapTarget};
+pub use usage::MaterialUsage;
+
+pub(crate) use random::RngStream;
+pub(crate) use remapping::remap_texture_control;
+
+#[cfg(feature = "editor")]
+use {
+    bevy::{ecs::schedule::StateData, prelude::*},
+    coverage::StrokeCoverage,
+    history::PaintingHistory,
+};
+
+#[cfg(feature = "editor")]
+use crate::{
+    heightmap::{HeightmapChanges, TerrainHeightMap},
+    terrain_clipmap::{
+        ClipmapTracker, HeightmapClipmap, TerrainShadowsClipmap, TextureControlClipmap, TintClipmap,
+    },
+    texturecontrol::TerrainHoleChanges,
+};
+
+#[cfg(feature = "editor")]
+use {
+    autotexturing::AutoTexturing,
+    remapping::MaterialRemapping,
+    selection::{PaintingSelection, SelectionClipboard},
+};
+
+#[cfg(feature = "editor")]
+pub use {
+    autotexturing::{AutoTexturingEvent, AutoTexturingRule, NoiseFilter, RangeFilter},
+    remapping::{MaterialRemapEvent, MaterialRemapResultEvent},
+    selection::{ClipboardTransform, SelectionEvent, SelectionResultEvent, SelectionShape},
+    usage::{MaterialUsageEvent, MaterialUsageResultEvent},
+};
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 pub struct TerrainPaintingPlugin;
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+#[derive(Debug, Clone, Hash, Eq, PartialEq, SystemLabel)]
+enum PaintingSystemLabel {
+    AutoTexturingEvents,
+    MaterialRemapEvents,
+}
+// ----------------------------------------------------------------------------
+mod brush;
+mod random;
+mod remapping;
+mod usage;
+
+#[cfg(feature = "editor")]
+mod autotexturing;
+#[cfg(feature = "editor")]
+mod coverage;
+#[cfg(feature = "editor")]
+mod history;
+#[cfg(feature = "editor")]
+mod selection;
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 impl TerrainPaintingPlugin {
     // ------------------------------------------------------------------------
     pub fn process_brush_operations<T: StateData>(state: T) -> SystemSet {
+        use PaintingSystemLabel::{AutoTexturingEvents, MaterialRemapEvents};
+
         SystemSet::on_update(state)
-            .with_system(process_brush_operations)
+            // an active auto texturing preview must be discarded and a running
+            // material remapping canceled before any other painting operation
+            .with_system(autotexturing::process_autotexturing_events.label(AutoTexturingEvents))
+            .with_system(autotexturing::generate_autotexturing.after(AutoTexturingEvents))
+            .with_system(
+                remapping::process_remap_events
+                    .label(MaterialRemapEvents)
+                    .after(AutoTexturingEvents),
+            )
+            .with_system(remapping::remap_materials.after(MaterialRemapEvents))
+            .with_system(process_brush_operations.after(MaterialRemapEvents))
+            .with_system(process_tint_operations.after(MaterialRemapEvents))
+            .with_system(process_sculpt_operations.after(MaterialRemapEvents))
+            .with_system(selection::process_selection_events.after(MaterialRemapEvents))
             .with_system(process_picker_operations)
+            .with_system(usage::count_material_usage)
+            .with_system(process_history_operations.after(MaterialRemapEvents))
+    }
+    // ------------------------------------------------------------------------
+    pub fn reset_data<T: StateData>(state: T) -> SystemSet {
+        SystemSet::on_enter(state)
+            .with_system(reset_history)
+            .with_system(autotexturing::reset_autotexturing)
+            .with_system(remapping::reset_remapping)
+            .with_system(selection::reset_selection)
     }
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
 // painting operations
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 #[derive(Debug)]
-pub struct PaintingEvent(BrushPlacement, Vec<PaintCommand>);
+pub struct PaintingEvent(StrokeId, BrushPlacement, Vec<PaintCommand>);
+#[cfg(feature = "editor")]
 pub struct PickerEvent(BrushPlacement, Vec<PickedType>);
+#[cfg(feature = "editor")]
 pub struct PickerResultEvent(PickerResult);
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+#[derive(Debug)]
+pub enum PaintingHistoryEvent {
+    Undo,
+    Redo,
+}
+// ----------------------------------------------------------------------------
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// all painting events with the same id (e.g. one mouse drag) are grouped
+/// into one undo step
+pub struct StrokeId(u32);
+// ----------------------------------------------------------------------------
 #[derive(Debug)]
 pub struct BrushPlacement {
     pos: Vec2,
     radius: f32,
+    shape: BrushShape,
 }
 // ----------------------------------------------------------------------------
 #[derive(Clone, Copy, Debug)]
@@ -43,12 +146,27 @@ pub struct TextureScale(pub u8);
 pub struct SlopeBlendThreshold(pub u8);
 #[derive(Clone, Copy, Debug)]
 pub struct Variance(pub u8);
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// RGB tint color. 128 is neutral (see terrain shader), lower values darken
+/// and higher values lighten the terrain texture color.
+pub struct TintColor(pub [u8; 3]);
+#[derive(Clone, Copy, Debug)]
+pub struct TintStrength(pub u8);
+#[derive(Clone, Copy, Debug)]
+pub struct TintJitter(pub u8);
+#[derive(Clone, Copy, Debug)]
+/// height change (in heightmap units) per brush application
+pub struct HeightDelta(pub u16);
+#[derive(Clone, Copy, Debug)]
+/// percentage of adjustment towards target height per brush application
+pub struct SculptStrength(pub u8);
 // ----------------------------------------------------------------------------
 pub enum PickedType {
     OverlayTexture,
     BackgroundTexture,
     SlopeBlendThreshold,
     BackgroundScaling,
+    TintColor,
 }
 // ----------------------------------------------------------------------------
 pub enum PickerResult {
@@ -56,15 +174,22 @@ pub enum PickerResult {
     BackgroundTexture(MaterialSlot),
     BlendThreshold(SlopeBlendThreshold),
     BackgroundScaling(TextureScale),
+    Tint(TintColor),
+    /// picked material is a terrain hole (no overlay or background material)
+    Hole,
 }
 // ----------------------------------------------------------------------------
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum PaintCommand {
     // textures
     SetOverlayMaterial(MaterialSlot),
     SetBackgroundMaterial(MaterialSlot),
     RandomizedSetOverlayMaterial(OverwriteProbability, MaterialSlot),
     RandomizedSetBackgroundMaterial(OverwriteProbability, MaterialSlot),
+    // holes
+    CutHole,
+    /// overlay and background material for holes (other datapoints unchanged)
+    FillHole(MaterialSlot, MaterialSlot),
     // scaling
     SetBackgroundScaling(TextureScale),
     SetBackgroundScalingWithVariance(TextureScale, Variance),
@@ -97,35 +222,77 @@ pub enum PaintCommand {
     RandomizedIncreaseSlopeBlendThresholdWithVariance(OverwriteProbability, Variance),
     RandomizedReduceSlopeBlendThreshold(OverwriteProbability),
     RandomizedReduceSlopeBlendThresholdWithVariance(OverwriteProbability, Variance),
+    // tint
+    SetTint(TintColor),
+    MultiplyTint(TintColor),
+    LightenTint(TintStrength),
+    DarkenTint(TintStrength),
+    JitterTint(TintJitter),
+    // heightmap sculpting
+    RaiseHeight(HeightDelta),
+    LowerHeight(HeightDelta),
+    SmoothHeight(SculptStrength),
+    FlattenHeight(SculptStrength),
+    NoiseHeight(HeightDelta),
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 impl Plugin for TerrainPaintingPlugin {
     // ------------------------------------------------------------------------
     fn build(&self, app: &mut App) {
-        app.add_event::<PaintingEvent>()
+        app.init_resource::<PaintingHistory>()
+            .init_resource::<PaintingRng>()
+            .init_resource::<AutoTexturing>()
+            .init_resource::<MaterialRemapping>()
+            .init_resource::<PaintingSelection>()
+            .init_resource::<SelectionClipboard>()
+            .add_event::<PaintingEvent>()
+            .add_event::<AutoTexturingEvent>()
+            .add_event::<MaterialRemapEvent>()
+            .add_event::<MaterialRemapResultEvent>()
+            .add_event::<MaterialUsageEvent>()
+            .add_event::<MaterialUsageResultEvent>()
             .add_event::<PickerEvent>()
-            .add_event::<PickerResultEvent>();
+            .add_event::<PickerResultEvent>()
+            .add_event::<SelectionEvent>()
+            .add_event::<SelectionResultEvent>()
+            .add_event::<PaintingHistoryEvent>();
     }
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
 // systems
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 fn process_picker_operations(
     config: Res<TerrainConfig>,
     texture_clipmap: Res<TextureControlClipmap>,
+    tint_clipmap: Res<TintClipmap>,
     mut picker_events: EventReader<PickerEvent>,
     mut picker_results: EventWriter<PickerResultEvent>,
 ) {
     use PickerResult::*;
 
     for PickerEvent(placement, picks) in picker_events.iter() {
-        let (rectangle, _) = calculate_region_of_interest(&*config, placement);
+        let (rectangle, _) = calculate_region_of_interest(&config, placement);
 
         let data = texture_clipmap.extract_fullres(&rectangle)[0];
 
+        // holes have no material: texture picks report the hole (once)
+        let hole = is_terrain_hole(data);
+        let texture_picked = picks.iter().any(|p| {
+            matches!(
+                p,
+                PickedType::OverlayTexture | PickedType::BackgroundTexture
+            )
+        });
+        if hole && texture_picked {
+            picker_results.send(PickerResultEvent(Hole));
+        }
+
         for target in picks {
             match target {
+                PickedType::OverlayTexture | PickedType::BackgroundTexture if hole => {}
                 PickedType::OverlayTexture => {
                     let slot =
                         pick_material::<OVERLAY_TEXTURE_BITMASK, OVERLAY_TEXTURE_BITPOS>(data);
@@ -145,21 +312,37 @@ fn process_picker_operations(
                     let value = pick_value::<SCALING_BITMASK, SCALING_BITPOS>(data);
                     picker_results.send(PickerResultEvent(BackgroundScaling(TextureScale(value))));
                 }
+                PickedType::TintColor => {
+                    let rgba = tint_clipmap.extract_fullres(&rectangle);
+                    let color = TintColor([rgba[0], rgba[1], rgba[2]]);
+                    picker_results.send(PickerResultEvent(Tint(color)));
+                }
             }
         }
     }
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+#[allow(clippy::too_many_arguments)]
 fn process_brush_operations(
     config: Res<TerrainConfig>,
     mut paint_events: EventReader<PaintingEvent>,
+    selection: Res<PaintingSelection>,
     mut texture_clipmap: ResMut<TextureControlClipmap>,
+    mut hole_changes: ResMut<TerrainHoleChanges>,
     mut clipmap_tracker: ResMut<ClipmapTracker>,
+    mut history: ResMut<PaintingHistory>,
+    mut painting_rng: ResMut<PaintingRng>,
+    mut coverage: Local<StrokeCoverage>,
 ) {
-    use PaintCommand::*;
-
-    for PaintingEvent(placement, cmds) in paint_events.iter() {
-        let (rectangle, mask) = calculate_region_of_interest(&*config, placement);
+    for PaintingEvent(stroke, placement, cmds) in paint_events.iter() {
+        // tint and sculpt commands are processed in dedicated systems
+        if !cmds.iter().any(PaintCommand::is_texture_control_cmd) {
+            continue;
+        }
+        let (rectangle, mut mask) = calculate_region_of_interest(&config, placement);
+        // only selected datapoints are changed (if there is a selection)
+        selection.restrict(&rectangle, &mut mask);
 
         // disabling cache will force clipmaptracker to always use current data
         // for clipmap generation. so it is ok to paint only on highest res data.
@@ -168,190 +351,451 @@ fn process_brush_operations(
         texture_clipmap.disable_cache();
 
         let mut data = texture_clipmap.extract_fullres(&rectangle);
-        // TODO: insert rectangle into an undo stack
-
-        for cmd in cmds {
-            match cmd {
-                // -- texturing
-                SetOverlayMaterial(slot) => {
-                    paint_texture::<OVERLAY_TEXTURE_BITMASK, OVERLAY_TEXTURE_BITPOS>(
-                        &mask, &mut data, slot,
-                    );
-                }
-                SetBackgroundMaterial(slot) => {
-                    paint_texture::<BKGRND_TEXTURE_BITMASK, BKGRND_TEXTURE_BITPOS>(
-                        &mask, &mut data, slot,
-                    );
-                }
-                // -- texturing randomized versions
-                RandomizedSetOverlayMaterial(prob, slot) => {
-                    randomized_paint_texture::<OVERLAY_TEXTURE_BITMASK, OVERLAY_TEXTURE_BITPOS>(
-                        &mask, &mut data, slot, *prob,
-                    );
-                }
-                RandomizedSetBackgroundMaterial(prob, slot) => {
-                    randomized_paint_texture::<BKGRND_TEXTURE_BITMASK, BKGRND_TEXTURE_BITPOS>(
-                        &mask, &mut data, slot, *prob,
-                    );
-                }
-                // -- scaling
-                SetBackgroundScaling(value) => {
-                    set_value::<SCALING_BITMASK, SCALING_BITPOS, TextureScale>(
-                        &mask, &mut data, *value,
-                    );
-                }
-                SetBackgroundScalingWithVariance(value, variance) => {
-                    set_value_with_variance::<SCALING_BITMASK, SCALING_BITPOS, TextureScale>(
-                        &mask, &mut data, *value, *variance,
-                    );
-                }
-                IncreaseBackgroundScaling => {
-                    increase_value::<SCALING_BITMASK, SCALING_BITPOS>(&mask, &mut data);
-                }
-                ReduceBackgroundScaling => {
-                    reduce_value::<SCALING_BITMASK, SCALING_BITPOS>(&mask, &mut data);
-                }
-                IncreaseBackgroundScalingWithVariance(variance) => {
-                    increase_value_with_variance::<SCALING_BITMASK, SCALING_BITPOS>(
-                        &mask, &mut data, *variance,
-                    );
-                }
-                ReduceBackgroundScalingWithVariance(variance) => {
-                    reduce_value_with_variance::<SCALING_BITMASK, SCALING_BITPOS>(
-                        &mask, &mut data, *variance,
-                    );
-                }
-                // -- scaling randomized versions
-                RandomizedSetBackgroundScaling(prob, value) => {
-                    randomized_set_value::<SCALING_BITMASK, SCALING_BITPOS, TextureScale>(
-                        &mask, &mut data, *value, *prob,
-                    );
-                }
-                RandomizedSetBackgroundScalingWithVariance(prob, value, variance) => {
-                    randomized_set_value_with_variance::<
-                        SCALING_BITMASK,
-                        SCALING_BITPOS,
-                        TextureScale,
-                    >(&mask, &mut data, *value, *variance, *prob);
-                }
-                RandomizedIncreaseBackgroundScaling(prob) => {
-                    randomized_increase_value::<SCALING_BITMASK, SCALING_BITPOS>(
-                        &mask, &mut data, *prob,
-                    );
-                }
-                RandomizedIncreaseBackgroundScalingWithVariance(prob, variance) => {
-                    randomized_increase_value_with_variance::<SCALING_BITMASK, SCALING_BITPOS>(
-                        &mask, &mut data, *variance, *prob,
-                    );
-                }
-                RandomizedReduceBackgroundScaling(prob) => {
-                    randomized_reduce_value::<SCALING_BITMASK, SCALING_BITPOS>(
-                        &mask, &mut data, *prob,
-                    );
-                }
-                RandomizedReduceBackgroundScalingWithVariance(prob, variance) => {
-                    randomized_reduce_value_with_variance::<SCALING_BITMASK, SCALING_BITPOS>(
-                        &mask, &mut data, *variance, *prob,
-                    );
-                }
-                // -- blending
-                SetSlopeBlendThreshold(value) => {
-                    set_value::<BLENDING_BITMASK, BLENDING_BITPOS, SlopeBlendThreshold>(
-                        &mask, &mut data, *value,
-                    );
-                }
-                SetSlopeBlendThresholdWithVariance(value, variance) => {
-                    set_value_with_variance::<BLENDING_BITMASK, BLENDING_BITPOS, SlopeBlendThreshold>(
-                        &mask, &mut data, *value, *variance,
-                    );
-                }
-                IncreaseSlopeBlendThreshold => {
-                    increase_value::<BLENDING_BITMASK, BLENDING_BITPOS>(&mask, &mut data);
-                }
-                ReduceSlopeBlendThreshold => {
-                    reduce_value::<BLENDING_BITMASK, BLENDING_BITPOS>(&mask, &mut data);
-                }
-                IncreaseSlopeBlendThresholdWithVariance(variance) => {
-                    increase_value_with_variance::<BLENDING_BITMASK, BLENDING_BITPOS>(
-                        &mask, &mut data, *variance,
-                    );
-                }
-                ReduceSlopeBlendThresholdWithVariance(variance) => {
-                    reduce_value_with_variance::<BLENDING_BITMASK, BLENDING_BITPOS>(
-                        &mask, &mut data, *variance,
-                    );
-                }
-                // -- blending randomized versions
-                RandomizedSetSlopeBlendThreshold(prob, value) => {
-                    randomized_set_value::<BLENDING_BITMASK, BLENDING_BITPOS, SlopeBlendThreshold>(
-                        &mask, &mut data, *value, *prob,
-                    );
-                }
-                RandomizedSetSlopeBlendThresholdWithVariance(prob, value, variance) => {
-                    randomized_set_value_with_variance::<
-                        BLENDING_BITMASK,
-                        BLENDING_BITPOS,
-                        SlopeBlendThreshold,
-                    >(&mask, &mut data, *value, *variance, *prob);
-                }
-                RandomizedIncreaseSlopeBlendThreshold(prob) => {
-                    randomized_increase_value::<BLENDING_BITMASK, BLENDING_BITPOS>(
-                        &mask, &mut data, *prob,
-                    );
-                }
-                RandomizedIncreaseSlopeBlendThresholdWithVariance(prob, variance) => {
-                    randomized_increase_value_with_variance::<BLENDING_BITMASK, BLENDING_BITPOS>(
-                        &mask, &mut data, *variance, *prob,
-                    );
-                }
-                RandomizedReduceSlopeBlendThreshold(prob) => {
-                    randomized_reduce_value::<BLENDING_BITMASK, BLENDING_BITPOS>(
-                        &mask, &mut data, *prob,
-                    );
-                }
-                RandomizedReduceSlopeBlendThresholdWithVariance(prob, variance) => {
-                    randomized_reduce_value_with_variance::<BLENDING_BITMASK, BLENDING_BITPOS>(
-                        &mask, &mut data, *variance, *prob,
-                    );
-                }
-            }
-        }
+        history.record_texture_control(*stroke, &rectangle, &data);
+        let rng = painting_rng.stroke_rng(*stroke, RngStream::TextureControl);
+        // overlapping placements of the stroke apply randomized commands only
+        // once per datapoint
+        apply_with_stroke_coverage(
+            &mut coverage,
+            (*stroke, rng),
+            &rectangle,
+            &mask,
+            cmds,
+            true,
+            |rng, mask, cmds| apply_texture_control_cmds(rng, mask, &mut data, cmds),
+        );
         // updating full resolution is not enough: the clipmap must also be
         // regenerated and upload to the gpu
         texture_clipmap.update_fullres(&rectangle, &data);
         clipmap_tracker.force_update();
+
+        // holes are cut out of the tile meshes
+        if cmds.iter().any(PaintCommand::is_hole_cmd) {
+            hole_changes.add(&rectangle);
+        }
     }
 }
 // ----------------------------------------------------------------------------
-fn calculate_region_of_interest(
+#[cfg(feature = "editor")]
+#[allow(clippy::too_many_arguments)]
+fn process_tint_operations(
+    config: Res<TerrainConfig>,
+    mut paint_events: EventReader<PaintingEvent>,
+    selection: Res<PaintingSelection>,
+    mut tint_clipmap: ResMut<TintClipmap>,
+    mut clipmap_tracker: ResMut<ClipmapTracker>,
+    mut history: ResMut<PaintingHistory>,
+    mut painting_rng: ResMut<PaintingRng>,
+    mut coverage: Local<StrokeCoverage>,
+) {
+    for PaintingEvent(stroke, placement, cmds) in paint_events.iter() {
+        // texture control commands are processed in process_brush_operations
+        if !cmds.iter().any(PaintCommand::is_tint_cmd) {
+            continue;
+        }
+        let (rectangle, mut mask) = calculate_region_of_interest(&config, placement);
+        selection.restrict(&rectangle, &mut mask);
+
+        // see process_brush_operations
+        tint_clipmap.disable_cache();
+
+        let mut data = tint_clipmap.extract_fullres(&rectangle);
+        history.record_tint(*stroke, &rectangle, &data);
+        let rng = painting_rng.stroke_rng(*stroke, RngStream::Tint);
+        // blending commands accumulate but random offsets are applied once
+        apply_with_stroke_coverage(
+            &mut coverage,
+            (*stroke, rng),
+            &rectangle,
+            &mask,
+            cmds,
+            false,
+            |rng, mask, cmds| apply_tint_cmds(rng, mask, &mut data, cmds),
+        );
+        tint_clipmap.update_fullres(&rectangle, &data);
+        clipmap_tracker.force_update();
+    }
+}
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+#[allow(clippy::too_many_arguments)]
+fn process_sculpt_operations(
+    config: Res<TerrainConfig>,
+    mut paint_events: EventReader<PaintingEvent>,
+    selection: Res<PaintingSelection>,
+    mut heightmap: ResMut<TerrainHeightMap>,
+    mut heightmap_clipmap: ResMut<HeightmapClipmap>,
+    mut heightmap_changes: ResMut<HeightmapChanges>,
+    mut terrain_shadows: ResMut<TerrainShadowsClipmap>,
+    mut clipmap_tracker: ResMut<ClipmapTracker>,
+    mut history: ResMut<PaintingHistory>,
+    mut painting_rng: ResMut<PaintingRng>,
+    mut coverage: Local<StrokeCoverage>,
+) {
+    for PaintingEvent(stroke, placement, cmds) in paint_events.iter() {
+        // texture control and tint commands are processed in dedicated systems
+        if !cmds.iter().any(PaintCommand::is_sculpt_cmd) {
+            continue;
+        }
+        let (rectangle, mut mask) = calculate_region_of_interest(&config, placement);
+        selection.restrict(&rectangle, &mut mask);
+
+        // see process_brush_operations
+        heightmap_clipmap.disable_cache();
+
+        let mut data = heightmap_clipmap.extract_fullres(&rectangle);
+        history.record_heightmap(*stroke, &rectangle, &data);
+        let rng = painting_rng.stroke_rng(*stroke, RngStream::Sculpt);
+        let width = rectangle.size.x as usize;
+        // see process_tint_operations
+        apply_with_stroke_coverage(
+            &mut coverage,
+            (*stroke, rng),
+            &rectangle,
+            &mask,
+            cmds,
+            false,
+            |rng, mask, cmds| apply_sculpt_cmds(rng, mask, &mut data, width, cmds),
+        );
+        // heightmap clipmap (shadows) and heightmap (meshes) have separate data
+        heightmap_clipmap.update_fullres(&rectangle, &data);
+        heightmap.update_rectangle(&rectangle, &data);
+
+        // normals, errormaps and meshes are regenerated for changed region
+        heightmap_changes.add(&rectangle);
+        clipmap_tracker.force_update();
+        // lightheight map depends on heightmap
+        terrain_shadows.set_changed();
+    }
+}
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+/// randomized commands use a copy of the mask restricted by the stroke
+/// coverage (changed at most once per stroke). all other commands of the event
+/// accumulate and use the full mask. commands are applied in event order.
+fn apply_with_stroke_coverage(
+    coverage: &mut StrokeCoverage,
+    (stroke, rng): (StrokeId, &mut PaintRng),
+    rectangle: &Rectangle,
+    mask: &[f32],
+    cmds: &[PaintCommand],
+    weights_are_probabilities: bool,
+    mut apply: impl FnMut(&mut PaintRng, &[f32], &[PaintCommand]),
+) {
+    if !cmds.iter().any(PaintCommand::is_once_per_stroke_cmd) {
+        apply(rng, mask, cmds);
+        return;
+    }
+    let mut restricted = mask.to_vec();
+    coverage.restrict(
+        stroke,
+        rng,
+        rectangle,
+        &mut restricted,
+        weights_are_probabilities,
+    );
+
+    for cmd in cmds {
+        let mask = if cmd.is_once_per_stroke_cmd() {
+            &restricted
+        } else {
+            mask
+        };
+        apply(rng, mask, std::slice::from_ref(cmd));
+    }
+}
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+#[allow(clippy::too_many_arguments)]
+fn process_history_operations(
+    mut history_events: EventReader<PaintingHistoryEvent>,
+    mut history: ResMut<PaintingHistory>,
+    mut texture_clipmap: ResMut<TextureControlClipmap>,
+    mut tint_clipmap: ResMut<TintClipmap>,
+    mut heightmap_clipmap: ResMut<HeightmapClipmap>,
+    mut heightmap: ResMut<TerrainHeightMap>,
+    mut heightmap_changes: ResMut<HeightmapChanges>,
+    mut hole_changes: ResMut<TerrainHoleChanges>,
+    mut terrain_shadows: ResMut<TerrainShadowsClipmap>,
+    mut clipmap_tracker: ResMut<ClipmapTracker>,
+) {
+    for event in history_events.iter() {
+        let step = match event {
+            PaintingHistoryEvent::Undo => history.pop_undo(),
+            PaintingHistoryEvent::Redo => history.pop_redo(),
+        };
+
+        if let Some(step) = step {
+            // see process_brush_operations
+            texture_clipmap.disable_cache();
+            tint_clipmap.disable_cache();
+            heightmap_clipmap.disable_cache();
+
+            if step.changes_heightmap() {
+                // see process_sculpt_operations
+                terrain_shadows.set_changed();
+            }
+
+            let reverse_step = step.restore(
+                &mut texture_clipmap,
+                &mut tint_clipmap,
+                &mut heightmap_clipmap,
+                &mut heightmap,
+                &mut heightmap_changes,
+                &mut hole_changes,
+            );
+            match event {
+                PaintingHistoryEvent::Undo => history.push_redo(reverse_step),
+                PaintingHistoryEvent::Redo => history.push_undo(reverse_step),
+            }
+            clipmap_tracker.force_update();
+        } else {
+            debug!("painting history: nothing to {:?}", event);
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+fn reset_history(mut history: ResMut<PaintingHistory>) {
+    history.clear();
+}
+// ----------------------------------------------------------------------------
+// command application (shared with TerrainDocument)
+// ----------------------------------------------------------------------------
+/// applies all texture control commands to the extracted region. non texture
+/// control commands are ignored.
+pub(crate) fn apply_texture_control_cmds(
+    rng: &mut PaintRng,
+    mask: &[f32],
+    data: &mut [u16],
+    cmds: &[PaintCommand],
+) {
+    use PaintCommand::*;
+
+    for cmd in cmds {
+        match cmd {
+            // -- texturing
+            SetOverlayMaterial(slot) => {
+                paint_texture::<OVERLAY_TEXTURE_BITMASK, OVERLAY_TEXTURE_BITPOS>(
+                    rng, mask, data, slot,
+                );
+            }
+            SetBackgroundMaterial(slot) => {
+                paint_texture::<BKGRND_TEXTURE_BITMASK, BKGRND_TEXTURE_BITPOS>(
+                    rng, mask, data, slot,
+                );
+            }
+            // -- texturing randomized versions
+            RandomizedSetOverlayMaterial(prob, slot) => {
+                randomized_paint_texture::<OVERLAY_TEXTURE_BITMASK, OVERLAY_TEXTURE_BITPOS>(
+                    rng, mask, data, slot, *prob,
+                );
+            }
+            RandomizedSetBackgroundMaterial(prob, slot) => {
+                randomized_paint_texture::<BKGRND_TEXTURE_BITMASK, BKGRND_TEXTURE_BITPOS>(
+                    rng, mask, data, slot, *prob,
+                );
+            }
+            // -- holes
+            CutHole => cut_hole(rng, mask, data),
+            FillHole(overlay, bkgrnd) => fill_hole(rng, mask, data, overlay, bkgrnd),
+            // -- scaling
+            SetBackgroundScaling(value) => {
+                set_value::<SCALING_BITMASK, SCALING_BITPOS, TextureScale>(rng, mask, data, *value);
+            }
+            SetBackgroundScalingWithVariance(value, variance) => {
+                set_value_with_variance::<SCALING_BITMASK, SCALING_BITPOS, TextureScale>(
+                    rng, mask, data, *value, *variance,
+                );
+            }
+            IncreaseBackgroundScaling => {
+                increase_value::<SCALING_BITMASK, SCALING_BITPOS>(rng, mask, data);
+            }
+            ReduceBackgroundScaling => {
+                reduce_value::<SCALING_BITMASK, SCALING_BITPOS>(rng, mask, data);
+            }
+            IncreaseBackgroundScalingWithVariance(variance) => {
+                increase_value_with_variance::<SCALING_BITMASK, SCALING_BITPOS>(
+                    rng, mask, data, *variance,
+                );
+            }
+            ReduceBackgroundScalingWithVariance(variance) => {
+                reduce_value_with_variance::<SCALING_BITMASK, SCALING_BITPOS>(
+                    rng, mask, data, *variance,
+                );
+            }
+            // -- scaling randomized versions
+            RandomizedSetBackgroundScaling(prob, value) => {
+                randomized_set_value::<SCALING_BITMASK, SCALING_BITPOS, TextureScale>(
+                    rng, mask, data, *value, *prob,
+                );
+            }
+            RandomizedSetBackgroundScalingWithVariance(prob, value, variance) => {
+                randomized_set_value_with_variance::<SCALING_BITMASK, SCALING_BITPOS, TextureScale>(
+                    rng, mask, data, *value, *variance, *prob,
+                );
+            }
+            RandomizedIncreaseBackgroundScaling(prob) => {
+                randomized_increase_value::<SCALING_BITMASK, SCALING_BITPOS>(
+                    rng, mask, data, *prob,
+                );
+            }
+            RandomizedIncreaseBackgroundScalingWithVariance(prob, variance) => {
+                randomized_increase_value_with_variance::<SCALING_BITMASK, SCALING_BITPOS>(
+                    rng, mask, data, *variance, *prob,
+                );
+            }
+            RandomizedReduceBackgroundScaling(prob) => {
+                randomized_reduce_value::<SCALING_BITMASK, SCALING_BITPOS>(rng, mask, data, *prob);
+            }
+            RandomizedReduceBackgroundScalingWithVariance(prob, variance) => {
+                randomized_reduce_value_with_variance::<SCALING_BITMASK, SCALING_BITPOS>(
+                    rng, mask, data, *variance, *prob,
+                );
+            }
+            // -- blending
+            SetSlopeBlendThreshold(value) => {
+                set_value::<BLENDING_BITMASK, BLENDING_BITPOS, SlopeBlendThreshold>(
+                    rng, mask, data, *value,
+                );
+            }
+            SetSlopeBlendThresholdWithVariance(value, variance) => {
+                set_value_with_variance::<BLENDING_BITMASK, BLENDING_BITPOS, SlopeBlendThreshold>(
+                    rng, mask, data, *value, *variance,
+                );
+            }
+            IncreaseSlopeBlendThreshold => {
+                increase_value::<BLENDING_BITMASK, BLENDING_BITPOS>(rng, mask, data);
+            }
+            ReduceSlopeBlendThreshold => {
+                reduce_value::<BLENDING_BITMASK, BLENDING_BITPOS>(rng, mask, data);
+            }
+            IncreaseSlopeBlendThresholdWithVariance(variance) => {
+                increase_value_with_variance::<BLENDING_BITMASK, BLENDING_BITPOS>(
+                    rng, mask, data, *variance,
+                );
+            }
+            ReduceSlopeBlendThresholdWithVariance(variance) => {
+                reduce_value_with_variance::<BLENDING_BITMASK, BLENDING_BITPOS>(
+                    rng, mask, data, *variance,
+                );
+            }
+            // -- blending randomized versions
+            RandomizedSetSlopeBlendThreshold(prob, value) => {
+                randomized_set_value::<BLENDING_BITMASK, BLENDING_BITPOS, SlopeBlendThreshold>(
+                    rng, mask, data, *value, *prob,
+                );
+            }
+            RandomizedSetSlopeBlendThresholdWithVariance(prob, value, variance) => {
+                randomized_set_value_with_variance::<
+                    BLENDING_BITMASK,
+                    BLENDING_BITPOS,
+                    SlopeBlendThreshold,
+                >(rng, mask, data, *value, *variance, *prob);
+            }
+            RandomizedIncreaseSlopeBlendThreshold(prob) => {
+                randomized_increase_value::<BLENDING_BITMASK, BLENDING_BITPOS>(
+                    rng, mask, data, *prob,
+                );
+            }
+            RandomizedIncreaseSlopeBlendThresholdWithVariance(prob, variance) => {
+                randomized_increase_value_with_variance::<BLENDING_BITMASK, BLENDING_BITPOS>(
+                    rng, mask, data, *variance, *prob,
+                );
+            }
+            RandomizedReduceSlopeBlendThreshold(prob) => {
+                randomized_reduce_value::<BLENDING_BITMASK, BLENDING_BITPOS>(
+                    rng, mask, data, *prob,
+                );
+            }
+            RandomizedReduceSlopeBlendThresholdWithVariance(prob, variance) => {
+                randomized_reduce_value_with_variance::<BLENDING_BITMASK, BLENDING_BITPOS>(
+                    rng, mask, data, *variance, *prob,
+                );
+            }
+            // -- tint: see process_tint_operations
+            SetTint(_) | MultiplyTint(_) | LightenTint(_) | DarkenTint(_) | JitterTint(_) => {}
+            // -- sculpting: see process_sculpt_operations
+            RaiseHeight(_) | LowerHeight(_) | SmoothHeight(_) | FlattenHeight(_)
+            | NoiseHeight(_) => {}
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+/// applies all tint commands to the extracted (RGBA) region. other commands
+/// are ignored.
+pub(crate) fn apply_tint_cmds(
+    rng: &mut PaintRng,
+    mask: &[f32],
+    data: &mut [u8],
+    cmds: &[PaintCommand],
+) {
+    use PaintCommand::*;
+
+    for cmd in cmds {
+        match cmd {
+            SetTint(color) => set_tint(mask, data, color),
+            MultiplyTint(color) => multiply_tint(mask, data, color),
+            LightenTint(strength) => lighten_tint(mask, data, *strength),
+            DarkenTint(strength) => darken_tint(mask, data, *strength),
+            JitterTint(jitter) => jitter_tint(rng, mask, data, *jitter),
+            _ => {}
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+/// applies all sculpt commands to the extracted heightmap region with the
+/// provided width. other commands are ignored.
+pub(crate) fn apply_sculpt_cmds(
+    rng: &mut PaintRng,
+    mask: &[f32],
+    data: &mut [u16],
+    width: usize,
+    cmds: &[PaintCommand],
+) {
+    use PaintCommand::*;
+
+    for cmd in cmds {
+        match cmd {
+            RaiseHeight(delta) => raise_height(mask, data, *delta),
+            LowerHeight(delta) => lower_height(mask, data, *delta),
+            SmoothHeight(strength) => smooth_height(mask, data, width, *strength),
+            FlattenHeight(strength) => flatten_height(mask, data, *strength),
+            NoiseHeight(delta) => noise_height(rng, mask, data, *delta),
+            _ => {}
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+pub(crate) fn calculate_region_of_interest(
     config: &TerrainConfig,
     placement: &BrushPlacement,
-) -> (Rectangle, Vec<bool>) {
+) -> (Rectangle, Vec<f32>) {
     // respect resolution of clipmap data which differes from world resolution:
     // map world coordinates/resolution to map coordinates/resolution
     let map_brush_center = config.world_pos_to_map_pos(placement.pos);
     let map_brush_center = vec2(map_brush_center.x as f32, map_brush_center.y as f32);
     let map_radius = (placement.radius / config.resolution()).round();
 
-    let min = config.world_pos_to_map_pos(placement.pos - Vec2::splat(placement.radius));
-    let max =
-        config.world_pos_to_map_pos(placement.pos + Vec2::splat(placement.radius)) + uvec2(1, 1);
+    // stamps may cover more than the brush circle (scaled and rotated)
+    let extent = placement.radius * placement.shape.extent();
+    let min = config.world_pos_to_map_pos(placement.pos - Vec2::splat(extent));
+    let max = config.world_pos_to_map_pos(placement.pos + Vec2::splat(extent)) + uvec2(1, 1);
 
     let size = (max - min).max(uvec2(1, 1));
     let rectangle = Rectangle { pos: min, size };
 
-    // precalculate filter for all pixels not in the brush circle (roughly)
+    // precalculate weights for all pixels in the brush area (0 outside)
     let mut mask = Vec::with_capacity((size.y * size.x) as usize);
     for y in min.y..min.y + size.y {
         for x in min.x..min.x + size.x {
-            let distance = map_brush_center.distance(vec2(x as f32, y as f32));
-            //
-            mask.push(distance < map_radius);
+            let offset = (vec2(x as f32, y as f32) - map_brush_center) / map_radius.max(1.0);
+            mask.push(placement.shape.weight(offset));
         }
     }
     // special edge case: prevent completely empty mask (rounding errors)
     if size.x * size.y == 1 {
-        mask = vec![true];
+        mask = vec![1.0];
     }
 
     (rectangle, mask)
@@ -373,8 +817,54 @@ const BLENDING_BITMASK: u16 = 0b0001_1100_0000_0000;
 const SCALING_BITPOS: u8 = 13;
 const SCALING_BITMASK: u16 = 0b1110_0000_0000_0000;
 // ----------------------------------------------------------------------------
+/// decoded texture control map datapoint
+#[derive(Clone, Copy, Debug)]
+pub struct ControlMapEntry {
+    /// None is a hole
+    pub overlay: Option<MaterialSlot>,
+    /// None is a hole
+    pub background: Option<MaterialSlot>,
+    pub slope_blend: SlopeBlendThreshold,
+    pub scaling: TextureScale,
+}
+// ----------------------------------------------------------------------------
+impl ControlMapEntry {
+    // ------------------------------------------------------------------------
+    pub fn decode(data: u16) -> Self {
+        // zero is reserved for holes
+        let material = |v: u8| (v > 0).then(|| MaterialSlot::from(v - 1));
+
+        Self {
+            overlay: material(pick_value::<OVERLAY_TEXTURE_BITMASK, OVERLAY_TEXTURE_BITPOS>(data)),
+            background: material(pick_value::<BKGRND_TEXTURE_BITMASK, BKGRND_TEXTURE_BITPOS>(
+                data,
+            )),
+            slope_blend: SlopeBlendThreshold(pick_value::<BLENDING_BITMASK, BLENDING_BITPOS>(data)),
+            scaling: TextureScale(pick_value::<SCALING_BITMASK, SCALING_BITPOS>(data)),
+        }
+    }
+    // ------------------------------------------------------------------------
+    pub fn encode(&self) -> u16 {
+        let material = |slot: Option<MaterialSlot>| slot.map_or(0, |s| *s as u16 + 1);
+
+        ((material(self.overlay) << OVERLAY_TEXTURE_BITPOS) & OVERLAY_TEXTURE_BITMASK)
+            | ((material(self.background) << BKGRND_TEXTURE_BITPOS) & BKGRND_TEXTURE_BITMASK)
+            | ((self.slope_blend.as_value() << BLENDING_BITPOS) & BLENDING_BITMASK)
+            | ((self.scaling.as_value() << SCALING_BITPOS) & SCALING_BITMASK)
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+/// zero background material marks a terrain hole (the overlay material is
+/// ignored by the game)
+#[inline(always)]
+pub(crate) fn is_terrain_hole(data: u16) -> bool {
+    data & BKGRND_TEXTURE_BITMASK == 0
+}
+// ----------------------------------------------------------------------------
 // picker operation
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 #[inline(always)]
 fn pick_material<const BIT_MASK: u16, const BIT_POS: u8>(data: u16) -> MaterialSlot {
     // zero is reserved for holes
@@ -388,36 +878,88 @@ fn pick_value<const BIT_MASK: u16, const BIT_POS: u8>(data: u16) -> u8 {
     ((data & BIT_MASK) >> BIT_POS) as u8
 }
 // ----------------------------------------------------------------------------
+#[inline(always)]
+fn set_bits<const BIT_MASK: u16, const BIT_POS: u8>(data: u16, value: u16) -> u16 {
+    (data & !BIT_MASK) | ((value << BIT_POS) & BIT_MASK)
+}
+// ----------------------------------------------------------------------------
 // painting operations
 // ----------------------------------------------------------------------------
+/// control map values are discrete: weights < 1 of soft brush falloff or stamp
+/// masks are interpreted as probability to change a datapoint (multiplied with
+/// the overwrite probability of randomized commands).
+#[inline(always)]
+fn masked_values<'a>(
+    rng: &mut PaintRng,
+    mask: &'a [f32],
+    data: &'a mut [u16],
+    probability: f32,
+) -> impl Iterator<Item = &'a mut u16> + 'a {
+    // separate (but deterministically derived) rng so the caller can still use
+    // its rng while iterating
+    let mut rng = PaintRng::seed_from_u64(rng.gen());
+
+    data.iter_mut()
+        .zip(mask.iter())
+        .filter_map(move |(d, weight)| {
+            let p = weight * probability;
+            (p >= 1.0 || (p > 0.0 && rng.gen::<f32>() < p)).then_some(d)
+        })
+}
+// ----------------------------------------------------------------------------
 #[inline(always)]
 fn paint_texture<const BIT_MASK: u16, const BIT_POS: u8>(
-    mask: &[bool],
+    rng: &mut PaintRng,
+    mask: &[f32],
     data: &mut [u16],
     slot: &MaterialSlot,
 ) {
-    // zero is reserved for holes
+    // zero is reserved for holes. holes are only changed by the hole brush
     let material = **slot as u16 + 1;
-    for (d, _) in data.iter_mut().zip(mask.iter()).filter(|(_, m)| **m) {
+    for d in masked_values(rng, mask, data, 1.0).filter(|d| !is_terrain_hole(**d)) {
         *d = (*d & !BIT_MASK) + (material << BIT_POS);
     }
 }
 // ----------------------------------------------------------------------------
 #[inline(always)]
 fn randomized_paint_texture<const BIT_MASK: u16, const BIT_POS: u8>(
-    mask: &[bool],
+    rng: &mut PaintRng,
+    mask: &[f32],
     data: &mut [u16],
     slot: &MaterialSlot,
     probability: OverwriteProbability,
 ) {
-    let mut rng = thread_rng();
-
-    // zero is reserved for holes
+    // zero is reserved for holes. holes are only changed by the hole brush
     let material = **slot as u16 + 1;
-    for (d, _) in data.iter_mut().zip(mask.iter()).filter(|(_, m)| **m) {
-        if rng.gen_bool(*probability as f64) {
-            *d = (*d & !BIT_MASK) + (material << BIT_POS);
-        }
+    for d in masked_values(rng, mask, data, *probability).filter(|d| !is_terrain_hole(**d)) {
+        *d = (*d & !BIT_MASK) + (material << BIT_POS);
+    }
+}
+// ----------------------------------------------------------------------------
+// hole operations
+// ----------------------------------------------------------------------------
+#[inline(always)]
+fn cut_hole(rng: &mut PaintRng, mask: &[f32], data: &mut [u16]) {
+    // overlay is reset, too: the game ignores it but a later filled hole should
+    // not reveal an old overlay material
+    for d in masked_values(rng, mask, data, 1.0) {
+        *d &= !(OVERLAY_TEXTURE_BITMASK | BKGRND_TEXTURE_BITMASK);
+    }
+}
+// ----------------------------------------------------------------------------
+#[inline(always)]
+fn fill_hole(
+    rng: &mut PaintRng,
+    mask: &[f32],
+    data: &mut [u16],
+    overlay: &MaterialSlot,
+    bkgrnd: &MaterialSlot,
+) {
+    let overlay = **overlay as u16 + 1;
+    let bkgrnd = **bkgrnd as u16 + 1;
+    for d in masked_values(rng, mask, data, 1.0).filter(|d| is_terrain_hole(**d)) {
+        *d = set_bits::<OVERLAY_TEXTURE_BITMASK, OVERLAY_TEXTURE_BITPOS>(*d, overlay);
+        *d = set_bits::<BKGRND_TEXTURE_BITMASK, BKGRND_TEXTURE_BITPOS>(*d, bkgrnd);
     }
 }
 // ----------------------------------------------------------------------------
@@ -425,45 +967,43 @@ fn randomized_paint_texture<const BIT_MASK: u16, const BIT_POS: u8>(
 // ----------------------------------------------------------------------------
 #[inline(always)]
 fn set_value<const BIT_MASK: u16, const BIT_POS: u8, V: ControlMapValue>(
-    mask: &[bool],
+    rng: &mut PaintRng,
+    mask: &[f32],
     data: &mut [u16],
     value: V,
 ) {
     // let value = (*value) as u16;
     let value = value.as_value();
-    for (d, _) in data.iter_mut().zip(mask.iter()).filter(|(_, m)| **m) {
+    for d in masked_values(rng, mask, data, 1.0) {
         *d = (*d & !BIT_MASK) + (value << BIT_POS);
     }
-} // ----------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
 #[inline(always)]
 fn randomized_set_value<const BIT_MASK: u16, const BIT_POS: u8, V: ControlMapValue>(
-    mask: &[bool],
+    rng: &mut PaintRng,
+    mask: &[f32],
     data: &mut [u16],
     value: V,
     probability: OverwriteProbability,
 ) {
-    let mut rng = thread_rng();
-
     let value = value.as_value();
-    for (d, _) in data.iter_mut().zip(mask.iter()).filter(|(_, m)| **m) {
-        if rng.gen_bool(*probability as f64) {
-            *d = (*d & !BIT_MASK) + (value << BIT_POS);
-        }
+    for d in masked_values(rng, mask, data, *probability) {
+        *d = (*d & !BIT_MASK) + (value << BIT_POS);
     }
 }
 // ----------------------------------------------------------------------------
 #[inline(always)]
 fn set_value_with_variance<const BIT_MASK: u16, const BIT_POS: u8, V: ControlMapValue>(
-    mask: &[bool],
+    rng: &mut PaintRng,
+    mask: &[f32],
     data: &mut [u16],
     value: V,
     variance: Variance,
 ) {
-    let mut rng = thread_rng();
-
     let value = value.as_value();
     let variance = variance.as_value();
-    for (d, _) in data.iter_mut().zip(mask.iter()).filter(|(_, m)| **m) {
+    for d in masked_values(rng, mask, data, 1.0) {
         let value = (value + rng.gen_range(0..=variance)).clamp(0, 7);
         *d = (*d & !BIT_MASK) + (value << BIT_POS);
     }
@@ -475,27 +1015,28 @@ fn randomized_set_value_with_variance<
     const BIT_POS: u8,
     V: ControlMapValue,
 >(
-    mask: &[bool],
+    rng: &mut PaintRng,
+    mask: &[f32],
     data: &mut [u16],
     value: V,
     variance: Variance,
     probability: OverwriteProbability,
 ) {
-    let mut rng = thread_rng();
-
     let value = value.as_value();
     let variance = variance.as_value();
-    for (d, _) in data.iter_mut().zip(mask.iter()).filter(|(_, m)| **m) {
-        if rng.gen_bool(*probability as f64) {
-            let value = (value + rng.gen_range(0..=variance)).clamp(0, 7);
-            *d = (*d & !BIT_MASK) + (value << BIT_POS);
-        }
+    for d in masked_values(rng, mask, data, *probability) {
+        let value = (value + rng.gen_range(0..=variance)).clamp(0, 7);
+        *d = (*d & !BIT_MASK) + (value << BIT_POS);
     }
 }
 // ----------------------------------------------------------------------------
 #[inline(always)]
-fn increase_value<const BIT_MASK: u16, const BIT_POS: u8>(mask: &[bool], data: &mut [u16]) {
-    for (d, _) in data.iter_mut().zip(mask.iter()).filter(|(_, m)| **m) {
+fn increase_value<const BIT_MASK: u16, const BIT_POS: u8>(
+    rng: &mut PaintRng,
+    mask: &[f32],
+    data: &mut [u16],
+) {
+    for d in masked_values(rng, mask, data, 1.0) {
         let value = (((*d & BIT_MASK) >> BIT_POS) + 1).clamp(0, 7);
         *d = (*d & !BIT_MASK) + (value << BIT_POS);
     }
@@ -503,30 +1044,26 @@ fn increase_value<const BIT_MASK: u16, const BIT_POS: u8>(mask: &[bool], data: &
 // ----------------------------------------------------------------------------
 #[inline(always)]
 fn randomized_increase_value<const BIT_MASK: u16, const BIT_POS: u8>(
-    mask: &[bool],
+    rng: &mut PaintRng,
+    mask: &[f32],
     data: &mut [u16],
     probability: OverwriteProbability,
 ) {
-    let mut rng = thread_rng();
-
-    for (d, _) in data.iter_mut().zip(mask.iter()).filter(|(_, m)| **m) {
-        if rng.gen_bool(*probability as f64) {
-            let value = (((*d & BIT_MASK) >> BIT_POS) + 1).clamp(0, 7);
-            *d = (*d & !BIT_MASK) + (value << BIT_POS);
-        }
+    for d in masked_values(rng, mask, data, *probability) {
+        let value = (((*d & BIT_MASK) >> BIT_POS) + 1).clamp(0, 7);
+        *d = (*d & !BIT_MASK) + (value << BIT_POS);
     }
 }
 // ----------------------------------------------------------------------------
 #[inline(always)]
 fn increase_value_with_variance<const BIT_MASK: u16, const BIT_POS: u8>(
-    mask: &[bool],
+    rng: &mut PaintRng,
+    mask: &[f32],
     data: &mut [u16],
     variance: Variance,
 ) {
-    let mut rng = thread_rng();
-
     let variance = variance.as_value();
-    for (d, _) in data.iter_mut().zip(mask.iter()).filter(|(_, m)| **m) {
+    for d in masked_values(rng, mask, data, 1.0) {
         let value = (((*d & BIT_MASK) >> BIT_POS) + rng.gen_range(0..=variance)).clamp(0, 7);
         *d = (*d & !BIT_MASK) + (value << BIT_POS);
     }
@@ -534,25 +1071,26 @@ fn increase_value_with_variance<const BIT_MASK: u16, const BIT_POS: u8>(
 // ----------------------------------------------------------------------------
 #[inline(always)]
 fn randomized_increase_value_with_variance<const BIT_MASK: u16, const BIT_POS: u8>(
-    mask: &[bool],
+    rng: &mut PaintRng,
+    mask: &[f32],
     data: &mut [u16],
     variance: Variance,
     probability: OverwriteProbability,
 ) {
-    let mut rng = thread_rng();
-
     let variance = variance.as_value();
-    for (d, _) in data.iter_mut().zip(mask.iter()).filter(|(_, m)| **m) {
-        if rng.gen_bool(*probability as f64) {
-            let value = (((*d & BIT_MASK) >> BIT_POS) + rng.gen_range(0..=variance)).clamp(0, 7);
-            *d = (*d & !BIT_MASK) + (value << BIT_POS);
-        }
+    for d in masked_values(rng, mask, data, *probability) {
+        let value = (((*d & BIT_MASK) >> BIT_POS) + rng.gen_range(0..=variance)).clamp(0, 7);
+        *d = (*d & !BIT_MASK) + (value << BIT_POS);
     }
 }
 // ----------------------------------------------------------------------------
 #[inline(always)]
-fn reduce_value<const BIT_MASK: u16, const BIT_POS: u8>(mask: &[bool], data: &mut [u16]) {
-    for (d, _) in data.iter_mut().zip(mask.iter()).filter(|(_, m)| **m) {
+fn reduce_value<const BIT_MASK: u16, const BIT_POS: u8>(
+    rng: &mut PaintRng,
+    mask: &[f32],
+    data: &mut [u16],
+) {
+    for d in masked_values(rng, mask, data, 1.0) {
         let value = ((*d & BIT_MASK) >> BIT_POS).saturating_sub(1);
         *d = (*d & !BIT_MASK) + (value << BIT_POS);
     }
@@ -560,30 +1098,26 @@ fn reduce_value<const BIT_MASK: u16, const BIT_POS: u8>(mask: &[bool], data: &mu
 // ----------------------------------------------------------------------------
 #[inline(always)]
 fn randomized_reduce_value<const BIT_MASK: u16, const BIT_POS: u8>(
-    mask: &[bool],
+    rng: &mut PaintRng,
+    mask: &[f32],
     data: &mut [u16],
     probability: OverwriteProbability,
 ) {
-    let mut rng = thread_rng();
-
-    for (d, _) in data.iter_mut().zip(mask.iter()).filter(|(_, m)| **m) {
-        if rng.gen_bool(*probability as f64) {
-            let value = ((*d & BIT_MASK) >> BIT_POS).saturating_sub(1);
-            *d = (*d & !BIT_MASK) + (value << BIT_POS);
-        }
+    for d in masked_values(rng, mask, data, *probability) {
+        let value = ((*d & BIT_MASK) >> BIT_POS).saturating_sub(1);
+        *d = (*d & !BIT_MASK) + (value << BIT_POS);
     }
 }
 // ----------------------------------------------------------------------------
 #[inline(always)]
 fn reduce_value_with_variance<const BIT_MASK: u16, const BIT_POS: u8>(
-    mask: &[bool],
+    rng: &mut PaintRng,
+    mask: &[f32],
     data: &mut [u16],
     variance: Variance,
 ) {
-    let mut rng = thread_rng();
-
     let variance = variance.as_value();
-    for (d, _) in data.iter_mut().zip(mask.iter()).filter(|(_, m)| **m) {
+    for d in masked_values(rng, mask, data, 1.0) {
         let value = ((*d & BIT_MASK) >> BIT_POS).saturating_sub(rng.gen_range(0..=variance));
         *d = (*d & !BIT_MASK) + (value << BIT_POS);
     }
@@ -591,32 +1125,276 @@ fn reduce_value_with_variance<const BIT_MASK: u16, const BIT_POS: u8>(
 // ----------------------------------------------------------------------------
 #[inline(always)]
 fn randomized_reduce_value_with_variance<const BIT_MASK: u16, const BIT_POS: u8>(
-    mask: &[bool],
+    rng: &mut PaintRng,
+    mask: &[f32],
     data: &mut [u16],
     variance: Variance,
     probability: OverwriteProbability,
 ) {
-    let mut rng = thread_rng();
-
     let variance = variance.as_value();
-    for (d, _) in data.iter_mut().zip(mask.iter()).filter(|(_, m)| **m) {
-        if rng.gen_bool(*probability as f64) {
-            let value = ((*d & BIT_MASK) >> BIT_POS).saturating_sub(rng.gen_range(0..=variance));
-            *d = (*d & !BIT_MASK) + (value << BIT_POS);
+    for d in masked_values(rng, mask, data, *probability) {
+        let value = ((*d & BIT_MASK) >> BIT_POS).saturating_sub(rng.gen_range(0..=variance));
+        *d = (*d & !BIT_MASK) + (value << BIT_POS);
+    }
+}
+// ----------------------------------------------------------------------------
+// tint operations
+// ----------------------------------------------------------------------------
+// tint data is RGBA. alpha is not used for rendering and is kept unchanged.
+// brush weights blend between current and new color.
+// ----------------------------------------------------------------------------
+#[inline(always)]
+fn blend_channel(current: u8, target: f32, weight: f32) -> u8 {
+    (current as f32 + (target - current as f32) * weight)
+        .round()
+        .clamp(0.0, 255.0) as u8
+}
+// ----------------------------------------------------------------------------
+#[inline(always)]
+fn set_tint(mask: &[f32], data: &mut [u8], color: &TintColor) {
+    for (d, w) in data
+        .chunks_exact_mut(4)
+        .zip(mask.iter())
+        .filter(|(_, w)| **w > 0.0)
+    {
+        for (v, c) in d.iter_mut().zip(color.0.iter()) {
+            *v = blend_channel(*v, *c as f32, *w);
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+#[inline(always)]
+fn multiply_tint(mask: &[f32], data: &mut [u8], color: &TintColor) {
+    // multiply relative to neutral tint so a neutral color does not change
+    // anything
+    for (d, w) in data
+        .chunks_exact_mut(4)
+        .zip(mask.iter())
+        .filter(|(_, w)| **w > 0.0)
+    {
+        for (v, c) in d.iter_mut().zip(color.0.iter()) {
+            let target = (*v as u32 * *c as u32 / 128).min(255);
+            *v = blend_channel(*v, target as f32, *w);
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+#[inline(always)]
+fn lighten_tint(mask: &[f32], data: &mut [u8], strength: TintStrength) {
+    for (d, w) in data
+        .chunks_exact_mut(4)
+        .zip(mask.iter())
+        .filter(|(_, w)| **w > 0.0)
+    {
+        for v in d[..3].iter_mut() {
+            *v = blend_channel(*v, v.saturating_add(strength.0) as f32, *w);
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+#[inline(always)]
+fn darken_tint(mask: &[f32], data: &mut [u8], strength: TintStrength) {
+    for (d, w) in data
+        .chunks_exact_mut(4)
+        .zip(mask.iter())
+        .filter(|(_, w)| **w > 0.0)
+    {
+        for v in d[..3].iter_mut() {
+            *v = blend_channel(*v, v.saturating_sub(strength.0) as f32, *w);
         }
     }
 }
 // ----------------------------------------------------------------------------
+#[inline(always)]
+fn jitter_tint(rng: &mut PaintRng, mask: &[f32], data: &mut [u8], jitter: TintJitter) {
+    // same offset for all channels: changes brightness but not the hue
+    let jitter = jitter.0 as i16;
+    for (d, w) in data
+        .chunks_exact_mut(4)
+        .zip(mask.iter())
+        .filter(|(_, w)| **w > 0.0)
+    {
+        let offset = rng.gen_range(-jitter..=jitter);
+        for v in d[..3].iter_mut() {
+            *v = blend_channel(*v, *v as f32 + offset as f32, *w);
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+// sculpt operations
+// ----------------------------------------------------------------------------
+// brush weights scale height changes and strength.
+// ----------------------------------------------------------------------------
+#[inline(always)]
+fn raise_height(mask: &[f32], data: &mut [u16], delta: HeightDelta) {
+    for (d, w) in data.iter_mut().zip(mask.iter()).filter(|(_, w)| **w > 0.0) {
+        *d = d.saturating_add((delta.0 as f32 * w).round() as u16);
+    }
+}
+// ----------------------------------------------------------------------------
+#[inline(always)]
+fn lower_height(mask: &[f32], data: &mut [u16], delta: HeightDelta) {
+    for (d, w) in data.iter_mut().zip(mask.iter()).filter(|(_, w)| **w > 0.0) {
+        *d = d.saturating_sub((delta.0 as f32 * w).round() as u16);
+    }
+}
+// ----------------------------------------------------------------------------
+#[inline(always)]
+fn smooth_height(mask: &[f32], data: &mut [u16], width: usize, strength: SculptStrength) {
+    let height = data.len() / width;
+    let strength = strength.as_factor();
+    // averages must be calculated from unchanged data
+    let src = data.to_vec();
+
+    for (i, (d, w)) in data
+        .iter_mut()
+        .zip(mask.iter())
+        .enumerate()
+        .filter(|(_, (_, w))| **w > 0.0)
+    {
+        let (x, y) = (i % width, i / width);
+
+        // average of 3x3 neighborhood (clamped to brush rectangle)
+        let mut sum = 0u32;
+        let mut count = 0u32;
+        for ny in y.saturating_sub(1)..=(y + 1).min(height - 1) {
+            for nx in x.saturating_sub(1)..=(x + 1).min(width - 1) {
+                sum += src[ny * width + nx] as u32;
+                count += 1;
+            }
+        }
+        let average = sum as f32 / count as f32;
+        *d = (*d as f32 + (average - *d as f32) * strength * w).round() as u16;
+    }
+}
+// ----------------------------------------------------------------------------
+#[inline(always)]
+fn flatten_height(mask: &[f32], data: &mut [u16], strength: SculptStrength) {
+    let strength = strength.as_factor();
+
+    // flatten towards (weighted) average height of brush area
+    let (sum, weights) = data
+        .iter()
+        .zip(mask.iter())
+        .filter(|(_, w)| **w > 0.0)
+        .fold((0f64, 0f64), |(sum, weights), (d, w)| {
+            (sum + *d as f64 * *w as f64, weights + *w as f64)
+        });
+
+    if weights <= 0.0 {
+        return;
+    }
+    let target = (sum / weights) as f32;
+
+    for (d, w) in data.iter_mut().zip(mask.iter()).filter(|(_, w)| **w > 0.0) {
+        *d = (*d as f32 + (target - *d as f32) * strength * w).round() as u16;
+    }
+}
+// ----------------------------------------------------------------------------
+#[inline(always)]
+fn noise_height(rng: &mut PaintRng, mask: &[f32], data: &mut [u16], delta: HeightDelta) {
+    let delta = delta.0 as i32;
+    for (d, w) in data.iter_mut().zip(mask.iter()).filter(|(_, w)| **w > 0.0) {
+        let offset = (rng.gen_range(-delta..=delta) as f32 * w).round() as i32;
+        *d = (*d as i32 + offset).clamp(0, u16::MAX as i32) as u16;
+    }
+}
+// ----------------------------------------------------------------------------
 // painting event
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 impl PaintingEvent {
     // ------------------------------------------------------------------------
-    pub fn new(placement: BrushPlacement, cmds: Vec<PaintCommand>) -> Self {
-        Self(placement, cmds)
+    pub fn new(stroke: StrokeId, placement: BrushPlacement, cmds: Vec<PaintCommand>) -> Self {
+        Self(stroke, placement, cmds)
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl PaintCommand {
+    // ------------------------------------------------------------------------
+    pub(crate) fn is_tint_cmd(&self) -> bool {
+        use PaintCommand::*;
+        matches!(
+            self,
+            SetTint(_) | MultiplyTint(_) | LightenTint(_) | DarkenTint(_) | JitterTint(_)
+        )
+    }
+    // ------------------------------------------------------------------------
+    pub(crate) fn is_sculpt_cmd(&self) -> bool {
+        use PaintCommand::*;
+        matches!(
+            self,
+            RaiseHeight(_) | LowerHeight(_) | SmoothHeight(_) | FlattenHeight(_) | NoiseHeight(_)
+        )
+    }
+    // ------------------------------------------------------------------------
+    pub(crate) fn is_texture_control_cmd(&self) -> bool {
+        !self.is_tint_cmd() && !self.is_sculpt_cmd()
+    }
+    // ------------------------------------------------------------------------
+    #[cfg(feature = "editor")]
+    /// true if command changes a datapoint at most once per stroke (see
+    /// StrokeCoverage): randomized texture control commands roll their
+    /// overwrite probability or variance, jitter and noise add random offsets.
+    /// all other commands accumulate while the brush is held.
+    pub(crate) fn is_once_per_stroke_cmd(&self) -> bool {
+        use PaintCommand::*;
+        matches!(
+            self,
+            RandomizedSetOverlayMaterial(..)
+                | RandomizedSetBackgroundMaterial(..)
+                | SetBackgroundScalingWithVariance(..)
+                | IncreaseBackgroundScalingWithVariance(_)
+                | ReduceBackgroundScalingWithVariance(_)
+                | RandomizedSetBackgroundScaling(..)
+                | RandomizedSetBackgroundScalingWithVariance(..)
+                | RandomizedIncreaseBackgroundScaling(_)
+                | RandomizedIncreaseBackgroundScalingWithVariance(..)
+                | RandomizedReduceBackgroundScaling(_)
+                | RandomizedReduceBackgroundScalingWithVariance(..)
+                | SetSlopeBlendThresholdWithVariance(..)
+                | IncreaseSlopeBlendThresholdWithVariance(_)
+                | ReduceSlopeBlendThresholdWithVariance(_)
+                | RandomizedSetSlopeBlendThreshold(..)
+                | RandomizedSetSlopeBlendThresholdWithVariance(..)
+                | RandomizedIncreaseSlopeBlendThreshold(_)
+                | RandomizedIncreaseSlopeBlendThresholdWithVariance(..)
+                | RandomizedReduceSlopeBlendThreshold(_)
+                | RandomizedReduceSlopeBlendThresholdWithVariance(..)
+                | JitterTint(_)
+                | NoiseHeight(_)
+        )
+    }
+    // ------------------------------------------------------------------------
+    #[cfg(feature = "editor")]
+    /// true if command adds or removes terrain holes
+    pub(crate) fn is_hole_cmd(&self) -> bool {
+        matches!(self, PaintCommand::CutHole | PaintCommand::FillHole(..))
     }
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
+impl StrokeId {
+    // ------------------------------------------------------------------------
+    pub fn next(self) -> Self {
+        Self(self.0.wrapping_add(1))
+    }
+    // ------------------------------------------------------------------------
+    /// stroke counter (see PaintingRng)
+    pub fn id(&self) -> u32 {
+        self.0
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl From<u32> for StrokeId {
+    fn from(id: u32) -> Self {
+        Self(id)
+    }
+}
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 impl PickerEvent {
     // ------------------------------------------------------------------------
     pub fn new(placement: BrushPlacement, picks: Vec<PickedType>) -> Self {
@@ -625,6 +1403,7 @@ impl PickerEvent {
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 impl std::ops::Deref for PickerResultEvent {
     type Target = PickerResult;
 
@@ -640,9 +1419,15 @@ impl BrushPlacement {
         BrushPlacement {
             pos: world_pos,
             radius,
+            shape: BrushShape::default(),
         }
     }
     // ------------------------------------------------------------------------
+    pub fn with_shape(mut self, shape: BrushShape) -> Self {
+        self.shape = shape;
+        self
+    }
+    // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
 // helper trait for generic paint operations
@@ -671,6 +1456,12 @@ impl Variance {
     }
 }
 // ----------------------------------------------------------------------------
+impl SculptStrength {
+    fn as_factor(&self) -> f32 {
+        self.0.min(100) as f32 / 100.0
+    }
+}
+// ----------------------------------------------------------------------------
 // Default impl
 // ----------------------------------------------------------------------------
 #[allow(clippy::derivable_impls)]
@@ -686,6 +1477,30 @@ impl Default for SlopeBlendThreshold {
     }
 }
 // ----------------------------------------------------------------------------
+impl Default for TintStrength {
+    fn default() -> Self {
+        Self(8)
+    }
+}
+// ----------------------------------------------------------------------------
+impl Default for TintJitter {
+    fn default() -> Self {
+        Self(8)
+    }
+}
+// ----------------------------------------------------------------------------
+impl Default for HeightDelta {
+    fn default() -> Self {
+        Self(32)
+    }
+}
+// ----------------------------------------------------------------------------
+impl Default for SculptStrength {
+    fn default() -> Self {
+        Self(25)
+    }
+}
+// ----------------------------------------------------------------------------
 // Deref
 // ----------------------------------------------------------------------------
 use std::ops::Deref;
@@ -0,0 +1,130 @@
+// ----------------------------------------------------------------------------
+// deterministic randomization of paint commands
+// ----------------------------------------------------------------------------
+use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
+
+use tracing::{info, trace};
+
+use super::StrokeId;
+// ----------------------------------------------------------------------------
+pub(crate) type PaintRng = StdRng;
+// ----------------------------------------------------------------------------
+/// Every stroke uses its own rngs seeded with a seed derived from the session
+/// seed and the stroke id (which is a counter of strokes). Replaying the same
+/// strokes (same stroke ids and paint commands in the same order) with the same
+/// session seed reproduces the result bit-exactly.
+///
+/// Texture control, tint and sculpt commands of a stroke are processed by
+/// different systems in unspecified order. Every system uses a separate
+/// stream so the result does not depend on this order.
+pub struct PaintingRng {
+    session_seed: u64,
+    current: Option<(StrokeId, [PaintRng; 3])>,
+}
+// ----------------------------------------------------------------------------
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RngStream {
+    TextureControl,
+    Tint,
+    Sculpt,
+}
+// ----------------------------------------------------------------------------
+impl PaintingRng {
+    // ------------------------------------------------------------------------
+    pub fn new(session_seed: u64) -> Self {
+        Self {
+            session_seed,
+            current: None,
+        }
+    }
+    // ------------------------------------------------------------------------
+    pub fn session_seed(&self) -> u64 {
+        self.session_seed
+    }
+    // ------------------------------------------------------------------------
+    pub fn set_session_seed(&mut self, seed: u64) {
+        info!("painting: session seed set to {}", seed);
+        self.session_seed = seed;
+        self.current = None;
+    }
+    // ------------------------------------------------------------------------
+    /// seed all rng streams of the stroke are derived from
+    pub fn stroke_seed(&self, stroke: StrokeId) -> u64 {
+        splitmix64(self.session_seed ^ splitmix64(stroke.0 as u64))
+    }
+    // ------------------------------------------------------------------------
+    /// rng stream for the stroke. state of every stream continues for
+    /// subsequent paint events with the same stroke id.
+    pub(crate) fn stroke_rng(&mut self, stroke: StrokeId, stream: RngStream) -> &mut PaintRng {
+        match self.current {
+            Some((current, _)) if current == stroke => {}
+            _ => {
+                let seed = self.stroke_seed(stroke);
+                trace!("painting: stroke {:?} uses seed {}", stroke, seed);
+                let streams = [
+                    RngStream::TextureControl,
+                    RngStream::Tint,
+                    RngStream::Sculpt,
+                ]
+                .map(|stream| PaintRng::seed_from_u64(stream_seed(seed, stream)));
+                self.current = Some((stroke, streams));
+            }
+        }
+        // unwrap is safe: set above
+        &mut self.current.as_mut().unwrap().1[stream as usize]
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl Default for PaintingRng {
+    fn default() -> Self {
+        Self::new(thread_rng().gen())
+    }
+}
+// ----------------------------------------------------------------------------
+// helper
+// ----------------------------------------------------------------------------
+#[inline]
+fn stream_seed(stroke_seed: u64, stream: RngStream) -> u64 {
+    splitmix64(stroke_seed ^ splitmix64(stream as u64 + 1))
+}
+// ----------------------------------------------------------------------------
+#[inline]
+fn splitmix64(v: u64) -> u64 {
+    let mut z = v.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+// ----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streams_do_not_depend_on_processing_order() {
+        use RngStream::{Sculpt, TextureControl, Tint};
+
+        let stroke = StrokeId::default().next();
+        let sample = |order: &[RngStream]| {
+            let mut rng = PaintingRng::new(42);
+            let mut values = order
+                .iter()
+                .map(|stream| {
+                    (
+                        *stream as usize,
+                        rng.stroke_rng(stroke, *stream).gen::<u64>(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            values.sort_unstable();
+            values
+        };
+
+        let values = sample(&[TextureControl, Tint, Sculpt]);
+        assert_eq!(values, sample(&[Sculpt, Tint, TextureControl]));
+        // streams differ from each other
+        assert!(values[0].1 != values[1].1 && values[1].1 != values[2].1);
+    }
+}
+// ----------------------------------------------------------------------------
@@ -0,0 +1,523 @@
+// ----------------------------------------------------------------------------
+// global remapping of material slots (e.g. after reordering a material set)
+// ----------------------------------------------------------------------------
+use tracing::warn;
+
+use crate::terrain_material::MaterialSlot;
+
+use super::{is_terrain_hole, pick_value, set_bits};
+use super::{BKGRND_TEXTURE_BITMASK, BKGRND_TEXTURE_BITPOS};
+use super::{OVERLAY_TEXTURE_BITMASK, OVERLAY_TEXTURE_BITPOS};
+
+#[cfg(feature = "editor")]
+use {
+    super::history::PaintingHistory,
+    super::{AutoTexturingEvent, PaintingEvent, PaintingHistoryEvent, SelectionEvent},
+    bevy::{math::uvec2, prelude::*, tasks::ComputeTaskPool},
+};
+
+#[cfg(feature = "editor")]
+use crate::{
+    clipmap::Rectangle,
+    cmds::{AsyncCommandManager, AsyncTaskFinishedEvent, AsyncTaskStartEvent},
+    cmds::{RemapMaterials, TrackedProgress},
+    config::TerrainConfig,
+    terrain_clipmap::{ClipmapTracker, TextureControlClipmap},
+    EditorEvent,
+};
+// ----------------------------------------------------------------------------
+/// rows of the control map processed in one (parallelized) step
+#[cfg(feature = "editor")]
+const BAND_ROWS: u32 = 256;
+/// rows of a band processed by one thread
+#[cfg(feature = "editor")]
+const BAND_CHUNK_ROWS: u32 = 32;
+/// max time per frame spent in remapping (multiple bands are processed until
+/// time is exceeded)
+#[cfg(feature = "editor")]
+const MAX_REMAPPING_TIME_MS: instant::Duration = instant::Duration::from_millis(30);
+/// material slots addressable in texture control map (zero is reserved for
+/// holes)
+const MAX_MATERIAL_SLOTS: u8 = 31;
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+#[derive(Debug)]
+pub enum MaterialRemapEvent {
+    /// counts datapoints changed by the remapping without changing the map
+    Preview(MaterialRemapTable),
+    /// remaps complete map (as one undo step)
+    Apply(MaterialRemapTable),
+}
+// ----------------------------------------------------------------------------
+/// result of a finished remap preview or remapping
+#[cfg(feature = "editor")]
+pub struct MaterialRemapResultEvent {
+    pub table: MaterialRemapTable,
+    pub stats: MaterialRemapStats,
+}
+// ----------------------------------------------------------------------------
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemapTarget {
+    Overlay,
+    Background,
+    Both,
+}
+// ----------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Maps material slots to other material slots. All entries are applied
+/// simultaneously, e.g. two slots can be swapped. If a slot is mapped more
+/// than once the last entry is used. Holes are never changed.
+pub struct MaterialRemapTable {
+    pub target: RemapTarget,
+    /// (from, to) slot pairs
+    pub entries: Vec<(MaterialSlot, MaterialSlot)>,
+}
+// ----------------------------------------------------------------------------
+#[derive(Debug, Clone, Default)]
+pub struct MaterialRemapStats {
+    /// changed overlay datapoints per remap table entry
+    pub overlay: Vec<usize>,
+    /// changed background datapoints per remap table entry
+    pub background: Vec<usize>,
+    /// datapoints with at least one changed material
+    pub datapoints: usize,
+    /// false for preview stats
+    pub applied: bool,
+}
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+#[derive(Default)]
+pub(super) struct MaterialRemapping {
+    request: Option<(MaterialRemapTable, bool)>,
+    remapping: Option<Remapping>,
+}
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+struct Remapping {
+    table: MaterialRemapTable,
+    lookup: RemapLookup,
+    /// false only counts changes (preview)
+    apply: bool,
+    map_size: u32,
+    next_row: u32,
+    stats: MaterialRemapStats,
+    /// changed bands with texture control data before remapping
+    backup: Vec<(Rectangle, Vec<u16>)>,
+}
+// ----------------------------------------------------------------------------
+/// new material value and responsible remap table entry per current material
+/// value (None is unchanged)
+#[derive(Clone, Copy)]
+struct RemapLookup {
+    overlay: [Option<(u16, usize)>; 32],
+    background: [Option<(u16, usize)>; 32],
+}
+// ----------------------------------------------------------------------------
+// systems
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+#[allow(clippy::too_many_arguments)]
+pub(super) fn process_remap_events(
+    mut events: EventReader<MaterialRemapEvent>,
+    mut paint_events: EventReader<PaintingEvent>,
+    mut history_events: EventReader<PaintingHistoryEvent>,
+    mut autotexturing_events: EventReader<AutoTexturingEvent>,
+    mut selection_events: EventReader<SelectionEvent>,
+    mut remapping: ResMut<MaterialRemapping>,
+    mut texture_clipmap: ResMut<TextureControlClipmap>,
+    mut clipmap_tracker: ResMut<ClipmapTracker>,
+    mut task_manager: ResMut<AsyncCommandManager>,
+    mut task_finished: EventWriter<AsyncTaskFinishedEvent>,
+) {
+    // changes on top of a partially remapped map would break the undo step
+    // -> cancel running remapping first
+    // (count instead of any: all events must be consumed)
+    let autotexturing = autotexturing_events
+        .iter()
+        .filter(|e| matches!(e, AutoTexturingEvent::Preview(..)))
+        .count()
+        > 0;
+    let pasting = selection_events
+        .iter()
+        .filter(|e| matches!(e, SelectionEvent::Paste(..)))
+        .count()
+        > 0;
+    let changing =
+        !paint_events.is_empty() || !history_events.is_empty() || autotexturing || pasting;
+    paint_events.iter().for_each(drop);
+    history_events.iter().for_each(drop);
+
+    if changing && remapping.as_ref().is_applying() {
+        info!("material remapping: painting operation cancels remapping.");
+        if remapping.cancel(&mut texture_clipmap) {
+            task_finished.send(AsyncTaskFinishedEvent::MaterialsRemapped);
+        }
+        clipmap_tracker.force_update();
+    }
+
+    for event in events.iter() {
+        if remapping.remapping.is_some() || remapping.request.is_some() {
+            warn!("material remapping: remapping still in progress. ignoring request.");
+            continue;
+        }
+        let (table, apply) = match event {
+            MaterialRemapEvent::Preview(table) => (table, false),
+            MaterialRemapEvent::Apply(table) => (table, true),
+        };
+        remapping.request = Some((table.clone(), apply));
+        task_manager.add_new(RemapMaterials.into());
+    }
+}
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+#[allow(clippy::too_many_arguments)]
+pub(super) fn remap_materials(
+    config: Res<TerrainConfig>,
+    thread_pool: Res<ComputeTaskPool>,
+    mut remapping: ResMut<MaterialRemapping>,
+    mut texture_clipmap: ResMut<TextureControlClipmap>,
+    mut clipmap_tracker: ResMut<ClipmapTracker>,
+    mut history: ResMut<PaintingHistory>,
+    mut tasks_queued: EventReader<AsyncTaskStartEvent>,
+    mut task_finished: EventWriter<AsyncTaskFinishedEvent>,
+    mut remap_results: EventWriter<MaterialRemapResultEvent>,
+    mut editor_events: EventWriter<EditorEvent>,
+) {
+    use instant::Instant;
+
+    for task in tasks_queued.iter() {
+        if let AsyncTaskStartEvent::RemapMaterials = task {
+            match remapping.request.take() {
+                Some((table, apply)) => {
+                    debug!("remapping materials {:?} (apply: {})...", table, apply);
+
+                    if apply {
+                        // see process_brush_operations
+                        texture_clipmap.disable_cache();
+                    }
+                    remapping.remapping = Some(Remapping {
+                        lookup: RemapLookup::from(&table),
+                        stats: MaterialRemapStats::new(&table, apply),
+                        table,
+                        apply,
+                        map_size: config.map_size(),
+                        next_row: 0,
+                        backup: Vec::default(),
+                    });
+                }
+                None => {
+                    // canceled
+                    task_finished.send(AsyncTaskFinishedEvent::MaterialsRemapped);
+                }
+            }
+        }
+    }
+
+    let running = match remapping.remapping.as_mut() {
+        Some(running) => running,
+        None => return,
+    };
+
+    let start_time = Instant::now();
+    let map_size = running.map_size;
+    let chunk_size = (BAND_CHUNK_ROWS * map_size) as usize;
+
+    // ...measure duration after every band
+    while running.next_row < map_size
+        && Instant::now().duration_since(start_time) < MAX_REMAPPING_TIME_MS
+    {
+        let rows = BAND_ROWS.min(map_size - running.next_row);
+        let band = Rectangle {
+            pos: uvec2(0, running.next_row),
+            size: uvec2(map_size, rows),
+        };
+        let original = texture_clipmap.extract_fullres(&band);
+        let mut data = original.clone();
+
+        let lookup = &running.lookup;
+        let entries = running.table.entries.len();
+
+        let band_stats = thread_pool.scope(|s| {
+            for chunk in data.chunks_mut(chunk_size) {
+                s.spawn(async move {
+                    let mut stats = MaterialRemapStats::with_entries(entries);
+                    lookup.remap(chunk, &mut stats);
+                    stats
+                });
+            }
+        });
+
+        let changed = band_stats.iter().any(|stats| stats.datapoints > 0);
+        band_stats.iter().for_each(|stats| running.stats.add(stats));
+
+        if running.apply && changed {
+            texture_clipmap.update_fullres(&band, &data);
+            running.backup.push((band, original));
+        }
+        running.next_row += rows;
+    }
+
+    // progress update for GUI
+    editor_events.send(EditorEvent::ProgressTrackingUpdate(
+        TrackedProgress::RemappedMaterials(running.next_row as usize, map_size as usize),
+    ));
+
+    if running.next_row >= map_size {
+        if let Some(finished) = remapping.remapping.take() {
+            if finished.apply {
+                info!(
+                    "material remapping: changed {} datapoints.",
+                    finished.stats.datapoints
+                );
+                if !finished.backup.is_empty() {
+                    history.record_texture_control_patches(finished.backup);
+                    clipmap_tracker.force_update();
+                }
+            }
+            remap_results.send(MaterialRemapResultEvent {
+                table: finished.table,
+                stats: finished.stats,
+            });
+        }
+        task_finished.send(AsyncTaskFinishedEvent::MaterialsRemapped);
+    }
+}
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+pub(super) fn reset_remapping(mut remapping: ResMut<MaterialRemapping>) {
+    *remapping = MaterialRemapping::default();
+}
+// ----------------------------------------------------------------------------
+/// remaps material slots of texture control data and returns changed
+/// datapoints per remap table entry
+pub(crate) fn remap_texture_control(
+    table: &MaterialRemapTable,
+    data: &mut [u16],
+) -> MaterialRemapStats {
+    let mut stats = MaterialRemapStats::new(table, true);
+    RemapLookup::from(table).remap(data, &mut stats);
+    stats
+}
+// ----------------------------------------------------------------------------
+// impl
+// ----------------------------------------------------------------------------
+impl MaterialRemapTable {
+    // ------------------------------------------------------------------------
+    /// replaces every usage of a material slot with another slot
+    pub fn replace_all(target: RemapTarget, from: MaterialSlot, to: MaterialSlot) -> Self {
+        Self {
+            target,
+            entries: vec![(from, to)],
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// true if at least one entry changes a material slot
+    pub fn has_changes(&self) -> bool {
+        self.entries
+            .iter()
+            .any(|(from, to)| from != to && is_valid_slot(*from) && is_valid_slot(*to))
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl MaterialRemapStats {
+    // ------------------------------------------------------------------------
+    /// changed overlay and background datapoints of remap table entry
+    pub fn entry_changes(&self, entry: usize) -> (usize, usize) {
+        (
+            self.overlay.get(entry).copied().unwrap_or_default(),
+            self.background.get(entry).copied().unwrap_or_default(),
+        )
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+// helper
+// ----------------------------------------------------------------------------
+#[inline]
+fn is_valid_slot(slot: MaterialSlot) -> bool {
+    *slot < MAX_MATERIAL_SLOTS
+}
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+impl MaterialRemapping {
+    // ------------------------------------------------------------------------
+    fn is_applying(&self) -> bool {
+        matches!(&self.request, Some((_, true)))
+            || matches!(&self.remapping, Some(remapping) if remapping.apply)
+    }
+    // ------------------------------------------------------------------------
+    /// restores all already remapped bands. returns true if a running
+    /// remapping was canceled.
+    fn cancel(&mut self, texture_clipmap: &mut TextureControlClipmap) -> bool {
+        self.request = None;
+        if let Some(remapping) = self.remapping.take() {
+            for (band, data) in &remapping.backup {
+                texture_clipmap.update_fullres(band, data);
+            }
+            true
+        } else {
+            false
+        }
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl MaterialRemapStats {
+    // ------------------------------------------------------------------------
+    fn new(table: &MaterialRemapTable, applied: bool) -> Self {
+        Self {
+            applied,
+            ..Self::with_entries(table.entries.len())
+        }
+    }
+    // ------------------------------------------------------------------------
+    fn with_entries(entries: usize) -> Self {
+        Self {
+            overlay: vec![0; entries],
+            background: vec![0; entries],
+            datapoints: 0,
+            applied: false,
+        }
+    }
+    // ------------------------------------------------------------------------
+    #[cfg(feature = "editor")]
+    fn add(&mut self, other: &MaterialRemapStats) {
+        for (count, other) in self.overlay.iter_mut().zip(other.overlay.iter()) {
+            *count += other;
+        }
+        for (count, other) in self.background.iter_mut().zip(other.background.iter()) {
+            *count += other;
+        }
+        self.datapoints += other.datapoints;
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl RemapLookup {
+    // ------------------------------------------------------------------------
+    fn remap(&self, data: &mut [u16], stats: &mut MaterialRemapStats) {
+        for value in data.iter_mut().filter(|value| !is_terrain_hole(**value)) {
+            let original = *value;
+
+            let overlay = pick_value::<OVERLAY_TEXTURE_BITMASK, OVERLAY_TEXTURE_BITPOS>(original);
+            if let Some((new, entry)) = self.overlay[overlay as usize] {
+                *value = set_bits::<OVERLAY_TEXTURE_BITMASK, OVERLAY_TEXTURE_BITPOS>(*value, new);
+                stats.overlay[entry] += 1;
+            }
+            let bkgrnd = pick_value::<BKGRND_TEXTURE_BITMASK, BKGRND_TEXTURE_BITPOS>(original);
+            if let Some((new, entry)) = self.background[bkgrnd as usize] {
+                *value = set_bits::<BKGRND_TEXTURE_BITMASK, BKGRND_TEXTURE_BITPOS>(*value, new);
+                stats.background[entry] += 1;
+            }
+            if *value != original {
+                stats.datapoints += 1;
+            }
+        }
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl From<&MaterialRemapTable> for RemapLookup {
+    fn from(table: &MaterialRemapTable) -> Self {
+        let mut lookup = Self {
+            overlay: [None; 32],
+            background: [None; 32],
+        };
+        let (overlay, background) = match table.target {
+            RemapTarget::Overlay => (true, false),
+            RemapTarget::Background => (false, true),
+            RemapTarget::Both => (true, true),
+        };
+
+        for (entry, (from, to)) in table.entries.iter().enumerate() {
+            if !is_valid_slot(*from) || !is_valid_slot(*to) {
+                warn!(
+                    "material remapping: ignoring invalid entry {} -> {}",
+                    from, to
+                );
+                continue;
+            }
+            // zero is reserved for holes
+            let (value, new) = (**from as usize + 1, **to as u16 + 1);
+            let mapping = (from != to).then_some((new, entry));
+
+            if overlay {
+                lookup.overlay[value] = mapping;
+            }
+            if background {
+                lookup.background[value] = mapping;
+            }
+        }
+        lookup
+    }
+}
+// ----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// control map value for material slots (blend control bits set)
+    fn value(overlay: u8, background: u8) -> u16 {
+        0b1010_1100_0000_0000 | (overlay as u16 + 1) | ((background as u16 + 1) << 5)
+    }
+
+    fn table(target: RemapTarget, entries: &[(u8, u8)]) -> MaterialRemapTable {
+        MaterialRemapTable {
+            target,
+            entries: entries
+                .iter()
+                .map(|(from, to)| (MaterialSlot::from(*from), MaterialSlot::from(*to)))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn swapped_slots_are_remapped_simultaneously() {
+        let mut data = vec![value(0, 1), value(1, 0), value(2, 2)];
+
+        let stats = remap_texture_control(&table(RemapTarget::Both, &[(0, 1), (1, 0)]), &mut data);
+
+        assert_eq!(data, vec![value(1, 0), value(0, 1), value(2, 2)]);
+        assert_eq!(stats.entry_changes(0), (1, 1));
+        assert_eq!(stats.entry_changes(1), (1, 1));
+        assert_eq!(stats.datapoints, 2);
+    }
+
+    #[test]
+    fn only_target_layer_is_remapped() {
+        let swap = [(0, 1), (1, 0)];
+
+        let mut data = vec![value(0, 1)];
+        remap_texture_control(&table(RemapTarget::Overlay, &swap), &mut data);
+        assert_eq!(data, vec![value(1, 1)]);
+
+        let mut data = vec![value(0, 1)];
+        remap_texture_control(&table(RemapTarget::Background, &swap), &mut data);
+        assert_eq!(data, vec![value(0, 0)]);
+    }
+
+    #[test]
+    fn last_entry_of_a_slot_is_used() {
+        let mut data = vec![value(0, 3)];
+
+        let stats = remap_texture_control(&table(RemapTarget::Both, &[(0, 1), (0, 2)]), &mut data);
+
+        assert_eq!(data, vec![value(2, 3)]);
+        assert_eq!(stats.entry_changes(0), (0, 0));
+        assert_eq!(stats.entry_changes(1), (1, 0));
+    }
+
+    #[test]
+    fn holes_and_invalid_entries_are_ignored() {
+        let hole = value(0, 0) & !BKGRND_TEXTURE_BITMASK;
+        let mut data = vec![hole, value(0, 0)];
+
+        let remap = table(RemapTarget::Both, &[(0, 1), (MAX_MATERIAL_SLOTS, 0)]);
+        let stats = remap_texture_control(&remap, &mut data);
+
+        assert_eq!(data, vec![hole, value(1, 1)]);
+        assert_eq!(stats.datapoints, 1);
+        assert!(!table(RemapTarget::Both, &[(0, 0), (31, 2)]).has_changes());
+    }
+}
@@ -0,0 +1,518 @@
+// ----------------------------------------------------------------------------
+// rectangle and lasso selections of map regions: painting is restricted to the
+// selected datapoints. selected texture control (and optionally tint) data can
+// be copied into a clipboard and pasted with 90° rotations and mirroring.
+// ----------------------------------------------------------------------------
+use bevy::math::{ivec2, uvec2, IVec2};
+use bevy::prelude::*;
+
+use crate::clipmap::Rectangle;
+use crate::config::TerrainConfig;
+use crate::terrain_clipmap::{ClipmapTracker, TextureControlClipmap, TintClipmap};
+use crate::texturecontrol::TerrainHoleChanges;
+
+use super::history::PaintingHistory;
+use super::{is_terrain_hole, StrokeId};
+// ----------------------------------------------------------------------------
+/// RGBA tint datapoints
+const TINT_DATAPOINT_SIZE: usize = 4;
+// ----------------------------------------------------------------------------
+#[derive(Debug)]
+pub enum SelectionEvent {
+    /// starts (new stroke) or extends (same stroke) the selection with the
+    /// world position
+    Select(StrokeId, SelectionShape, Vec2),
+    Clear,
+    /// copies selected texture control (and optionally tint) data into the
+    /// clipboard
+    Copy {
+        include_tint: bool,
+    },
+    /// pastes clipboard centered at world position (once per stroke)
+    Paste(StrokeId, Vec2, ClipboardTransform),
+}
+// ----------------------------------------------------------------------------
+#[derive(Debug)]
+pub enum SelectionResultEvent {
+    /// world space bounds (min, max) of the current selection. None if nothing
+    /// is selected.
+    Selection(Option<(Vec2, Vec2)>),
+    /// size (in datapoints) of copied region and if tint data was included
+    Clipboard(UVec2, bool),
+}
+// ----------------------------------------------------------------------------
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionShape {
+    /// spanned by start and current position of stroke
+    Rectangle,
+    /// freehand polygon along the stroke (implicitly closed)
+    Lasso,
+}
+// ----------------------------------------------------------------------------
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// transformation of clipboard data on paste. mirroring is applied before the
+/// rotation.
+pub struct ClipboardTransform {
+    /// number of 90° rotations (0..3)
+    pub rotation: u8,
+    /// mirrored along the vertical axis
+    pub mirror: bool,
+}
+// ----------------------------------------------------------------------------
+#[derive(Default)]
+pub(super) struct PaintingSelection {
+    stroke: Option<StrokeId>,
+    shape: Option<SelectionShape>,
+    /// stroke positions in map coordinates
+    path: Vec<UVec2>,
+    region: Option<SelectionMask>,
+}
+// ----------------------------------------------------------------------------
+#[derive(Default)]
+pub(super) struct SelectionClipboard {
+    content: Option<ClipboardContent>,
+    /// stroke of last paste operation
+    pasted: Option<StrokeId>,
+}
+// ----------------------------------------------------------------------------
+/// selected datapoints within bounding rectangle
+struct SelectionMask {
+    rectangle: Rectangle,
+    mask: Vec<bool>,
+}
+// ----------------------------------------------------------------------------
+struct ClipboardContent {
+    size: UVec2,
+    mask: Vec<bool>,
+    texture_control: Vec<u16>,
+    tint: Option<Vec<u8>>,
+}
+// ----------------------------------------------------------------------------
+// systems
+// ----------------------------------------------------------------------------
+#[allow(clippy::too_many_arguments)]
+pub(super) fn process_selection_events(
+    config: Res<TerrainConfig>,
+    mut events: EventReader<SelectionEvent>,
+    mut results: EventWriter<SelectionResultEvent>,
+    mut selection: ResMut<PaintingSelection>,
+    mut clipboard: ResMut<SelectionClipboard>,
+    mut texture_clipmap: ResMut<TextureControlClipmap>,
+    mut tint_clipmap: ResMut<TintClipmap>,
+    mut hole_changes: ResMut<TerrainHoleChanges>,
+    mut clipmap_tracker: ResMut<ClipmapTracker>,
+    mut history: ResMut<PaintingHistory>,
+) {
+    for event in events.iter() {
+        match event {
+            SelectionEvent::Select(stroke, shape, pos) => {
+                if selection.extend(*stroke, *shape, config.world_pos_to_map_pos(*pos)) {
+                    results.send(SelectionResultEvent::Selection(selection.bounds(&config)));
+                }
+            }
+            SelectionEvent::Clear => {
+                *selection = PaintingSelection::default();
+                results.send(SelectionResultEvent::Selection(None));
+            }
+            SelectionEvent::Copy { include_tint } => {
+                let region = match selection.region.as_ref() {
+                    Some(region) => region,
+                    None => {
+                        warn!("selection: nothing selected to copy.");
+                        continue;
+                    }
+                };
+                let tint = include_tint.then(|| tint_clipmap.extract_fullres(&region.rectangle));
+
+                clipboard.content = Some(ClipboardContent {
+                    size: region.rectangle.size,
+                    mask: region.mask.clone(),
+                    texture_control: texture_clipmap.extract_fullres(&region.rectangle),
+                    tint,
+                });
+                info!("selection: copied {:?} datapoints.", region.rectangle.size);
+                results.send(SelectionResultEvent::Clipboard(
+                    region.rectangle.size,
+                    *include_tint,
+                ));
+            }
+            // holding the button must not paste again while the pointer moves
+            SelectionEvent::Paste(stroke, _, _) if clipboard.pasted == Some(*stroke) => {}
+            SelectionEvent::Paste(stroke, pos, transform) => {
+                let content = match clipboard.content.as_ref() {
+                    Some(content) => content,
+                    None => {
+                        warn!("selection: clipboard is empty.");
+                        continue;
+                    }
+                };
+                let center = config.world_pos_to_map_pos(*pos);
+                let (rectangle, mapping) =
+                    match content.paste_mapping(center, config.map_size(), *transform) {
+                        Some(result) => result,
+                        None => continue,
+                    };
+
+                // see process_brush_operations
+                texture_clipmap.disable_cache();
+                tint_clipmap.disable_cache();
+
+                let mut data = texture_clipmap.extract_fullres(&rectangle);
+                history.record_texture_control(*stroke, &rectangle, &data);
+
+                let holes_changed = data.iter().any(|d| is_terrain_hole(*d));
+                for (target, source) in &mapping {
+                    data[*target] = content.texture_control[*source];
+                }
+                let holes_changed = holes_changed || data.iter().any(|d| is_terrain_hole(*d));
+                texture_clipmap.update_fullres(&rectangle, &data);
+
+                if let Some(tint) = content.tint.as_ref() {
+                    let mut data = tint_clipmap.extract_fullres(&rectangle);
+                    history.record_tint(*stroke, &rectangle, &data);
+
+                    for (target, source) in &mapping {
+                        let target = target * TINT_DATAPOINT_SIZE;
+                        let source = source * TINT_DATAPOINT_SIZE;
+                        data[target..target + TINT_DATAPOINT_SIZE]
+                            .copy_from_slice(&tint[source..source + TINT_DATAPOINT_SIZE]);
+                    }
+                    tint_clipmap.update_fullres(&rectangle, &data);
+                }
+                clipmap_tracker.force_update();
+
+                // holes are cut out of the tile meshes
+                if holes_changed {
+                    hole_changes.add(&rectangle);
+                }
+                clipboard.pasted = Some(*stroke);
+            }
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+pub(super) fn reset_selection(
+    mut selection: ResMut<PaintingSelection>,
+    mut results: EventWriter<SelectionResultEvent>,
+) {
+    // clipboard content is independent of the terrain and kept
+    *selection = PaintingSelection::default();
+    results.send(SelectionResultEvent::Selection(None));
+}
+// ----------------------------------------------------------------------------
+// selection
+// ----------------------------------------------------------------------------
+impl PaintingSelection {
+    // ------------------------------------------------------------------------
+    /// sets brush weights of all datapoints outside of the selection to zero.
+    /// without selection the mask is unchanged.
+    pub(super) fn restrict(&self, rectangle: &Rectangle, mask: &mut [f32]) {
+        if let Some(region) = self.region.as_ref() {
+            let width = rectangle.size.x as usize;
+            for (i, weight) in mask.iter_mut().enumerate() {
+                let pos = rectangle.pos + uvec2((i % width) as u32, (i / width) as u32);
+                if !region.contains(pos) {
+                    *weight = 0.0;
+                }
+            }
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// returns true if the selection changed
+    fn extend(&mut self, stroke: StrokeId, shape: SelectionShape, pos: UVec2) -> bool {
+        if self.stroke != Some(stroke) || self.shape != Some(shape) {
+            self.stroke = Some(stroke);
+            self.shape = Some(shape);
+            self.path.clear();
+        }
+        // pointer events are sent every frame even without movement
+        if self.path.last() == Some(&pos) {
+            return false;
+        }
+        match shape {
+            SelectionShape::Rectangle => {
+                // only start and current corner are relevant
+                self.path.truncate(1);
+                self.path.push(pos);
+            }
+            SelectionShape::Lasso => self.path.push(pos),
+        }
+        self.region = match shape {
+            SelectionShape::Rectangle => SelectionMask::from_rectangle(&self.path),
+            SelectionShape::Lasso => SelectionMask::from_polygon(&self.path),
+        };
+        true
+    }
+    // ------------------------------------------------------------------------
+    /// world space bounds of selected region (outer borders of datapoints)
+    fn bounds(&self, config: &TerrainConfig) -> Option<(Vec2, Vec2)> {
+        self.region.as_ref().map(|region| {
+            let min = region.rectangle.pos.as_vec2() - Vec2::splat(0.5);
+            let max = min + region.rectangle.size.as_vec2();
+
+            (
+                config.map_offset() + min * config.resolution(),
+                config.map_offset() + max * config.resolution(),
+            )
+        })
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl SelectionMask {
+    // ------------------------------------------------------------------------
+    /// rectangle spanned by two corners (inclusive). a single position is
+    /// interpreted as empty selection.
+    fn from_rectangle(corners: &[UVec2]) -> Option<Self> {
+        match corners {
+            [start, end] => {
+                let min = start.min(*end);
+                let size = start.max(*end) - min + uvec2(1, 1);
+                Some(Self {
+                    rectangle: Rectangle { pos: min, size },
+                    mask: vec![true; (size.x * size.y) as usize],
+                })
+            }
+            _ => None,
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// datapoints inside the (implicitly closed) polygon. uses scanline
+    /// filling with even-odd rule.
+    fn from_polygon(points: &[UVec2]) -> Option<Self> {
+        if points.len() < 3 {
+            return None;
+        }
+        let min = points.iter().fold(points[0], |min, p| min.min(*p));
+        let max = points.iter().fold(points[0], |max, p| max.max(*p));
+        let size = max - min + uvec2(1, 1);
+
+        let mut mask = vec![false; (size.x * size.y) as usize];
+        let mut crossings = Vec::new();
+
+        for y in min.y..=max.y {
+            // datapoint centers are at integer positions
+            let scanline = y as f32;
+            crossings.clear();
+
+            for (a, b) in points.iter().zip(points.iter().cycle().skip(1)) {
+                let (a, b) = (a.as_vec2(), b.as_vec2());
+                if (a.y <= scanline) != (b.y <= scanline) {
+                    crossings.push(a.x + (scanline - a.y) * (b.x - a.x) / (b.y - a.y));
+                }
+            }
+            crossings.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+            let row = ((y - min.y) * size.x) as usize;
+            for span in crossings.chunks_exact(2) {
+                let from = (span[0].ceil() as u32).max(min.x);
+                let to = (span[1].floor() as u32).min(max.x);
+                for x in from..=to {
+                    mask[row + (x - min.x) as usize] = true;
+                }
+            }
+        }
+
+        mask.iter().any(|selected| *selected).then_some(Self {
+            rectangle: Rectangle { pos: min, size },
+            mask,
+        })
+    }
+    // ------------------------------------------------------------------------
+    #[inline(always)]
+    fn contains(&self, pos: UVec2) -> bool {
+        let Rectangle { pos: min, size } = self.rectangle;
+
+        if pos.x < min.x || pos.y < min.y || pos.x >= min.x + size.x || pos.y >= min.y + size.y {
+            return false;
+        }
+        let offset = pos - min;
+        self.mask[(offset.y * size.x + offset.x) as usize]
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+// clipboard
+// ----------------------------------------------------------------------------
+impl ClipboardContent {
+    // ------------------------------------------------------------------------
+    /// calculates target rectangle (clipped to map) of transformed content
+    /// centered at map position and the (target, source) index pairs of all
+    /// selected datapoints within it.
+    fn paste_mapping(
+        &self,
+        center: UVec2,
+        map_size: u32,
+        transform: ClipboardTransform,
+    ) -> Option<(Rectangle, Vec<(usize, usize)>)> {
+        let size = self.size.as_ivec2();
+        let rotation = transform.rotation % 4;
+
+        let target_size = if rotation % 2 == 1 {
+            ivec2(size.y, size.x)
+        } else {
+            size
+        };
+        let origin = center.as_ivec2() - target_size / 2;
+
+        // clip to map
+        let min = origin.max(IVec2::ZERO);
+        let max = (origin + target_size).min(IVec2::splat(map_size as i32));
+        if min.x >= max.x || min.y >= max.y {
+            return None;
+        }
+        let rectangle = Rectangle {
+            pos: min.as_uvec2(),
+            size: (max - min).as_uvec2(),
+        };
+
+        let mut mapping = Vec::with_capacity((rectangle.size.x * rectangle.size.y) as usize);
+        for y in min.y..max.y {
+            for x in min.x..max.x {
+                // inverse transformation: target -> unrotated -> unmirrored source
+                let local = ivec2(x, y) - origin;
+                let unrotated = match rotation {
+                    1 => ivec2(local.y, size.y - 1 - local.x),
+                    2 => ivec2(size.x - 1 - local.x, size.y - 1 - local.y),
+                    3 => ivec2(size.x - 1 - local.y, local.x),
+                    _ => local,
+                };
+                let source = if transform.mirror {
+                    ivec2(size.x - 1 - unrotated.x, unrotated.y)
+                } else {
+                    unrotated
+                };
+                let source = (source.y * size.x + source.x) as usize;
+
+                if self.mask[source] {
+                    let target = ((y - min.y) * (max.x - min.x) + (x - min.x)) as usize;
+                    mapping.push((target, source));
+                }
+            }
+        }
+        Some((rectangle, mapping))
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 3x2 content with source index as value:
+    ///   0 1 2
+    ///   3 4 5
+    fn content(mask: Vec<bool>) -> ClipboardContent {
+        ClipboardContent {
+            size: uvec2(3, 2),
+            mask,
+            texture_control: (0..6).collect(),
+            tint: None,
+        }
+    }
+
+    fn transform(rotation: u8, mirror: bool) -> ClipboardTransform {
+        ClipboardTransform { rotation, mirror }
+    }
+
+    /// pasted source indices (target rectangle row by row, None if unselected)
+    fn pasted(
+        content: &ClipboardContent,
+        center: UVec2,
+        transform: ClipboardTransform,
+    ) -> (Rectangle, Vec<Option<usize>>) {
+        let (rectangle, mapping) = content.paste_mapping(center, 16, transform).unwrap();
+        let mut result = vec![None; (rectangle.size.x * rectangle.size.y) as usize];
+        for (target, source) in mapping {
+            result[target] = Some(source);
+        }
+        (rectangle, result)
+    }
+
+    /// forward transformation (mirror, then rotate clockwise) of all datapoints
+    fn transformed(size: UVec2, transform: ClipboardTransform) -> (UVec2, Vec<usize>) {
+        let mut size = size;
+        let mut grid = (0..(size.x * size.y) as usize).collect::<Vec<_>>();
+        if transform.mirror {
+            for row in grid.chunks_mut(size.x as usize) {
+                row.reverse();
+            }
+        }
+        for _ in 0..transform.rotation % 4 {
+            let mut rotated = vec![0; grid.len()];
+            for y in 0..size.y {
+                for x in 0..size.x {
+                    let target = x * size.y + (size.y - 1 - y);
+                    rotated[target as usize] = grid[(y * size.x + x) as usize];
+                }
+            }
+            grid = rotated;
+            size = uvec2(size.y, size.x);
+        }
+        (size, grid)
+    }
+
+    #[test]
+    fn paste_rotated_clockwise() {
+        let (rectangle, result) = pasted(&content(vec![true; 6]), uvec2(1, 1), transform(1, false));
+
+        assert_eq!(rectangle.pos, uvec2(0, 0));
+        assert_eq!(rectangle.size, uvec2(2, 3));
+        let expected = [3, 0, 4, 1, 5, 2];
+        assert_eq!(
+            result,
+            expected.iter().map(|i| Some(*i)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn paste_mirrors_before_rotation() {
+        let (_, result) = pasted(&content(vec![true; 6]), uvec2(1, 1), transform(1, true));
+
+        // mirrored: 2 1 0 / 5 4 3, rotated clockwise
+        let expected = [5, 2, 4, 1, 3, 0];
+        assert_eq!(
+            result,
+            expected.iter().map(|i| Some(*i)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn paste_mapping_inverts_all_transformations() {
+        let content = content(vec![true; 6]);
+
+        for rotation in 0..8 {
+            for mirror in [false, true] {
+                let transform = transform(rotation, mirror);
+                let (rectangle, result) = pasted(&content, uvec2(1, 1), transform);
+                let (size, expected) = transformed(content.size, transform);
+
+                assert_eq!(rectangle.size, size, "{:?}", transform);
+                assert_eq!(
+                    result,
+                    expected.into_iter().map(Some).collect::<Vec<_>>(),
+                    "{:?}",
+                    transform
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn paste_skips_unselected_and_clipped_datapoints() {
+        let content = content(vec![true, false, true, true, true, false]);
+
+        let (_, result) = pasted(&content, uvec2(1, 1), transform(0, false));
+        assert_eq!(result, vec![Some(0), None, Some(2), Some(3), Some(4), None]);
+
+        // origin at (-1, -1): only the lower right 2x1 part is on the map
+        let (rectangle, result) = pasted(&content, uvec2(0, 0), transform(0, false));
+        assert_eq!(rectangle.pos, uvec2(0, 0));
+        assert_eq!(rectangle.size, uvec2(2, 1));
+        assert_eq!(result, vec![Some(4), None]);
+
+        assert!(content
+            .paste_mapping(uvec2(20, 20), 16, transform(0, false))
+            .is_none());
+    }
+}
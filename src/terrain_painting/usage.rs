@@ -0,0 +1,116 @@
+// ----------------------------------------------------------------------------
+// usage statistics of material slots and blend control values
+// ----------------------------------------------------------------------------
+use crate::terrain_material::MaterialSlot;
+
+use super::pick_value;
+use super::{BKGRND_TEXTURE_BITMASK, BKGRND_TEXTURE_BITPOS};
+use super::{BLENDING_BITMASK, BLENDING_BITPOS};
+use super::{OVERLAY_TEXTURE_BITMASK, OVERLAY_TEXTURE_BITPOS};
+use super::{SCALING_BITMASK, SCALING_BITPOS};
+
+#[cfg(feature = "editor")]
+use {
+    crate::terrain_clipmap::TextureControlClipmap,
+    bevy::{prelude::*, tasks::ComputeTaskPool},
+};
+// ----------------------------------------------------------------------------
+/// datapoints counted by one thread
+#[cfg(feature = "editor")]
+const CHUNK_SIZE: usize = 1024 * 1024;
+// ----------------------------------------------------------------------------
+/// requests usage statistics of the complete texture control map
+#[cfg(feature = "editor")]
+pub struct MaterialUsageEvent;
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+pub struct MaterialUsageResultEvent(pub MaterialUsage);
+// ----------------------------------------------------------------------------
+/// datapoint count per material value. index 0 counts holes, index n counts
+/// material slot n - 1.
+#[derive(Clone, Debug, Default)]
+pub struct MaterialUsage {
+    pub overlay: [usize; 32],
+    pub background: [usize; 32],
+    /// datapoint count per slope blend threshold value
+    pub blend_threshold: [usize; 8],
+    /// datapoint count per background uv scaling value
+    pub uv_scaling: [usize; 8],
+}
+// ----------------------------------------------------------------------------
+// systems
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+pub(super) fn count_material_usage(
+    thread_pool: Res<ComputeTaskPool>,
+    texture_clipmap: Res<TextureControlClipmap>,
+    mut events: EventReader<MaterialUsageEvent>,
+    mut results: EventWriter<MaterialUsageResultEvent>,
+) {
+    // multiple requests in the same frame have the same result
+    if events.iter().count() == 0 {
+        return;
+    }
+    let data = texture_clipmap.fullres_data_slice();
+
+    let usage = thread_pool
+        .scope(|s| {
+            for chunk in data.chunks(CHUNK_SIZE) {
+                s.spawn(async move { MaterialUsage::from_texture_control(chunk) });
+            }
+        })
+        .iter()
+        .fold(MaterialUsage::default(), |mut usage, chunk_usage| {
+            usage.add(chunk_usage);
+            usage
+        });
+
+    debug!("material usage: {} datapoints", usage.datapoints());
+    results.send(MaterialUsageResultEvent(usage));
+}
+// ----------------------------------------------------------------------------
+// impl
+// ----------------------------------------------------------------------------
+impl MaterialUsage {
+    // ------------------------------------------------------------------------
+    /// counts all values of texture control data
+    pub fn from_texture_control(data: &[u16]) -> Self {
+        let mut usage = Self::default();
+        for value in data.iter().copied() {
+            let overlay = pick_value::<OVERLAY_TEXTURE_BITMASK, OVERLAY_TEXTURE_BITPOS>(value);
+            let background = pick_value::<BKGRND_TEXTURE_BITMASK, BKGRND_TEXTURE_BITPOS>(value);
+            let blending = pick_value::<BLENDING_BITMASK, BLENDING_BITPOS>(value);
+            let scaling = pick_value::<SCALING_BITMASK, SCALING_BITPOS>(value);
+
+            usage.overlay[overlay as usize] += 1;
+            usage.background[background as usize] += 1;
+            usage.blend_threshold[blending as usize] += 1;
+            usage.uv_scaling[scaling as usize] += 1;
+        }
+        usage
+    }
+    // ------------------------------------------------------------------------
+    /// number of counted datapoints
+    pub fn datapoints(&self) -> usize {
+        self.overlay.iter().sum()
+    }
+    // ------------------------------------------------------------------------
+    /// overlay and background datapoints using the material slot
+    pub fn slot_usage(&self, slot: MaterialSlot) -> (usize, usize) {
+        let idx = *slot as usize + 1;
+        (self.overlay[idx], self.background[idx])
+    }
+    // ------------------------------------------------------------------------
+    #[cfg(feature = "editor")]
+    fn add(&mut self, other: &MaterialUsage) {
+        fn add_counts(counts: &mut [usize], other: &[usize]) {
+            counts.iter_mut().zip(other).for_each(|(a, b)| *a += b);
+        }
+        add_counts(&mut self.overlay, &other.overlay);
+        add_counts(&mut self.background, &other.background);
+        add_counts(&mut self.blend_threshold, &other.blend_threshold);
+        add_counts(&mut self.uv_scaling, &other.uv_scaling);
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
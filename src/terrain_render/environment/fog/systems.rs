@@ -38,7 +38,7 @@ pub(super) fn queue_fog_info(
     if let (Some(view_bindung), Some(env)) =
         (view_uniforms.uniforms.binding(), environment.as_ref())
     {
-        let key = FogRenderPipelineKey::from_settings(&*settings);
+        let key = FogRenderPipelineKey::from_settings(&settings);
 
         pipeline_id.0 = Some(pipelines.specialize(&mut pipeline_cache, &fog_pipeline, key));
 
@@ -50,10 +50,6 @@ pub struct FogState {
     pub vertical_density: f32,
     pub vertical_density_light_front: f32,
     pub vertical_density_light_back: f32,
-    //pub sky_density_scale: f32,
-    //pub clouds_density_scale: f32,
-    //pub sky_vertical_density_light_front_scale: f32,
-    //pub sky_vertical_density_light_back_scale: f32,
     pub vertical_density_rim_range: f32,
     pub custom_color: Color,
     pub custom_color_start: f32,
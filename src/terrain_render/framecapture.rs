@@ -0,0 +1,281 @@
+// ----------------------------------------------------------------------------
+// capturing of rendered (tonemapped) frames for offline frame sequence rendering
+// ----------------------------------------------------------------------------
+use std::num::NonZeroU32;
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_resource::{
+            Buffer, BufferAsyncError, BufferDescriptor, BufferUsages, Extent3d, ImageCopyBuffer,
+            ImageCopyTexture, ImageDataLayout, Maintain, MapMode, Origin3d, Texture, TextureAspect,
+            TextureDescriptor, TextureDimension, TextureFormat, TextureUsages, TextureView,
+            TextureViewDescriptor,
+        },
+        renderer::{RenderContext, RenderDevice},
+        texture::BevyDefault,
+        view::ExtractedView,
+        RenderApp, RenderStage,
+    },
+};
+
+use async_channel::{Receiver, Sender};
+// ----------------------------------------------------------------------------
+/// request to capture the next rendered frame. the frame is identified by the
+/// provided number.
+#[derive(Default)]
+pub struct FrameCaptureRequest(Option<u32>);
+// ----------------------------------------------------------------------------
+/// rgba8 data of a captured frame
+pub struct CapturedFrame {
+    pub frame: u32,
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+// ----------------------------------------------------------------------------
+pub struct CapturedFrameReceiver(Receiver<CapturedFrame>);
+// ----------------------------------------------------------------------------
+struct CapturedFrameDispatcher(Sender<CapturedFrame>);
+// ----------------------------------------------------------------------------
+#[derive(Default)]
+pub struct FrameCapturePlugin;
+// ----------------------------------------------------------------------------
+impl Plugin for FrameCapturePlugin {
+    // ------------------------------------------------------------------------
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FrameCaptureRequest>();
+
+        // channel to push captured frames to app world
+        let (frame_sender, frame_receiver) = async_channel::unbounded();
+
+        app.insert_resource(CapturedFrameReceiver(frame_receiver));
+
+        app.sub_app_mut(RenderApp)
+            .init_resource::<Option<GpuFrameCapture>>()
+            .insert_resource(ExtractedFrameCaptureRequest(None))
+            .insert_resource(CapturedFrameDispatcher(frame_sender))
+            .add_system_to_stage(RenderStage::Extract, extract_frame_capture_request)
+            .add_system_to_stage(RenderStage::Prepare, prepare_frame_capture)
+            // data can only be read *after* the command queue was submitted!
+            .add_system_to_stage(RenderStage::Cleanup, check_captured_frame);
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl FrameCaptureRequest {
+    // ------------------------------------------------------------------------
+    pub fn request(&mut self, frame: u32) {
+        self.0 = Some(frame);
+    }
+    // ------------------------------------------------------------------------
+    pub fn is_pending(&self) -> bool {
+        self.0.is_some()
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+// render world
+// ----------------------------------------------------------------------------
+struct ExtractedFrameCaptureRequest(Option<u32>);
+// ----------------------------------------------------------------------------
+/// offscreen target for the tonemapping pass and staging buffer to read back
+/// the result
+pub(super) struct GpuFrameCapture {
+    frame: u32,
+    pub requested: bool,
+    size: Extent3d,
+    padded_bytes_per_row: u32,
+    texture: Texture,
+    pub view: TextureView,
+    staging_buffer: Buffer,
+}
+// ----------------------------------------------------------------------------
+// systems
+// ----------------------------------------------------------------------------
+fn extract_frame_capture_request(mut commands: Commands, mut request: ResMut<FrameCaptureRequest>) {
+    // Note: as_ref required to prevent setting "changed" flag
+    if request.as_ref().is_pending() {
+        commands.insert_resource(ExtractedFrameCaptureRequest(request.0.take()));
+    } else {
+        commands.insert_resource(ExtractedFrameCaptureRequest(None));
+    }
+}
+// ----------------------------------------------------------------------------
+fn prepare_frame_capture(
+    render_device: Res<RenderDevice>,
+    request: Res<ExtractedFrameCaptureRequest>,
+    views: Query<&ExtractedView>,
+    mut capture: ResMut<Option<GpuFrameCapture>>,
+) {
+    match (request.0, views.get_single()) {
+        (Some(frame), Ok(view)) => {
+            let size = Extent3d {
+                width: view.width,
+                height: view.height,
+                depth_or_array_layers: 1,
+            };
+            // target is reused as long as the window size does not change
+            if (*capture).as_ref().is_none_or(|c| c.size != size) {
+                *capture = Some(GpuFrameCapture::new(&render_device, size));
+            }
+            if let Some(capture) = capture.as_mut() {
+                capture.frame = frame;
+                capture.requested = true;
+            }
+        }
+        (Some(frame), Err(_)) => {
+            error!("failed to capture frame {}: no view found", frame);
+        }
+        (None, _) => {
+            if let Some(capture) = capture.as_mut() {
+                capture.requested = false;
+            }
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+fn check_captured_frame(
+    render_device: Res<RenderDevice>,
+    capture: Res<Option<GpuFrameCapture>>,
+    dispatcher: Res<CapturedFrameDispatcher>,
+) {
+    let capture = match capture.as_ref() {
+        Some(capture) if capture.requested => capture,
+        _ => return,
+    };
+
+    let buf_slice = capture.staging_buffer.slice(..);
+    let buf_future = buf_slice.map_async(MapMode::Read);
+    let x = async move {
+        buf_future.await?;
+        Ok(buf_slice)
+    };
+
+    // Note: device.poll has to be called *after* wait_for_result (slice.map_async)
+    // and before get_result!
+    render_device.poll(Maintain::Wait);
+
+    use futures_lite::future;
+    let result: Result<CapturedFrame, BufferAsyncError> = future::block_on(x).map(|slice| {
+        let data = slice.get_mapped_range();
+        let frame = capture.unpadded_rgba(&data);
+
+        drop(data);
+        capture.staging_buffer.unmap();
+        frame
+    });
+
+    match result {
+        Ok(frame) => {
+            dispatcher
+                .0
+                .try_send(frame)
+                .map_err(|e| error!("failed to send captured frame: {}", e))
+                .ok();
+        }
+        Err(e) => error!("failed to read captured frame {}: {}", capture.frame, e),
+    }
+}
+// ----------------------------------------------------------------------------
+// helper
+// ----------------------------------------------------------------------------
+/// bytes per row of texture to buffer copies must be a multiple of this
+const COPY_BYTES_PER_ROW_ALIGNMENT: u32 = 256;
+// ----------------------------------------------------------------------------
+impl GpuFrameCapture {
+    // ------------------------------------------------------------------------
+    fn new(render_device: &RenderDevice, size: Extent3d) -> Self {
+        let bytes_per_row = size.width * 4;
+        let padded_bytes_per_row =
+            bytes_per_row.div_ceil(COPY_BYTES_PER_ROW_ALIGNMENT) * COPY_BYTES_PER_ROW_ALIGNMENT;
+
+        let texture = render_device.create_texture(&TextureDescriptor {
+            label: Some("framecapture_texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::bevy_default(),
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+        });
+        let view = texture.create_view(&TextureViewDescriptor::default());
+
+        let staging_buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("framecapture_staging_buffer"),
+            size: (padded_bytes_per_row * size.height) as u64,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            frame: 0,
+            requested: false,
+            size,
+            padded_bytes_per_row,
+            texture,
+            view,
+            staging_buffer,
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// copies rendered capture target into staging buffer. must be called
+    /// after the capture target was rendered.
+    pub fn copy_to_staging_buffer(&self, render_context: &mut RenderContext) {
+        render_context.command_encoder.copy_texture_to_buffer(
+            ImageCopyTexture {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: Origin3d::ZERO,
+                aspect: TextureAspect::All,
+            },
+            ImageCopyBuffer {
+                buffer: &self.staging_buffer,
+                layout: ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: NonZeroU32::new(self.padded_bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            self.size,
+        );
+    }
+    // ------------------------------------------------------------------------
+    /// removes row padding and converts to rgba if necessary
+    fn unpadded_rgba(&self, padded: &[u8]) -> CapturedFrame {
+        let bytes_per_row = (self.size.width * 4) as usize;
+        let is_bgra = matches!(
+            TextureFormat::bevy_default(),
+            TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb
+        );
+
+        let mut data = Vec::with_capacity(bytes_per_row * self.size.height as usize);
+        for row in padded.chunks(self.padded_bytes_per_row as usize) {
+            data.extend_from_slice(&row[..bytes_per_row]);
+        }
+        if is_bgra {
+            for pixel in data.chunks_exact_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+
+        CapturedFrame {
+            frame: self.frame,
+            width: self.size.width,
+            height: self.size.height,
+            data,
+        }
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+use std::ops::Deref;
+
+impl Deref for CapturedFrameReceiver {
+    type Target = Receiver<CapturedFrame>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+// ----------------------------------------------------------------------------
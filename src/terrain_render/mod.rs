@@ -7,17 +7,19 @@ use crate::resource::{RenderResourcePlugin, RenderResourceSystemLabel};
 use crate::texturearray::TextureArray;
 
 use crate::terrain_clipmap::{HeightmapClipmap, TextureControlClipmap, TintClipmap};
+use crate::terrain_material::TerrainMaterialParam;
 // ----------------------------------------------------------------------------
 pub struct TerrainRenderPlugin;
 // ----------------------------------------------------------------------------
 pub use brush::{BrushPointer, BrushPointerEventData, BrushPointerEventReceiver};
 
-pub use environment::{DirectionalLight, EnvironmentData, FogState};
+pub use environment::{EnvironmentData, FogState};
 
-pub use terrain::{TerrainMesh, TerrainMeshStats, TerrainMeshVertexData};
+pub use framecapture::{CapturedFrame, CapturedFrameReceiver, FrameCaptureRequest};
 
 pub use terrain_shadows::{
     TerrainLightheightClipmap, TerrainShadowsComputeInput, TerrainShadowsRenderSettings,
+    TerrainShadowsUpdateTracker,
 };
 
 pub use self::terrain_shadows::TerrainShadowsComputePlugin;
@@ -55,6 +57,12 @@ pub struct TerrainRenderSettings {
     pub show_bkgrnd_scaling: bool,
     pub show_tint_map: bool,
     pub show_lightheight_map: bool,
+    pub show_material_coverage: bool,
+
+    /// material slot highlighted by material coverage view
+    pub highlighted_material: u8,
+    /// world space bounds (min, max) of the painting selection (outlined)
+    pub selection: Option<(Vec2, Vec2)>,
 }
 // ----------------------------------------------------------------------------
 #[derive(Default, Clone)]
@@ -65,18 +73,6 @@ pub struct TerrainMaterialSet {
     pub parameter: [TerrainMaterialParam; 31],
 }
 // ----------------------------------------------------------------------------
-#[derive(Clone, Copy)]
-pub struct TerrainMaterialParam {
-    pub blend_sharpness: f32,
-    pub slope_base_dampening: f32,
-    pub slope_normal_dampening: f32,
-    pub specularity_scale: f32,
-    pub specularity: f32,
-    pub specularity_base: f32,
-    pub _specularity_scale_copy: f32,
-    pub falloff: f32,
-}
-// ----------------------------------------------------------------------------
 #[derive(Component, Clone, Copy)]
 pub struct ClipmapAssignment {
     pub level: u8,
@@ -113,6 +109,8 @@ pub struct ClipmapLayerInfo {
 // ----------------------------------------------------------------------------
 mod brush;
 mod environment;
+mod framecapture;
+mod render_settings;
 mod rendergraph;
 mod terrain;
 mod terrain_info;
@@ -121,6 +119,7 @@ mod tonemapping;
 // ----------------------------------------------------------------------------
 mod gpu {
     pub(super) use super::environment::{GpuDirectionalLight, GpuTonemappingInfo};
+    pub(super) use super::render_settings::GpuTerrainRenderSettings;
     pub(super) use super::terrain::gpu::GpuClipmapInfo;
     pub(super) use super::terrain_info::GpuTerrainMapInfoSettings;
     pub(super) use super::terrain_shadows::ExtractedTerrainShadowsRenderSettings as GpuTerrainShadowsRenderSettings;
@@ -132,6 +131,7 @@ impl Plugin for TerrainRenderPlugin {
         app.init_resource::<TerrainRenderSettings>()
             .init_resource::<TerrainMaterialSet>()
             .init_resource::<TerrainMapInfo>()
+            .add_plugin(RenderResourcePlugin::<TerrainRenderSettings>::default())
             .add_plugin(
                 RenderResourcePlugin::<TerrainMapInfo>::default()
                     .prepare_label(TerrainRenderSystemLabel::PrepareMapInfo),
@@ -141,6 +141,7 @@ impl Plugin for TerrainRenderPlugin {
             .add_plugin(terrain::TerrainMeshRenderPlugin)
             .add_plugin(terrain_shadows::TerrainShadowsComputePlugin)
             .add_plugin(tonemapping::TonemappingPlugin)
+            .add_plugin(framecapture::FrameCapturePlugin)
             .add_plugin(brush::BrushPointerRenderPlugin);
 
         app.sub_app_mut(RenderApp)
@@ -230,6 +231,7 @@ impl TerrainRenderSettings {
             || self.show_bkgrnd_scaling
             || self.show_tint_map
             || self.show_lightheight_map
+            || self.show_material_coverage
     }
     // ------------------------------------------------------------------------
     pub fn reset_exclusive_view(&mut self) {
@@ -239,6 +241,7 @@ impl TerrainRenderSettings {
         self.show_bkgrnd_scaling = false;
         self.show_tint_map = false;
         self.show_lightheight_map = false;
+        self.show_material_coverage = false;
     }
     // ------------------------------------------------------------------------
 }
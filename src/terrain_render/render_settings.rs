@@ -0,0 +1,75 @@
+// ----------------------------------------------------------------------------
+use bevy::{
+    ecs::system::{lifetimeless::SRes, SystemParamItem},
+    math::Vec2,
+    render::{
+        render_resource::{
+            std140::{AsStd140, Std140},
+            Buffer, BufferInitDescriptor, BufferUsages,
+        },
+        renderer::RenderDevice,
+    },
+};
+
+use crate::resource::{PrepareResourceError, RenderResource};
+
+use super::TerrainRenderSettings;
+// ----------------------------------------------------------------------------
+// gpu representation of render settings which cannot be expressed as shader defs
+// ----------------------------------------------------------------------------
+#[derive(AsStd140, Clone)]
+pub struct GpuTerrainRenderSettings {
+    highlighted_material: u32,
+    show_selection: u32,
+    selection_min: Vec2,
+    selection_max: Vec2,
+}
+// ----------------------------------------------------------------------------
+pub struct GpuTerrainRenderSettingsBuffer(Buffer);
+// ----------------------------------------------------------------------------
+// terrain render settings -> renderresource processing
+// ----------------------------------------------------------------------------
+impl RenderResource for TerrainRenderSettings {
+    // In RenderStage::Extract step the resource is extracted from "app world" to
+    // "render world" into an "ExtractedResource".
+    type ExtractedResource = GpuTerrainRenderSettings;
+    // in RenderStage::Prepare step the extracted resource is transformed into its
+    // GPU representation "PreparedResource"
+    type PreparedResource = GpuTerrainRenderSettingsBuffer;
+    // defines query for ecs data in the prepare resource step
+    type Param = SRes<RenderDevice>;
+    // ------------------------------------------------------------------------
+    fn extract_resource(&self) -> Self::ExtractedResource {
+        let (selection_min, selection_max) = self.selection.unwrap_or_default();
+
+        GpuTerrainRenderSettings {
+            highlighted_material: self.highlighted_material as u32,
+            show_selection: self.selection.is_some() as u32,
+            selection_min,
+            selection_max,
+        }
+    }
+    // ------------------------------------------------------------------------
+    fn prepare_resource(
+        settings: Self::ExtractedResource,
+        render_device: &mut SystemParamItem<Self::Param>,
+    ) -> Result<Self::PreparedResource, PrepareResourceError<Self::ExtractedResource>> {
+        let settings_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("terrain_render_settings_buffer"),
+            usage: BufferUsages::UNIFORM,
+            contents: settings.as_std140().as_bytes(),
+        });
+
+        Ok(GpuTerrainRenderSettingsBuffer(settings_buffer))
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl std::ops::Deref for GpuTerrainRenderSettingsBuffer {
+    type Target = Buffer;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+// ----------------------------------------------------------------------------
@@ -59,8 +59,8 @@ pub(super) fn prepare_rendertargets(
                 label: Some("terrain_world_pos"),
                 size: Extent3d {
                     depth_or_array_layers: 1,
-                    width: view.width as u32,
-                    height: view.height as u32,
+                    width: view.width,
+                    height: view.height,
                 },
                 mip_level_count: 1,
                 sample_count,
@@ -75,8 +75,8 @@ pub(super) fn prepare_rendertargets(
                 label: Some("terrain_hdr_1"),
                 size: Extent3d {
                     depth_or_array_layers: 1,
-                    width: view.width as u32,
-                    height: view.height as u32,
+                    width: view.width,
+                    height: view.height,
                 },
                 mip_level_count: 1,
                 sample_count,
@@ -93,8 +93,8 @@ pub(super) fn prepare_rendertargets(
                 label: Some("terrain_hdr_2"),
                 size: Extent3d {
                     depth_or_array_layers: 1,
-                    width: view.width as u32,
-                    height: view.height as u32,
+                    width: view.width,
+                    height: view.height,
                 },
                 mip_level_count: 1,
                 sample_count,
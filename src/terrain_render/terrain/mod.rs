@@ -14,7 +14,7 @@ use bevy::{
 use crate::mut_renderasset::{MutRenderAssetPlugin, MutRenderAssets};
 use crate::resource::RenderResourcePlugin;
 
-use crate::terrain_tiles::TerrainTileComponent;
+use crate::terrain_tiles::{TerrainMesh, TerrainTileComponent};
 
 use self::terrain_mesh::extract_meshes as extract_terrain_meshes;
 use self::terrain_mesh::queue_mesh_bind_group as queue_terrain_mesh_bind_group;
@@ -26,7 +26,10 @@ use self::pipeline::{TerrainMeshPipelineKey, TerrainMeshRenderPipeline};
 use super::environment::EnvironmentData;
 use super::rendergraph::Terrain3d;
 
-use super::gpu::{GpuDirectionalLight, GpuTerrainMapInfoSettings, GpuTerrainShadowsRenderSettings};
+use super::gpu::{
+    GpuDirectionalLight, GpuTerrainMapInfoSettings, GpuTerrainRenderSettings,
+    GpuTerrainShadowsRenderSettings,
+};
 use super::{
     ClipmapAssignment, ClipmapInfo, TerrainClipmap, TerrainMaterialParam, TerrainMaterialSet,
     TerrainRenderSettings, TerrainShadowsRenderSettings,
@@ -37,11 +40,9 @@ mod terrain_clipmap;
 mod terrain_material;
 mod terrain_mesh;
 // ----------------------------------------------------------------------------
-pub use self::terrain_mesh::{TerrainMesh, TerrainMeshStats, TerrainMeshVertexData};
-// ----------------------------------------------------------------------------
 pub(super) mod gpu {
-    pub use super::pipeline::{TerrainMeshPipelineKey, TerrainMeshRenderPipeline};
-    pub use super::terrain_clipmap::{GpuClipmapInfo, GpuClipmapLayerInfo};
+    pub use super::pipeline::TerrainMeshRenderPipeline;
+    pub use super::terrain_clipmap::GpuClipmapInfo;
 }
 // ----------------------------------------------------------------------------
 pub struct TerrainMeshRenderPlugin;
@@ -93,7 +94,7 @@ fn queue_terrain_rendering(
     // distorts the terrain because the updated vertexbuffer data is not
     // uploaded). to make the switch not break geometry meshes will be assigned
     // to different specialized pipelines based on availability of the data.
-    let key = TerrainMeshPipelineKey::from_settings(&*settings);
+    let key = TerrainMeshPipelineKey::from_settings(&settings);
     let wireframe_key = key | TerrainMeshPipelineKey::SHOW_WIREFRAME;
     let no_wireframe_key = key & !TerrainMeshPipelineKey::SHOW_WIREFRAME;
 
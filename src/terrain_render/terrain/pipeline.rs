@@ -104,6 +104,7 @@ bitflags::bitflags! {
         const SHOW_UV_SCALING           = 0b1000_0000_0100_0000;
         const SHOW_TINT_MAP             = 0b1000_0000_0101_0000;
         const SHOW_LIGHTHEIGHT_MAP      = 0b1000_0000_0110_0000;
+        const SHOW_MATERIAL_COVERAGE    = 0b1000_0000_0111_0000;
     }
 }
 // ----------------------------------------------------------------------------
@@ -125,6 +126,8 @@ impl TerrainMeshPipelineKey {
             flags = TerrainMeshPipelineKey::SHOW_TINT_MAP;
         } else if settings.show_lightheight_map {
             flags = TerrainMeshPipelineKey::SHOW_LIGHTHEIGHT_MAP;
+        } else if settings.show_material_coverage {
+            flags = TerrainMeshPipelineKey::SHOW_MATERIAL_COVERAGE;
         } else {
             // combined
             if settings.use_flat_shading {
@@ -158,6 +161,9 @@ impl TerrainMeshPipelineKey {
 
         if self.contains(Self::EXCLUSIVE_OVERRIDE) {
             // note: order is backwards!
+            if self.contains(Self::SHOW_MATERIAL_COVERAGE) {
+                return vec!["SHOW_MATERIAL_COVERAGE".to_string()];
+            }
             if self.contains(Self::SHOW_LIGHTHEIGHT_MAP) {
                 return vec!["SHOW_LIGHTHEIGHT_MAP".to_string()];
             }
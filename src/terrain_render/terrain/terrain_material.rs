@@ -214,23 +214,6 @@ impl<const I: usize> EntityRenderCommand for SetTerrainMaterialSetBindGroup<I> {
 // ----------------------------------------------------------------------------
 // material params
 // ----------------------------------------------------------------------------
-impl Default for TerrainMaterialParam {
-    // ------------------------------------------------------------------------
-    fn default() -> Self {
-        Self {
-            blend_sharpness: 0.5,
-            slope_base_dampening: 0.5,
-            slope_normal_dampening: 0.5,
-            specularity_scale: 0.0,
-            specularity: 0.0,
-            specularity_base: 0.0,
-            _specularity_scale_copy: 0.0,
-            falloff: 0.0,
-        }
-    }
-    // ------------------------------------------------------------------------
-}
-// ----------------------------------------------------------------------------
 impl From<&TerrainMaterialParam> for GpuTerrainMaterialParam {
     // ------------------------------------------------------------------------
     fn from(s: &TerrainMaterialParam) -> Self {
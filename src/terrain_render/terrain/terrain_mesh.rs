@@ -2,22 +2,21 @@
 // based on bevy pbr mesh pipeline and simplified to terrain mesh usecase.
 // ----------------------------------------------------------------------------
 use bevy::{
-    core::cast_slice,
     ecs::system::{
         lifetimeless::{Read, SQuery, SRes},
         SystemParamItem,
     },
     prelude::*,
-    reflect::TypeUuid,
     render::{
-        mesh::{GpuBufferInfo, Indices},
+        mesh::GpuBufferInfo,
         render_asset::PrepareAssetError,
         render_component::{ComponentUniforms, DynamicUniformIndex},
         render_phase::{EntityRenderCommand, RenderCommandResult, TrackedRenderPass},
         render_resource::{
             std140::AsStd140, BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayoutEntry,
             BindingType, Buffer, BufferBindingType, BufferInitDescriptor, BufferSize, BufferUsages,
-            ShaderStages, VertexAttribute, VertexBufferLayout, VertexFormat, VertexStepMode,
+            IndexFormat, ShaderStages, VertexAttribute, VertexBufferLayout, VertexFormat,
+            VertexStepMode,
         },
         renderer::RenderDevice,
         view::{ViewUniform, ViewUniformOffset, ViewUniforms},
@@ -28,36 +27,17 @@ use crate::mut_renderasset::{MutRenderAsset, MutRenderAssets};
 use crate::resource::PreparedRenderResource;
 
 use crate::terrain_render::TerrainMapInfo;
-use crate::terrain_tiles::TerrainTileComponent;
+use crate::terrain_tiles::{
+    TerrainMesh, TerrainMeshIndices, TerrainMeshVertexData, TerrainTileComponent,
+};
 
 use super::pipeline::{TerrainMeshPipelineKey, TerrainMeshRenderPipeline};
 use super::{
     ClipmapAssignment, EnvironmentData, GpuDirectionalLight, GpuTerrainMapInfoSettings,
-    GpuTerrainShadowsRenderSettings, TerrainShadowsRenderSettings,
+    GpuTerrainRenderSettings, GpuTerrainShadowsRenderSettings, TerrainRenderSettings,
+    TerrainShadowsRenderSettings,
 };
 // ----------------------------------------------------------------------------
-// mesh
-// ----------------------------------------------------------------------------
-#[derive(TypeUuid)]
-#[uuid = "dd81109b-f363-4c59-be19-5038df017247"]
-pub struct TerrainMesh {
-    vertex_data: Option<TerrainMeshVertexData>,
-    indices: Option<Indices>,
-    stats: TerrainMeshStats,
-}
-// ----------------------------------------------------------------------------
-pub enum TerrainMeshVertexData {
-    PositionAndNormal(Vec<[f32; 4]>),
-    WithBarycentricCoordinates(Vec<[f32; 5]>),
-}
-// ----------------------------------------------------------------------------
-#[derive(Default, Clone)]
-pub struct TerrainMeshStats {
-    pub vertices: u32,
-    pub triangles: u32,
-    pub data_bytes: usize,
-}
-// ----------------------------------------------------------------------------
 // render cmds
 // ----------------------------------------------------------------------------
 pub struct DrawMesh;
@@ -105,14 +85,6 @@ pub(super) fn mesh_vertex_buffer_layout(key: TerrainMeshPipelineKey) -> VertexBu
 }
 // ----------------------------------------------------------------------------
 impl TerrainMeshVertexData {
-    // ------------------------------------------------------------------------
-    const fn size(&self) -> usize {
-        use TerrainMeshVertexData::*;
-        match self {
-            PositionAndNormal(_) => 4 * 4,
-            WithBarycentricCoordinates(_) => 5 * 4,
-        }
-    }
     // ------------------------------------------------------------------------
     fn vertex_buffer_layout(key: TerrainMeshPipelineKey) -> VertexBufferLayout {
         let (vertex_array_stride, vertex_attributes) =
@@ -175,7 +147,7 @@ pub struct TerrainMeshViewBindGroup {
     value: BindGroup,
 }
 // ----------------------------------------------------------------------------
-pub(super) fn mesh_view_bind_group_layout() -> [BindGroupLayoutEntry; 4] {
+pub(super) fn mesh_view_bind_group_layout() -> [BindGroupLayoutEntry; 5] {
     [
         // View
         BindGroupLayoutEntry {
@@ -225,6 +197,19 @@ pub(super) fn mesh_view_bind_group_layout() -> [BindGroupLayoutEntry; 4] {
             },
             count: None,
         },
+        // Terrain render settings
+        BindGroupLayoutEntry {
+            binding: 4,
+            visibility: ShaderStages::FRAGMENT,
+            ty: BindingType::Buffer {
+                ty: BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: BufferSize::new(
+                    GpuTerrainRenderSettings::std140_size_static() as u64
+                ),
+            },
+            count: None,
+        },
     ]
 }
 // ----------------------------------------------------------------------------
@@ -270,6 +255,7 @@ pub(super) fn extract_meshes(
 // ----------------------------------------------------------------------------
 // systems (queue)
 // ----------------------------------------------------------------------------
+#[allow(clippy::too_many_arguments)]
 pub(super) fn queue_mesh_view_bind_group(
     mut commands: Commands,
     render_device: Res<RenderDevice>,
@@ -278,12 +264,20 @@ pub(super) fn queue_mesh_view_bind_group(
     environment: Res<PreparedRenderResource<EnvironmentData>>,
     map_info: Res<PreparedRenderResource<TerrainMapInfo>>,
     shadow_settings: Res<PreparedRenderResource<TerrainShadowsRenderSettings>>,
+    render_settings: Res<PreparedRenderResource<TerrainRenderSettings>>,
 ) {
-    if let (Some(view_binding), Some(env), Some(map_info), Some(shadow_settings)) = (
+    if let (
+        Some(view_binding),
+        Some(env),
+        Some(map_info),
+        Some(shadow_settings),
+        Some(render_settings),
+    ) = (
         view_uniforms.uniforms.binding(),
         environment.as_ref(),
         map_info.as_ref(),
         shadow_settings.as_ref(),
+        render_settings.as_ref(),
     ) {
         let view_bind_group = render_device.create_bind_group(&BindGroupDescriptor {
             entries: &[
@@ -303,6 +297,10 @@ pub(super) fn queue_mesh_view_bind_group(
                     binding: 3,
                     resource: shadow_settings.buffer.as_entire_binding(),
                 },
+                BindGroupEntry {
+                    binding: 4,
+                    resource: render_settings.as_entire_binding(),
+                },
             ],
             label: Some("terrain_mesh_view_bind_group"),
             layout: &mesh_pipeline.view_layout,
@@ -334,68 +332,6 @@ pub(super) fn queue_mesh_bind_group(
     }
 }
 // ----------------------------------------------------------------------------
-// terrain mesh
-// ----------------------------------------------------------------------------
-impl TerrainMesh {
-    // ------------------------------------------------------------------------
-    pub fn new(vertex_data: TerrainMeshVertexData, indices: Indices) -> Self {
-        Self {
-            stats: TerrainMeshStats {
-                vertices: vertex_data.len() as u32,
-                triangles: indices.triangles(),
-                data_bytes: vertex_data.buffer_size() + indices.buffer_size(),
-            },
-            vertex_data: Some(vertex_data),
-            indices: Some(indices),
-        }
-    }
-    // ------------------------------------------------------------------------
-    pub fn stats(&self) -> &TerrainMeshStats {
-        &self.stats
-    }
-    // ------------------------------------------------------------------------
-    pub fn pending_upload(&self) -> bool {
-        self.vertex_data.is_some()
-    }
-    // ------------------------------------------------------------------------
-    fn get_vertex_buffer_bytes(&self) -> &[u8] {
-        use TerrainMeshVertexData::*;
-
-        match self
-            .vertex_data
-            .as_ref()
-            .expect("missing terrain mesh vertex buffer")
-        {
-            PositionAndNormal(data) => cast_slice(data),
-            WithBarycentricCoordinates(data) => cast_slice(data),
-        }
-    }
-    // ------------------------------------------------------------------------
-    /// Computes and returns the index data of the mesh as bytes.
-    /// This is used to transform the index data into a GPU friendly format.
-    fn get_index_buffer_bytes(&self) -> &[u8] {
-        match self.indices() {
-            Indices::U16(indices) => cast_slice(&indices[..]),
-            Indices::U32(indices) => cast_slice(&indices[..]),
-        }
-    }
-    // ------------------------------------------------------------------------
-    /// Retrieves the vertex `indices` of the mesh.
-    #[inline(always)]
-    fn indices(&self) -> &Indices {
-        self.indices.as_ref().expect("missing terrain mesh indices")
-    }
-    // ------------------------------------------------------------------------
-    #[inline(always)]
-    fn has_barycentric_data(&self) -> bool {
-        matches!(
-            self.vertex_data,
-            Some(TerrainMeshVertexData::WithBarycentricCoordinates(_))
-        )
-    }
-    // ------------------------------------------------------------------------
-}
-// ----------------------------------------------------------------------------
 // terrainmesh -> renderasst processing
 // ----------------------------------------------------------------------------
 impl MutRenderAsset for TerrainMesh {
@@ -410,11 +346,7 @@ impl MutRenderAsset for TerrainMesh {
     }
     // ------------------------------------------------------------------------
     fn extract_asset(&mut self) -> Self::ExtractedAsset {
-        TerrainMesh {
-            vertex_data: self.vertex_data.take(),
-            indices: self.indices.take(),
-            stats: TerrainMeshStats::default(),
-        }
+        self.take_data()
     }
     // ------------------------------------------------------------------------
     fn prepare_asset(
@@ -434,7 +366,10 @@ impl MutRenderAsset for TerrainMesh {
                 label: Some("Mesh Index Buffer"),
             }),
             count: mesh.indices().len() as u32,
-            index_format: mesh.indices().into(),
+            index_format: match mesh.indices() {
+                TerrainMeshIndices::U16(_) => IndexFormat::Uint16,
+                TerrainMeshIndices::U32(_) => IndexFormat::Uint32,
+            },
         };
 
         Ok(GpuTerrainMesh {
@@ -530,63 +465,3 @@ impl EntityRenderCommand for DrawMesh {
         }
     }
 }
-// ----------------------------------------------------------------------------
-// stats helper
-// ----------------------------------------------------------------------------
-trait MeshIndicesStats {
-    // ------------------------------------------------------------------------
-    fn triangles(&self) -> u32;
-    // ------------------------------------------------------------------------
-    fn buffer_size(&self) -> usize;
-    // ------------------------------------------------------------------------
-}
-// ----------------------------------------------------------------------------
-impl MeshIndicesStats for Indices {
-    // ------------------------------------------------------------------------
-    fn triangles(&self) -> u32 {
-        self.len() as u32 / 3
-    }
-    // ------------------------------------------------------------------------
-    fn buffer_size(&self) -> usize {
-        match self {
-            Indices::U16(v) => v.len() * 2,
-            Indices::U32(v) => v.len() * 4,
-        }
-    }
-    // ------------------------------------------------------------------------
-}
-// ----------------------------------------------------------------------------
-impl TerrainMeshVertexData {
-    // ------------------------------------------------------------------------
-    fn len(&self) -> usize {
-        use TerrainMeshVertexData::*;
-        match self {
-            PositionAndNormal(d) => d.len(),
-            WithBarycentricCoordinates(d) => d.len(),
-        }
-    }
-    // ------------------------------------------------------------------------
-    fn buffer_size(&self) -> usize {
-        use TerrainMeshVertexData::*;
-        match self {
-            PositionAndNormal(d) => d.len() * self.size(),
-            WithBarycentricCoordinates(d) => d.len() * self.size(),
-        }
-    }
-    // ------------------------------------------------------------------------
-}
-// ----------------------------------------------------------------------------
-use std::ops::Add;
-
-impl<'a, 'b> Add<&'b TerrainMeshStats> for &'a TerrainMeshStats {
-    type Output = TerrainMeshStats;
-
-    fn add(self, other: &TerrainMeshStats) -> TerrainMeshStats {
-        TerrainMeshStats {
-            vertices: self.vertices + other.vertices,
-            triangles: self.triangles + other.triangles,
-            data_bytes: self.data_bytes + other.data_bytes,
-        }
-    }
-}
-// ----------------------------------------------------------------------------
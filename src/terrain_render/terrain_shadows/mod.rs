@@ -37,7 +37,6 @@ pub struct TerrainShadowsRenderSettings {
     pub falloff_bias: f32,
     pub interpolation_range: f32,
 
-    pub fast_shadows: bool,
     pub recompute_frequency: u8,
 }
 // ----------------------------------------------------------------------------
@@ -56,6 +55,13 @@ pub struct TerrainShadowsComputeInput {
     clipmap_info: ClipmapInfo,
 }
 // ----------------------------------------------------------------------------
+/// tracks required recomputation of shadows (throttled by recompute frequency)
+pub struct TerrainShadowsUpdateTracker {
+    tick: u32,
+    recompute_frequency: u32,
+    recompute: bool,
+}
+// ----------------------------------------------------------------------------
 pub use self::compute_node::ComputeTerrainShadowsNode;
 
 pub(super) use self::resource::ExtractedTerrainShadowsRenderSettings;
@@ -179,13 +185,6 @@ impl TerrainShadowsComputeInput {
 // ----------------------------------------------------------------------------
 // plugin internal
 // ----------------------------------------------------------------------------
-// #[derive(Clone)]
-struct TerrainShadowsUpdateTracker {
-    tick: u32,
-    recompute_frequency: u32,
-    recompute: bool,
-}
-// ----------------------------------------------------------------------------
 struct TerrainShadowsComputeTrigger {
     recompute: bool,
     trace_direction: LightrayDirection,
@@ -253,7 +252,6 @@ impl Default for TerrainShadowsRenderSettings {
             falloff_scale: 2000.0,
             falloff_bias: 50.0,
             interpolation_range: 250.0,
-            fast_shadows: false,
             recompute_frequency: 3,
         }
     }
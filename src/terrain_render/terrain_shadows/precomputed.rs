@@ -227,7 +227,7 @@ fn generate_jobs_for_5_level(
             let stride = 1 << clipmap;
             jobs.push(ComputeThreadJob::new(
                 ray_count as u8,
-                (ray_number - layers[clipmap as usize].ray_1) / stride,
+                (ray_number - layers[clipmap].ray_1) / stride,
                 clipmap as u8,
             ));
             ray_number += ray_count * stride;
@@ -263,7 +263,7 @@ fn generate_jobs_for_4_level(
             let stride = 1 << clipmap;
             jobs.push(ComputeThreadJob::new(
                 ray_count as u8,
-                (ray_number - layers[clipmap as usize].ray_1) / stride,
+                (ray_number - layers[clipmap].ray_1) / stride,
                 clipmap as u8,
             ));
             ray_number += ray_count * stride;
@@ -297,7 +297,7 @@ fn generate_jobs_for_3_level(
             let stride = 1 << clipmap;
             jobs.push(ComputeThreadJob::new(
                 ray_count as u8,
-                (ray_number - layers[clipmap as usize].ray_1) / stride,
+                (ray_number - layers[clipmap].ray_1) / stride,
                 clipmap as u8,
             ));
             ray_number += ray_count * stride;
@@ -329,7 +329,7 @@ fn generate_jobs_for_2_level(
             let stride = 1 << clipmap;
             jobs.push(ComputeThreadJob::new(
                 ray_count as u8,
-                (ray_number - layers[clipmap as usize].ray_1) / stride,
+                (ray_number - layers[clipmap].ray_1) / stride,
                 clipmap as u8,
             ));
             ray_number += ray_count * stride;
@@ -357,7 +357,7 @@ fn generate_jobs_for_1_level(
             let stride = 1 << clipmap;
             jobs.push(ComputeThreadJob::new(
                 ray_count as u8,
-                (ray_number - layers[clipmap as usize].ray_1) / stride,
+                (ray_number - layers[clipmap].ray_1) / stride,
                 clipmap as u8,
             ));
             ray_number += ray_count * stride;
@@ -164,6 +164,12 @@ pub(super) fn queue_terrain_shadows_info(
 // helper
 // ----------------------------------------------------------------------------
 impl TerrainShadowsUpdateTracker {
+    // ------------------------------------------------------------------------
+    /// true if shadows are outdated and will be recomputed in one of the next
+    /// frames
+    pub fn is_recompute_pending(&self) -> bool {
+        self.recompute
+    }
     // ------------------------------------------------------------------------
     fn force_recompute(&mut self) {
         self.recompute = true;
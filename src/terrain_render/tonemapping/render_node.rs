@@ -8,13 +8,15 @@ use bevy::{
         render_phase::TrackedRenderPass,
         render_resource::{
             BindGroup, BindGroupDescriptor, BindGroupEntry, BindingResource, LoadOp, Operations,
-            PipelineCache, RenderPassDescriptor, TextureViewId,
+            PipelineCache, RenderPassColorAttachment, RenderPassDescriptor, TextureViewId,
         },
         renderer::RenderContext,
         view::{ExtractedView, ViewTarget},
     },
 };
 
+use crate::terrain_render::framecapture::GpuFrameCapture;
+
 use super::{
     pipeline::TonemappingRenderPipeline,
     systems::{TonemappingBindGroup, TonemappingPipelineId},
@@ -135,6 +137,41 @@ impl Node for TonemappingNode {
             tracked_pass.draw(0..3, 0..1);
         }
 
+        // requested frame captures are rendered into a dedicated offscreen target
+        // (without any overlays) which is copied into a readable buffer
+        if let Some(capture) = world
+            .get_resource::<Option<GpuFrameCapture>>()
+            .and_then(|c| c.as_ref())
+            .filter(|c| c.requested)
+        {
+            let pass_descriptor = RenderPassDescriptor {
+                label: Some("tonemapping_capture_pass"),
+                color_attachments: &[RenderPassColorAttachment {
+                    view: &capture.view,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Clear(Color::BLACK.into()),
+                        store: true,
+                    },
+                }],
+                depth_stencil_attachment: None,
+            };
+
+            {
+                let render_pass = render_context
+                    .command_encoder
+                    .begin_render_pass(&pass_descriptor);
+
+                let mut tracked_pass = TrackedRenderPass::new(render_pass);
+
+                tracked_pass.set_render_pipeline(pipeline);
+                tracked_pass.set_bind_group(0, input_bind_group, &[]);
+                tracked_pass.set_bind_group(1, tonemapping_bind_group, &[]);
+                tracked_pass.draw(0..3, 0..1);
+            }
+            capture.copy_to_staging_buffer(render_context);
+        }
+
         Ok(())
     }
     // ------------------------------------------------------------------------
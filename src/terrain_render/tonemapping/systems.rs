@@ -34,7 +34,7 @@ pub(super) fn queue_tonemapping_info(
     mut pipeline_id: ResMut<TonemappingPipelineId>,
 ) {
     if let Some(env) = environment.as_ref() {
-        let key = TonemappingPipelineKey::from_settings(&*settings);
+        let key = TonemappingPipelineKey::from_settings(&settings);
 
         pipeline_id.0 = Some(pipelines.specialize(&mut pipeline_cache, &tonemapping_pipeline, key));
 
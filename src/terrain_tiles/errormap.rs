@@ -1,26 +1,28 @@
 // ----------------------------------------------------------------------------
-use bevy::{
-    math::{uvec2, UVec2},
-    prelude::*,
-};
+use glam::{uvec2, UVec2};
+
+#[cfg(feature = "editor")]
+use bevy::prelude::Component;
 
 use super::generator::TileTriangle;
 use super::{TerrainDataView, TerrainTileId, TILE_SIZE};
 // ----------------------------------------------------------------------------
-type ErrorMapPostprocessingPackage = (Entity, TerrainTileId<TILE_SIZE>, TileHeightErrors);
+/// key identifies the errormap of a tile (e.g. its entity)
+type ErrorMapPostprocessingPackage<K> = (K, TerrainTileId<TILE_SIZE>, TileHeightErrors);
 // ----------------------------------------------------------------------------
-#[derive(Component)]
+#[cfg_attr(feature = "editor", derive(Component))]
+#[derive(Clone)]
 pub struct TileHeightErrors {
     errors: Vec<f32>,
 }
 // ----------------------------------------------------------------------------
-pub struct ErrorMapsPostprocessing {
+pub struct ErrorMapsPostprocessing<K> {
     is_active: bool,
     finished: bool,
     tiles: usize,
     seams: TileHeightErrorSeams,
-    queue: Vec<ErrorMapPostprocessingPackage>,
-    processed: Vec<ErrorMapPostprocessingPackage>,
+    queue: Vec<ErrorMapPostprocessingPackage<K>>,
+    processed: Vec<ErrorMapPostprocessingPackage<K>>,
 }
 // ----------------------------------------------------------------------------
 /// Holds the table for mapping triangle labels to precalculated triangles.
@@ -238,7 +240,7 @@ impl TileHeightErrors {
 // ----------------------------------------------------------------------------
 // mesh seam optimization
 // ----------------------------------------------------------------------------
-impl ErrorMapsPostprocessing {
+impl<K> ErrorMapsPostprocessing<K> {
     // ------------------------------------------------------------------------
     pub fn new(map_size: u32, tiles: usize) -> Self {
         Self {
@@ -268,11 +270,11 @@ impl ErrorMapsPostprocessing {
     // ------------------------------------------------------------------------
     pub fn add_errormap(
         &mut self,
-        entity: Entity,
+        key: K,
         tileid: TerrainTileId<TILE_SIZE>,
         errormap: TileHeightErrors,
     ) {
-        self.queue.push((entity, tileid, errormap))
+        self.queue.push((key, tileid, errormap))
     }
     // ------------------------------------------------------------------------
     #[inline(always)]
@@ -286,15 +288,15 @@ impl ErrorMapsPostprocessing {
     }
     // ------------------------------------------------------------------------
     #[inline(always)]
-    pub fn next_package(&mut self) -> Option<ErrorMapPostprocessingPackage> {
+    pub fn next_package(&mut self) -> Option<ErrorMapPostprocessingPackage<K>> {
         self.queue.pop()
     }
     // ------------------------------------------------------------------------
-    pub fn append_results(&mut self, results: &mut Vec<ErrorMapPostprocessingPackage>) {
+    pub fn append_results(&mut self, results: &mut Vec<ErrorMapPostprocessingPackage<K>>) {
         self.processed.append(results)
     }
     // ------------------------------------------------------------------------
-    pub fn drain_results(&mut self) -> impl Iterator<Item = ErrorMapPostprocessingPackage> + '_ {
+    pub fn drain_results(&mut self) -> impl Iterator<Item = ErrorMapPostprocessingPackage<K>> + '_ {
         assert!(self.finished);
         // Note: after pass is finalized the results are back in input queue
         // because the seam merging was started to check if another pass is
@@ -327,7 +329,7 @@ impl ErrorMapsPostprocessing {
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
-impl ErrorMapsPostprocessing {
+impl<K> ErrorMapsPostprocessing<K> {
     // ------------------------------------------------------------------------
     fn merge_seams(&mut self) {
         self.seams.is_dirty = false;
@@ -387,43 +389,39 @@ impl TileHeightErrorSeams {
         let mut is_dirty = false;
 
         // top seam
-        let mut offset = self.top_seam_offset(tileid);
-        for value in errormap.top_seam::<TILE_SIZE_>() {
+        let offset = self.top_seam_offset(tileid);
+        for (offset, value) in (offset..).zip(errormap.top_seam::<TILE_SIZE_>()) {
             if self.horizontal[offset] < *value {
                 self.horizontal[offset] = *value;
                 is_dirty = true;
             }
-            offset += 1;
         }
 
         // bottom seam
-        let mut offset = self.bottom_seam_offset(tileid);
-        for value in errormap.bottom_seam::<TILE_SIZE_>() {
+        let offset = self.bottom_seam_offset(tileid);
+        for (offset, value) in (offset..).zip(errormap.bottom_seam::<TILE_SIZE_>()) {
             if self.horizontal[offset] < *value {
                 self.horizontal[offset] = *value;
                 is_dirty = true;
             }
-            offset += 1;
         }
 
         // left seam
-        let mut offset = self.left_seam_offset(tileid);
-        for value in errormap.left_seam::<TILE_SIZE_>() {
+        let offset = self.left_seam_offset(tileid);
+        for (offset, value) in (offset..).zip(errormap.left_seam::<TILE_SIZE_>()) {
             if self.vertical[offset] < value {
                 self.vertical[offset] = value;
                 is_dirty = true;
             }
-            offset += 1;
         }
 
         // right seam
-        let mut offset = self.right_seam_offset(tileid);
-        for value in errormap.right_seam::<TILE_SIZE_>() {
+        let offset = self.right_seam_offset(tileid);
+        for (offset, value) in (offset..).zip(errormap.right_seam::<TILE_SIZE_>()) {
             if self.vertical[offset] < value {
                 self.vertical[offset] = value;
                 is_dirty = true;
             }
-            offset += 1;
         }
 
         if is_dirty {
@@ -607,11 +605,6 @@ struct PrecalculatedTriangle {
     middle_offset: u32,
 } // ----------------------------------------------------------------------------
 impl PrecalculatedTriangle {
-    // ------------------------------------------------------------------------
-    #[inline(always)]
-    fn is_seam(&self, tile_size: u16) -> bool {
-        self.m_x == 0 || self.m_y == 0 || self.m_x == tile_size || self.m_y == tile_size
-    }
     // ------------------------------------------------------------------------
     #[inline(always)]
     fn a(&self) -> UVec2 {
@@ -693,7 +686,7 @@ impl From<TileTriangle> for PrecalculatedTriangle {
 // ----------------------------------------------------------------------------
 // default
 // ----------------------------------------------------------------------------
-impl Default for ErrorMapsPostprocessing {
+impl<K> Default for ErrorMapsPostprocessing<K> {
     fn default() -> Self {
         Self::new(0, 0)
     }
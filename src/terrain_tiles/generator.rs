@@ -104,15 +104,14 @@
 //    also used for lookup
 //
 // ----------------------------------------------------------------------------
-use bevy::{
-    math::{uvec2, uvec3, UVec2, UVec3},
-    render::{mesh::Indices, render_resource::internal::bytemuck::cast},
-    utils::HashMap,
-};
+use bytemuck::cast;
+use glam::{uvec2, uvec3, UVec2, UVec3};
+use hashbrown::HashMap;
 
+use super::holes::TileHoles;
 use super::{
-    MeshReduction, TerrainDataView, TerrainMesh, TerrainMeshVertexData, TerrainTileId,
-    TileHeightErrors, TILE_SIZE,
+    MeshReduction, TerrainDataView, TerrainMesh, TerrainMeshIndices, TerrainMeshVertexData,
+    TerrainTileId, TileHeightErrors, TILE_SIZE,
 };
 // ----------------------------------------------------------------------------
 /// Right-angled triangle with counter clockwise vertices [a, b, c] where c is
@@ -190,6 +189,7 @@ pub(super) fn generate_tilemesh(
     base_height: f32,
     error_thresholds: &MeshReduction,
     terraindata_view: TerrainDataView,
+    holes: Option<TileHoles>,
     triangle_errors: &TileHeightErrors,
     include_wireframe_info: bool,
     small_index: bool,
@@ -200,6 +200,7 @@ pub(super) fn generate_tilemesh(
             map_resolution,
             base_height,
             terraindata_view,
+            holes,
             small_index,
         );
 
@@ -214,6 +215,7 @@ pub(super) fn generate_tilemesh(
             map_resolution,
             base_height,
             terraindata_view,
+            holes,
             small_index,
         );
         if small_index {
@@ -385,16 +387,8 @@ impl TileTriangle {
         // Note: abs_diff not stable yet  #![feature(int_abs_diff)]
         // if (a.x.abs_diff(c.x) + a.y.abs_diff(c.y)) > 1 { ...
         // #[rustfmt::skip]
-        let diff_x = if self.a.x < self.c.x {
-            self.c.x - self.a.x
-        } else {
-            self.a.x - self.c.x
-        };
-        let diff_y = if self.a.y < self.c.y {
-            self.c.y - self.a.y
-        } else {
-            self.a.y - self.c.y
-        };
+        let diff_x = self.c.x.abs_diff(self.a.x);
+        let diff_y = self.c.y.abs_diff(self.a.y);
 
         diff_x + diff_y > 1
     }
@@ -408,6 +402,8 @@ struct TileMeshBuilder<'heightmap, 'normalmap> {
     resolution: f32,
     base_height: f32,
     terrain_data: TerrainDataView<'heightmap, 'normalmap>,
+    /// triangles completely covered by holes are skipped
+    holes: Option<TileHoles>,
 
     known_indices: HashMap<UVec2, u32>,
     indices_u32: Vec<u32>,
@@ -422,6 +418,7 @@ impl<'heightmap, 'normalmap> TileMeshBuilder<'heightmap, 'normalmap> {
         map_resolution: f32,
         base_height: f32,
         terrain_data: TerrainDataView<'heightmap, 'normalmap>,
+        holes: Option<TileHoles>,
         use_small_index: bool,
     ) -> Self {
         let max_vertex_count = (TILE_SIZE * TILE_SIZE) as usize;
@@ -438,6 +435,7 @@ impl<'heightmap, 'normalmap> TileMeshBuilder<'heightmap, 'normalmap> {
             resolution: map_resolution,
             base_height,
             terrain_data,
+            holes,
 
             known_indices: HashMap::with_capacity(max_vertex_count),
             indices_u32,
@@ -480,6 +478,13 @@ impl<'heightmap, 'normalmap> TileMeshBuilder<'heightmap, 'normalmap> {
         next_index
     }
     // ------------------------------------------------------------------------
+    #[inline(always)]
+    fn is_cut_out(&self, triangle: &TileTriangle) -> bool {
+        self.holes
+            .as_ref()
+            .is_some_and(|holes| holes.covers(triangle))
+    }
+    // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
 /// Helper Mesh builder: same as TileMeshBuilder but also adds barycentric info
@@ -492,6 +497,8 @@ struct WireframedTileMeshBuilder<'heightmap, 'normalmap> {
     resolution: f32,
     base_height: f32,
     terrain_data: TerrainDataView<'heightmap, 'normalmap>,
+    /// triangles completely covered by holes are skipped
+    holes: Option<TileHoles>,
 
     known_indices: HashMap<UVec3, u32>,
     indices_u32: Vec<u32>,
@@ -506,6 +513,7 @@ impl<'heightmap, 'normalmap> WireframedTileMeshBuilder<'heightmap, 'normalmap> {
         map_resolution: f32,
         base_height: f32,
         terrain_data: TerrainDataView<'heightmap, 'normalmap>,
+        holes: Option<TileHoles>,
         use_small_index: bool,
     ) -> Self {
         let max_vertex_count = (TILE_SIZE * TILE_SIZE) as usize;
@@ -522,6 +530,7 @@ impl<'heightmap, 'normalmap> WireframedTileMeshBuilder<'heightmap, 'normalmap> {
             resolution: map_resolution,
             base_height,
             terrain_data,
+            holes,
 
             known_indices: HashMap::with_capacity(max_vertex_count),
             indices_u32,
@@ -567,6 +576,13 @@ impl<'heightmap, 'normalmap> WireframedTileMeshBuilder<'heightmap, 'normalmap> {
         next_index
     }
     // ------------------------------------------------------------------------
+    #[inline(always)]
+    fn is_cut_out(&self, triangle: &TileTriangle) -> bool {
+        self.holes
+            .as_ref()
+            .is_some_and(|holes| holes.covers(triangle))
+    }
+    // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
 // helper trait to support different kinds of builders
@@ -578,6 +594,9 @@ trait MeshBuilder {
 impl<'heightmap, 'normalmap> MeshBuilder for TileMeshBuilder<'heightmap, 'normalmap> {
     // ------------------------------------------------------------------------
     fn add_triangle<const USE_SMALL_INDEX: bool>(&mut self, triangle: TileTriangle) {
+        if self.is_cut_out(&triangle) {
+            return;
+        }
         for vertex_2d in [triangle.a, triangle.b, triangle.c].iter().copied() {
             if let Some(index) = self.known_indices.get(&uvec2(vertex_2d.x, vertex_2d.y)) {
                 if USE_SMALL_INDEX {
@@ -602,12 +621,14 @@ impl<'heightmap, 'normalmap> MeshBuilder for TileMeshBuilder<'heightmap, 'normal
             if self.indices_u16.is_empty() {
                 if self.known_indices.len() < u16::MAX as usize {
                     // remap to smaller index
-                    Indices::U16(self.indices_u32.iter().copied().map(|i| i as u16).collect())
+                    TerrainMeshIndices::U16(
+                        self.indices_u32.iter().copied().map(|i| i as u16).collect(),
+                    )
                 } else {
-                    Indices::U32(self.indices_u32)
+                    TerrainMeshIndices::U32(self.indices_u32)
                 }
             } else {
-                Indices::U16(self.indices_u16)
+                TerrainMeshIndices::U16(self.indices_u16)
             },
         )
     }
@@ -617,6 +638,9 @@ impl<'heightmap, 'normalmap> MeshBuilder for TileMeshBuilder<'heightmap, 'normal
 impl<'heightmap, 'normalmap> MeshBuilder for WireframedTileMeshBuilder<'heightmap, 'normalmap> {
     // ------------------------------------------------------------------------
     fn add_triangle<const USE_SMALL_INDEX: bool>(&mut self, triangle: TileTriangle) {
+        if self.is_cut_out(&triangle) {
+            return;
+        }
         for (i, vertex_2d) in [triangle.a, triangle.b, triangle.c]
             .iter()
             .copied()
@@ -648,12 +672,14 @@ impl<'heightmap, 'normalmap> MeshBuilder for WireframedTileMeshBuilder<'heightma
             if self.indices_u16.is_empty() {
                 if self.known_indices.len() < u16::MAX as usize {
                     // remap to smaller index
-                    Indices::U16(self.indices_u32.iter().copied().map(|i| i as u16).collect())
+                    TerrainMeshIndices::U16(
+                        self.indices_u32.iter().copied().map(|i| i as u16).collect(),
+                    )
                 } else {
-                    Indices::U32(self.indices_u32)
+                    TerrainMeshIndices::U32(self.indices_u32)
                 }
             } else {
-                Indices::U16(self.indices_u16)
+                TerrainMeshIndices::U16(self.indices_u16)
             },
         )
     }
@@ -0,0 +1,95 @@
+// ----------------------------------------------------------------------------
+// terrain holes (zero background material in texture control map) are cut out
+// of the tile meshes
+// ----------------------------------------------------------------------------
+use glam::{uvec2, UVec2};
+
+use crate::terrain_painting::is_terrain_hole;
+
+use super::generator::TileTriangle;
+use super::{TerrainTileId, TILE_SIZE};
+// ----------------------------------------------------------------------------
+/// tile meshes include the shared border vertices of the next tiles
+const VERTICES_PER_EDGE: u32 = TILE_SIZE + 1;
+// ----------------------------------------------------------------------------
+/// hole flags of all vertex positions of a tile
+pub(super) struct TileHoles {
+    holes: Vec<bool>,
+}
+// ----------------------------------------------------------------------------
+impl TileHoles {
+    // ------------------------------------------------------------------------
+    /// extracts holes of the tile from full resolution texture control data.
+    /// returns None if the tile has no holes (or data is not available).
+    pub(super) fn extract(
+        tile_id: TerrainTileId<TILE_SIZE>,
+        map_size: u32,
+        texture_control: &[u16],
+    ) -> Option<Self> {
+        if map_size == 0 || texture_control.len() != (map_size * map_size) as usize {
+            return None;
+        }
+        let offset = tile_id.sampling_offset();
+        let last = UVec2::splat(map_size - 1);
+
+        let mut holes = Vec::with_capacity((VERTICES_PER_EDGE * VERTICES_PER_EDGE) as usize);
+        for y in 0..VERTICES_PER_EDGE {
+            for x in 0..VERTICES_PER_EDGE {
+                // same as heightmap sampling: repeat last col & row of map
+                let pos = (offset + uvec2(x, y)).min(last);
+                holes.push(is_terrain_hole(
+                    texture_control[(pos.y * map_size + pos.x) as usize],
+                ));
+            }
+        }
+        holes.iter().any(|hole| *hole).then_some(Self { holes })
+    }
+    // ------------------------------------------------------------------------
+    /// true if all datapoints covered by the triangle are holes
+    pub(super) fn covers(&self, triangle: &TileTriangle) -> bool {
+        let (a, b, c) = (triangle.a(), triangle.b(), triangle.c());
+
+        // most triangles have at least one vertex which is not a hole
+        if !self.is_hole(a) || !self.is_hole(b) || !self.is_hole(c) {
+            return false;
+        }
+        let min = a.min(b).min(c);
+        let max = a.max(b).max(c);
+
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let pos = uvec2(x, y);
+                if !self.is_hole(pos) && is_inside(a, b, c, pos) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+    // ------------------------------------------------------------------------
+    #[inline(always)]
+    fn is_hole(&self, pos: UVec2) -> bool {
+        self.holes[(pos.y * VERTICES_PER_EDGE + pos.x) as usize]
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+// helper
+// ----------------------------------------------------------------------------
+/// true if pos is inside of triangle or on one of its edges (independent of
+/// vertex order)
+#[inline(always)]
+fn is_inside(a: UVec2, b: UVec2, c: UVec2, pos: UVec2) -> bool {
+    let ab = edge_side(a, b, pos);
+    let bc = edge_side(b, c, pos);
+    let ca = edge_side(c, a, pos);
+
+    (ab >= 0 && bc >= 0 && ca >= 0) || (ab <= 0 && bc <= 0 && ca <= 0)
+}
+// ----------------------------------------------------------------------------
+#[inline(always)]
+fn edge_side(from: UVec2, to: UVec2, pos: UVec2) -> i64 {
+    let (fx, fy) = (from.x as i64, from.y as i64);
+    (to.x as i64 - fx) * (pos.y as i64 - fy) - (to.y as i64 - fy) * (pos.x as i64 - fx)
+}
+// ----------------------------------------------------------------------------
@@ -6,11 +6,10 @@ use bevy::{
 };
 
 use super::{
-    AdaptiveTileMeshLods, MeshReduction, TerrainConfig, TerrainLodAnchor, TerrainLodSettings,
-    TerrainMeshSettings, TerrainTileComponent, TerrainTileId, TileMeshGenerationQueued, TILE_SIZE,
+    AdaptiveTileMeshLods, TerrainConfig, TerrainLodAnchor, TerrainLodSettings, TerrainMeshSettings,
+    TerrainTileComponent, TerrainTileId, TileMeshGenerationQueued, TILE_SIZE,
 };
 
-use super::generator::TileTriangle;
 // ----------------------------------------------------------------------------
 #[derive(Default)]
 pub(super) struct MeshLodTracker {
@@ -468,63 +467,6 @@ impl TrackedMeshErrorThresholds {
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
-impl MeshReduction {
-    // ------------------------------------------------------------------------
-    pub fn get_error_threshold(&self, triangle: &TileTriangle) -> f32 {
-        // TODO reduce to less checks?
-        if self.special_case {
-            if triangle.a() == UVec2::ZERO
-                || triangle.b() == UVec2::ZERO
-                || triangle.c() == UVec2::ZERO
-            {
-                self.target_top
-                    .min(self.target_left)
-                    .min(self.target_corner_tl)
-            } else if triangle.a() == UVec2::new(0, TILE_SIZE)
-                || triangle.b() == UVec2::new(0, TILE_SIZE)
-                || triangle.c() == UVec2::new(0, TILE_SIZE)
-            {
-                self.target_bottom
-                    .min(self.target_left)
-                    .min(self.target_corner_bl)
-            } else if triangle.a() == UVec2::new(TILE_SIZE, 0)
-                || triangle.b() == UVec2::new(TILE_SIZE, 0)
-                || triangle.c() == UVec2::new(TILE_SIZE, 0)
-            {
-                self.target_top
-                    .min(self.target_right)
-                    .min(self.target_corner_tr)
-            } else if triangle.a() == UVec2::new(TILE_SIZE, TILE_SIZE)
-                || triangle.b() == UVec2::new(TILE_SIZE, TILE_SIZE)
-                || triangle.c() == UVec2::new(TILE_SIZE, TILE_SIZE)
-            {
-                self.target_bottom
-                    .min(self.target_right)
-                    .min(self.target_corner_br)
-            } else if triangle.a().x == 0 || triangle.b().x == 0 || triangle.c().x == 0 {
-                self.target_left
-            } else if triangle.a().x == TILE_SIZE
-                || triangle.b().x == TILE_SIZE
-                || triangle.c().x == TILE_SIZE
-            {
-                self.target_right
-            } else if triangle.a().y == 0 || triangle.b().y == 0 || triangle.c().y == 0 {
-                self.target_top
-            } else if triangle.a().y == TILE_SIZE
-                || triangle.b().y == TILE_SIZE
-                || triangle.c().y == TILE_SIZE
-            {
-                self.target_bottom
-            } else {
-                self.target
-            }
-        } else {
-            self.target
-        }
-    }
-    // ------------------------------------------------------------------------
-}
-// ----------------------------------------------------------------------------
 impl<const TILE_SIZE: u32> TerrainTileId<TILE_SIZE> {
     // ------------------------------------------------------------------------
     #[inline(always)]
@@ -0,0 +1,182 @@
+// ----------------------------------------------------------------------------
+// generated tile mesh data (gpu upload see terrain_render)
+// ----------------------------------------------------------------------------
+use std::ops::Add;
+
+#[cfg(feature = "editor")]
+use {bevy::reflect::TypeUuid, bytemuck::cast_slice};
+// ----------------------------------------------------------------------------
+#[cfg_attr(feature = "editor", derive(TypeUuid))]
+#[cfg_attr(feature = "editor", uuid = "dd81109b-f363-4c59-be19-5038df017247")]
+pub struct TerrainMesh {
+    vertex_data: Option<TerrainMeshVertexData>,
+    indices: Option<TerrainMeshIndices>,
+    stats: TerrainMeshStats,
+}
+// ----------------------------------------------------------------------------
+pub enum TerrainMeshVertexData {
+    PositionAndNormal(Vec<[f32; 4]>),
+    WithBarycentricCoordinates(Vec<[f32; 5]>),
+}
+// ----------------------------------------------------------------------------
+/// triangle list indices. u16 indices are used if all vertices are addressable.
+pub enum TerrainMeshIndices {
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+}
+// ----------------------------------------------------------------------------
+#[derive(Default, Clone)]
+pub struct TerrainMeshStats {
+    pub vertices: u32,
+    pub triangles: u32,
+    pub data_bytes: usize,
+}
+// ----------------------------------------------------------------------------
+impl TerrainMesh {
+    // ------------------------------------------------------------------------
+    pub fn new(vertex_data: TerrainMeshVertexData, indices: TerrainMeshIndices) -> Self {
+        Self {
+            stats: TerrainMeshStats {
+                vertices: vertex_data.len() as u32,
+                triangles: indices.triangles(),
+                data_bytes: vertex_data.buffer_size() + indices.buffer_size(),
+            },
+            vertex_data: Some(vertex_data),
+            indices: Some(indices),
+        }
+    }
+    // ------------------------------------------------------------------------
+    pub fn stats(&self) -> &TerrainMeshStats {
+        &self.stats
+    }
+    // ------------------------------------------------------------------------
+    pub fn pending_upload(&self) -> bool {
+        self.vertex_data.is_some()
+    }
+    // ------------------------------------------------------------------------
+    /// vertex data of a mesh which was not (yet) uploaded to the gpu
+    pub fn vertex_data(&self) -> Option<&TerrainMeshVertexData> {
+        self.vertex_data.as_ref()
+    }
+    // ------------------------------------------------------------------------
+    /// index data of a mesh which was not (yet) uploaded to the gpu
+    pub fn index_data(&self) -> Option<&TerrainMeshIndices> {
+        self.indices.as_ref()
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+// gpu upload helper
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+impl TerrainMesh {
+    // ------------------------------------------------------------------------
+    /// moves vertex and index data into a new mesh (stats are not moved)
+    pub(crate) fn take_data(&mut self) -> Self {
+        Self {
+            vertex_data: self.vertex_data.take(),
+            indices: self.indices.take(),
+            stats: TerrainMeshStats::default(),
+        }
+    }
+    // ------------------------------------------------------------------------
+    pub(crate) fn get_vertex_buffer_bytes(&self) -> &[u8] {
+        use TerrainMeshVertexData::*;
+
+        match self
+            .vertex_data
+            .as_ref()
+            .expect("missing terrain mesh vertex buffer")
+        {
+            PositionAndNormal(data) => cast_slice(data),
+            WithBarycentricCoordinates(data) => cast_slice(data),
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// Computes and returns the index data of the mesh as bytes.
+    /// This is used to transform the index data into a GPU friendly format.
+    pub(crate) fn get_index_buffer_bytes(&self) -> &[u8] {
+        match self.indices() {
+            TerrainMeshIndices::U16(indices) => cast_slice(&indices[..]),
+            TerrainMeshIndices::U32(indices) => cast_slice(&indices[..]),
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// Retrieves the vertex `indices` of the mesh.
+    #[inline(always)]
+    pub(crate) fn indices(&self) -> &TerrainMeshIndices {
+        self.indices.as_ref().expect("missing terrain mesh indices")
+    }
+    // ------------------------------------------------------------------------
+    #[inline(always)]
+    pub(crate) fn has_barycentric_data(&self) -> bool {
+        matches!(
+            self.vertex_data,
+            Some(TerrainMeshVertexData::WithBarycentricCoordinates(_))
+        )
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl TerrainMeshVertexData {
+    // ------------------------------------------------------------------------
+    pub(crate) const fn size(&self) -> usize {
+        use TerrainMeshVertexData::*;
+        match self {
+            PositionAndNormal(_) => 4 * 4,
+            WithBarycentricCoordinates(_) => 5 * 4,
+        }
+    }
+    // ------------------------------------------------------------------------
+    fn len(&self) -> usize {
+        use TerrainMeshVertexData::*;
+        match self {
+            PositionAndNormal(d) => d.len(),
+            WithBarycentricCoordinates(d) => d.len(),
+        }
+    }
+    // ------------------------------------------------------------------------
+    fn buffer_size(&self) -> usize {
+        self.len() * self.size()
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl TerrainMeshIndices {
+    // ------------------------------------------------------------------------
+    pub fn len(&self) -> usize {
+        match self {
+            TerrainMeshIndices::U16(v) => v.len(),
+            TerrainMeshIndices::U32(v) => v.len(),
+        }
+    }
+    // ------------------------------------------------------------------------
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    // ------------------------------------------------------------------------
+    fn triangles(&self) -> u32 {
+        self.len() as u32 / 3
+    }
+    // ------------------------------------------------------------------------
+    fn buffer_size(&self) -> usize {
+        match self {
+            TerrainMeshIndices::U16(v) => v.len() * 2,
+            TerrainMeshIndices::U32(v) => v.len() * 4,
+        }
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+impl Add<&TerrainMeshStats> for &TerrainMeshStats {
+    type Output = TerrainMeshStats;
+
+    fn add(self, other: &TerrainMeshStats) -> TerrainMeshStats {
+        TerrainMeshStats {
+            vertices: self.vertices + other.vertices,
+            triangles: self.triangles + other.triangles,
+            data_bytes: self.data_bytes + other.data_bytes,
+        }
+    }
+}
+// ----------------------------------------------------------------------------
@@ -1,48 +1,67 @@
 // ----------------------------------------------------------------------------
 /// defines the max time for blocking errormap/mesh generation until remaining
 /// work is deferred to next frame. prevents blocking of complete app.
+#[cfg(feature = "editor")]
 const MAX_MESH_GENERATION_TIME_MS: instant::Duration = instant::Duration::from_millis(5);
+#[cfg(feature = "editor")]
 const MAX_ERRORMAP_GENERATION_TIME_MS: instant::Duration = instant::Duration::from_millis(35);
 // ----------------------------------------------------------------------------
 /// defines how many tiles are processed in parallel before a check for max
 /// generation time is made
+#[cfg(feature = "editor")]
 const MESH_GENERATION_QUEUE_CHUNKSIZE: usize = 10;
 // ----------------------------------------------------------------------------
-use std::ops::Deref;
 use std::sync::Arc;
 
-use bevy::{
-    ecs::schedule::StateData,
-    math::{vec3, vec3a, Vec2, Vec3, Vec3Swizzles},
-    prelude::*,
-    render::primitives::Aabb,
-    tasks::{AsyncComputeTaskPool, ComputeTaskPool, TaskPool},
-};
+use glam::UVec2;
 
-use crate::cmds::{AsyncTaskFinishedEvent, AsyncTaskStartEvent, TrackedProgress};
 use crate::config::{TerrainConfig, TILE_SIZE};
-use crate::heightmap::{
-    TerrainDataView, TerrainHeightMap, TerrainHeightMapView, TerrainNormals, TerrainTileId,
-};
-use crate::terrain_clipmap::ClipmapAssignment;
-use crate::terrain_render::{
-    TerrainMesh, TerrainMeshStats, TerrainMeshVertexData, TerrainRenderSettings,
+use crate::heightmap::{TerrainDataView, TerrainHeightMap, TerrainNormals, TerrainTileId};
+
+use self::errormap::{ErrorMapsPostprocessing, TileHeightErrors};
+use self::generator::TileTriangle;
+use self::holes::TileHoles;
+
+pub use self::mesh::{TerrainMesh, TerrainMeshIndices, TerrainMeshStats, TerrainMeshVertexData};
+
+#[cfg(feature = "editor")]
+use {
+    bevy::{
+        ecs::schedule::StateData,
+        math::{uvec2, vec3, vec3a, Vec2, Vec3, Vec3Swizzles},
+        prelude::*,
+        render::primitives::Aabb,
+        tasks::{AsyncComputeTaskPool, ComputeTaskPool, TaskPool},
+    },
+    std::ops::Deref,
+    TerrainTileSystemLabel::*,
 };
-use crate::EditorEvent;
 
-use TerrainTileSystemLabel::*;
+#[cfg(feature = "editor")]
+use crate::{
+    clipmap::Rectangle,
+    cmds::{AsyncTaskFinishedEvent, AsyncTaskStartEvent, TrackedProgress},
+    heightmap::{HeightmapChanges, TerrainHeightMapView},
+    terrain_clipmap::{ClipmapAssignment, TextureControlClipmap},
+    terrain_render::TerrainRenderSettings,
+    texturecontrol::TerrainHoleChanges,
+    EditorEvent,
+};
 
-use self::errormap::{ErrorMapsPostprocessing, TileHeightErrors};
-use self::lod::MeshLodTracker;
-use self::settings::TerrainLodSettings;
+#[cfg(feature = "editor")]
+use self::{lod::MeshLodTracker, settings::TerrainLodSettings};
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 pub struct TerrainTilesGeneratorPlugin;
 
+#[cfg(feature = "editor")]
 pub use self::settings::{LodSlot, TerrainMeshSettings};
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 #[derive(Component)]
 pub struct TerrainLodAnchor;
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 #[derive(Debug, Clone, Hash, Eq, PartialEq, SystemLabel)]
 pub enum TerrainTileSystemLabel {
     ErrorMapGeneration,
@@ -50,6 +69,7 @@ pub enum TerrainTileSystemLabel {
     MeshGeneration,
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 impl TerrainTilesGeneratorPlugin {
     // ------------------------------------------------------------------------
     /// async (re)generation of terrain tiles, errormaps, meshes based on lod_anchor
@@ -64,6 +84,7 @@ impl TerrainTilesGeneratorPlugin {
                     .after(ErrorMapGeneration),
             )
             .with_system(async_tilemesh_generation.label(MeshGeneration))
+            .with_system(queue_tiles_with_changed_holes.before(MeshGeneration))
             .with_system(lod::adjust_tile_mesh_lod.before(MeshGeneration))
             .with_system(lod::adjust_meshes_on_config_change.before(MeshGeneration))
             .with_system(collect_stats.after(MeshGeneration))
@@ -76,18 +97,21 @@ impl TerrainTilesGeneratorPlugin {
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 impl Plugin for TerrainTilesGeneratorPlugin {
     // ------------------------------------------------------------------------
     fn build(&self, app: &mut App) {
         app.init_resource::<TerrainMeshSettings>()
             .init_resource::<TerrainStats>()
             .init_resource::<MeshLodTracker>()
-            .init_resource::<ErrorMapsPostprocessing>()
+            .init_resource::<ErrorMapsPostprocessing<Entity>>()
+            .init_resource::<TerrainHoleChanges>()
             .init_resource::<errormap::TileTriangleLookup>();
     }
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 #[derive(Default)]
 pub struct TerrainStats {
     pub tiles: u16,
@@ -99,8 +123,11 @@ pub struct TerrainStats {
     pub last_update_triangles: usize,
     pub last_update_data_bytes: usize,
     pending_updates: bool,
+    /// tiles waiting for (re)generation of their mesh
+    queued_tiles: usize,
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 #[derive(Component, Clone)]
 pub struct TerrainTileComponent {
     id: TerrainTileId<TILE_SIZE>,
@@ -111,6 +138,7 @@ pub struct TerrainTileComponent {
     pos_center: Vec3,
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 impl TerrainTileComponent {
     // ------------------------------------------------------------------------
     pub fn assigned_lod(&self) -> u8 {
@@ -119,6 +147,16 @@ impl TerrainTileComponent {
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+impl TerrainStats {
+    // ------------------------------------------------------------------------
+    /// true if tile meshes are queued for generation or not yet uploaded
+    pub fn has_pending_updates(&self) -> bool {
+        self.pending_updates || self.queued_tiles > 0
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
 #[derive(Clone, Copy)]
 struct IndexBound(f32);
 // ----------------------------------------------------------------------------
@@ -147,9 +185,15 @@ struct MeshReduction {
 // ----------------------------------------------------------------------------
 mod errormap;
 mod generator;
+mod holes;
+mod mesh;
+
+#[cfg(feature = "editor")]
 mod lod;
+#[cfg(feature = "editor")]
 mod settings;
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 impl TerrainTileComponent {
     // ------------------------------------------------------------------------
     fn new(
@@ -198,11 +242,13 @@ impl TerrainTileComponent {
 /// Marks a tile to be usable for mesh lod assignment based on (some) distance
 /// measure to TerrainLodAnchor. In general all mesh tiles should have adaptive
 /// lods. But if errormaps are (re)calculated any lod changes have to be stopped.
+#[cfg(feature = "editor")]
 #[derive(Component)]
 struct AdaptiveTileMeshLods;
 // ----------------------------------------------------------------------------
 // systems
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 fn update_mesh_index_bound(
     mut query: Query<(&mut TerrainTileComponent, &Handle<TerrainMesh>)>,
     meshes: Res<Assets<TerrainMesh>>,
@@ -237,6 +283,7 @@ fn update_mesh_index_bound(
     }
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 fn collect_stats(mut stats: ResMut<TerrainStats>, meshes: Res<Assets<TerrainMesh>>) {
     if meshes.is_changed() {
         let (summed, pending, count, count_pending) = meshes.iter().fold(
@@ -263,7 +310,7 @@ fn collect_stats(mut stats: ResMut<TerrainStats>, meshes: Res<Assets<TerrainMesh
         stats.tiles = count;
         stats.vertices = summed.vertices as usize;
         stats.triangles = summed.triangles as usize;
-        stats.data_bytes = summed.data_bytes as usize;
+        stats.data_bytes = summed.data_bytes;
 
         if count_pending > 0 {
             // Note: this is not accurate. if generating of tiles is too slow
@@ -281,72 +328,137 @@ fn collect_stats(mut stats: ResMut<TerrainStats>, meshes: Res<Assets<TerrainMesh
             stats.last_update_tiles += count_pending;
             stats.last_update_vertices += pending.vertices as usize;
             stats.last_update_triangles += pending.triangles as usize;
-            stats.last_update_data_bytes += pending.data_bytes as usize;
+            stats.last_update_data_bytes += pending.data_bytes;
         } else {
             stats.pending_updates = false;
         }
     }
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 #[allow(clippy::too_many_arguments)]
 fn start_async_terraintile_tasks(
     mut commands: Commands,
     terrain_config: Res<TerrainConfig>,
     heightmap: Res<TerrainHeightMap>,
+    mut heightmap_changes: ResMut<HeightmapChanges>,
     thread_pool: Res<ComputeTaskPool>,
 
-    tiles: Query<Entity, With<TerrainTileComponent>>,
-    mut errormaps_postprocessing: ResMut<ErrorMapsPostprocessing>,
+    mut tiles: Query<(
+        Entity,
+        &mut TerrainTileComponent,
+        &mut Aabb,
+        Option<&TileHeightErrors>,
+    )>,
+    mut errormaps_postprocessing: ResMut<ErrorMapsPostprocessing<Entity>>,
 
     mut tasks_queued: EventReader<AsyncTaskStartEvent>,
     mut task_finished: EventWriter<AsyncTaskFinishedEvent>,
 ) {
     use AsyncTaskStartEvent::*;
 
+    // on heightmap changes only the tiles covering the changed region and
+    // their direct neighbors (seams) have to be regenerated
+    let changed_region = heightmap_changes.processing_affected(terrain_config.map_size());
+
     for task in tasks_queued.iter() {
         match task {
             GenerateTerrainTiles => {
                 debug!("generating terrain tiles...");
-                commands.spawn_batch(generate_tiles(&*terrain_config, &*heightmap, &*thread_pool));
-                commands.insert_resource(MeshLodTracker::new(&*terrain_config));
+                commands.spawn_batch(generate_tiles(&terrain_config, &heightmap, &thread_pool));
+                commands.insert_resource(MeshLodTracker::new(&terrain_config));
                 task_finished.send(AsyncTaskFinishedEvent::TerrainTilesGenerated);
             }
-            GenerateTerrainMeshErrorMaps => {
-                debug!("generating error maps...");
-                tiles.iter().for_each(|entity| {
-                    commands
-                        .entity(entity)
-                        .insert(TileHeightErrorGenerationQueued);
-                });
-                *errormaps_postprocessing = ErrorMapsPostprocessing::new(
-                    terrain_config.map_size(),
-                    terrain_config.tile_count(),
-                );
-            }
+            GenerateTerrainMeshErrorMaps => match &changed_region {
+                None => {
+                    debug!("generating error maps...");
+                    tiles.iter().for_each(|(entity, ..)| {
+                        commands
+                            .entity(entity)
+                            .insert(TileHeightErrorGenerationQueued);
+                    });
+                    *errormaps_postprocessing = ErrorMapsPostprocessing::new(
+                        terrain_config.map_size(),
+                        terrain_config.tile_count(),
+                    );
+                }
+                Some(region) => {
+                    debug!("regenerating error maps of changed tiles...");
+                    let changed = TileRange::from_region(region);
+                    let neighbors = changed.grow(terrain_config.tiles_per_edge());
+
+                    *errormaps_postprocessing = ErrorMapsPostprocessing::new(
+                        terrain_config.map_size(),
+                        neighbors.tile_count(),
+                    );
+
+                    let height_offset = terrain_config.min_height();
+                    let height_scaling = terrain_config.height_scaling();
+                    let resolution = terrain_config.resolution();
+
+                    for (entity, mut tile, mut aabb, errors) in tiles.iter_mut() {
+                        if changed.contains(tile.id) {
+                            // heights changed -> bounding box must be updated
+                            let (min, max) = heightmap.tile_min_max_height(tile.id);
+                            tile.min_height = min.to_f32();
+                            tile.max_height = max.to_f32();
+                            *aabb = tile.compute_aabb(height_offset, height_scaling, resolution);
+
+                            commands
+                                .entity(entity)
+                                .insert(TileHeightErrorGenerationQueued);
+                        } else if neighbors.contains(tile.id) {
+                            // errormaps of neighbors are still valid but their
+                            // seams must be merged with the regenerated ones
+                            if let Some(errors) = errors {
+                                errormaps_postprocessing.add_errormap(
+                                    entity,
+                                    tile.id,
+                                    errors.clone(),
+                                );
+                                commands.entity(entity).remove::<TileHeightErrors>();
+                            }
+                        }
+                    }
+                }
+            },
             MergeTerrainMeshErrorMapSeams => {
                 debug!("merging error map seams...");
                 errormaps_postprocessing.start();
             }
             GenerateTerrainMeshes => {
                 debug!("generating tile meshes...");
-                tiles.iter().for_each(|entity| {
-                    commands
-                        .entity(entity)
-                        .insert(TileMeshGenerationQueued)
-                        // from this point on the lod for the meshes may be
-                        // changed by a dedicated system
-                        .insert(AdaptiveTileMeshLods);
+                let neighbors = changed_region.as_ref().map(|region| {
+                    TileRange::from_region(region).grow(terrain_config.tiles_per_edge())
                 });
+
+                tiles
+                    .iter()
+                    .filter(|(_, tile, ..)| neighbors.as_ref().is_none_or(|n| n.contains(tile.id)))
+                    .for_each(|(entity, ..)| {
+                        commands
+                            .entity(entity)
+                            .insert(TileMeshGenerationQueued)
+                            // from this point on the lod for the meshes may be
+                            // changed by a dedicated system
+                            .insert(AdaptiveTileMeshLods);
+                    });
+
+                // all required data for mesh generation is ready. next heightmap
+                // changes can be processed
+                heightmap_changes.finish_processing();
             }
             _ => {}
         }
     }
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 #[derive(Component)]
 /// marker for tiles which require regeneration of errormap
 struct TileHeightErrorGenerationQueued;
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 #[allow(clippy::too_many_arguments)]
 fn async_errormap_generation(
     mut commands: Commands,
@@ -358,7 +470,7 @@ fn async_errormap_generation(
     mut task_finished: EventWriter<AsyncTaskFinishedEvent>,
     mut editor_events: EventWriter<EditorEvent>,
     mut triangle_table: ResMut<errormap::TileTriangleLookup>,
-    mut seamprocessing_queue: ResMut<ErrorMapsPostprocessing>,
+    mut seamprocessing_queue: ResMut<ErrorMapsPostprocessing<Entity>>,
 ) {
     if !tiles.is_empty() {
         use instant::Instant;
@@ -436,9 +548,10 @@ fn async_errormap_generation(
     }
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 fn async_errormap_seam_processing(
     mut commands: Commands,
-    mut errormaps_postprocessing: ResMut<ErrorMapsPostprocessing>,
+    mut errormaps_postprocessing: ResMut<ErrorMapsPostprocessing<Entity>>,
     thread_pool: Res<ComputeTaskPool>,
     mut task_finished: EventWriter<AsyncTaskFinishedEvent>,
     mut editor_events: EventWriter<EditorEvent>,
@@ -501,10 +614,12 @@ fn async_errormap_seam_processing(
     }
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 #[derive(Component)]
 /// marker for tiles which require regeneration of meshes
 struct TileMeshGenerationQueued;
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 #[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn async_tilemesh_generation(
     mut commands: Commands,
@@ -512,6 +627,7 @@ fn async_tilemesh_generation(
     render_settings: Res<TerrainRenderSettings>,
     heightmap: Res<TerrainHeightMap>,
     normals: Res<TerrainNormals>,
+    texture_clipmap: Res<TextureControlClipmap>,
     mut meshes: ResMut<Assets<TerrainMesh>>,
     tiles: Query<
         (
@@ -523,15 +639,23 @@ fn async_tilemesh_generation(
         With<TileMeshGenerationQueued>,
     >,
     thread_pool: Res<AsyncComputeTaskPool>,
+    mut stats: ResMut<TerrainStats>,
     mut task_finished: EventWriter<AsyncTaskFinishedEvent>,
     mut editor_events: EventWriter<EditorEvent>,
 ) {
-    if !tiles.is_empty() {
+    if tiles.is_empty() {
+        // Note: as_ref required to prevent setting "changed" flag
+        if stats.as_ref().queued_tiles > 0 {
+            stats.queued_tiles = 0;
+        }
+    } else {
         use instant::Instant;
 
         let include_wireframe_info = render_settings.overlay_wireframe;
         let heightmap = Arc::new(heightmap.deref());
         let normals = Arc::new(normals.deref());
+        let texture_control = texture_clipmap.fullres_data_slice();
+        let map_size = terrain_config.map_size();
         let terrain_config = &terrain_config;
         let start_time = Instant::now();
 
@@ -559,12 +683,15 @@ fn async_tilemesh_generation(
                             );
 
                             s.spawn(async move {
+                                let holes = TileHoles::extract(tile.id, map_size, texture_control);
+
                                 generator::generate_tilemesh(
                                     tile.id,
                                     terrain_config.resolution(),
                                     terrain_config.min_height(),
                                     &tile.mesh_conf,
                                     terraindata_view,
+                                    holes,
                                     triangle_errors,
                                     include_wireframe_info,
                                     tile.mesh_conf.use_small_index(include_wireframe_info),
@@ -593,6 +720,8 @@ fn async_tilemesh_generation(
                 None => break,
             }
         }
+        stats.queued_tiles = remaining_tiles;
+
         // progress update for GUI
         let max_tiles = terrain_config.tile_count();
         editor_events.send(EditorEvent::ProgressTrackingUpdate(
@@ -608,6 +737,190 @@ fn async_tilemesh_generation(
     }
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+fn queue_tiles_with_changed_holes(
+    mut commands: Commands,
+    terrain_config: Res<TerrainConfig>,
+    mut hole_changes: ResMut<TerrainHoleChanges>,
+    tiles: Query<(Entity, &TerrainTileComponent)>,
+) {
+    // Note: as_ref required to prevent setting "changed" flag
+    if !hole_changes.as_ref().has_changes() {
+        return;
+    }
+    if let Some(region) = hole_changes.take() {
+        // errormaps are independent of holes: only the meshes of the tiles
+        // covering the region have to be regenerated. tiles share their border
+        // vertices -> include the datapoints left and above of the region
+        let min = uvec2(
+            region.pos.x.saturating_sub(1),
+            region.pos.y.saturating_sub(1),
+        );
+        let max = (region.pos + region.size).min(UVec2::splat(terrain_config.map_size()));
+        let changed = TileRange::from_region(&Rectangle {
+            pos: min,
+            size: max - min,
+        });
+
+        for (entity, tile) in tiles.iter() {
+            if changed.contains(tile.id) {
+                commands.entity(entity).insert(TileMeshGenerationQueued);
+            }
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+// headless generation (without ecs, see TerrainDocument)
+// ----------------------------------------------------------------------------
+/// errormaps (with merged seams) of all tiles of a terrain.
+pub(crate) struct TileErrorMaps {
+    tiles_per_edge: u8,
+    errormaps: Vec<TileHeightErrors>,
+}
+// ----------------------------------------------------------------------------
+impl TileErrorMaps {
+    // ------------------------------------------------------------------------
+    /// blocking generation of errormaps for all tiles including the seam
+    /// merging passes.
+    pub(crate) fn generate(
+        terrain_config: &TerrainConfig,
+        heightmap: &TerrainHeightMap,
+        normals: &TerrainNormals,
+    ) -> Self {
+        let tiles_per_edge = terrain_config.tiles_per_edge();
+        let tiles = (0..tiles_per_edge)
+            .flat_map(|y| (0..tiles_per_edge).map(move |x| TerrainTileId::new(x, y)))
+            .collect::<Vec<TerrainTileId<TILE_SIZE>>>();
+
+        let mut triangle_table = errormap::TileTriangleLookup::default();
+        triangle_table.generate();
+
+        // sharable references for scoped threads
+        let triangles = &triangle_table;
+        let heightmap = Arc::new(heightmap);
+        let normals = Arc::new(normals);
+
+        let generated_errormaps = parallel_map(tiles.clone(), |tileid| {
+            let terraindata_view =
+                TerrainDataView::new(tileid.sampling_offset(), heightmap.clone(), normals.clone());
+            errormap::generate_errormap(triangles, &terraindata_view)
+        });
+
+        // tiles are not entities here: use tile index as key to restore the
+        // order after seam processing
+        let mut postprocessing =
+            ErrorMapsPostprocessing::new(terrain_config.map_size(), tiles.len());
+
+        for (i, (tileid, errors)) in tiles.iter().zip(generated_errormaps).enumerate() {
+            postprocessing.add_errormap(i, *tileid, errors);
+        }
+        postprocessing.start();
+
+        // at least one pass is required to finalize the processing (even if
+        // all seams already match)
+        loop {
+            let packages = std::iter::from_fn(|| postprocessing.next_package()).collect();
+            let mut updated_errormaps = parallel_map(packages, |(i, tileid, mut errormap)| {
+                errormap::update_errormap(triangles, &mut errormap);
+                (i, tileid, errormap)
+            });
+            postprocessing.append_results(&mut updated_errormaps);
+            postprocessing.finalize_pass();
+
+            if !postprocessing.processing_required() {
+                break;
+            }
+        }
+
+        let mut errormaps = postprocessing
+            .drain_results()
+            .map(|(i, _, errors)| (i, errors))
+            .collect::<Vec<_>>();
+        errormaps.sort_unstable_by_key(|(i, _)| *i);
+
+        Self {
+            tiles_per_edge,
+            errormaps: errormaps.drain(..).map(|(_, errors)| errors).collect(),
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// blocking generation of the tile mesh with the same error threshold for
+    /// the complete tile (including the seams). uses u32 indices. holes of the
+    /// full resolution texture control data are cut out.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn generate_mesh(
+        &self,
+        terrain_config: &TerrainConfig,
+        heightmap: &TerrainHeightMap,
+        normals: &TerrainNormals,
+        texture_control: &[u16],
+        tile_id: TerrainTileId<TILE_SIZE>,
+        error_threshold: f32,
+    ) -> Result<TerrainMesh, String> {
+        let idx = tile_id.y() as usize * self.tiles_per_edge as usize + tile_id.x() as usize;
+        let triangle_errors = self
+            .errormaps
+            .get(idx)
+            .filter(|_| tile_id.x() < self.tiles_per_edge && tile_id.y() < self.tiles_per_edge)
+            .ok_or_else(|| format!("tile {:?} is outside of terrain", tile_id))?;
+
+        let mesh_conf = MeshReduction::uniform(error_threshold);
+        let terraindata_view = TerrainDataView::new(
+            tile_id.sampling_offset(),
+            Arc::new(heightmap),
+            Arc::new(normals),
+        );
+        let holes = TileHoles::extract(tile_id, terrain_config.map_size(), texture_control);
+
+        Ok(generator::generate_tilemesh(
+            tile_id,
+            terrain_config.resolution(),
+            terrain_config.min_height(),
+            &mesh_conf,
+            terraindata_view,
+            holes,
+            triangle_errors,
+            false,
+            false,
+        ))
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+/// maps all items in parallel (one chunk of items per available thread) and
+/// returns the results in the same order.
+fn parallel_map<T, R, F>(items: Vec<T>, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+{
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = items.len().div_ceil(threads).max(1);
+
+    let mut chunks = Vec::with_capacity(threads);
+    let mut items = items.into_iter();
+    loop {
+        let chunk = items.by_ref().take(chunk_size).collect::<Vec<_>>();
+        if chunk.is_empty() {
+            break;
+        }
+        chunks.push(chunk);
+    }
+
+    let f = &f;
+    std::thread::scope(|s| {
+        chunks
+            .into_iter()
+            .map(|chunk| s.spawn(move || chunk.into_iter().map(f).collect::<Vec<_>>()))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("tile processing thread panicked"))
+            .collect()
+    })
+}
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 type TerrainTileBundle = (
     TerrainTileComponent,
     ClipmapAssignment,
@@ -618,6 +931,7 @@ type TerrainTileBundle = (
     ComputedVisibility,
 );
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 fn generate_tiles(
     terrain_config: &TerrainConfig,
     heightmap: &TerrainHeightMap,
@@ -630,7 +944,7 @@ fn generate_tiles(
     // an async task/thread pool -> scoped, blocking threadpool
 
     // extract min max heights per tile to precaclulate bounding boxes
-    let hm = Arc::new(heightmap.deref());
+    let hm = Arc::new(heightmap);
     let mut tile_elevation = thread_pool.scope(|s| {
         for y in 0..tiles {
             let heightmap_strip =
@@ -680,15 +994,73 @@ fn generate_tiles(
         .collect::<Vec<_>>()
 }
 // ----------------------------------------------------------------------------
+/// inclusive range of tile coordinates
+#[cfg(feature = "editor")]
+struct TileRange {
+    min: UVec2,
+    max: UVec2,
+}
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+impl TileRange {
+    // ------------------------------------------------------------------------
+    /// all tiles overlapping the region (map coordinates)
+    fn from_region(region: &Rectangle) -> Self {
+        Self {
+            min: region.pos / TILE_SIZE,
+            max: (region.pos + region.size - UVec2::ONE) / TILE_SIZE,
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// extends range by one tile in every direction (clamped to map)
+    fn grow(&self, tiles_per_edge: u8) -> Self {
+        let last = tiles_per_edge as u32 - 1;
+        Self {
+            min: uvec2(self.min.x.saturating_sub(1), self.min.y.saturating_sub(1)),
+            max: (self.max + UVec2::ONE).min(UVec2::splat(last)),
+        }
+    }
+    // ------------------------------------------------------------------------
+    fn contains(&self, tile: TerrainTileId<TILE_SIZE>) -> bool {
+        let (x, y) = (tile.x() as u32, tile.y() as u32);
+        self.min.x <= x && x <= self.max.x && self.min.y <= y && y <= self.max.y
+    }
+    // ------------------------------------------------------------------------
+    fn tile_count(&self) -> usize {
+        let size = self.max - self.min + UVec2::ONE;
+        (size.x * size.y) as usize
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 fn despawn_tiles(mut commands: Commands, tiles: Query<Entity, With<TerrainTileComponent>>) {
     for tile in tiles.iter() {
         commands.entity(tile).despawn();
     }
     commands.insert_resource(TerrainStats::default());
     commands.insert_resource(MeshLodTracker::default());
+    commands.insert_resource(TerrainHoleChanges::default());
 }
 // ----------------------------------------------------------------------------
 impl MeshReduction {
+    // ------------------------------------------------------------------------
+    /// same error threshold for complete tile (no lod transitions to neighbors)
+    fn uniform(error_threshold: f32) -> Self {
+        Self {
+            current: error_threshold,
+            target: error_threshold,
+            target_top: error_threshold,
+            target_bottom: error_threshold,
+            target_left: error_threshold,
+            target_right: error_threshold,
+            target_corner_tl: error_threshold,
+            target_corner_tr: error_threshold,
+            target_corner_bl: error_threshold,
+            target_corner_br: error_threshold,
+            ..Default::default()
+        }
+    }
     // ------------------------------------------------------------------------
     #[inline(always)]
     fn use_small_index(&self, with_wireframe: bool) -> bool {
@@ -699,10 +1071,63 @@ impl MeshReduction {
         }
     }
     // ------------------------------------------------------------------------
+    pub fn get_error_threshold(&self, triangle: &TileTriangle) -> f32 {
+        // TODO reduce to less checks?
+        if self.special_case {
+            if triangle.a() == UVec2::ZERO
+                || triangle.b() == UVec2::ZERO
+                || triangle.c() == UVec2::ZERO
+            {
+                self.target_top
+                    .min(self.target_left)
+                    .min(self.target_corner_tl)
+            } else if triangle.a() == UVec2::new(0, TILE_SIZE)
+                || triangle.b() == UVec2::new(0, TILE_SIZE)
+                || triangle.c() == UVec2::new(0, TILE_SIZE)
+            {
+                self.target_bottom
+                    .min(self.target_left)
+                    .min(self.target_corner_bl)
+            } else if triangle.a() == UVec2::new(TILE_SIZE, 0)
+                || triangle.b() == UVec2::new(TILE_SIZE, 0)
+                || triangle.c() == UVec2::new(TILE_SIZE, 0)
+            {
+                self.target_top
+                    .min(self.target_right)
+                    .min(self.target_corner_tr)
+            } else if triangle.a() == UVec2::new(TILE_SIZE, TILE_SIZE)
+                || triangle.b() == UVec2::new(TILE_SIZE, TILE_SIZE)
+                || triangle.c() == UVec2::new(TILE_SIZE, TILE_SIZE)
+            {
+                self.target_bottom
+                    .min(self.target_right)
+                    .min(self.target_corner_br)
+            } else if triangle.a().x == 0 || triangle.b().x == 0 || triangle.c().x == 0 {
+                self.target_left
+            } else if triangle.a().x == TILE_SIZE
+                || triangle.b().x == TILE_SIZE
+                || triangle.c().x == TILE_SIZE
+            {
+                self.target_right
+            } else if triangle.a().y == 0 || triangle.b().y == 0 || triangle.c().y == 0 {
+                self.target_top
+            } else if triangle.a().y == TILE_SIZE
+                || triangle.b().y == TILE_SIZE
+                || triangle.c().y == TILE_SIZE
+            {
+                self.target_bottom
+            } else {
+                self.target
+            }
+        } else {
+            self.target
+        }
+    }
 }
 // ----------------------------------------------------------------------------
 impl IndexBound {
     // ------------------------------------------------------------------------
+    #[cfg(feature = "editor")]
     #[inline(always)]
     fn needs_update(&self, error_threshold: f32) -> bool {
         // vertex count grows with error threshold monotonically. thus the
@@ -710,6 +1135,7 @@ impl IndexBound {
         error_threshold < self.0
     }
     // ------------------------------------------------------------------------
+    #[cfg(feature = "editor")]
     #[inline(always)]
     fn update(&mut self, error_threshold: f32, vertex_count: u32) {
         if vertex_count < u16::MAX as u32 {
@@ -1,7 +1,8 @@
 // ----------------------------------------------------------------------------
-use bevy::render::render_resource::TextureFormat;
-
 use crate::clipmap::ClipmapData;
+
+#[cfg(feature = "editor")]
+use {crate::clipmap::Rectangle, bevy::render::render_resource::TextureFormat};
 // ----------------------------------------------------------------------------
 #[derive(Default)]
 pub struct TextureControl {
@@ -9,6 +10,15 @@ pub struct TextureControl {
     data: Vec<u16>,
 }
 // ----------------------------------------------------------------------------
+/// [Resource] Tracks texture control regions (map coordinates) where terrain
+/// holes were added or removed. Holes are cut out of the tile meshes so the
+/// meshes of the affected tiles must be regenerated.
+#[cfg(feature = "editor")]
+#[derive(Default)]
+pub struct TerrainHoleChanges {
+    pending: Option<Rectangle>,
+}
+// ----------------------------------------------------------------------------
 impl TextureControl {
     // ------------------------------------------------------------------------
     pub fn new(size: u32, data: Vec<u16>) -> Self {
@@ -18,6 +28,36 @@ impl TextureControl {
     // ------------------------------------------------------------------------
 }
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
+impl TerrainHoleChanges {
+    // ------------------------------------------------------------------------
+    /// adds changed region (will be merged with all other pending changes)
+    pub fn add(&mut self, rectangle: &Rectangle) {
+        self.pending = Some(match self.pending.take() {
+            Some(pending) => {
+                let min = pending.pos.min(rectangle.pos);
+                let max = (pending.pos + pending.size).max(rectangle.pos + rectangle.size);
+                Rectangle {
+                    pos: min,
+                    size: max - min,
+                }
+            }
+            None => rectangle.clone(),
+        });
+    }
+    // ------------------------------------------------------------------------
+    /// true if changes are pending
+    pub fn has_changes(&self) -> bool {
+        self.pending.is_some()
+    }
+    // ------------------------------------------------------------------------
+    /// returns merged region of all pending changes and resets tracking
+    pub fn take(&mut self) -> Option<Rectangle> {
+        self.pending.take()
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
 impl ClipmapData for TextureControl {
     // ------------------------------------------------------------------------
     type DataType = u16;
@@ -28,6 +68,7 @@ impl ClipmapData for TextureControl {
         1
     }
     // ------------------------------------------------------------------------
+    #[cfg(feature = "editor")]
     #[inline(always)]
     fn texture_format(&self) -> TextureFormat {
         TextureFormat::R16Uint
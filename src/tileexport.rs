@@ -0,0 +1,315 @@
+// ----------------------------------------------------------------------------
+// export of terrain data into the per tile buffer layout of the game
+// ----------------------------------------------------------------------------
+//
+// the map is split into the same tiles as the terrain of the project
+// (TerrainConfig::tiles_per_edge). every tile covers tile resolution
+// (map size / tiles per edge) datapoints per edge *plus* the first row and
+// column of the next tile: neighbouring tiles share their border datapoints so
+// the tile meshes of the game do not show seams. the last row/column of the
+// tiles at the map edge repeats the last datapoint of the map.
+//
+// every tile is exported as (up to) three headerless buffers with
+// (resolution + 1)^2 datapoints (row major, same orientation as the editor
+// maps):
+//   <tile>.height.buf   u16 little endian heights (0..u16::MAX mapped onto
+//                       min..max height of the terrain)
+//   <tile>.control.buf  u16 little endian control map values (same bit layout
+//                       as the game: overlay, background, slope blend, scale)
+//   <tile>.color.buf    rgba8 tint (color) map
+//
+// a manifest.ron describing the terrain setup and all exported tiles is written
+// into the same directory.
+//
+// Note: only the raw buffers are written. they are *not* wrapped into the cr2w
+// containers (w2ter) of the game, this is left to the cooking tools.
+//
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::config::TerrainConfig;
+// ----------------------------------------------------------------------------
+pub const TILE_EXPORT_MANIFEST: &str = "manifest.ron";
+// ----------------------------------------------------------------------------
+#[derive(Clone, Debug)]
+/// the tile layout (tiles per edge, resolution) is defined by the terrain
+pub struct TileExportSettings {
+    pub directory: String,
+    pub heightmap: bool,
+    pub controlmap: bool,
+    pub tintmap: bool,
+}
+// ----------------------------------------------------------------------------
+#[derive(Serialize)]
+pub(crate) struct TileExportManifest {
+    name: String,
+    map_size: u32,
+    terrain_size: f32,
+    min_height: f32,
+    max_height: f32,
+    tiles_per_edge: u32,
+    tile_resolution: u32,
+    /// datapoints per tile edge (including the shared border)
+    tile_datapoints: u32,
+    /// tile edge length in meters
+    tile_size: f32,
+    height_format: Option<&'static str>,
+    control_format: Option<&'static str>,
+    color_format: Option<&'static str>,
+    tiles: Vec<ExportedTileInfo>,
+}
+// ----------------------------------------------------------------------------
+#[derive(Serialize)]
+pub(crate) struct ExportedTileInfo {
+    x: u32,
+    y: u32,
+    /// world position of the tile corner with the lowest coordinates
+    origin: (f32, f32),
+    /// height range of the tile in meters
+    min_height: f32,
+    max_height: f32,
+    height: Option<String>,
+    control: Option<String>,
+    color: Option<String>,
+}
+// ----------------------------------------------------------------------------
+/// buffers of one tile (empty if the map is not exported)
+pub(crate) struct TileBuffers {
+    pub height: Vec<u8>,
+    pub control: Vec<u8>,
+    pub color: Vec<u8>,
+}
+// ----------------------------------------------------------------------------
+impl TileExportSettings {
+    // ------------------------------------------------------------------------
+    /// exports all maps into the default directory of the terrain
+    pub fn new(config: &TerrainConfig) -> Self {
+        Self {
+            directory: default_directory(config),
+            ..Default::default()
+        }
+    }
+    // ------------------------------------------------------------------------
+    pub fn validate(&self, config: &TerrainConfig) -> Result<(), String> {
+        if self.directory.trim().is_empty() {
+            return Err("tile export directory must not be empty".to_string());
+        }
+        if !(self.heightmap || self.controlmap || self.tintmap) {
+            return Err("at least one map must be selected for the tile export".to_string());
+        }
+        let (map_size, tiles) = (config.map_size(), tiles_per_edge(config));
+        if tiles == 0 || map_size % tiles != 0 {
+            return Err(format!(
+                "map size ({}) must be a multiple of tiles per edge ({})",
+                map_size, tiles
+            ));
+        }
+        Ok(())
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+#[inline]
+pub fn tiles_per_edge(config: &TerrainConfig) -> u32 {
+    config.tiles_per_edge() as u32
+}
+// ----------------------------------------------------------------------------
+#[inline]
+/// datapoints per tile edge without the shared border
+pub fn tile_resolution(config: &TerrainConfig) -> u32 {
+    config.map_size() / tiles_per_edge(config).max(1)
+}
+// ----------------------------------------------------------------------------
+/// default export directory: "w3tiles" next to the heightmap
+pub(crate) fn default_directory(config: &TerrainConfig) -> String {
+    Path::new(config.heightmap())
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join("w3tiles")
+        .to_string_lossy()
+        .to_string()
+}
+// ----------------------------------------------------------------------------
+#[inline]
+pub(crate) fn tile_basename(x: u32, y: u32, tile_resolution: u32) -> String {
+    format!("tile_{}_x_{}_res{}", x, y, tile_resolution)
+}
+// ----------------------------------------------------------------------------
+/// map offsets of all datapoints of tile (x, y) in row major order including
+/// the border shared with the next tiles
+fn tile_datapoints(
+    map_size: u32,
+    tile_resolution: u32,
+    (x, y): (u32, u32),
+) -> impl Iterator<Item = usize> {
+    let (size, res) = (map_size as usize, tile_resolution as usize);
+    let (first_col, first_row) = (x as usize * res, y as usize * res);
+
+    // shared border: the last tiles repeat the last row/column of the map
+    (first_row..=first_row + res).flat_map(move |row| {
+        let offset = row.min(size - 1) * size;
+        (first_col..=first_col + res).map(move |col| offset + col.min(size - 1))
+    })
+}
+// ----------------------------------------------------------------------------
+/// slices the selected maps into the buffers of tile (x, y)
+pub(crate) fn extract_tile(
+    map_size: u32,
+    tile_resolution: u32,
+    tile: (u32, u32),
+    settings: &TileExportSettings,
+    heightmap: &[u16],
+    controlmap: &[u16],
+    tintmap: &[u8],
+) -> TileBuffers {
+    let datapoints = (tile_resolution as usize + 1).pow(2);
+    let capacity = |selected: bool, bytes: usize| if selected { datapoints * bytes } else { 0 };
+
+    let mut buffers = TileBuffers {
+        height: Vec::with_capacity(capacity(settings.heightmap, 2)),
+        control: Vec::with_capacity(capacity(settings.controlmap, 2)),
+        color: Vec::with_capacity(capacity(settings.tintmap, 4)),
+    };
+
+    for pos in tile_datapoints(map_size, tile_resolution, tile) {
+        if settings.heightmap {
+            buffers.height.extend(heightmap[pos].to_le_bytes());
+        }
+        if settings.controlmap {
+            buffers.control.extend(controlmap[pos].to_le_bytes());
+        }
+        if settings.tintmap {
+            buffers
+                .color
+                .extend_from_slice(&tintmap[pos * 4..pos * 4 + 4]);
+        }
+    }
+    buffers
+}
+// ----------------------------------------------------------------------------
+impl TileExportManifest {
+    // ------------------------------------------------------------------------
+    pub(crate) fn new(config: &TerrainConfig, settings: &TileExportSettings) -> Self {
+        let tiles_per_edge = tiles_per_edge(config);
+        let tile_resolution = tile_resolution(config);
+        let format = |selected: bool, format| selected.then_some(format);
+        Self {
+            name: config.name().to_string(),
+            map_size: config.map_size(),
+            terrain_size: config.terrain_size(),
+            min_height: config.min_height(),
+            max_height: config.max_height(),
+            tiles_per_edge,
+            tile_resolution,
+            tile_datapoints: tile_resolution + 1,
+            tile_size: config.terrain_size() / tiles_per_edge as f32,
+            height_format: format(settings.heightmap, "u16le"),
+            control_format: format(settings.controlmap, "u16le"),
+            color_format: format(settings.tintmap, "rgba8"),
+            tiles: Vec::with_capacity((tiles_per_edge * tiles_per_edge) as usize),
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// adds tile (x, y) with height range of the tile in heightmap
+    pub(crate) fn add_tile(
+        &mut self,
+        config: &TerrainConfig,
+        (x, y): (u32, u32),
+        heightmap: &[u16],
+    ) {
+        let (min, max) = tile_datapoints(self.map_size, self.tile_resolution, (x, y))
+            .map(|pos| heightmap[pos])
+            .fold((u16::MAX, u16::MIN), |(min, max), h| {
+                (min.min(h), max.max(h))
+            });
+
+        let origin = config.map_offset() + glam::vec2(x as f32, y as f32) * self.tile_size;
+
+        let basename = tile_basename(x, y, self.tile_resolution);
+        let file =
+            |format: Option<&str>, suffix| format.map(|_| format!("{}.{}.buf", basename, suffix));
+        self.tiles.push(ExportedTileInfo {
+            x,
+            y,
+            origin: (origin.x, origin.y),
+            min_height: config.min_height() + min as f32 * config.height_scaling(),
+            max_height: config.min_height() + max as f32 * config.height_scaling(),
+            height: file(self.height_format, "height"),
+            control: file(self.control_format, "control"),
+            color: file(self.color_format, "color"),
+        });
+    }
+    // ------------------------------------------------------------------------
+    pub(crate) fn to_ron(&self) -> Result<String, String> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| format!("failed to serialize tile manifest: {}", e))
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+// Default impl
+// ----------------------------------------------------------------------------
+impl Default for TileExportSettings {
+    fn default() -> Self {
+        Self {
+            directory: String::default(),
+            heightmap: true,
+            controlmap: true,
+            tintmap: true,
+        }
+    }
+}
+// ----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    // ------------------------------------------------------------------------
+    #[test]
+    fn neighbouring_tiles_share_border_datapoints() {
+        // 8x8 map with 2 tiles per edge -> 4 datapoints + 1 border per tile edge
+        let size = 8u32;
+        let heights = (0..size * size).map(|i| i as u16).collect::<Vec<_>>();
+        let control = heights.iter().map(|h| h + 1000).collect::<Vec<_>>();
+        let tint = heights
+            .iter()
+            .flat_map(|h| [*h as u8, 0, 0, 255])
+            .collect::<Vec<_>>();
+        let settings = TileExportSettings::default();
+
+        let tile = |x, y| extract_tile(size, 4, (x, y), &settings, &heights, &control, &tint);
+        let u16s = |buf: &[u8]| {
+            buf.chunks_exact(2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]))
+                .collect::<Vec<_>>()
+        };
+        let (first, second) = (tile(0, 0), tile(1, 0));
+        let (a, b) = (u16s(&first.height), u16s(&second.height));
+        assert_eq!(a.len(), 25);
+        assert_eq!(first.color.len(), 25 * 4);
+
+        for row in 0..5 {
+            // last column of the first tile is the first column of the next tile
+            assert_eq!(a[row * 5 + 4], b[row * 5]);
+            assert_eq!(a[row * 5 + 4], (row * 8 + 4) as u16);
+            // border at the map edge repeats the last column of the map
+            assert_eq!(b[row * 5 + 4], (row * 8 + 7) as u16);
+        }
+        // bottom tile border repeats the last row
+        let c = u16s(&tile(0, 1).control);
+        assert_eq!(&c[20..25], &c[15..20]);
+        assert_eq!(c[20], 1000 + 7 * 8);
+
+        // unselected maps are skipped
+        let heights_only = TileExportSettings {
+            controlmap: false,
+            tintmap: false,
+            ..settings.clone()
+        };
+        let tile = extract_tile(size, 4, (1, 1), &heights_only, &heights, &control, &tint);
+        assert_eq!(tile.height.len(), 25 * 2);
+        assert!(tile.control.is_empty() && tile.color.is_empty());
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
@@ -1,4 +1,5 @@
 // ----------------------------------------------------------------------------
+#[cfg(feature = "editor")]
 use bevy::render::render_resource::TextureFormat;
 
 use crate::clipmap::ClipmapData;
@@ -35,6 +36,7 @@ impl ClipmapData for TintMap {
         4
     }
     // ------------------------------------------------------------------------
+    #[cfg(feature = "editor")]
     #[inline(always)]
     fn texture_format(&self) -> TextureFormat {
         TextureFormat::Rgba8Unorm
@@ -0,0 +1,533 @@
+// ----------------------------------------------------------------------------
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use tracing::{debug, info, warn};
+
+use futures_lite::Future;
+
+use png::{BitDepth, ColorType};
+
+use crate::clipmap::ClipmapData;
+use crate::config;
+use crate::heightmap::TerrainHeightMap;
+use crate::tileexport::{self, TileExportManifest, TileExportSettings, TILE_EXPORT_MANIFEST};
+use crate::TaskResultData;
+// ----------------------------------------------------------------------------
+pub struct WriterPlugin;
+// ----------------------------------------------------------------------------
+impl WriterPlugin {
+    // ------------------------------------------------------------------------
+    pub(crate) fn save_texturemap(
+        config: &config::TerrainConfig,
+        controlmap: Vec<u16>,
+    ) -> impl Future<Output = Result<TaskResultData, String>> {
+        let (size, texturing) = (config.map_size(), config.texturemaps().clone());
+        async move {
+            let (background_path, overlay_path, blendcontrol_path) = (
+                texturing.background(),
+                texturing.overlay(),
+                texturing.blendcontrol(),
+            );
+
+            for (path, dataname) in [
+                (background_path, "background texture map"),
+                (overlay_path, "overlay texture map"),
+                (blendcontrol_path, "blendcontrol map"),
+            ] {
+                if path.is_empty() {
+                    return Err(format!("no filepath for {} defined", dataname));
+                }
+            }
+
+            if controlmap.len() != (size * size) as usize {
+                return Err("size of texture control map does not match map size!".to_string());
+            }
+
+            debug!("splitting texture control map...");
+            let mut background = Vec::with_capacity(controlmap.len());
+            let mut overlay = Vec::with_capacity(controlmap.len());
+            let mut blendcontrol = Vec::with_capacity(controlmap.len());
+
+            for value in controlmap {
+                // 0..4 overlay texture idx
+                // 5..9 background textures idx
+                // 10..15 blend control
+                //   10..12 slope threshold
+                //   13..15 UV scale
+                overlay.push((value & 0b1_1111) as u8);
+                background.push(((value >> 5) & 0b1_1111) as u8);
+                blendcontrol.push((value >> 10) as u8);
+            }
+
+            // write everything into temporary files first and replace the
+            // original maps only if *all* maps were written successfully
+            let mut tmpfiles = Vec::with_capacity(3);
+            for (filepath, palette_size, data) in [
+                (background_path, 32, background),
+                (overlay_path, 32, overlay),
+                (blendcontrol_path, 64, blendcontrol),
+            ] {
+                debug!("saving {}...", filepath);
+                let palette = Self::grayscale_palette(palette_size);
+                match Self::save_png_data_tmp(
+                    ColorType::Indexed,
+                    BitDepth::Eight,
+                    size,
+                    Some(&palette),
+                    &data,
+                    filepath,
+                ) {
+                    Ok(tmpfile) => tmpfiles.push((tmpfile, filepath)),
+                    Err(e) => {
+                        Self::remove_tmpfiles(&tmpfiles);
+                        return Err(e);
+                    }
+                }
+            }
+
+            Self::replace_files(&tmpfiles)?;
+
+            Ok(TaskResultData::TextureMapSaved)
+        }
+    }
+    // ------------------------------------------------------------------------
+    pub(crate) fn save_tintmap(
+        config: &config::TerrainConfig,
+        tintmap: Vec<u8>,
+    ) -> impl Future<Output = Result<TaskResultData, String>> {
+        let (filepath, size) = (config.tintmap().to_string(), config.map_size());
+        async move {
+            if filepath.is_empty() {
+                return Err("no filepath for tint map defined".to_string());
+            }
+
+            if tintmap.len() != 4 * (size * size) as usize {
+                return Err("size of tint map does not match map size!".to_string());
+            }
+
+            debug!("saving {}...", filepath);
+            let tmpfile = Self::save_png_data_tmp(
+                ColorType::Rgba,
+                BitDepth::Eight,
+                size,
+                None,
+                &tintmap,
+                &filepath,
+            )?;
+
+            Self::replace_files(&[(tmpfile, filepath)])?;
+
+            Ok(TaskResultData::TintMapSaved)
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// saves heightmap as 16bit big endian grayscale png (same format as
+    /// expected by the heightmap loader).
+    pub(crate) fn save_heightmap(
+        config: &config::TerrainConfig,
+        heightmap: TerrainHeightMap,
+    ) -> impl Future<Output = Result<TaskResultData, String>> {
+        let (filepath, size) = (config.heightmap().to_string(), config.map_size());
+        let height_range = (config.min_height(), config.max_height());
+        async move {
+            if filepath.is_empty() {
+                return Err("no filepath for heightmap defined".to_string());
+            }
+            if heightmap.size() != size {
+                return Err("size of heightmap does not match map size!".to_string());
+            }
+            Self::check_heightmap_clipping(&heightmap, height_range);
+
+            debug!("saving {}...", filepath);
+            let tmpfile = Self::save_png_data_tmp(
+                ColorType::Grayscale,
+                BitDepth::Sixteen,
+                size,
+                None,
+                &heightmap.to_be_bytes(),
+                &filepath,
+            )?;
+
+            Self::replace_files(&[(tmpfile, filepath)])?;
+
+            Ok(TaskResultData::HeightmapSaved)
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// exports heightmap as headerless 16bit little endian raw file next to the
+    /// heightmap png (same filename with .r16 extension).
+    pub(crate) fn export_heightmap_r16(
+        config: &config::TerrainConfig,
+        heightmap: TerrainHeightMap,
+    ) -> impl Future<Output = Result<TaskResultData, String>> {
+        let (heightmap_path, size) = (config.heightmap().to_string(), config.map_size());
+        let height_range = (config.min_height(), config.max_height());
+        async move {
+            if heightmap_path.is_empty() {
+                return Err("no filepath for heightmap defined".to_string());
+            }
+            if heightmap.size() != size {
+                return Err("size of heightmap does not match map size!".to_string());
+            }
+            Self::check_heightmap_clipping(&heightmap, height_range);
+
+            let filepath = Path::new(&heightmap_path)
+                .with_extension("r16")
+                .to_string_lossy()
+                .to_string();
+
+            debug!("exporting {}...", filepath);
+            let tmpfile = Self::save_raw_data_tmp(&heightmap.to_le_bytes(), &filepath)?;
+
+            Self::replace_files(&[(tmpfile, filepath)])?;
+
+            Ok(TaskResultData::HeightmapExported)
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// exports heightmap, texture control map and tint map sliced into raw per
+    /// tile buffers (see tileexport module) and a manifest describing all
+    /// exported tiles.
+    pub(crate) fn export_tiles(
+        config: &config::TerrainConfig,
+        settings: TileExportSettings,
+        heightmap: TerrainHeightMap,
+        controlmap: Vec<u16>,
+        tintmap: Vec<u8>,
+    ) -> impl Future<Output = Result<TaskResultData, String>> {
+        let config = config.clone();
+        async move {
+            settings.validate(&config)?;
+
+            let size = config.map_size();
+            let datapoints = (size * size) as usize;
+            if heightmap.size() != size
+                || controlmap.len() != datapoints
+                || tintmap.len() != 4 * datapoints
+            {
+                return Err("size of terrain maps does not match map size!".to_string());
+            }
+            if settings.heightmap {
+                Self::check_heightmap_clipping(
+                    &heightmap,
+                    (config.min_height(), config.max_height()),
+                );
+            }
+
+            let directory = settings.directory.trim();
+            fs::create_dir_all(directory)
+                .map_err(|e| format!("failed to create directory {}: {}", directory, e))?;
+
+            let tiles_per_edge = tileexport::tiles_per_edge(&config);
+            let tile_resolution = tileexport::tile_resolution(&config);
+            let mut manifest = TileExportManifest::new(&config, &settings);
+            let mut tmpfiles =
+                Vec::with_capacity((tiles_per_edge * tiles_per_edge * 3 + 1) as usize);
+
+            let target = |filename: &str| {
+                Path::new(directory)
+                    .join(filename)
+                    .to_string_lossy()
+                    .to_string()
+            };
+
+            // write everything into temporary files first and replace existing
+            // tiles only if *all* tiles were written successfully
+            let result = (|| -> Result<(), String> {
+                for y in 0..tiles_per_edge {
+                    for x in 0..tiles_per_edge {
+                        debug!("exporting tile {}x{}...", x, y);
+                        let tile = tileexport::extract_tile(
+                            size,
+                            tile_resolution,
+                            (x, y),
+                            &settings,
+                            heightmap.as_slice(),
+                            &controlmap,
+                            &tintmap,
+                        );
+                        let basename = tileexport::tile_basename(x, y, tile_resolution);
+
+                        for (suffix, selected, data) in [
+                            ("height", settings.heightmap, &tile.height),
+                            ("control", settings.controlmap, &tile.control),
+                            ("color", settings.tintmap, &tile.color),
+                        ] {
+                            if selected {
+                                let filepath = target(&format!("{}.{}.buf", basename, suffix));
+                                let tmpfile = Self::save_raw_data_tmp(data, &filepath)?;
+                                tmpfiles.push((tmpfile, filepath));
+                            }
+                        }
+                        // height range is part of the manifest even if the
+                        // heightmap is not exported
+                        manifest.add_tile(&config, (x, y), heightmap.as_slice());
+                    }
+                }
+                let filepath = target(TILE_EXPORT_MANIFEST);
+                let tmpfile = Self::save_raw_data_tmp(manifest.to_ron()?.as_bytes(), &filepath)?;
+                tmpfiles.push((tmpfile, filepath));
+                Ok(())
+            })();
+
+            if let Err(e) = result {
+                Self::remove_tmpfiles(&tmpfiles);
+                return Err(e);
+            }
+
+            Self::replace_files(&tmpfiles)?;
+            info!(
+                "exported {}x{} tiles ({}x{} datapoints incl. shared border) into {}",
+                tiles_per_edge,
+                tiles_per_edge,
+                tile_resolution + 1,
+                tile_resolution + 1,
+                directory
+            );
+
+            Ok(TaskResultData::TilesExported)
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// writes data into a temporary file next to the provided filepath and
+    /// returns the temporary filepath. use `replace_files` to atomically replace
+    /// the target file.
+    pub fn save_raw_data_tmp(data: &[u8], filepath: &str) -> Result<String, String> {
+        let tmpfilepath = format!("{}.tmp", filepath);
+
+        let result = File::create(&tmpfilepath)
+            .map_err(|e| format!("failed to create file {}: {}", tmpfilepath, e))
+            .and_then(|file| {
+                let mut writer = BufWriter::new(&file);
+                writer
+                    .write_all(data)
+                    .and_then(|_| writer.flush())
+                    .map_err(|e| format!("failed to write data for {}: {}", filepath, e))?;
+
+                // see save_png_data_tmp
+                file.sync_all()
+                    .map_err(|e| format!("failed to write file {}: {}", tmpfilepath, e))
+            });
+
+        match result {
+            Ok(_) => Ok(tmpfilepath),
+            Err(e) => {
+                fs::remove_file(&tmpfilepath).ok();
+                Err(e)
+            }
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// writes png data into a temporary file next to the provided filepath and
+    /// returns the temporary filepath. use `replace_files` to atomically replace
+    /// the target file.
+    pub fn save_png_data_tmp(
+        colortype: ColorType,
+        bitdepth: BitDepth,
+        resolution: u32,
+        palette: Option<&[u8]>,
+        data: &[u8],
+        filepath: &str,
+    ) -> Result<String, String> {
+        use png::Encoder;
+
+        let tmpfilepath = format!("{}.tmp", filepath);
+
+        let result = File::create(&tmpfilepath)
+            .map_err(|e| format!("failed to create file {}: {}", tmpfilepath, e))
+            .and_then(|file| {
+                let mut buffer = BufWriter::new(&file);
+                let mut encoder = Encoder::new(&mut buffer, resolution, resolution);
+                encoder.set_color(colortype);
+                encoder.set_depth(bitdepth);
+                if let Some(palette) = palette {
+                    encoder.set_palette(palette);
+                }
+
+                let mut writer = encoder
+                    .write_header()
+                    .map_err(|e| format!("failed to encode png file {}: {}", filepath, e))?;
+
+                writer
+                    .write_image_data(data)
+                    .map_err(|e| format!("failed to write png data for {}: {}", filepath, e))?;
+
+                writer
+                    .finish()
+                    .map_err(|e| format!("failed to finish png file {}: {}", filepath, e))?;
+
+                // dropping the buffer would silently ignore write errors
+                buffer
+                    .flush()
+                    .map_err(|e| format!("failed to write file {}: {}", tmpfilepath, e))?;
+
+                // make sure all data hit the disk before the original file is
+                // replaced
+                file.sync_all()
+                    .map_err(|e| format!("failed to write file {}: {}", tmpfilepath, e))
+            });
+
+        match result {
+            Ok(_) => Ok(tmpfilepath),
+            Err(e) => {
+                fs::remove_file(&tmpfilepath).ok();
+                Err(e)
+            }
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// renames all (tmpfile, target) pairs. rename is atomic (on the same
+    /// filesystem) so a target file is either the old or the new version.
+    /// if any target cannot be replaced all already replaced targets are
+    /// restored from a backup and all tmpfiles are removed: the targets are
+    /// never left as a mix of old and new versions.
+    pub fn replace_files<S: AsRef<str>, T: AsRef<str>>(files: &[(S, T)]) -> Result<(), String> {
+        // (target, backup of previous version if target existed)
+        let mut replaced = Vec::with_capacity(files.len());
+
+        let result = files.iter().try_for_each(|(tmpfile, target)| {
+            let (tmpfile, target) = (tmpfile.as_ref(), target.as_ref());
+            let backup = Self::backup_file(target)?;
+
+            match fs::rename(tmpfile, target) {
+                Ok(_) => {
+                    replaced.push((target, backup));
+                    Ok(())
+                }
+                Err(e) => {
+                    if let Some(backup) = backup {
+                        fs::remove_file(backup).ok();
+                    }
+                    Err(format!(
+                        "failed to replace file {} with {}: {}",
+                        target, tmpfile, e
+                    ))
+                }
+            }
+        });
+
+        match result {
+            Ok(_) => {
+                for (_, backup) in replaced {
+                    if let Some(backup) = backup {
+                        fs::remove_file(backup).ok();
+                    }
+                }
+                Ok(())
+            }
+            Err(e) => {
+                for (target, backup) in replaced.iter().rev() {
+                    let restored = match backup {
+                        Some(backup) => fs::rename(backup, target),
+                        None => fs::remove_file(target),
+                    };
+                    if let Err(e) = restored {
+                        warn!("failed to restore previous version of {}: {}", target, e);
+                    }
+                }
+                Self::remove_tmpfiles(files);
+                Err(e)
+            }
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// creates a backup of an existing file (hard link or copy if links are
+    /// not supported). returns None if the file does not exist.
+    fn backup_file(filepath: &str) -> Result<Option<String>, String> {
+        if !Path::new(filepath).exists() {
+            return Ok(None);
+        }
+        let backup = format!("{}.bak", filepath);
+        fs::remove_file(&backup).ok();
+        fs::hard_link(filepath, &backup)
+            .or_else(|_| fs::copy(filepath, &backup).map(|_| ()))
+            .map_err(|e| format!("failed to backup file {}: {}", filepath, e))?;
+        Ok(Some(backup))
+    }
+    // ------------------------------------------------------------------------
+    fn remove_tmpfiles<S: AsRef<str>, T>(files: &[(S, T)]) {
+        for (tmpfile, _) in files {
+            fs::remove_file(tmpfile.as_ref()).ok();
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// heights outside of the terrain height range cannot be represented and
+    /// are clamped to min/max height (e.g. by sculpting). this information is
+    /// lost on export so the user should be warned.
+    fn check_heightmap_clipping(
+        heightmap: &TerrainHeightMap,
+        (min_height, max_height): (f32, f32),
+    ) {
+        let (at_min, at_max) = heightmap.clipped_values();
+        if at_min > 0 {
+            warn!(
+                "heightmap: {} datapoints are clipped at min height ({}). consider lowering min height.",
+                at_min, min_height
+            );
+        }
+        if at_max > 0 {
+            warn!(
+                "heightmap: {} datapoints are clipped at max height ({}). consider raising max height.",
+                at_max, max_height
+            );
+        }
+    }
+    // ------------------------------------------------------------------------
+    /// generates an RGB palette with the requested amount of entries ranging
+    /// from black to white.
+    fn grayscale_palette(entries: usize) -> Vec<u8> {
+        let max = (entries.max(2) - 1) as f32;
+        (0..entries)
+            .flat_map(|i| {
+                let v = (i as f32 / max * 255.0).round() as u8;
+                [v, v, v]
+            })
+            .collect()
+    }
+    // ------------------------------------------------------------------------
+}
+// ----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_replace_restores_all_targets() {
+        let dir = std::env::temp_dir().join(format!("terrain-writer-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = |filename: &str| dir.join(filename).to_string_lossy().to_string();
+
+        let (first, second, new) = (path("first.buf"), path("second.buf"), path("new.buf"));
+        fs::write(&first, "old").unwrap();
+        fs::write(&second, "old").unwrap();
+
+        let tmpfiles = vec![
+            (
+                WriterPlugin::save_raw_data_tmp(b"new", &first).unwrap(),
+                first.clone(),
+            ),
+            (
+                WriterPlugin::save_raw_data_tmp(b"new", &new).unwrap(),
+                new.clone(),
+            ),
+            // missing tmpfile: replacing fails after two targets were replaced
+            (path("missing.tmp"), second.clone()),
+        ];
+        let result = WriterPlugin::replace_files(&tmpfiles);
+
+        let content = |filepath: &str| fs::read_to_string(filepath).ok();
+        let (first, second, new) = (content(&first), content(&second), content(&new));
+        let leftovers = fs::read_dir(&dir).unwrap().count();
+        fs::remove_dir_all(&dir).ok();
+
+        assert!(result.is_err());
+        assert_eq!(first.as_deref(), Some("old"));
+        assert_eq!(second.as_deref(), Some("old"));
+        assert_eq!(new, None);
+        // no backups or tmpfiles are left behind
+        assert_eq!(leftovers, 2);
+    }
+}
+// ----------------------------------------------------------------------------